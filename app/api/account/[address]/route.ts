@@ -4,6 +4,9 @@ import type { CombinedAccountResponse, AccountData, WalletInfo, BalanceResponse
 import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
 import { fetchWithCache } from '@/lib/aggregator-cache';
 import { HttpError } from '@/lib/http-client';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/account/[address]' });
 
 export async function GET(
   request: Request,
@@ -33,7 +36,7 @@ export async function GET(
     let accountData: AccountData | null = null;
     const apiUrl = process.env.API_URL;
     if (!apiUrl) {
-      console.error("Failed to fetch user account: No API_URL defined in env");
+      log.error('Failed to fetch user account: No API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch user account" }, { status: 500 });
     }
 
@@ -63,7 +66,7 @@ export async function GET(
       );
       accountData = data;
     } catch (error) {
-      console.error("Error fetching account data:", error instanceof Error ? error.message : 'Unknown error');
+      log.error('Error fetching account data', { error });
       // accountData stays null
     }
 
@@ -96,7 +99,7 @@ export async function GET(
           };
         }
       } catch (error) {
-        console.error("Error fetching lightning data:", error instanceof Error ? error.message : 'Unknown error');
+        log.error('Error fetching lightning data', { error });
         // lightningData stays null
       }
     }
@@ -109,7 +112,7 @@ export async function GET(
 
     return NextResponse.json(combinedResponse);
   } catch (error) {
-    console.error("Error in account endpoint:", error instanceof Error ? error.message : 'Unknown error');
+    log.error('Error in account endpoint', { error });
     return NextResponse.json(
       { error: "Failed to fetch account data" },
       { status: 500 }
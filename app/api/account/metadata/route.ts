@@ -2,12 +2,15 @@ import { NextResponse } from 'next/server';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
 import type { AccountMetadataUpdate } from '@/app/api/account/types';
 import { getDb } from '@/lib/db';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/account/metadata' });
 
 export async function POST(request: Request) {
   try {
     const apiUrl = process.env.API_URL;
     if (!apiUrl) {
-      console.error("Failed to update account metadata: No API_URL defined in env");
+      log.error('Failed to update account metadata: No API_URL defined in env');
       return NextResponse.json({ error: "Failed to update account metadata" }, { status: 500 });
     }
 
@@ -67,17 +70,19 @@ export async function POST(request: Request) {
 
     if ('is_private' in metadata) {
       try {
-        const db = getDb();
-        db.prepare('UPDATE monitored_users SET is_public = ? WHERE address = ?')
-          .run(metadata.is_private ? 0 : 1, btc_address);
+        const db = await getDb();
+        await db.run(
+          'UPDATE monitored_users SET is_public = ? WHERE address = ?',
+          [metadata.is_private ? 0 : 1, btc_address]
+        );
       } catch (dbError) {
-        console.error('Failed to sync is_public to local DB:', dbError);
+        log.error('Failed to sync is_public to local DB', { error: dbError });
       }
     }
 
     return NextResponse.json(accountData);
   } catch (error) {
-    console.error("Error updating account metadata:", error);
+    log.error('Error updating account metadata', { error });
     return NextResponse.json({ error: "Failed to update account metadata" }, { status: 500 });
   }
 }
@@ -1,12 +1,15 @@
 import { NextResponse } from 'next/server';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
 import type { AccountUpdate } from '@/app/api/account/types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/account/update' });
 
 export async function POST(request: Request) {
   try {
     const apiUrl = process.env.API_URL;
     if (!apiUrl) {
-      console.error("Failed to update user account: No API_URL defined in env");
+      log.error('Failed to update user account: No API_URL defined in env');
       return NextResponse.json({ error: "Failed to update user account" }, { status: 500 });
     }
 
@@ -57,7 +60,7 @@ export async function POST(request: Request) {
 
     return NextResponse.json(accountData);
   } catch (error) {
-    console.error("Error updating user account:", error);
+    log.error('Error updating user account', { error });
     return NextResponse.json({ error: "Failed to update user account" }, { status: 500 });
   }
 }
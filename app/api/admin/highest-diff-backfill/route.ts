@@ -0,0 +1,42 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { requireAdmin } from '@/app/api/lib/admin-auth';
+import {
+  NO_CACHE_HEADERS,
+  toHighestDiffBackfillProgress,
+  type HighestDiffBackfillRow,
+  type StoredBlocksRow,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/admin/highest-diff-backfill' });
+
+/**
+ * Progress of the historical highest diff backfill, with the range of blocks
+ * stored so far. Requires ADMIN_API_TOKEN.
+ */
+export async function GET(request: Request) {
+  const denied = requireAdmin(request);
+  if (denied) return denied;
+
+  try {
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+
+    const row = await db.get<HighestDiffBackfillRow>(
+      'SELECT * FROM highest_diff_backfill WHERE id = 1'
+    );
+    const stored = await db.get<StoredBlocksRow>(`
+      SELECT COUNT(*) AS count, MIN(block_height) AS lowest, MAX(block_height) AS highest
+      FROM block_highest_diff
+    `);
+
+    return NextResponse.json(
+      toHighestDiffBackfillProgress(row, stored!, now),
+      { headers: NO_CACHE_HEADERS }
+    );
+  } catch (error) {
+    log.error('Error fetching highest diff backfill progress', { error });
+    return NextResponse.json({ error: "Failed to fetch backfill progress" }, { status: 500 });
+  }
+}
@@ -0,0 +1,171 @@
+/**
+ * Shared types for admin API routes
+ */
+
+export const NO_CACHE_HEADERS = {
+  'Cache-Control': 'no-cache, no-store, must-revalidate',
+  'Pragma': 'no-cache',
+  'Expires': '0',
+};
+
+// A running backfill writes its cursor after every block; one that hasn't
+// for this long has stopped (jobs process down, or paused on an API error)
+export const BACKFILL_STALE_AFTER_SECONDS = 5 * 60;
+
+export interface HighestDiffBackfillRow {
+  start_height: number;
+  next_height: number;
+  floor_height: number;
+  blocks_checked: number;
+  blocks_stored: number;
+  blocks_empty: number;
+  consecutive_empty: number;
+  last_error: string | null;
+  started_at: number;
+  updated_at: number;
+  completed_at: number | null;
+}
+
+export interface StoredBlocksRow {
+  count: number;
+  lowest: number | null;
+  highest: number | null;
+}
+
+export type BackfillStatus = 'not_started' | 'running' | 'paused' | 'complete';
+
+export interface HighestDiffBackfillProgress {
+  status: BackfillStatus;
+  startHeight: number | null;
+  nextHeight: number | null;
+  floorHeight: number | null;
+  blocksChecked: number;
+  blocksStored: number;
+  blocksEmpty: number;
+  consecutiveEmpty: number;
+  blocksRemaining: number | null;
+  percentComplete: number | null;
+  lastError: string | null;
+  startedAt: number | null;
+  updatedAt: number | null;
+  completedAt: number | null;
+  storedBlocks: {
+    count: number;
+    lowestHeight: number | null;
+    highestHeight: number | null;
+  };
+}
+
+export function getBackfillStatus(row: HighestDiffBackfillRow | undefined, now: number): BackfillStatus {
+  if (!row) return 'not_started';
+  if (row.completed_at !== null) return 'complete';
+  return now - row.updated_at <= BACKFILL_STALE_AFTER_SECONDS && !row.last_error ? 'running' : 'paused';
+}
+
+export function toHighestDiffBackfillProgress(
+  row: HighestDiffBackfillRow | undefined,
+  stored: StoredBlocksRow,
+  now: number
+): HighestDiffBackfillProgress {
+  const storedBlocks = {
+    count: stored.count,
+    lowestHeight: stored.lowest,
+    highestHeight: stored.highest,
+  };
+
+  if (!row) {
+    return {
+      status: 'not_started',
+      startHeight: null,
+      nextHeight: null,
+      floorHeight: null,
+      blocksChecked: 0,
+      blocksStored: 0,
+      blocksEmpty: 0,
+      consecutiveEmpty: 0,
+      blocksRemaining: null,
+      percentComplete: null,
+      lastError: null,
+      startedAt: null,
+      updatedAt: null,
+      completedAt: null,
+      storedBlocks,
+    };
+  }
+
+  const status = getBackfillStatus(row, now);
+  const blocksRemaining = status === 'complete' ? 0 : Math.max(0, row.next_height - row.floor_height + 1);
+  const total = row.blocks_checked + blocksRemaining;
+
+  return {
+    status,
+    startHeight: row.start_height,
+    nextHeight: row.next_height,
+    floorHeight: row.floor_height,
+    blocksChecked: row.blocks_checked,
+    blocksStored: row.blocks_stored,
+    blocksEmpty: row.blocks_empty,
+    consecutiveEmpty: row.consecutive_empty,
+    blocksRemaining,
+    percentComplete: total > 0 ? Math.round((row.blocks_checked / total) * 1000) / 10 : 100,
+    lastError: row.last_error,
+    startedAt: row.started_at,
+    updatedAt: row.updated_at,
+    completedAt: row.completed_at,
+    storedBlocks,
+  };
+}
+
+export interface UserCollectionCycleRow {
+  started_at: number;
+  duration_ms: number;
+  eligible_users: number;
+  due_users: number;
+  collected: number;
+  failed: number;
+  deferred: number;
+  rate_limited: number;
+  coverage: number;
+  avg_lag_seconds: number;
+  max_lag_seconds: number;
+}
+
+export interface UserCollectionCycle {
+  startedAt: number;
+  durationMs: number;
+  eligible: number;
+  due: number;
+  collected: number;
+  failed: number;
+  deferred: number;
+  rateLimited: boolean;
+  coverage: number;
+  avgLagSeconds: number;
+  maxLagSeconds: number;
+}
+
+export interface UserCollectionStatus {
+  /** Active users that are due now, and how overdue the oldest one is */
+  backlog: {
+    due: number;
+    oldestDueSeconds: number | null;
+  };
+  /** Most recent cycle first */
+  cycles: UserCollectionCycle[];
+}
+
+export function toUserCollectionCycle(row: UserCollectionCycleRow): UserCollectionCycle {
+  return {
+    startedAt: row.started_at,
+    durationMs: row.duration_ms,
+    eligible: row.eligible_users,
+    due: row.due_users,
+    collected: row.collected,
+    failed: row.failed,
+    deferred: row.deferred,
+    rateLimited: row.rate_limited === 1,
+    coverage: row.coverage,
+    avgLagSeconds: row.avg_lag_seconds,
+    maxLagSeconds: row.max_lag_seconds,
+  };
+}
@@ -0,0 +1,62 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { requireAdmin } from '@/app/api/lib/admin-auth';
+import {
+  NO_CACHE_HEADERS,
+  toUserCollectionCycle,
+  type UserCollectionCycleRow,
+  type UserCollectionStatus,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/admin/user-collection' });
+
+/**
+ * Coverage and lag of the adaptive user collection: the recent cycle reports
+ * (`?limit=`, default 60) and the current backlog of due users. Requires
+ * ADMIN_API_TOKEN.
+ */
+export async function GET(request: Request) {
+  const denied = requireAdmin(request);
+  if (denied) return denied;
+
+  try {
+    const { searchParams } = new URL(request.url);
+    const parsedLimit = Number.parseInt(searchParams.get('limit') || '', 10);
+    const limit = Number.isFinite(parsedLimit)
+      ? Math.min(Math.max(parsedLimit, 1), 1440)
+      : 60;
+
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+
+    const rows = await db.all<UserCollectionCycleRow>(`
+      SELECT started_at, duration_ms, eligible_users, due_users, collected, failed,
+        deferred, rate_limited, coverage, avg_lag_seconds, max_lag_seconds
+      FROM user_collection_cycles
+      ORDER BY started_at DESC, id DESC
+      LIMIT ?
+    `, [limit]);
+
+    // Users without a schedule row have never been collected and are due
+    const backlog = await db.get<{ due: number; oldest: number | null }>(`
+      SELECT COUNT(*) AS due, MIN(s.next_collect_at) AS oldest
+      FROM monitored_users u
+      LEFT JOIN user_collection_schedule s ON s.user_id = u.id
+      WHERE u.is_active = 1 AND COALESCE(s.next_collect_at, 0) <= ?
+    `, [now]);
+
+    const status: UserCollectionStatus = {
+      backlog: {
+        due: backlog?.due ?? 0,
+        oldestDueSeconds: backlog?.oldest ? now - backlog.oldest : null,
+      },
+      cycles: rows.map(toUserCollectionCycle),
+    };
+
+    return NextResponse.json(status, { headers: NO_CACHE_HEADERS });
+  } catch (error) {
+    log.error('Error fetching user collection status', { error });
+    return NextResponse.json({ error: "Failed to fetch user collection status" }, { status: 500 });
+  }
+}
@@ -0,0 +1,54 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { checkRateLimit, getRateLimitHeaders } from '@/app/api/lib/rate-limit';
+import { DEFAULT_LIMIT, MAX_LIMIT, toBlockFound, type BlockFoundRow } from './types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/blocks' });
+
+/**
+ * Blocks found by the pool, newest first, with the template that found them.
+ *
+ * Query params: ?limit= (default 50, max 200), ?before= (height) to page back
+ */
+export async function GET(request: Request) {
+  const rateLimitResult = checkRateLimit(request);
+
+  if (!rateLimitResult.success) {
+    return NextResponse.json(
+      { error: 'Too many requests. Please try again later.' },
+      {
+        status: 429,
+        headers: getRateLimitHeaders(rateLimitResult),
+      }
+    );
+  }
+
+  try {
+    const { searchParams } = new URL(request.url);
+    const limit = Math.min(Math.max(parseInt(searchParams.get('limit') || String(DEFAULT_LIMIT), 10) || DEFAULT_LIMIT, 1), MAX_LIMIT);
+    const beforeParam = searchParams.get('before');
+
+    if (beforeParam !== null && !/^\d+$/.test(beforeParam)) {
+      return NextResponse.json({ error: 'Invalid before height' }, { status: 400 });
+    }
+
+    const db = await getDb();
+    const rows = await db.all<BlockFoundRow>(`
+      SELECT * FROM blocks_found
+      WHERE block_height < ?
+      ORDER BY block_height DESC
+      LIMIT ?
+    `, [beforeParam !== null ? parseInt(beforeParam, 10) : Number.MAX_SAFE_INTEGER, limit]);
+
+    return NextResponse.json(rows.map(toBlockFound), {
+      headers: getRateLimitHeaders(rateLimitResult),
+    });
+  } catch (error) {
+    log.error('Error fetching found blocks', { error });
+    return NextResponse.json(
+      { error: 'Failed to fetch found blocks' },
+      { status: 500 }
+    );
+  }
+}
@@ -0,0 +1,82 @@
+import { formatAddress } from '@/app/utils/formatters';
+
+export const DEFAULT_LIMIT = 50;
+export const MAX_LIMIT = 200;
+
+export interface BlockFoundRow {
+  block_height: number;
+  block_hash: string;
+  reward: number | null;
+  finder_username: string | null;
+  winner_diff: number | null;
+  found_at: number | null;
+  found_at_source: 'stratum' | 'block' | null;
+  time_to_find: number | null;
+  template_pool: string | null;
+  template_job_id: string | null;
+  template_job_count: number | null;
+  template_first_job_at: number | null;
+  template_coinbase_value: number | null;
+  template_fees: number | null;
+  template_output_count: number | null;
+  template_merkle_branch_count: number | null;
+  template_version: string | null;
+  template_n_time: string | null;
+}
+
+export interface BlockTemplateDetails {
+  pool: string;
+  jobId: string | null;
+  jobCount: number | null;
+  firstJobAt: number | null;
+  coinbaseValue: number | null;
+  fees: number | null;
+  outputCount: number | null;
+  merkleBranchCount: number | null;
+  version: string | null;
+  nTime: string | null;
+}
+
+export interface BlockFound {
+  blockHeight: number;
+  blockHash: string;
+  reward: number | null;
+  finder: string | null;
+  winnerDiff: number | null;
+  foundAt: number | null;
+  foundAtSource: 'stratum' | 'block' | null;
+  // Seconds since the previous block the pool found
+  timeToFind: number | null;
+  // Last template the pool handed out before finding the block; null if not observed
+  template: BlockTemplateDetails | null;
+}
+
+/**
+ * PRIVACY: finder addresses are truncated
+ */
+export function toBlockFound(row: BlockFoundRow): BlockFound {
+  return {
+    blockHeight: row.block_height,
+    blockHash: row.block_hash,
+    reward: row.reward,
+    finder: row.finder_username ? formatAddress(row.finder_username) : null,
+    winnerDiff: row.winner_diff,
+    foundAt: row.found_at,
+    foundAtSource: row.found_at_source,
+    timeToFind: row.time_to_find,
+    template: row.template_pool
+      ? {
+          pool: row.template_pool,
+          jobId: row.template_job_id,
+          jobCount: row.template_job_count,
+          firstJobAt: row.template_first_job_at,
+          coinbaseValue: row.template_coinbase_value,
+          fees: row.template_fees,
+          outputCount: row.template_output_count,
+          merkleBranchCount: row.template_merkle_branch_count,
+          version: row.template_version,
+          nTime: row.template_n_time,
+        }
+      : null,
+  };
+}
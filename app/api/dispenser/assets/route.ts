@@ -1,11 +1,14 @@
 import { NextResponse } from 'next/server';
 import { fetch } from '@/lib/http-client';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/dispenser/assets' });
 
 export async function GET() {
   try {
     const apiUrl = process.env.DISPENSER_API_URL;
     if (!apiUrl) {
-      console.error("Error fetching assets: No DISPENSER_API_URL defined in env");
+      log.error('Error fetching assets: No DISPENSER_API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch assets" }, { status: 500 });
     }
 
@@ -29,7 +32,7 @@ export async function GET() {
     const data = await response.json();
     return NextResponse.json(data);
   } catch (error) {
-    console.error("Error fetching assets:", error);
+    log.error('Error fetching assets', { error });
     return NextResponse.json({ error: "Failed to fetch assets" }, { status: 500 });
   }
 }
@@ -1,11 +1,14 @@
 import { NextResponse } from 'next/server';
 import { fetch } from '@/lib/http-client';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/dispenser/claim' });
 
 export async function POST(request: Request) {
   try {
     const apiUrl = process.env.DISPENSER_API_URL;
     if (!apiUrl) {
-      console.error("Error posting claim: No DISPENSER_API_URL defined in env");
+      log.error('Error posting claim: No DISPENSER_API_URL defined in env');
       return NextResponse.json({ error: "Failed to submit claim" }, { status: 500 });
     }
 
@@ -32,7 +35,7 @@ export async function POST(request: Request) {
 
     return NextResponse.json(data);
   } catch (error) {
-    console.error("Error posting claim:", error);
+    log.error('Error posting claim', { error });
     return NextResponse.json({ error: "Failed to submit claim" }, { status: 500 });
   }
 }
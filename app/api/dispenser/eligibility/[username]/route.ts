@@ -1,5 +1,8 @@
 import { NextResponse } from 'next/server';
 import { fetch } from '@/lib/http-client';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/dispenser/eligibility/[username]' });
 
 export async function GET(
   _request: Request,
@@ -9,7 +12,7 @@ export async function GET(
     const { username } = await params;
     const apiUrl = process.env.DISPENSER_API_URL;
     if (!apiUrl) {
-      console.error("Error fetching user eligibility: No DISPENSER_API_URL defined in env");
+      log.error('Error fetching user eligibility: No DISPENSER_API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch user eligibility" }, { status: 500 });
     }
 
@@ -39,7 +42,7 @@ export async function GET(
     const data = await response.json();
     return NextResponse.json(data);
   } catch (error) {
-    console.error("Error fetching user eligibility:", error);
+    log.error('Error fetching user eligibility', { error });
     return NextResponse.json({ error: "Failed to fetch user eligibility" }, { status: 500 });
   }
 }
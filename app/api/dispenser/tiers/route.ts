@@ -1,11 +1,14 @@
 import { NextResponse } from 'next/server';
 import { fetch } from '@/lib/http-client';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/dispenser/tiers' });
 
 export async function GET() {
   try {
     const apiUrl = process.env.DISPENSER_API_URL;
     if (!apiUrl) {
-      console.error("Error fetching tiers: No DISPENSER_API_URL defined in env");
+      log.error('Error fetching tiers: No DISPENSER_API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch tiers" }, { status: 500 });
     }
 
@@ -29,7 +32,7 @@ export async function GET() {
     const data = await response.json();
     return NextResponse.json(data);
   } catch (error) {
-    console.error("Error fetching tiers:", error);
+    log.error('Error fetching tiers', { error });
     return NextResponse.json({ error: "Failed to fetch tiers" }, { status: 500 });
   }
 }
@@ -0,0 +1,34 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { getJobsHealth } from '@/lib/job-supervisor';
+import { getLease } from '@/lib/leader-lease';
+import { NO_CACHE_HEADERS } from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/health/jobs' });
+
+export const dynamic = 'force-dynamic';
+
+/**
+ * Status of every job the jobs process runs: schedule, last run, duration,
+ * last error, and overlap and dependency skips, plus which process holds the
+ * leader lease.
+ *
+ * Responds 503 when a job is hung or stale (or the jobs process has never
+ * registered any), so it can be used directly as a liveness probe.
+ */
+export async function GET() {
+  try {
+    const db = await getDb();
+    const { healthy, jobs } = await getJobsHealth(db);
+    const leader = await getLease(db);
+
+    return NextResponse.json(
+      { healthy, leader, jobs },
+      { status: healthy ? 200 : 503, headers: NO_CACHE_HEADERS }
+    );
+  } catch (error) {
+    log.error('Error fetching job health', { error });
+    return NextResponse.json({ error: 'Failed to fetch job health' }, { status: 500 });
+  }
+}
@@ -0,0 +1,10 @@
+export type { JobHealth, JobHealthStatus } from '@/lib/job-supervisor';
+export type { LeaderLease } from '@/lib/leader-lease';
+export type { CircuitState, CircuitStatus } from '@/lib/circuit-breaker';
+
+// Health checks must always reflect the current state
+export const NO_CACHE_HEADERS = {
+  'Cache-Control': 'no-cache, no-store, must-revalidate',
+  'Pragma': 'no-cache',
+  'Expires': '0',
+};
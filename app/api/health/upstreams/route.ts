@@ -0,0 +1,19 @@
+import { NextResponse } from 'next/server';
+import { getCircuitStates } from '@/lib/circuit-breaker';
+import { NO_CACHE_HEADERS } from '../types';
+
+export const dynamic = 'force-dynamic';
+
+/**
+ * Circuit breaker state of every upstream as seen by this web server (router,
+ * dispenser and lightning calls made from API routes). The jobs process
+ * reports its own breakers on its METRICS_PORT /health.
+ *
+ * Always 200: an open circuit means an upstream is down, not this server.
+ */
+export async function GET() {
+  const upstreams = getCircuitStates();
+  const open = upstreams.filter(upstream => upstream.state !== 'closed').map(upstream => upstream.upstream);
+
+  return NextResponse.json({ open, upstreams }, { headers: NO_CACHE_HEADERS });
+}
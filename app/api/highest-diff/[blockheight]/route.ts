@@ -44,16 +44,16 @@ export async function GET(
       );
     }
 
-    const db = getDb();
+    const db = await getDb();
 
     // Get the block data first to check if it exists
-    const blockData = db.prepare(`
+    const blockData = await db.get<{ block_height: number; block_timestamp: number }>(`
       SELECT 
         block_height,
         block_timestamp
       FROM block_highest_diff
       WHERE block_height = ?
-    `).get(blockHeight) as { block_height: number; block_timestamp: number } | undefined;
+    `, [blockHeight]);
 
     if (!blockData) {
       return NextResponse.json(
@@ -64,7 +64,7 @@ export async function GET(
 
     // Get all user diffs for this block, filtered to only include public users
     // Users not in monitored_users are treated as public by default
-    const userDiffs = db.prepare(`
+    const userDiffs = await db.all<UserBlockDiffRow>(`
       SELECT 
         u.address,
         u.difficulty
@@ -73,7 +73,7 @@ export async function GET(
       WHERE u.block_height = ? AND (m.is_public = 1 OR m.address IS NULL)
       ORDER BY u.difficulty DESC
       LIMIT ?
-    `).all(blockHeight, MAX_USERS_PER_BLOCK) as UserBlockDiffRow[];
+    `, [blockHeight, MAX_USERS_PER_BLOCK]);
 
     // The top diff is now the highest public user, not necessarily the original winner
     const topPublicUser = userDiffs.length > 0 ? userDiffs[0] : null;
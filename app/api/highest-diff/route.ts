@@ -44,14 +44,14 @@ export async function GET(request: Request) {
       );
     }
 
-    const db = getDb();
+    const db = await getDb();
     const claimedSet = getClaimedAddresses();
 
     if (type === 'leaderboard') {
       // Get leaderboard of users by watermark count (how many times they had the top diff)
       // Only include users who are public (or not in monitored_users, which defaults to public)
       // Note: DB column is winner_address, aliased to top_diff_address for API consistency
-      const topUsers = db.prepare(`
+      const topUsers = await db.all<UserDiffCountRow>(`
         SELECT 
           b.winner_address as address,
           COUNT(*) as watermark_count,
@@ -63,7 +63,7 @@ export async function GET(request: Request) {
         GROUP BY b.winner_address
         ORDER BY watermark_count DESC
         LIMIT ?
-      `).all(limit) as UserDiffCountRow[];
+      `, [limit]);
 
       return NextResponse.json(
         topUsers.map(u => ({
@@ -86,9 +86,9 @@ export async function GET(request: Request) {
 
     if (address && type === 'user-diffs') {
       // Check if this user is public before returning their diffs
-      const userPublicCheck = db.prepare(`
+      const userPublicCheck = await db.get<{ is_public: number }>(`
         SELECT is_public FROM monitored_users WHERE address = ?
-      `).get(address) as { is_public: number } | undefined;
+      `, [address]);
       
       // If user exists in monitored_users and is not public, return empty
       if (userPublicCheck && !userPublicCheck.is_public) {
@@ -97,7 +97,7 @@ export async function GET(request: Request) {
 
       // Get this user's best diffs across all blocks (not just watermarks)
       // Join with block_highest_diff to get the block timestamp
-      const userDiffs = db.prepare(`
+      const userDiffs = await db.all<UserDiffWithTimestampRow>(`
         SELECT 
           u.block_height,
           u.address,
@@ -108,7 +108,7 @@ export async function GET(request: Request) {
         WHERE u.address = ?
         ORDER BY u.block_height DESC
         LIMIT ?
-      `).all(address, limit) as UserDiffWithTimestampRow[];
+      `, [address, limit]);
 
       return NextResponse.json(
         userDiffs.map(d => ({
@@ -124,9 +124,9 @@ export async function GET(request: Request) {
 
     if (address) {
       // Check if this user is public before returning their watermarks
-      const userPublicCheck = db.prepare(`
+      const userPublicCheck = await db.get<{ is_public: number }>(`
         SELECT is_public FROM monitored_users WHERE address = ?
-      `).get(address) as { is_public: number } | undefined;
+      `, [address]);
       
       // If user exists in monitored_users and is not public, return empty
       if (userPublicCheck && !userPublicCheck.is_public) {
@@ -135,7 +135,7 @@ export async function GET(request: Request) {
 
       // Get blocks where this user had the top diff (watermarks only)
       // Note: DB column is winner_address, aliased for API consistency
-      const userBlocks = db.prepare(`
+      const userBlocks = await db.all<BlockHighestDiffRow>(`
         SELECT 
           block_height,
           winner_address as top_diff_address,
@@ -145,7 +145,7 @@ export async function GET(request: Request) {
         WHERE winner_address = ?
         ORDER BY block_height DESC
         LIMIT ?
-      `).all(address, limit) as BlockHighestDiffRow[];
+      `, [address, limit]);
 
       return NextResponse.json(
         userBlocks.map(b => ({
@@ -162,26 +162,24 @@ export async function GET(request: Request) {
     // Default: recent block watermarks (highest diffs per block)
     // Show all blocks, but display the highest PUBLIC user's diff (not necessarily the original winner)
     // Step 1: Get recent blocks (fast, indexed query)
-    const recentBlocks = db.prepare(`
+    const recentBlocks = await db.all<{ block_height: number; block_timestamp: number }>(`
       SELECT block_height, block_timestamp
       FROM block_highest_diff
       ORDER BY block_height DESC
       LIMIT ?
-    `).all(limit) as { block_height: number; block_timestamp: number }[];
+    `, [limit]);
 
     // Step 2: For each block, get the top public user (small indexed queries)
     // This is O(limit) simple queries instead of one massive window function query
-    const getTopPublicUser = db.prepare(`
-      SELECT u.address, u.difficulty
-      FROM user_block_diff u
-      LEFT JOIN monitored_users m ON u.address = m.address
-      WHERE u.block_height = ? AND (m.is_public = 1 OR m.address IS NULL)
-      ORDER BY u.difficulty DESC
-      LIMIT 1
-    `);
-
-    const results = recentBlocks.map(block => {
-      const topUser = getTopPublicUser.get(block.block_height) as { address: string; difficulty: number } | undefined;
+    const results = await Promise.all(recentBlocks.map(async block => {
+      const topUser = await db.get<{ address: string; difficulty: number }>(`
+        SELECT u.address, u.difficulty
+        FROM user_block_diff u
+        LEFT JOIN monitored_users m ON u.address = m.address
+        WHERE u.block_height = ? AND (m.is_public = 1 OR m.address IS NULL)
+        ORDER BY u.difficulty DESC
+        LIMIT 1
+      `, [block.block_height]);
       return {
         block_height: block.block_height,
         top_diff_address: topUser ? formatAddress(topUser.address) : null,
@@ -189,7 +187,7 @@ export async function GET(request: Request) {
         difficulty: topUser?.difficulty ?? null,
         block_timestamp: block.block_timestamp,
       };
-    });
+    }));
 
     return NextResponse.json(results, { headers: getRateLimitHeaders(rateLimitResult) });
 
@@ -9,6 +9,8 @@
  * is simply a watermark/high-water mark for that block.
  */
 
+import { createLogger } from '@/lib/logger';
+
 // Constants
 export const MAX_LIMIT = 500;
 
@@ -93,13 +95,11 @@ export interface BlockLeaderboardResponse {
   user_count: number;
 }
 
+const log = createLogger({ module: 'highest-diff' });
+
 /**
  * Structured error logging helper
  */
 export function logError(context: string, error: unknown): void {
-  console.error(`[${context}]`, {
-    error: error instanceof Error ? error.message : String(error),
-    stack: error instanceof Error ? error.stack : undefined,
-    timestamp: new Date().toISOString(),
-  });
+  log.error('Request failed', { handler: context, error });
 }
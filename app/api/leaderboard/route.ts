@@ -1,8 +1,11 @@
 import { NextResponse } from 'next/server';
-import { getDb } from '@/lib/db';
+import { getDb, type Db } from '@/lib/db';
 import { formatAddress } from '@/app/utils/formatters';
 import { getClaimedAddresses } from '@/lib/dispenser-cache';
 import type { RoundParticipantRow } from '@/app/api/rounds/types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/leaderboard' });
 
 interface BaseUser {
   id: number;
@@ -42,8 +45,8 @@ function formatRoundParticipants(
   }));
 }
 
-function handleRoundQuery(
-  db: ReturnType<typeof getDb>,
+async function handleRoundQuery(
+  db: Db,
   type: string,
   blockHeight: number,
   limit: number,
@@ -51,7 +54,7 @@ function handleRoundQuery(
 ) {
   if (type === 'difficulty' || type === 'loyalty') {
     const isDiff = type === 'difficulty';
-    const rows = db.prepare(`
+    const rows = await db.all<RoundParticipantRow>(`
       SELECT rp.username, rp.top_diff, rp.blocks_participated
       FROM round_participants rp
       LEFT JOIN monitored_users m ON rp.username = m.address
@@ -59,12 +62,16 @@ function handleRoundQuery(
         AND ${isDiff ? 'rp.top_diff' : 'rp.blocks_participated'} > 0
       ORDER BY ${isDiff ? 'rp.top_diff' : 'rp.blocks_participated'} DESC
       LIMIT ?
-    `).all(blockHeight, limit) as RoundParticipantRow[];
+    `, [blockHeight, limit]);
     return formatRoundParticipants(rows, claimedSet);
   }
 
   // combined (default)
-  const rows = db.prepare(`
+  const rows = await db.all<RoundParticipantRow & {
+    diff_rank: number;
+    loyalty_rank: number;
+    combined_score: number;
+  }>(`
     WITH RankedParticipants AS (
       SELECT
         rp.username,
@@ -87,11 +94,7 @@ function handleRoundQuery(
     FROM RankedParticipants
     ORDER BY combined_score ASC
     LIMIT ?
-  `).all(blockHeight, limit) as (RoundParticipantRow & {
-    diff_rank: number;
-    loyalty_rank: number;
-    combined_score: number;
-  })[];
+  `, [blockHeight, limit]);
 
   return rows.map((row, index) => ({
     id: index + 1,
@@ -112,7 +115,7 @@ export async function GET(request: Request) {
     const limit = Math.min(Math.max(parseInt(searchParams.get('limit') || '9', 10) || 9, 1), 999);
     const round = searchParams.get('round');
 
-    const db = getDb();
+    const db = await getDb();
     const claimedSet = getClaimedAddresses();
 
     // Round-scoped leaderboard queries
@@ -121,7 +124,7 @@ export async function GET(request: Request) {
       if (round !== 'current' && isNaN(blockHeight)) {
         return NextResponse.json({ error: 'Invalid round parameter' }, { status: 400 });
       }
-      const users = handleRoundQuery(db, type, blockHeight, limit, claimedSet);
+      const users = await handleRoundQuery(db, type, blockHeight, limit, claimedSet);
       return NextResponse.json(users);
     }
 
@@ -130,7 +133,7 @@ export async function GET(request: Request) {
 
     switch (type) {
       case 'difficulty':
-        users = db.prepare(`
+        users = (await db.all<DifficultyUser>(`
           SELECT
             id,
             address,
@@ -139,15 +142,15 @@ export async function GET(request: Request) {
           WHERE is_active = 1 AND is_public = 1 AND bestever > 0
           ORDER BY bestever DESC
           LIMIT ?
-        `).all(limit).map((user: unknown) => ({
-          ...(user as DifficultyUser),
-          claimed: claimedSet.has((user as DifficultyUser).address),
-          address: formatAddress((user as DifficultyUser).address),
+        `, [limit])).map(user => ({
+          ...user,
+          claimed: claimedSet.has(user.address),
+          address: formatAddress(user.address),
         }));
         break;
 
       case 'loyalty':
-        users = db.prepare(`
+        users = (await db.all<LoyaltyUser>(`
           SELECT
             id,
             address,
@@ -156,16 +159,16 @@ export async function GET(request: Request) {
           WHERE is_active = 1 AND is_public = 1 AND total_blocks > 0
           ORDER BY total_blocks DESC
           LIMIT ?
-        `).all(limit).map((user: unknown) => ({
-          ...(user as LoyaltyUser),
-          claimed: claimedSet.has((user as LoyaltyUser).address),
-          address: formatAddress((user as LoyaltyUser).address),
+        `, [limit])).map(user => ({
+          ...user,
+          claimed: claimedSet.has(user.address),
+          address: formatAddress(user.address),
         }));
         break;
 
       case 'combined':
       default:
-        users = db.prepare(`
+        users = (await db.all<CombinedUser>(`
           WITH RankedUsers AS (
             SELECT
               id,
@@ -175,7 +178,7 @@ export async function GET(request: Request) {
               RANK() OVER (ORDER BY bestever DESC) as diff_rank,
               RANK() OVER (ORDER BY total_blocks DESC) as loyalty_rank
             FROM monitored_users
-            WHERE is_active = 1 AND is_public = 1 AND max(total_blocks, bestever) > 0
+            WHERE is_active = 1 AND is_public = 1 AND (total_blocks > 0 OR bestever > 0)
           )
           SELECT
             id,
@@ -188,10 +191,10 @@ export async function GET(request: Request) {
           FROM RankedUsers
           ORDER BY combined_score ASC
           LIMIT ?
-        `).all(limit).map((user: unknown) => ({
-          ...(user as CombinedUser),
-          claimed: claimedSet.has((user as CombinedUser).address),
-          address: formatAddress((user as CombinedUser).address),
+        `, [limit])).map(user => ({
+          ...user,
+          claimed: claimedSet.has(user.address),
+          address: formatAddress(user.address),
         }));
         break;
     }
@@ -199,7 +202,7 @@ export async function GET(request: Request) {
     return NextResponse.json(users);
 
   } catch (error) {
-    console.error('Error fetching leaderboard:', error);
+    log.error('Error fetching leaderboard', { error });
     return NextResponse.json(
       { error: 'Failed to fetch leaderboard data' },
       { status: 500 }
@@ -0,0 +1,26 @@
+import { timingSafeEqual } from 'crypto';
+import { NextResponse } from 'next/server';
+
+/**
+ * Bearer token check for admin API routes
+ *
+ * Admin routes are disabled (404) unless ADMIN_API_TOKEN is set, and answer
+ * 401 to requests without `Authorization: Bearer <ADMIN_API_TOKEN>`.
+ * Returns the response to send, or null when the request may proceed.
+ */
+export function requireAdmin(request: Request): NextResponse | null {
+  const token = process.env.ADMIN_API_TOKEN;
+  if (!token) {
+    return NextResponse.json({ error: 'Not found' }, { status: 404 });
+  }
+
+  const header = request.headers.get('authorization') ?? '';
+  const expected = Buffer.from(`Bearer ${token}`);
+  const received = Buffer.from(header);
+
+  if (received.length !== expected.length || !timingSafeEqual(received, expected)) {
+    return NextResponse.json({ error: 'Unauthorized' }, { status: 401 });
+  }
+
+  return null;
+}
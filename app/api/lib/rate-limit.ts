@@ -1,3 +1,5 @@
+import { recordRateLimitRejection, routeLabel } from '@/lib/metrics';
+
 /**
  * Simple in-memory rate limiter for API routes
  * 
@@ -113,6 +115,9 @@ export function checkRateLimit(
   entry.count++;
   
   if (entry.count > maxRequests) {
+    recordRateLimitRejection(
+      typeof identifierOrRequest === 'string' ? 'unknown' : routeLabel(identifierOrRequest.url)
+    );
     return {
       success: false,
       limit: maxRequests,
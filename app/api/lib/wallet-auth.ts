@@ -0,0 +1,66 @@
+import { createHash } from 'crypto';
+import { NextResponse } from 'next/server';
+import type { Db } from '@/lib/db';
+import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
+
+/**
+ * Address ownership check for routes that change per-address settings
+ *
+ * The Lightning login (useWallet's nonce signing flow) proves control of an
+ * address and returns a token. The login route records which address each
+ * token was issued for in wallet_sessions; requireWalletOwner looks the
+ * `X-Lightning-Token` header up there and asks the Lightning API whether the
+ * token is still valid.
+ */
+
+const SESSION_MAX_AGE_SECONDS = 30 * 24 * 60 * 60;
+
+function hashToken(token: string): string {
+  return createHash('sha256').update(token).digest('hex');
+}
+
+/** Remember that a freshly issued Lightning token belongs to `address` */
+export async function recordWalletSession(db: Db, token: string, address: string): Promise<void> {
+  const now = Math.floor(Date.now() / 1000);
+  await db.run(`
+    INSERT INTO wallet_sessions (token_hash, address, created_at)
+    VALUES (?, ?, ?)
+    ON CONFLICT(token_hash) DO UPDATE SET address = excluded.address, created_at = excluded.created_at
+  `, [hashToken(token), address, now]);
+  await db.run('DELETE FROM wallet_sessions WHERE created_at < ?', [now - SESSION_MAX_AGE_SECONDS]);
+}
+
+/**
+ * Returns the response to send when the request's Lightning token doesn't
+ * belong to `address`, or null when the request may proceed.
+ */
+export async function requireWalletOwner(db: Db, request: Request, address: string): Promise<NextResponse | null> {
+  const token = request.headers.get('X-Lightning-Token');
+  if (!token) {
+    return NextResponse.json({ error: 'Lightning token required' }, { status: 401 });
+  }
+
+  const session = await db.get<{ address: string }>(
+    'SELECT address FROM wallet_sessions WHERE token_hash = ? AND created_at >= ?',
+    [hashToken(token), Math.floor(Date.now() / 1000) - SESSION_MAX_AGE_SECONDS]
+  );
+  if (!session) {
+    return NextResponse.json({ error: 'Lightning token expired' }, { status: 401 });
+  }
+  if (session.address !== address) {
+    return NextResponse.json({ error: 'Not authorized for this address' }, { status: 403 });
+  }
+
+  const response = await fetchWithTimeout(`${process.env.LIGHTNING_API_URL}/wallet_user`, {
+    headers: { Authorization: `Bearer ${token}` },
+  });
+  await response.body?.cancel();
+  if (response.status === 401) {
+    return NextResponse.json({ error: 'Lightning token expired' }, { status: 401 });
+  }
+  if (!response.ok) {
+    return NextResponse.json({ error: 'Failed to verify Lightning token' }, { status: 502 });
+  }
+
+  return null;
+}
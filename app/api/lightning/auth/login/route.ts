@@ -1,5 +1,10 @@
 import { NextResponse } from 'next/server';
 import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
+import { recordWalletSession } from '@/app/api/lib/wallet-auth';
+import { getDb } from '@/lib/db';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/lightning/auth/login' });
 
 export async function POST(request: Request) {
   try {
@@ -7,7 +12,7 @@ export async function POST(request: Request) {
     const identifier = process.env.LIGHTNING_API_ID;
 
     if (!identifier) {
-      console.error('LIGHTNING_API_ID not configured');
+      log.error('LIGHTNING_API_ID not configured');
       return NextResponse.json(
         { error: 'Lightning authentication not configured' },
         { status: 500 }
@@ -90,9 +95,19 @@ export async function POST(request: Request) {
     }
 
     const data = await response.json();
+
+    // The token now proves ownership of this address to our own routes
+    if (typeof data.token === 'string') {
+      try {
+        await recordWalletSession(await getDb(), data.token, address);
+      } catch (error) {
+        log.error('Error recording wallet session', { error });
+      }
+    }
+
     return NextResponse.json({ token: data.token });
   } catch (error) {
-    console.error('Error during authentication:', error instanceof Error ? error.message : 'Unknown error');
+    log.error('Error during authentication', { error });
     return NextResponse.json(
       { error: 'Failed to authenticate' },
       { status: 500 }
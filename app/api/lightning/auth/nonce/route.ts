@@ -1,5 +1,8 @@
 import { NextResponse } from 'next/server';
 import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/lightning/auth/nonce' });
 
 export async function POST(request: Request) {
   try {
@@ -7,7 +10,7 @@ export async function POST(request: Request) {
     const identifier = process.env.LIGHTNING_API_ID;
 
     if (!identifier) {
-      console.error('LIGHTNING_API_ID not configured');
+      log.error('LIGHTNING_API_ID not configured');
       return NextResponse.json(
         { error: 'Lightning authentication not configured' },
         { status: 500 }
@@ -61,7 +64,7 @@ export async function POST(request: Request) {
     const data = await response.json();
     return NextResponse.json({ nonce: data.nonce });
   } catch (error) {
-    console.error('Error requesting nonce:', error instanceof Error ? error.message : 'Unknown error');
+    log.error('Error requesting nonce', { error });
     return NextResponse.json(
       { error: 'Failed to request nonce' },
       { status: 500 }
@@ -1,6 +1,9 @@
 import { NextResponse } from 'next/server';
 import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/lightning/withdraw/quote' });
 
 export async function POST(request: Request) {
   try {
@@ -67,7 +70,7 @@ export async function POST(request: Request) {
     const data = await response.json();
     return NextResponse.json(data);
   } catch (error) {
-    console.error('Error getting withdraw quote:', error instanceof Error ? error.message : 'Unknown error');
+    log.error('Error getting withdraw quote', { error });
     return NextResponse.json(
       { error: 'Failed to get withdraw quote' },
       { status: 500 }
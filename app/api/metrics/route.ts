@@ -0,0 +1,32 @@
+import { NextResponse } from 'next/server';
+import { collectMetrics, isMetricsRequestAuthorized } from '@/lib/metrics-exporter';
+import { METRICS_CONTENT_TYPE } from '@/lib/metrics';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/metrics' });
+
+export const dynamic = 'force-dynamic';
+
+/**
+ * Prometheus scrape endpoint for the web app: pool and stratum gauges from
+ * the database plus this process's HTTP client, aggregator cache and rate
+ * limiter metrics. Needs `Authorization: Bearer <METRICS_TOKEN>` when set.
+ */
+export async function GET(request: Request) {
+  if (!isMetricsRequestAuthorized(request.headers.get('authorization'))) {
+    return NextResponse.json({ error: 'Unauthorized' }, { status: 401 });
+  }
+
+  try {
+    const body = await collectMetrics();
+    return new Response(body, {
+      headers: {
+        'Content-Type': METRICS_CONTENT_TYPE,
+        'Cache-Control': 'no-store',
+      },
+    });
+  } catch (error) {
+    log.error('Error collecting metrics', { error });
+    return NextResponse.json({ error: 'Failed to collect metrics' }, { status: 500 });
+  }
+}
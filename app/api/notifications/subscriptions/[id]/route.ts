@@ -0,0 +1,45 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { deleteSubscription } from '@/lib/notifications';
+import { requireWalletOwner } from '@/app/api/lib/wallet-auth';
+import { isValidBitcoinAddress } from '@/app/utils/validators';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/notifications/subscriptions/[id]' });
+
+/**
+ * Remove one of an address's subscriptions (`?address=` names the owner)
+ */
+export async function DELETE(
+  request: Request,
+  { params }: { params: Promise<{ id: string }> }
+) {
+  try {
+    const { id } = await params;
+    const subscriptionId = Number.parseInt(id, 10);
+    if (!Number.isInteger(subscriptionId) || subscriptionId <= 0) {
+      return NextResponse.json({ error: 'Invalid subscription id' }, { status: 400 });
+    }
+
+    const address = new URL(request.url).searchParams.get('address');
+    if (!address || !isValidBitcoinAddress(address)) {
+      return NextResponse.json({ error: 'Invalid Bitcoin address' }, { status: 400 });
+    }
+
+    const db = await getDb();
+    const denied = await requireWalletOwner(db, request, address);
+    if (denied) return denied;
+
+    if (!(await deleteSubscription(db, address, subscriptionId))) {
+      return NextResponse.json({ error: 'Subscription not found' }, { status: 404 });
+    }
+
+    return new NextResponse(null, { status: 204 });
+  } catch (error) {
+    log.error('Error deleting notification subscription', { error });
+    return NextResponse.json(
+      { error: 'Failed to delete notification subscription' },
+      { status: 500 }
+    );
+  }
+}
@@ -0,0 +1,119 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import {
+  NOTIFICATION_CHANNELS,
+  NOTIFICATION_EVENTS,
+  SubscriptionError,
+  getConfiguredChannels,
+  listSubscriptions,
+  saveSubscription,
+  type NotificationChannel,
+  type NotificationEvent,
+} from '@/lib/notifications';
+import { requireWalletOwner } from '@/app/api/lib/wallet-auth';
+import { isValidBitcoinAddress } from '@/app/utils/validators';
+import {
+  NO_STORE_HEADERS,
+  toSubscriptionEntry,
+  type CreateSubscriptionRequest,
+  type CreatedSubscription,
+  type SubscriptionsResponse,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/notifications/subscriptions' });
+
+/**
+ * An address's notification subscriptions, for its owner
+ */
+export async function GET(request: Request) {
+  try {
+    const address = new URL(request.url).searchParams.get('address');
+    if (!address || !isValidBitcoinAddress(address)) {
+      return NextResponse.json({ error: 'Invalid Bitcoin address' }, { status: 400 });
+    }
+
+    const db = await getDb();
+    const denied = await requireWalletOwner(db, request, address);
+    if (denied) return denied;
+
+    const subscriptions = await listSubscriptions(db, address);
+    const response: SubscriptionsResponse = {
+      channels: getConfiguredChannels(),
+      events: [...NOTIFICATION_EVENTS],
+      subscriptions: subscriptions.map(toSubscriptionEntry),
+    };
+
+    return NextResponse.json(response, { headers: NO_STORE_HEADERS });
+  } catch (error) {
+    log.error('Error fetching notification subscriptions', { error });
+    return NextResponse.json(
+      { error: 'Failed to fetch notification subscriptions' },
+      { status: 500, headers: NO_STORE_HEADERS }
+    );
+  }
+}
+
+/**
+ * Subscribe a channel target to some of an address's events. Posting an
+ * existing channel and target replaces its events.
+ */
+export async function POST(request: Request) {
+  try {
+    let payload: Partial<CreateSubscriptionRequest>;
+    try {
+      payload = await request.json();
+    } catch {
+      return NextResponse.json({ error: 'Invalid JSON body' }, { status: 400 });
+    }
+
+    const { address, channel, target, events } = payload;
+    if (!address || typeof address !== 'string' || !isValidBitcoinAddress(address)) {
+      return NextResponse.json({ error: 'Invalid Bitcoin address' }, { status: 400 });
+    }
+    if (!channel || !(NOTIFICATION_CHANNELS as readonly string[]).includes(channel)) {
+      return NextResponse.json(
+        { error: `channel must be one of: ${NOTIFICATION_CHANNELS.join(', ')}` },
+        { status: 400 }
+      );
+    }
+    if (typeof target !== 'string' || target.length === 0 || target.length > 500) {
+      return NextResponse.json({ error: 'Invalid target' }, { status: 400 });
+    }
+    if (!Array.isArray(events) || events.some(event => !(NOTIFICATION_EVENTS as readonly string[]).includes(event))) {
+      return NextResponse.json(
+        { error: `events must be a list of: ${NOTIFICATION_EVENTS.join(', ')}` },
+        { status: 400 }
+      );
+    }
+
+    const db = await getDb();
+    const denied = await requireWalletOwner(db, request, address);
+    if (denied) return denied;
+
+    try {
+      const subscription = await saveSubscription(db, {
+        address,
+        channel: channel as NotificationChannel,
+        target,
+        events: events as NotificationEvent[],
+      });
+      const response: CreatedSubscription = {
+        ...toSubscriptionEntry(subscription),
+        secret: subscription.secret,
+      };
+      return NextResponse.json(response, { status: 201, headers: NO_STORE_HEADERS });
+    } catch (error) {
+      if (error instanceof SubscriptionError) {
+        return NextResponse.json({ error: error.message }, { status: 400 });
+      }
+      throw error;
+    }
+  } catch (error) {
+    log.error('Error saving notification subscription', { error });
+    return NextResponse.json(
+      { error: 'Failed to save notification subscription' },
+      { status: 500 }
+    );
+  }
+}
@@ -0,0 +1,45 @@
+import type {
+  NotificationChannel,
+  NotificationEvent,
+  NotificationSubscription,
+} from '@/lib/notifications/types';
+
+export const NO_STORE_HEADERS = {
+  'Cache-Control': 'no-store',
+};
+
+/** A subscription as shown to its owner; webhook secrets are only returned on creation */
+export interface SubscriptionEntry {
+  id: number;
+  channel: NotificationChannel;
+  target: string;
+  events: NotificationEvent[];
+}
+
+export interface SubscriptionsResponse {
+  /** Channels this server can deliver on */
+  channels: NotificationChannel[];
+  events: NotificationEvent[];
+  subscriptions: SubscriptionEntry[];
+}
+
+export interface CreatedSubscription extends SubscriptionEntry {
+  /** Webhook signing secret, shown once */
+  secret: string | null;
+}
+
+export interface CreateSubscriptionRequest {
+  address: string;
+  channel: NotificationChannel;
+  target: string;
+  events: NotificationEvent[];
+}
+
+export function toSubscriptionEntry(subscription: NotificationSubscription): SubscriptionEntry {
+  return {
+    id: subscription.id,
+    channel: subscription.channel,
+    target: subscription.target,
+    events: subscription.events,
+  };
+}
@@ -1,9 +1,59 @@
 import { NextResponse } from 'next/server';
 import { getDb } from '../../../../lib/db';
-import { parseHashrate } from '../../../utils/formatters';
+import { RESOLUTION_SECONDS, type PoolStatsResolution } from '../../../../lib/pool-stats-rollups';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/pool-stats/historical' });
 
 export const dynamic = 'force-dynamic';
 
+interface IntervalConfig {
+  seconds: number;
+  // Minute-level pool_stats rows, or the coarsest rollup that divides the interval
+  source: 'raw' | PoolStatsResolution;
+  maxPeriodDays: number;
+  cacheDuration: number;
+}
+
+const INTERVALS: Record<string, IntervalConfig> = {
+  '1m': { seconds: 60, source: 'raw', maxPeriodDays: 2, cacheDuration: 60 },
+  '5m': { seconds: 5 * 60, source: '5m', maxPeriodDays: 10, cacheDuration: 300 },
+  '15m': { seconds: 15 * 60, source: '5m', maxPeriodDays: 30, cacheDuration: 900 },
+  '30m': { seconds: 30 * 60, source: '5m', maxPeriodDays: 30, cacheDuration: 300 },
+  '1h': { seconds: 60 * 60, source: '1h', maxPeriodDays: 365, cacheDuration: 3600 },
+  '6h': { seconds: 6 * 60 * 60, source: '1h', maxPeriodDays: 5 * 365, cacheDuration: 3600 },
+  '1d': { seconds: 24 * 60 * 60, source: '1d', maxPeriodDays: 10 * 365, cacheDuration: 3600 },
+};
+
+const DEFAULT_INTERVAL = '5m';
+
+const AGGREGATES = ['last', 'avg', 'max', 'min'] as const;
+type Aggregate = typeof AGGREGATES[number];
+
+const POINT_METRICS = ['users', 'workers', 'hashrate15m', 'hashrate1hr', 'hashrate6hr', 'hashrate1d', 'hashrate7d'] as const;
+
+/**
+ * SELECT list for one point per interval from a rollup table's ranked
+ * buckets. `last` and `avg` take idle/disconnected from the interval's
+ * newest bucket (position 1).
+ */
+function rollupSelect(aggregate: Aggregate): string {
+  const newest = (column: string) => `MAX(CASE WHEN position = 1 THEN ${column} END)`;
+  const metrics = POINT_METRICS.map(metric => {
+    switch (aggregate) {
+      case 'last': return `${newest(`${metric}_last`)} AS ${metric}`;
+      case 'avg': return `SUM(${metric}_avg * ${metric}_count) / NULLIF(SUM(${metric}_count), 0) AS ${metric}`;
+      case 'max': return `MAX(${metric}_max) AS ${metric}`;
+      case 'min': return `MIN(${metric}_min) AS ${metric}`;
+    }
+  });
+  const counts = aggregate === 'max' || aggregate === 'min'
+    ? [`${aggregate.toUpperCase()}(idle_last) AS idle`, `${aggregate.toUpperCase()}(disconnected_last) AS disconnected`]
+    : [`${newest('idle_last')} AS idle`, `${newest('disconnected_last')} AS disconnected`];
+
+  return ['MAX(last_timestamp) AS timestamp', ...metrics, ...counts].join(',\n          ');
+}
+
 function smoothAnomalies(data: HistoricalPoolStats[]): HistoricalPoolStats[] {
   if (data.length < 2) return data;
   
@@ -68,13 +118,13 @@ export async function GET(request: Request) {
     
     // Parse parameters with defaults
     const period = searchParams.get('period') || '24h';
-    const interval = searchParams.get('interval') || '5m';
+    const interval = searchParams.get('interval') || DEFAULT_INTERVAL;
     
     // Validate interval is positive for numeric intervals
-    const intervalMatch = interval.match(/^(-?\d+)([mh])$/);
+    const intervalMatch = interval.match(/^(-?\d+)([mhd])$/);
     if (!intervalMatch || parseInt(intervalMatch[1], 10) <= 0) {
       return new NextResponse(
-        JSON.stringify({ error: "Interval must be a positive value with unit (e.g., '5m', '1h')" }), 
+        JSON.stringify({ error: "Interval must be a positive value with unit (e.g., '5m', '1h', '1d')" }), 
         { 
           status: 400,
           headers: {
@@ -84,27 +134,19 @@ export async function GET(request: Request) {
         }
       );
     }
+
+    // Unsupported intervals fall back to 5 minutes
+    const intervalConfig = INTERVALS[interval] ?? INTERVALS[DEFAULT_INTERVAL];
     
-    // Determine cache duration based on interval
-    let cacheDuration = 300; // Default 5 minutes
-    switch (interval) {
-      case '1m':
-        cacheDuration = 60; // 1 minute
-        break;
-      case '5m':
-        cacheDuration = 300; // 5 minutes
-        break;
-      case '15m':
-        cacheDuration = 900; // 15 minutes
-        break;
-      case '30m':
-        cacheDuration = 300; // 5 minutes
-        break;
-      case '1h':
-        cacheDuration = 3600; // 1 hour
-        break;
+    const aggregateParam = searchParams.get('aggregate') || 'last';
+    if (!(AGGREGATES as readonly string[]).includes(aggregateParam)) {
+      return new NextResponse(
+        JSON.stringify({ error: `Aggregate must be one of ${AGGREGATES.join(', ')}` }),
+        { status: 400, headers: { 'Content-Type': 'application/json', 'Cache-Control': 'no-store' } }
+      );
     }
-    
+    const aggregate = aggregateParam as Aggregate;
+
     // Calculate the time range based on the period
     const now = Math.floor(Date.now() / 1000);
     let startTime = now;
@@ -126,20 +168,10 @@ export async function GET(request: Request) {
       // Calculate total days for max period check
       const totalDays = unit === 'd' ? value : value / 24;
       
-      // Set max period based on the selected interval
-      let maxPeriodDays = 30; // Default max
-      
-      // Apply interval-specific limits
-      if (interval === '1m') {
-        maxPeriodDays = 2; // 2 days max for 1-minute intervals
-      } else if (interval === '5m') {
-        maxPeriodDays = 10; // 10 days max for 5-minute intervals
-      }
-      
-      if (totalDays > maxPeriodDays) {
+      if (totalDays > intervalConfig.maxPeriodDays) {
         return new NextResponse(
           JSON.stringify({ 
-            error: `For ${interval} interval, period cannot exceed ${maxPeriodDays} days` 
+            error: `For ${interval} interval, period cannot exceed ${intervalConfig.maxPeriodDays} days` 
           }),
           { status: 400, headers: { 'Content-Type': 'application/json', 'Cache-Control': 'no-store' } }
         );
@@ -153,83 +185,73 @@ export async function GET(request: Request) {
       startTime = now - 24 * 60 * 60;
     }
     
-    // Get the data from the database
-    const db = getDb();
-    
-    // Parse the interval
-    let intervalSeconds;
-    switch (interval) {
-      case '1m':
-        intervalSeconds = 60;
-        break;
-      case '5m':
-        intervalSeconds = 5 * 60;
-        break;
-      case '15m':
-        intervalSeconds = 15 * 60;
-        break;
-      case '30m':
-        intervalSeconds = 30 * 60;
-        break;
-      case '1h':
-        intervalSeconds = 60 * 60;
-        break;
-      default:
-        // Default to 5 minutes
-        intervalSeconds = 5 * 60;
-    }
-    
-    // Calculate intervals for the time range
-    const intervals = [];
-    for (let t = startTime; t < now; t += intervalSeconds) {
-      intervals.push({
-        start: t,
-        end: t + intervalSeconds
-      });
-    }
-    
-    // Query for each interval and aggregate
-    const results: HistoricalPoolStats[] = [];
-    
-    const stmt = db.prepare(`
-      SELECT
-        users,
-        workers,
-        idle,
-        disconnected,
-        hashrate15m,
-        hashrate1hr,
-        hashrate6hr,
-        hashrate1d,
-        hashrate7d,
-        timestamp
-      FROM pool_stats
-      WHERE timestamp >= ? AND timestamp <= ?
-      ORDER BY timestamp DESC
-      LIMIT 1
-    `);
+    // One query, grouped into intervals in SQL
+    const db = await getDb();
+    let rows: HistoricalPoolStats[];
 
-    for (const { start, end } of intervals) {
-      const clampedEnd = Math.min(end, now);
-      const row = stmt.get(start, clampedEnd) as HistoricalPoolStats | undefined;
-
-      if (row) {
-        if (row.users > 0 || row.workers > 0 || parseHashrate(row.hashrate15m) > 0 || parseHashrate(row.hashrate1d) > 0) {
-          results.push({
-            timestamp: row.timestamp,
-            users: row.users,
-            workers: row.workers,
-            idle: row.idle,
-            disconnected: row.disconnected,
-            hashrate15m: parseHashrate(row.hashrate15m),
-            hashrate1hr: parseHashrate(row.hashrate1hr),
-            hashrate6hr: parseHashrate(row.hashrate6hr),
-            hashrate1d: parseHashrate(row.hashrate1d),
-            hashrate7d: parseHashrate(row.hashrate7d)
-          });
-        }
-      }
+    if (intervalConfig.source === 'raw') {
+      // One sample per minute, so every aggregate is the interval's last sample
+      rows = await db.all<HistoricalPoolStats>(`
+        WITH ranked AS (
+          SELECT
+            *,
+            ROW_NUMBER() OVER (PARTITION BY (timestamp - ?) / ? ORDER BY timestamp DESC) AS position
+          FROM pool_stats
+          WHERE timestamp >= ? AND timestamp <= ?
+        )
+        SELECT
+          timestamp,
+          users,
+          workers,
+          idle,
+          disconnected,
+          hashrate15m_hs AS hashrate15m,
+          hashrate1hr_hs AS hashrate1hr,
+          hashrate6hr_hs AS hashrate6hr,
+          hashrate1d_hs AS hashrate1d,
+          hashrate7d_hs AS hashrate7d
+        FROM ranked
+        WHERE position = 1
+        ORDER BY timestamp ASC
+      `, [startTime, intervalConfig.seconds, startTime, now]);
+    } else {
+      // Buckets that started before the range but end inside it still count
+      const bucketSeconds = RESOLUTION_SECONDS[intervalConfig.source];
+      rows = await db.all<HistoricalPoolStats>(`
+        WITH ranked AS (
+          SELECT
+            *,
+            (last_timestamp - ?) / ? AS point,
+            ROW_NUMBER() OVER (PARTITION BY (last_timestamp - ?) / ? ORDER BY last_timestamp DESC) AS position
+          FROM pool_stats_${intervalConfig.source}
+          WHERE bucket_start > ? AND last_timestamp >= ? AND last_timestamp <= ?
+        )
+        SELECT
+          ${rollupSelect(aggregate)}
+        FROM ranked
+        GROUP BY point
+        ORDER BY timestamp ASC
+      `, [
+        startTime,
+        intervalConfig.seconds,
+        startTime,
+        intervalConfig.seconds,
+        startTime - bucketSeconds,
+        startTime,
+        now,
+      ]);
     }
+
+    const results = rows
+      .map(row => ({
+        ...row,
+        hashrate15m: row.hashrate15m ?? 0,
+        hashrate1hr: row.hashrate1hr ?? 0,
+        hashrate6hr: row.hashrate6hr ?? 0,
+        hashrate1d: row.hashrate1d ?? 0,
+        hashrate7d: row.hashrate7d ?? 0,
+      }))
+      .filter(row => row.users > 0 || row.workers > 0 || row.hashrate15m > 0 || row.hashrate1d > 0);
     
     // Apply anomaly smoothing to filter out measurement errors
     const smoothedResults = smoothAnomalies(results);
@@ -238,11 +260,11 @@ export async function GET(request: Request) {
     return new NextResponse(JSON.stringify(smoothedResults), {
       headers: {
         'Content-Type': 'application/json',
-        'Cache-Control': `s-maxage=${cacheDuration}, stale-while-revalidate=${cacheDuration * 2}`
+        'Cache-Control': `s-maxage=${intervalConfig.cacheDuration}, stale-while-revalidate=${intervalConfig.cacheDuration * 2}`
       }
     });
   } catch (error) {
-    console.error("Error fetching historical pool stats:", error);
+    log.error('Error fetching historical pool stats', { error });
     return new NextResponse(
       JSON.stringify({ error: "Failed to fetch historical pool stats" }), 
       { 
@@ -4,6 +4,9 @@ import { formatDifficulty, parseHashrate } from '../../utils/formatters';
 import { fetch, HttpError } from '@/lib/http-client';
 import { fetchWithCache } from '@/lib/aggregator-cache';
 import { getDb } from '@/lib/db';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/pool-stats' });
 
 interface AggregatorPoolData {
   statsData: Record<string, number>;
@@ -13,14 +16,15 @@ interface AggregatorPoolData {
 
 // Counted work (sum of accepted share difficulty) for the current round, from
 // the locally-cached per-participant totals.
-function getWorkSinceLastBlock(): number | null {
+async function getWorkSinceLastBlock(): Promise<number | null> {
   try {
-    const row = getDb()
-      .prepare('SELECT SUM(total_work) AS work FROM round_participants WHERE block_height = 0')
-      .get() as { work: number | null } | undefined;
+    const db = await getDb();
+    const row = await db.get<{ work: number | null }>(
+      'SELECT SUM(total_work) AS work FROM round_participants WHERE block_height = 0'
+    );
     return row?.work ?? null;
   } catch (error) {
-    console.error('Error computing work since last block:', error);
+    log.error('Error computing work since last block', { error });
     return null;
   }
 }
@@ -29,7 +33,7 @@ export async function GET() {
   try {
     const apiUrl = process.env.API_URL;
     if (!apiUrl) {
-      console.error("Error fetching pool stats: No API_URL defined in env");
+      log.error('Error fetching pool stats: No API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch pool stats" }, { status: 500 });
     }
 
@@ -73,7 +77,7 @@ export async function GET() {
         }
       }
     } catch (e) {
-      console.error("Error fetching last block from mempool.space:", e);
+      log.error('Error fetching last block from mempool.space', { error: e });
     }
 
     const poolStats: PoolStats = {
@@ -84,12 +88,12 @@ export async function GET() {
       hashrate: parseHashrate(hashrateData.hashrate5m),
       users: statsData.Users,
       workers: statsData.Workers,
-      workSinceLastBlock: getWorkSinceLastBlock(),
+      workSinceLastBlock: await getWorkSinceLastBlock(),
     };
     
     return NextResponse.json(poolStats);
   } catch (error) {
-    console.error("Error fetching pool stats:", error);
+    log.error('Error fetching pool stats', { error });
     return NextResponse.json({ error: "Failed to fetch pool stats" }, { status: 500 });
   }
 }
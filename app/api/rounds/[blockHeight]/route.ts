@@ -3,6 +3,9 @@ import { getDb } from '@/lib/db';
 import { getClaimedAddresses } from '@/lib/dispenser-cache';
 import type { RoundRow } from '../types';
 import { queryRoundParticipants } from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/rounds/[blockHeight]' });
 
 export async function GET(
   request: Request,
@@ -23,15 +26,16 @@ export async function GET(
       ? Math.min(Math.max(parsedLimit, 1), 999)
       : 99;
 
-    const db = getDb();
+    const db = await getDb();
     const claimedSet = getClaimedAddresses();
 
     // Block 0 is the current round — serve directly without status check
     if (blockHeight !== 0) {
       // Check if this round exists and its participant status
-      const round = db.prepare(
-        'SELECT participant_status FROM rounds WHERE block_height = ?'
-      ).get(blockHeight) as RoundRow | undefined;
+      const round = await db.get<RoundRow>(
+        'SELECT participant_status FROM rounds WHERE block_height = ?',
+        [blockHeight]
+      );
 
       if (!round) {
         return NextResponse.json({ error: 'Round not found' }, { status: 404 });
@@ -45,10 +49,10 @@ export async function GET(
       }
     }
 
-    const result = queryRoundParticipants(db, blockHeight, type, limit, claimedSet);
+    const result = await queryRoundParticipants(db, blockHeight, type, limit, claimedSet);
     return NextResponse.json(result);
   } catch (error) {
-    console.error('Error fetching round data:', error);
+    log.error('Error fetching round data', { error });
     return NextResponse.json(
       { error: 'Failed to fetch round data' },
       { status: 500 }
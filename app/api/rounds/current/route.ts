@@ -2,6 +2,9 @@ import { NextResponse } from 'next/server';
 import { getDb } from '@/lib/db';
 import { getClaimedAddresses } from '@/lib/dispenser-cache';
 import { queryRoundParticipants } from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/rounds/current' });
 
 export async function GET(request: Request) {
   try {
@@ -12,13 +15,13 @@ export async function GET(request: Request) {
       ? Math.min(Math.max(parsedLimit, 1), 999)
       : 99;
 
-    const db = getDb();
+    const db = await getDb();
     const claimedSet = getClaimedAddresses();
-    const result = queryRoundParticipants(db, 0, type, limit, claimedSet);
+    const result = await queryRoundParticipants(db, 0, type, limit, claimedSet);
 
     return NextResponse.json(result);
   } catch (error) {
-    console.error('Error fetching current round:', error);
+    log.error('Error fetching current round', { error });
     return NextResponse.json(
       { error: 'Failed to fetch current round data' },
       { status: 500 }
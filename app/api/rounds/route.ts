@@ -1,22 +1,25 @@
 import { NextResponse } from 'next/server';
 import { getDb } from '@/lib/db';
 import type { RoundRow } from './types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/rounds' });
 
 export async function GET() {
   try {
-    const db = getDb();
+    const db = await getDb();
 
-    const rounds = db.prepare(`
+    const rounds = await db.all<RoundRow>(`
       SELECT block_height, block_hash, coinbase_value, winner_diff, winner_username, participant_status
       FROM rounds
       WHERE block_height != 0
       ORDER BY block_height DESC
-    `).all() as RoundRow[];
+    `);
 
     // Prepend synthetic current-round entry if participant data exists
-    const currentRoundExists = db.prepare(
+    const currentRoundExists = await db.get(
       `SELECT 1 FROM round_participants WHERE block_height = 0 LIMIT 1`
-    ).get();
+    );
 
     if (currentRoundExists) {
       rounds.unshift({
@@ -32,7 +35,7 @@ export async function GET() {
 
     return NextResponse.json(rounds);
   } catch (error) {
-    console.error('Error fetching rounds:', error);
+    log.error('Error fetching rounds', { error });
     return NextResponse.json(
       { error: 'Failed to fetch rounds data' },
       { status: 500 }
@@ -1,5 +1,5 @@
 import { formatAddress } from '@/app/utils/formatters';
-import type { getDb } from '@/lib/db';
+import type { Db } from '@/lib/db';
 
 export type ParticipantStatus = 'pending' | 'fetching' | 'complete' | 'error';
 
@@ -24,8 +24,8 @@ export interface RoundRow {
  * Query round participants and format them for API responses.
  * Shared by /api/rounds/current and /api/rounds/[blockHeight].
  */
-export function queryRoundParticipants(
-  db: ReturnType<typeof getDb>,
+export async function queryRoundParticipants(
+  db: Db,
   blockHeight: number,
   type: string,
   limit: number,
@@ -37,14 +37,14 @@ export function queryRoundParticipants(
       ? 'total_work DESC'
       : 'top_diff DESC';
 
-  const rows = db.prepare(`
+  const rows = await db.all<RoundParticipantRow>(`
     SELECT rp.username, rp.top_diff, rp.blocks_participated, rp.total_work
     FROM round_participants rp
     LEFT JOIN monitored_users m ON rp.username = m.address
     WHERE rp.block_height = ? AND (m.is_public = 1 OR m.address IS NULL)
     ORDER BY ${orderBy}
     LIMIT ?
-  `).all(blockHeight, limit) as RoundParticipantRow[];
+  `, [blockHeight, limit]);
 
   return rows.map((row, index) => ({
     rank: index + 1,
@@ -1,4 +1,5 @@
 import { NextRequest, NextResponse } from 'next/server';
+import { fetch } from '@/lib/http-client';
 import type { OrderSummary } from '../types';
 import { getDb } from '@/lib/db';
 
@@ -10,10 +11,11 @@ export async function GET(request: NextRequest) {
     return NextResponse.json({ hasRefineryOperatorBadge: false }, { status: 400 });
   }
 
-  const db = getDb();
-  const user = db.prepare(
-    'SELECT has_refinery_badge FROM monitored_users WHERE address = ?'
-  ).get(address) as { has_refinery_badge: number } | undefined;
+  const db = await getDb();
+  const user = await db.get<{ has_refinery_badge: number }>(
+    'SELECT has_refinery_badge FROM monitored_users WHERE address = ?',
+    [address]
+  );
 
   if (user?.has_refinery_badge) {
     return NextResponse.json({ hasRefineryOperatorBadge: true }, { status: 200 });
@@ -43,9 +45,10 @@ export async function GET(request: NextRequest) {
       && orders.some((order: Partial<OrderSummary>) => order.status === 'fulfilled');
 
     if (hasRefineryOperatorBadge && user) {
-      db.prepare(
-        'UPDATE monitored_users SET has_refinery_badge = 1 WHERE address = ?'
-      ).run(address);
+      await db.run(
+        'UPDATE monitored_users SET has_refinery_badge = 1 WHERE address = ?',
+        [address]
+      );
     }
 
     return NextResponse.json({ hasRefineryOperatorBadge }, { status: 200 });
@@ -0,0 +1,122 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { checkRateLimit, getRateLimitHeaders } from '@/app/api/lib/rate-limit';
+import { reconstructHeader } from '@/lib/block-header';
+import {
+  toStratumNotification,
+  type HeaderReconstruction,
+  type StratumNotificationRow,
+} from '../../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/stratum/[id]/header' });
+
+const HEX_32 = /^[0-9a-f]{8}$/i;
+const HEX = /^(?:[0-9a-f]{2})+$/i;
+
+/**
+ * Rebuild the coinbase, merkle root and 80-byte header of a stored job with
+ * the caller's work, and hash it.
+ *
+ * `id` is the notification id returned by /api/stratum. Query params:
+ * ?extranonce2= (hex, extranonce2_size bytes, default zeros), ?nonce= (8 hex,
+ * default 00000000), ?ntime= (8 hex, default the job's), ?versionBits= (8 hex,
+ * needs a negotiated version rolling mask), ?extranonce1= (hex, default the
+ * collector's own session value)
+ */
+export async function GET(
+  request: Request,
+  { params }: { params: Promise<{ id: string }> }
+) {
+  const rateLimitResult = checkRateLimit(request);
+
+  if (!rateLimitResult.success) {
+    return NextResponse.json(
+      { error: 'Too many requests. Please try again later.' },
+      {
+        status: 429,
+        headers: getRateLimitHeaders(rateLimitResult),
+      }
+    );
+  }
+
+  try {
+    const { id } = await params;
+    const { searchParams } = new URL(request.url);
+
+    const db = await getDb();
+    const row = await db.get<StratumNotificationRow>(
+      'SELECT * FROM stratum_notifications WHERE notification_id = ?',
+      [id]
+    );
+
+    if (!row) {
+      return NextResponse.json({ error: 'Notification not found' }, { status: 404 });
+    }
+
+    const notification = toStratumNotification(row);
+    const extranonce2Size = notification.extranonce2Size ?? 4;
+    const extranonce1 = searchParams.get('extranonce1') ?? notification.extranonce1 ?? '';
+    const extranonce2 = searchParams.get('extranonce2') ?? '00'.repeat(extranonce2Size);
+    const nonce = searchParams.get('nonce') ?? '00000000';
+    const nTime = searchParams.get('ntime') ?? undefined;
+    const versionBits = searchParams.get('versionBits') ?? undefined;
+
+    if (extranonce1 !== '' && !HEX.test(extranonce1)) {
+      return NextResponse.json({ error: 'Invalid extranonce1' }, { status: 400 });
+    }
+    if (!HEX.test(extranonce2) || extranonce2.length !== extranonce2Size * 2) {
+      return NextResponse.json(
+        { error: `extranonce2 must be ${extranonce2Size} bytes of hex` },
+        { status: 400 }
+      );
+    }
+    if (!HEX_32.test(nonce)) {
+      return NextResponse.json({ error: 'Invalid nonce' }, { status: 400 });
+    }
+    if (nTime !== undefined && !HEX_32.test(nTime)) {
+      return NextResponse.json({ error: 'Invalid ntime' }, { status: 400 });
+    }
+    if (versionBits !== undefined) {
+      if (!HEX_32.test(versionBits)) {
+        return NextResponse.json({ error: 'Invalid versionBits' }, { status: 400 });
+      }
+      if (!notification.versionRollingMask) {
+        return NextResponse.json(
+          { error: 'Version rolling was not negotiated for this job' },
+          { status: 400 }
+        );
+      }
+    }
+
+    const reconstructed = reconstructHeader(notification, {
+      extranonce1,
+      extranonce2,
+      nonce,
+      nTime,
+      versionBits,
+      versionRollingMask: notification.versionRollingMask,
+    });
+
+    const response: HeaderReconstruction = {
+      notificationId: notification.id,
+      pool: notification.pool,
+      jobId: notification.jobId,
+      prevBlockHash: notification.prevBlockHash,
+      extranonce1: extranonce1.toLowerCase(),
+      extranonce2: extranonce2.toLowerCase(),
+      versionRollingMask: notification.versionRollingMask ?? null,
+      ...reconstructed,
+    };
+
+    return NextResponse.json(response, {
+      headers: getRateLimitHeaders(rateLimitResult),
+    });
+  } catch (error) {
+    log.error('Error reconstructing block header', { error });
+    return NextResponse.json(
+      { error: 'Failed to reconstruct block header' },
+      { status: 500 }
+    );
+  }
+}
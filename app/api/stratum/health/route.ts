@@ -0,0 +1,39 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import {
+  NO_CACHE_HEADERS,
+  toStratumPoolHealth,
+  type StratumHealthRow,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/stratum/health' });
+
+/**
+ * Connection health of every stratum collector.
+ *
+ * Responds 503 when the primary pool's feed is down or stale (or no collector
+ * has ever reported), so it can be used directly as an uptime check.
+ */
+export async function GET() {
+  try {
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+
+    const rows = await db.all<StratumHealthRow>(`
+      SELECT * FROM stratum_health ORDER BY is_primary DESC, pool
+    `);
+
+    const pools = rows.map(row => toStratumPoolHealth(row, now));
+    const primaryPools = pools.filter(pool => pool.primary);
+    const healthy = primaryPools.length > 0 && primaryPools.every(pool => pool.healthy);
+
+    return NextResponse.json(
+      { healthy, pools },
+      { status: healthy ? 200 : 503, headers: NO_CACHE_HEADERS }
+    );
+  } catch (error) {
+    log.error('Error fetching stratum health', { error });
+    return NextResponse.json({ error: "Failed to fetch stratum health" }, { status: 500 });
+  }
+}
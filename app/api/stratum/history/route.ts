@@ -0,0 +1,51 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import {
+  DEFAULT_POOL,
+  HISTORY_DEFAULT_DAYS,
+  HISTORY_MAX_DAYS,
+  NO_CACHE_HEADERS,
+  toBlockTemplateSummary,
+  type BlockTemplateSummaryRow,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/stratum/history' });
+
+/**
+ * Per-height block template summaries for one pool, oldest first.
+ *
+ * Query params: ?pool= (default Parasite), ?days= (default 30, max 365)
+ */
+export async function GET(request: Request) {
+  try {
+    const { searchParams } = new URL(request.url);
+    const pool = searchParams.get('pool') || DEFAULT_POOL;
+    const days = Math.min(
+      Math.max(parseInt(searchParams.get('days') || String(HISTORY_DEFAULT_DAYS), 10) || HISTORY_DEFAULT_DAYS, 1),
+      HISTORY_MAX_DAYS
+    );
+    const cutoff = Math.floor(Date.now() / 1000) - days * 24 * 60 * 60;
+
+    const db = await getDb();
+
+    // Clean-job latency is measured against the earliest job any pool sent for the height
+    const rows = await db.all<BlockTemplateSummaryRow>(`
+      SELECT s.*, earliest.first_job_at AS earliest_first_job_at
+      FROM stratum_block_summaries s
+      JOIN (
+        SELECT block_height, MIN(first_job_at) AS first_job_at
+        FROM stratum_block_summaries
+        WHERE first_job_at >= ?
+        GROUP BY block_height
+      ) earliest ON earliest.block_height = s.block_height
+      WHERE s.pool = ? AND s.first_job_at >= ?
+      ORDER BY s.block_height ASC
+    `, [cutoff, pool, cutoff]);
+
+    return NextResponse.json(rows.map(toBlockTemplateSummary), { headers: NO_CACHE_HEADERS });
+  } catch (error) {
+    log.error('Error fetching block template history', { error });
+    return NextResponse.json({ error: "Failed to fetch block template history" }, { status: 500 });
+  }
+}
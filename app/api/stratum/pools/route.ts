@@ -0,0 +1,39 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import {
+  NO_CACHE_HEADERS,
+  POOL_STALE_SECONDS,
+  toStratumNotification,
+  type StratumNotificationRow,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/stratum/pools' });
+
+/**
+ * Latest notification from every observed pool, for side-by-side comparison
+ * of their current block templates.
+ */
+export async function GET() {
+  try {
+    const db = await getDb();
+    const cutoff = Math.floor(Date.now() / 1000) - POOL_STALE_SECONDS;
+
+    // MAX(id) per pool picks the newest row; ids only ever increase
+    const rows = await db.all<StratumNotificationRow>(`
+      SELECT s.* FROM stratum_notifications s
+      JOIN (
+        SELECT pool, MAX(id) AS max_id
+        FROM stratum_notifications
+        GROUP BY pool
+      ) latest ON s.id = latest.max_id
+      WHERE s.created_at >= ?
+      ORDER BY s.pool
+    `, [cutoff]);
+
+    return NextResponse.json(rows.map(toStratumNotification), { headers: NO_CACHE_HEADERS });
+  } catch (error) {
+    log.error('Error fetching stratum pools', { error });
+    return NextResponse.json({ error: "Failed to fetch stratum pools" }, { status: 500 });
+  }
+}
@@ -0,0 +1,92 @@
+import { NextResponse } from 'next/server';
+import { checkRateLimit, getRateLimitHeaders } from '@/app/api/lib/rate-limit';
+import { subscribeStratumNotifications } from '../../../../lib/stratum-events';
+import { MAX_STREAM_CLIENTS, STREAM_HEARTBEAT_MS } from '../types';
+
+export const dynamic = 'force-dynamic';
+
+let activeClients = 0;
+
+/**
+ * Server-Sent Events stream of stratum notifications as they're recorded.
+ * Each `notification` event carries one StratumNotification; pass ?pool= to
+ * only receive a single pool's jobs.
+ */
+export async function GET(request: Request) {
+  // EventSource reconnects automatically, so limit how often a client can open streams
+  const rateLimitResult = checkRateLimit(request);
+
+  if (!rateLimitResult.success) {
+    return NextResponse.json(
+      { error: 'Too many requests. Please try again later.' },
+      {
+        status: 429,
+        headers: getRateLimitHeaders(rateLimitResult),
+      }
+    );
+  }
+
+  if (activeClients >= MAX_STREAM_CLIENTS) {
+    return NextResponse.json(
+      { error: 'Too many open streams. Please try again later.' },
+      { status: 503, headers: { 'Retry-After': '30' } }
+    );
+  }
+
+  const { searchParams } = new URL(request.url);
+  const pool = searchParams.get('pool');
+  const encoder = new TextEncoder();
+
+  let cleanup: (() => void) | null = null;
+
+  const stream = new ReadableStream<Uint8Array>({
+    start(controller) {
+      activeClients++;
+
+      const send = (chunk: string) => {
+        try {
+          controller.enqueue(encoder.encode(chunk));
+        } catch {
+          // Stream already closed; cleanup runs from the abort handler
+        }
+      };
+
+      const unsubscribe = subscribeStratumNotifications((notification) => {
+        if (pool && notification.pool !== pool) return;
+        send(`event: notification\ndata: ${JSON.stringify(notification)}\n\n`);
+      });
+
+      const heartbeat = setInterval(() => send(': heartbeat\n\n'), STREAM_HEARTBEAT_MS);
+
+      cleanup = () => {
+        cleanup = null;
+        activeClients--;
+        clearInterval(heartbeat);
+        unsubscribe();
+        try {
+          controller.close();
+        } catch {
+          // Already closed
+        }
+      };
+
+      request.signal.addEventListener('abort', () => cleanup?.());
+
+      // Tell the browser how long to wait before reconnecting
+      send('retry: 3000\n\n');
+    },
+    cancel() {
+      cleanup?.();
+    },
+  });
+
+  return new Response(stream, {
+    headers: {
+      'Content-Type': 'text/event-stream',
+      'Cache-Control': 'no-cache, no-transform',
+      'Connection': 'keep-alive',
+      // Disable response buffering in nginx
+      'X-Accel-Buffering': 'no',
+    },
+  });
+}
@@ -0,0 +1,94 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import {
+  DEFAULT_POOL,
+  NO_CACHE_HEADERS,
+  TIMELINE_RECENT_BLOCKS,
+  toTimelineJob,
+  type StratumJobDiffRow,
+  type TemplateTimeline,
+  type TimelineBlock,
+} from '../types';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/stratum/timeline' });
+
+/**
+ * How a pool's template evolved over one block: every job seen on a
+ * prev_block_hash with its changes against the job before it.
+ *
+ * Query params: ?pool= (default Parasite), and ?height= or ?prevHash= to pick
+ * the block; defaults to the block currently being mined.
+ */
+export async function GET(request: Request) {
+  try {
+    const { searchParams } = new URL(request.url);
+    const pool = searchParams.get('pool') || DEFAULT_POOL;
+    const heightParam = searchParams.get('height');
+    const prevHashParam = searchParams.get('prevHash');
+
+    if (heightParam !== null && !/^\d+$/.test(heightParam)) {
+      return NextResponse.json({ error: 'Invalid height' }, { status: 400 });
+    }
+    if (prevHashParam !== null && !/^[0-9a-f]{64}$/i.test(prevHashParam)) {
+      return NextResponse.json({ error: 'Invalid prevHash' }, { status: 400 });
+    }
+
+    const db = await getDb();
+
+    const recentBlocks = await db.all<TimelineBlock>(`
+      SELECT
+        prev_block_hash AS "prevBlockHash",
+        MAX(block_height) AS "blockHeight",
+        COUNT(*) AS "jobCount",
+        MIN(timestamp) AS "firstSeen",
+        MAX(timestamp) AS "lastSeen"
+      FROM stratum_job_diffs
+      WHERE pool = ?
+      GROUP BY prev_block_hash
+      ORDER BY "lastSeen" DESC
+      LIMIT ?
+    `, [pool, TIMELINE_RECENT_BLOCKS]);
+
+    let prevBlockHash: string | null = null;
+    if (prevHashParam) {
+      prevBlockHash = prevHashParam.toLowerCase();
+    } else if (heightParam) {
+      // Several prev hashes can share a height after a reorg; use the latest
+      const row = await db.get<{ prev_block_hash: string }>(`
+        SELECT prev_block_hash FROM stratum_job_diffs
+        WHERE pool = ? AND block_height = ?
+        ORDER BY id DESC
+        LIMIT 1
+      `, [pool, parseInt(heightParam, 10)]);
+      prevBlockHash = row?.prev_block_hash ?? null;
+    } else {
+      prevBlockHash = recentBlocks[0]?.prevBlockHash ?? null;
+    }
+
+    const rows = prevBlockHash
+      ? await db.all<StratumJobDiffRow>(`
+          SELECT * FROM stratum_job_diffs
+          WHERE pool = ? AND prev_block_hash = ?
+          ORDER BY id ASC
+        `, [pool, prevBlockHash])
+      : [];
+
+    if ((heightParam || prevHashParam) && rows.length === 0) {
+      return NextResponse.json({ error: 'No template timeline for that block' }, { status: 404 });
+    }
+
+    const timeline: TemplateTimeline = {
+      pool,
+      prevBlockHash,
+      blockHeight: rows.find(row => row.block_height !== null)?.block_height ?? null,
+      jobs: rows.map(toTimelineJob),
+      recentBlocks,
+    };
+
+    return NextResponse.json(timeline, { headers: NO_CACHE_HEADERS });
+  } catch (error) {
+    log.error('Error fetching template timeline', { error });
+    return NextResponse.json({ error: "Failed to fetch template timeline" }, { status: 500 });
+  }
+}
@@ -0,0 +1,315 @@
+/**
+ * Shared types for stratum API routes
+ */
+
+// Pool whose notifications are served when no ?pool= is given
+export const DEFAULT_POOL = 'Parasite';
+
+// Pools that haven't sent a notification within this window are omitted from comparisons
+export const POOL_STALE_SECONDS = 30 * 60;
+
+// Comment line sent on idle SSE streams so proxies don't close them
+export const STREAM_HEARTBEAT_MS = 15 * 1000;
+
+// Upper bound on concurrent /api/stratum/stream connections per server
+export const MAX_STREAM_CLIENTS = 500;
+
+export interface StratumNotification {
+  id: string;
+  timestamp: number;
+  pool: string;
+  jobId: string;
+  prevBlockHash: string;
+  coinbase1: string;
+  coinbase2: string;
+  merkleBranches: string[];
+  version: string;
+  nBits: string;
+  nTime: string;
+  cleanJobs: boolean;
+  extranonce1?: string;
+  extranonce2Size?: number;
+  // BIP 310 mask negotiated via mining.configure; undefined if the pool doesn't allow version rolling
+  versionRollingMask?: string;
+  // Share difficulty assigned by the pool when the job arrived
+  difficulty?: number;
+  raw: Record<string, unknown>;
+}
+
+export interface StratumNotificationRow {
+  id: number;
+  notification_id: string;
+  timestamp: number;
+  pool: string;
+  job_id: string;
+  prev_block_hash: string;
+  coinbase1: string;
+  coinbase2: string;
+  merkle_branches: string;
+  version: string;
+  n_bits: string;
+  n_time: string;
+  clean_jobs: number;
+  extranonce1: string | null;
+  extranonce2_size: number | null;
+  version_rolling_mask: string | null;
+  difficulty: number | null;
+  raw_message: string;
+}
+
+export const NO_CACHE_HEADERS = {
+  'Cache-Control': 'no-cache, no-store, must-revalidate',
+  'Pragma': 'no-cache',
+  'Expires': '0',
+};
+
+// The database id is optional so freshly recorded rows can be converted before they're read back
+export function toStratumNotification(row: Omit<StratumNotificationRow, 'id'>): StratumNotification {
+  return {
+    id: row.notification_id,
+    timestamp: row.timestamp,
+    pool: row.pool,
+    jobId: row.job_id,
+    prevBlockHash: row.prev_block_hash,
+    coinbase1: row.coinbase1,
+    coinbase2: row.coinbase2,
+    merkleBranches: JSON.parse(row.merkle_branches),
+    version: row.version,
+    nBits: row.n_bits,
+    nTime: row.n_time,
+    cleanJobs: Boolean(row.clean_jobs),
+    extranonce1: row.extranonce1 || undefined,
+    extranonce2Size: row.extranonce2_size || undefined,
+    versionRollingMask: row.version_rolling_mask || undefined,
+    difficulty: row.difficulty ?? undefined,
+    raw: JSON.parse(row.raw_message) as Record<string, unknown>
+  };
+}
+
+// Number of recent blocks listed alongside a timeline for navigation
+export const TIMELINE_RECENT_BLOCKS = 10;
+
+export interface TimelineOutput {
+  script: string;
+  value: number;
+  address?: string;
+}
+
+export interface TimelineJob {
+  jobId: string;
+  timestamp: number;
+  cleanJobs: boolean;
+  merkleBranchCount: number;
+  outputCount: number;
+  coinbaseValue: number;
+  fees: number | null;
+  nTime: string;
+  version: string;
+  // Deltas against the previous job on the same prev hash; null for the first job
+  previousJobId: string | null;
+  merkleBranchDelta: number | null;
+  outputCountDelta: number | null;
+  coinbaseValueDelta: number | null;
+  nTimeChanged: boolean | null;
+  versionChanged: boolean | null;
+  outputsAdded: TimelineOutput[];
+  outputsRemoved: TimelineOutput[];
+}
+
+export interface TimelineBlock {
+  prevBlockHash: string;
+  blockHeight: number | null;
+  jobCount: number;
+  firstSeen: number;
+  lastSeen: number;
+}
+
+export interface TemplateTimeline {
+  pool: string;
+  prevBlockHash: string | null;
+  blockHeight: number | null;
+  jobs: TimelineJob[];
+  recentBlocks: TimelineBlock[];
+}
+
+export interface StratumJobDiffRow {
+  id: number;
+  notification_id: string;
+  pool: string;
+  prev_block_hash: string;
+  block_height: number | null;
+  job_id: string;
+  timestamp: number;
+  clean_jobs: number;
+  merkle_branch_count: number;
+  output_count: number;
+  coinbase_value: number;
+  fees: number | null;
+  n_time: string;
+  version: string;
+  outputs: string;
+  previous_job_id: string | null;
+  merkle_branch_delta: number | null;
+  output_count_delta: number | null;
+  coinbase_value_delta: number | null;
+  n_time_changed: number | null;
+  version_changed: number | null;
+  outputs_added: string | null;
+  outputs_removed: string | null;
+}
+
+function toNullableBoolean(value: number | null): boolean | null {
+  return value === null ? null : Boolean(value);
+}
+
+export function toTimelineJob(row: StratumJobDiffRow): TimelineJob {
+  return {
+    jobId: row.job_id,
+    timestamp: row.timestamp,
+    cleanJobs: Boolean(row.clean_jobs),
+    merkleBranchCount: row.merkle_branch_count,
+    outputCount: row.output_count,
+    coinbaseValue: row.coinbase_value,
+    fees: row.fees,
+    nTime: row.n_time,
+    version: row.version,
+    previousJobId: row.previous_job_id,
+    merkleBranchDelta: row.merkle_branch_delta,
+    outputCountDelta: row.output_count_delta,
+    coinbaseValueDelta: row.coinbase_value_delta,
+    nTimeChanged: toNullableBoolean(row.n_time_changed),
+    versionChanged: toNullableBoolean(row.version_changed),
+    outputsAdded: row.outputs_added ? JSON.parse(row.outputs_added) : [],
+    outputsRemoved: row.outputs_removed ? JSON.parse(row.outputs_removed) : [],
+  };
+}
+
+// Default and maximum window for /api/stratum/history
+export const HISTORY_DEFAULT_DAYS = 30;
+export const HISTORY_MAX_DAYS = 365;
+
+export interface BlockTemplateSummary {
+  blockHeight: number;
+  prevBlockHash: string;
+  firstJobAt: number;
+  lastJobAt: number;
+  jobCount: number;
+  maxCoinbaseValue: number;
+  maxFees: number;
+  subsidy: number;
+  // Fees as a fraction of the best coinbase value seen for the height
+  feeShare: number;
+  // Seconds from the first job any observed pool sent for this height to this
+  // pool's first clean job; null if no clean job was seen
+  cleanJobLatency: number | null;
+}
+
+export interface BlockTemplateSummaryRow {
+  block_height: number;
+  prev_block_hash: string;
+  first_job_at: number;
+  last_job_at: number;
+  first_clean_job_at: number | null;
+  job_count: number;
+  max_coinbase_value: number;
+  max_fees: number;
+  subsidy: number;
+  earliest_first_job_at: number;
+}
+
+export function toBlockTemplateSummary(row: BlockTemplateSummaryRow): BlockTemplateSummary {
+  return {
+    blockHeight: row.block_height,
+    prevBlockHash: row.prev_block_hash,
+    firstJobAt: row.first_job_at,
+    lastJobAt: row.last_job_at,
+    jobCount: row.job_count,
+    maxCoinbaseValue: row.max_coinbase_value,
+    maxFees: row.max_fees,
+    subsidy: row.subsidy,
+    feeShare: row.max_coinbase_value > 0 ? row.max_fees / row.max_coinbase_value : 0,
+    cleanJobLatency:
+      row.first_clean_job_at !== null ? row.first_clean_job_at - row.earliest_first_job_at : null,
+  };
+}
+
+// A connected pool that hasn't sent anything for this long is reported unhealthy
+export const HEALTH_STALE_SECONDS = 3 * 60;
+
+export interface StratumPoolHealth {
+  pool: string;
+  host: string;
+  port: number;
+  primary: boolean;
+  status: 'connecting' | 'connected' | 'disconnected' | 'stopped';
+  healthy: boolean;
+  connectedAt: number | null;
+  lastMessageAt: number | null;
+  // Seconds since the last message from the pool; null if none was ever received
+  lastMessageAge: number | null;
+  lastNotificationAt: number | null;
+  reconnectCount: number;
+  consecutiveFailures: number;
+  lastError: string | null;
+  updatedAt: number;
+}
+
+export interface StratumHealthRow {
+  pool: string;
+  host: string;
+  port: number;
+  is_primary: number;
+  status: StratumPoolHealth['status'];
+  connected_at: number | null;
+  last_message_at: number | null;
+  last_notification_at: number | null;
+  reconnect_count: number;
+  consecutive_failures: number;
+  last_error: string | null;
+  updated_at: number;
+}
+
+export function toStratumPoolHealth(row: StratumHealthRow, now: number): StratumPoolHealth {
+  const lastMessageAge = row.last_message_at !== null ? now - row.last_message_at : null;
+
+  return {
+    pool: row.pool,
+    host: row.host,
+    port: row.port,
+    primary: Boolean(row.is_primary),
+    status: row.status,
+    // Also catches a collector process that died without updating its row
+    healthy: row.status === 'connected' && lastMessageAge !== null && lastMessageAge <= HEALTH_STALE_SECONDS,
+    connectedAt: row.connected_at,
+    lastMessageAt: row.last_message_at,
+    lastMessageAge,
+    lastNotificationAt: row.last_notification_at,
+    reconnectCount: row.reconnect_count,
+    consecutiveFailures: row.consecutive_failures,
+    lastError: row.last_error,
+    updatedAt: row.updated_at,
+  };
+}
+
+// /api/stratum/[id]/header response: a job's header rebuilt with the caller's work
+export interface HeaderReconstruction {
+  notificationId: string;
+  pool: string;
+  jobId: string;
+  prevBlockHash: string;
+  extranonce1: string;
+  extranonce2: string;
+  versionRollingMask: string | null;
+  blockHeight: number | null;
+  coinbase: string;
+  coinbaseTxid: string;
+  merkleRoot: string;
+  version: string;
+  nTime: string;
+  nonce: string;
+  header: string;
+  hash: string;
+  shareDifficulty: number;
+  networkDifficulty: number;
+  meetsNetworkTarget: boolean;
+}
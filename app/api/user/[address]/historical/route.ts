@@ -1,6 +1,9 @@
 import { NextResponse } from 'next/server';
-import { parseHashrate } from '../../../../utils/formatters';
 import { getDb } from '../../../../../lib/db';
+import { createLogger } from '@/lib/logger';
+import { AGGREGATES, aggregateQuery, type Aggregate } from '../../../../../lib/user-stats-history';
+
+const log = createLogger({ route: '/api/user/[address]/historical' });
 
 // Enable caching based on interval
 export const revalidate = 60;
@@ -19,6 +22,15 @@ export async function GET(
     const { searchParams } = new URL(request.url);
     const period = searchParams.get('period') || '24h';
     const interval = searchParams.get('interval') || '5m';
+    const aggregateParam = searchParams.get('aggregate') || 'last';
+
+    if (!(AGGREGATES as readonly string[]).includes(aggregateParam)) {
+      return new NextResponse(
+        JSON.stringify({ error: `Aggregate must be one of ${AGGREGATES.join(', ')}` }),
+        { status: 400, headers: { 'Content-Type': 'application/json', 'Cache-Control': 'no-store' } }
+      );
+    }
+    const aggregate = aggregateParam as Aggregate;
 
     // Validate interval is positive for numeric intervals
     const intervalMatch = interval.match(/^(-?\d+)([mh])$/);
@@ -59,16 +71,16 @@ export async function GET(
     let hashrateColumn: string;
     switch (interval) {
       case '1m':
-        hashrateColumn = 'hashrate1m';
+        hashrateColumn = 'hashrate1m_hs';
         break;
       case '5m':
-        hashrateColumn = 'hashrate5m';
+        hashrateColumn = 'hashrate5m_hs';
         break;
       case '1h':
-        hashrateColumn = 'hashrate1hr';
+        hashrateColumn = 'hashrate1hr_hs';
         break;
       default:
-        hashrateColumn = 'hashrate5m'; // Default to 5m (covers 15m/30m)
+        hashrateColumn = 'hashrate5m_hs'; // Default to 5m (covers 15m/30m)
     }
     
     // Calculate the time range based on the period
@@ -119,10 +131,10 @@ export async function GET(
       startTime = now - 24 * 60 * 60;
     }
 
-    const db = getDb();
+    const db = await getDb();
 
     // First get the user_id from monitored_users
-    const user = db.prepare('SELECT id FROM monitored_users WHERE address = ? AND is_active = 1').get(address) as { id: number } | undefined;
+    const user = await db.get<{ id: number }>('SELECT id FROM monitored_users WHERE address = ? AND is_active = 1', [address]);
 
     if (!user) {
       return NextResponse.json(
@@ -153,44 +165,18 @@ export async function GET(
         intervalSeconds = 5 * 60; // Default to 5 minutes
     }
 
-    // Calculate intervals for the time range
-    const intervals = [];
-    for (let t = startTime; t < now; t += intervalSeconds) {
-      intervals.push({
-        start: t,
-        end: t + intervalSeconds
-      });
-    }
+    const rows = await db.all<{ bucket: number; hashrate: number | null }>(
+      aggregateQuery(aggregate, hashrateColumn),
+      [startTime, intervalSeconds, user.id, startTime, now]
+    );
 
-    // Query for each interval and aggregate
-    const results: HistoricalUserStats[] = [];
-
-    const stmt = db.prepare(`
-      SELECT
-        ${hashrateColumn} as hashrate,
-        created_at as timestamp
-      FROM user_stats_history
-      WHERE user_id = ? AND created_at >= ? AND created_at < ?
-      ORDER BY created_at DESC
-      LIMIT 1
-    `);
-
-    for (const { start, end } of intervals) {
-      const rows = stmt.all(user.id, start, end) as { timestamp: number; hashrate: string }[];
-
-      if (rows.length > 0) {
-        const row = rows[0];
-        const hashrate = parseHashrate(row.hashrate);
-        
-        // Only include intervals that have real data
-        if (hashrate > 0) {
-          results.push({
-            timestamp: new Date(start * 1000).toISOString(),
-            hashrate: hashrate
-          });
-        }
-      }
-    }
+    // Only include intervals that have real data
+    const results: HistoricalUserStats[] = rows
+      .filter(row => row.hashrate !== null && row.hashrate > 0)
+      .map(row => ({
+        timestamp: new Date((startTime + row.bucket * intervalSeconds) * 1000).toISOString(),
+        hashrate: row.hashrate as number,
+      }));
 
     return new NextResponse(JSON.stringify(results), {
       headers: {
@@ -200,7 +186,7 @@ export async function GET(
     });
 
   } catch (error) {
-    console.error("Error fetching historical user stats:", error);
+    log.error('Error fetching historical user stats', { error });
     return new NextResponse(
       JSON.stringify({ error: "Failed to fetch historical user stats" }), 
       { 
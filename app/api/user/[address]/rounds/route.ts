@@ -1,5 +1,8 @@
 import { NextResponse } from 'next/server';
 import { getDb } from '@/lib/db';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/user/[address]/rounds' });
 
 export interface UserRoundHistoryEntry {
   block_height: number;
@@ -40,33 +43,36 @@ export async function GET(
       ? Math.min(Math.max(parsedLimit, 1), 100)
       : 20;
 
-    const db = getDb();
+    const db = await getDb();
 
     // Privacy check: return 403 if user is private
-    const userPublicCheck = db.prepare(
-      `SELECT is_public FROM monitored_users WHERE address = ?`
-    ).get(address) as { is_public: number } | undefined;
+    const userPublicCheck = await db.get<{ is_public: number }>(
+      `SELECT is_public FROM monitored_users WHERE address = ?`,
+      [address]
+    );
 
     if (userPublicCheck && !userPublicCheck.is_public) {
       return NextResponse.json({ error: 'This user profile is private' }, { status: 403 });
     }
 
     // Current round (block_height = 0 sentinel)
-    const currentUser = db.prepare(
-      `SELECT top_diff, blocks_participated, total_work FROM round_participants WHERE block_height = 0 AND username = ?`
-    ).get(address) as { top_diff: number; blocks_participated: number; total_work: number } | undefined;
+    const currentUser = await db.get<{ top_diff: number; blocks_participated: number; total_work: number }>(
+      `SELECT top_diff, blocks_participated, total_work FROM round_participants WHERE block_height = 0 AND username = ?`,
+      [address]
+    );
 
     let current_round: UserRoundsResponse['current_round'] = null;
 
     if (currentUser) {
-      const rankInfo = db.prepare(`
+      // COUNT(*) always returns a row
+      const rankInfo = (await db.get<{ rank: number; blocks_rank: number; work_rank: number; total: number }>(`
         SELECT
           COUNT(CASE WHEN top_diff > ? THEN 1 END) + 1 AS rank,
           COUNT(CASE WHEN blocks_participated > ? THEN 1 END) + 1 AS blocks_rank,
           COUNT(CASE WHEN total_work > ? THEN 1 END) + 1 AS work_rank,
           COUNT(*) AS total
         FROM round_participants WHERE block_height = 0
-      `).get(currentUser.top_diff, currentUser.blocks_participated, currentUser.total_work) as { rank: number; blocks_rank: number; work_rank: number; total: number };
+      `, [currentUser.top_diff, currentUser.blocks_participated, currentUser.total_work]))!;
 
       current_round = {
         rank: rankInfo.rank,
@@ -80,17 +86,29 @@ export async function GET(
     }
 
     // Rounds won
-    const wonRow = db.prepare(
-      `SELECT COUNT(*) AS count FROM rounds WHERE winner_username = ?`
-    ).get(address) as { count: number };
+    const wonRow = await db.get<{ count: number }>(
+      `SELECT COUNT(*) AS count FROM rounds WHERE winner_username = ?`,
+      [address]
+    );
 
     // Total rounds participated (based on block participants)
-    const participatedRow = db.prepare(
-      `SELECT COUNT(*) AS count FROM block_participants WHERE username = ?`
-    ).get(address) as { count: number };
+    const participatedRow = await db.get<{ count: number }>(
+      `SELECT COUNT(*) AS count FROM block_participants WHERE username = ?`,
+      [address]
+    );
 
     // History with rank, total participants, and winner status per round
-    const history = db.prepare(`
+    const history = await db.all<{
+      block_height: number;
+      top_diff: number;
+      blocks_participated: number;
+      total_work: number;
+      rank: number;
+      blocks_rank: number;
+      work_rank: number;
+      total_participants: number;
+      is_winner: number;
+    }>(`
       WITH user_blocks AS (
         SELECT block_height FROM block_participants WHERE username = ?
       ),
@@ -124,22 +142,12 @@ export async function GET(
       WHERE ranked.username = ?
       ORDER BY ranked.block_height DESC
       LIMIT ?
-    `).all(address, address, address, limit) as {
-      block_height: number;
-      top_diff: number;
-      blocks_participated: number;
-      total_work: number;
-      rank: number;
-      blocks_rank: number;
-      work_rank: number;
-      total_participants: number;
-      is_winner: number;
-    }[];
+    `, [address, address, address, limit]);
 
     const response: UserRoundsResponse = {
       current_round,
-      rounds_won: wonRow.count,
-      total_rounds_participated: participatedRow.count,
+      rounds_won: wonRow?.count ?? 0,
+      total_rounds_participated: participatedRow?.count ?? 0,
       history: history.map(row => ({
         block_height: row.block_height,
         rank: row.rank,
@@ -159,7 +167,7 @@ export async function GET(
       },
     });
   } catch (error) {
-    console.error('Error fetching user rounds:', error);
+    log.error('Error fetching user rounds', { error });
     return NextResponse.json(
       { error: 'Failed to fetch user rounds' },
       {
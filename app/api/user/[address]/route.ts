@@ -4,6 +4,11 @@ import { formatRelativeTime } from '@/app/utils/formatters';
 import { fetch, HttpError } from '@/lib/http-client';
 import { fetchWithCache } from '@/lib/aggregator-cache';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
+import { getDb } from '@/lib/db';
+import { recordUserView } from '@/lib/user-scheduler';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/user/[address]' });
 
 export interface WorkerData {
   workername: string;
@@ -61,10 +66,15 @@ export async function GET(
     return NextResponse.json({ error: "Invalid Bitcoin address" }, { status: 400 });
   }
 
+  // Someone is watching this user: the collector polls them on the active interval
+  getDb()
+    .then(db => recordUserView(db, address))
+    .catch(error => log.error('Error recording user view', { error }));
+
   try {
     const apiUrl = process.env.API_URL;
     if (!apiUrl) {
-      console.error("Failed to fetch user data: No API_URL defined in env");
+      log.error('Failed to fetch user data: No API_URL defined in env');
       return NextResponse.json({ error: "Failed to fetch user data" }, { status: 500 });
     }
 
@@ -101,17 +111,17 @@ export async function GET(
   } catch (error) {
     // Upstream 404 is routine (address not in the pool) — no stack trace needed
     if (error instanceof HttpError && error.status === 404) {
-      console.log(`User ${address} not found (404)`);
+      log.info('User not found upstream', { address });
       return NextResponse.json({ error: "User not found" }, { status: 404 });
     }
     if (error instanceof HttpError) {
-      console.error(`Error fetching user data: ${error.message}`);
+      log.error('Upstream error fetching user data', { address, status: error.status, error: error.message });
       return NextResponse.json(
         { error: "Failed to fetch user data" },
         { status: 502 }
       );
     }
-    console.error("Error fetching user data:", error);
+    log.error('Error fetching user data', { error });
     return NextResponse.json(
       { error: "Failed to fetch user data" },
       { status: 500 }
@@ -0,0 +1,105 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import type { WorkerEventType, WorkerState } from '@/lib/worker-events';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/user/[address]/worker-events' });
+
+export interface WorkerEventEntry {
+  worker: string;
+  name: string;
+  type: WorkerEventType;
+  previous_state: WorkerState | null;
+  state: WorkerState;
+  hashrate: number | null;
+  baseline_hashrate: number | null;
+  lastshare: number | null;
+  timestamp: number;
+}
+
+/**
+ * A user's worker state transitions (online, idle, offline, hashrate drops
+ * and recoveries), newest first
+ */
+export async function GET(
+  request: Request,
+  { params }: { params: Promise<{ address: string }> }
+) {
+  try {
+    const { address } = await params;
+    const { searchParams } = new URL(request.url);
+    const parsedLimit = Number.parseInt(searchParams.get('limit') || '', 10);
+    const limit = Number.isFinite(parsedLimit)
+      ? Math.min(Math.max(parsedLimit, 1), 200)
+      : 50;
+
+    const db = await getDb();
+
+    const user = await db.get<{ id: number; is_public: number }>(
+      `SELECT id, is_public FROM monitored_users WHERE address = ?`,
+      [address]
+    );
+
+    // Privacy check: return 403 if user is private
+    if (user && !user.is_public) {
+      return NextResponse.json({ error: 'This user profile is private' }, { status: 403 });
+    }
+
+    if (!user) {
+      return NextResponse.json([], {
+        headers: {
+          'Cache-Control': 's-maxage=60, stale-while-revalidate=120',
+        },
+      });
+    }
+
+    const rows = await db.all<{
+      worker_name: string;
+      event_type: WorkerEventType;
+      previous_state: WorkerState | null;
+      state: WorkerState;
+      hashrate_hs: number | null;
+      baseline_hashrate_hs: number | null;
+      lastshare: number | null;
+      created_at: number;
+    }>(`
+      SELECT worker_name, event_type, previous_state, state, hashrate_hs, baseline_hashrate_hs, lastshare, created_at
+      FROM worker_events
+      WHERE user_id = ?
+      ORDER BY created_at DESC, id DESC
+      LIMIT ?
+    `, [user.id, limit]);
+
+    const events: WorkerEventEntry[] = rows.map(row => {
+      const nameParts = row.worker_name.split('.');
+      return {
+        worker: row.worker_name,
+        name: nameParts.length > 1 ? nameParts[1] : 'default',
+        type: row.event_type,
+        previous_state: row.previous_state,
+        state: row.state,
+        hashrate: row.hashrate_hs,
+        baseline_hashrate: row.baseline_hashrate_hs,
+        lastshare: row.lastshare,
+        timestamp: row.created_at,
+      };
+    });
+
+    return NextResponse.json(events, {
+      headers: {
+        'Cache-Control': 's-maxage=60, stale-while-revalidate=120',
+      },
+    });
+  } catch (error) {
+    log.error('Error fetching worker events', { error });
+    return NextResponse.json(
+      { error: 'Failed to fetch worker events' },
+      {
+        status: 500,
+        headers: {
+          'Cache-Control': 'no-store',
+        },
+      }
+    );
+  }
+}
@@ -1,6 +1,9 @@
 import { NextResponse } from 'next/server';
-import { getDb } from '@/lib/db';
+import { getDb, isUniqueViolation } from '@/lib/db';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/user' });
 
 export async function POST(request: Request) {
   try {
@@ -20,7 +23,7 @@ export async function POST(request: Request) {
       );
     }
 
-    const db = getDb();
+    const db = await getDb();
     const now = Math.floor(Date.now() / 1000);
 
     // Check if user already exists
@@ -35,19 +38,19 @@ export async function POST(request: Request) {
       authorised_at: number;
     }
 
-    const existingUser = db.prepare('SELECT * FROM monitored_users WHERE address = ?').get(address) as MonitoredUser | undefined;
+    const existingUser = await db.get<MonitoredUser>('SELECT * FROM monitored_users WHERE address = ?', [address]);
 
     if (existingUser) {
       // If user exists but was inactive, reactivate them and reset failed attempts
       if (!existingUser.is_active) {
-        db.prepare(`
+        await db.run(`
           UPDATE monitored_users 
           SET 
             is_active = 1,
             failed_attempts = 0,
             updated_at = ?
           WHERE address = ?
-        `).run(now, address);
+        `, [now, address]);
 
         return NextResponse.json(
           { message: 'Address reactivated' },
@@ -63,16 +66,17 @@ export async function POST(request: Request) {
 
     // Check rate limiting (max 10 new users per minute)
     const oneMinuteAgo = now - (1 * 60);
-    const recentUsersCount = db.prepare(
-      'SELECT COUNT(*) as count FROM monitored_users WHERE created_at > ?'
-    ).get(oneMinuteAgo) as { count: number };
+    const recentUsersCount = await db.get<{ count: number }>(
+      'SELECT COUNT(*) as count FROM monitored_users WHERE created_at > ?',
+      [oneMinuteAgo]
+    );
     let numMaxAdds = 10;
     const autoDiscoverEnabled = process.env.AUTO_DISCOVER_USERS !== 'false';
     if (autoDiscoverEnabled) {
       numMaxAdds = 110; // Higher limit if auto-discovery is enabled
     }
 
-    if (recentUsersCount.count >= numMaxAdds) {
+    if ((recentUsersCount?.count ?? 0) >= numMaxAdds) {
       return NextResponse.json(
         { error: 'Too many addresses added recently, please try again later.' },
         { status: 429 }
@@ -80,7 +84,7 @@ export async function POST(request: Request) {
     }
 
     // Add new user
-    const stmt = db.prepare(`
+    await db.run(`
       INSERT INTO monitored_users (
         address,
         is_active,
@@ -89,18 +93,16 @@ export async function POST(request: Request) {
         updated_at,
         authorised_at
       ) VALUES (?, ?, ?, ?, ?, ?)
-    `);
-
-    stmt.run(
+    `, [
       address,
       1,  // is_active
       1,  // is_public
       now,
       now,
-      now  // authorised_at
-    );
+      now, // authorised_at
+    ]);
 
-    console.log(`Added new address to monitor: ${address}`);
+    log.info('Added new address to monitor', { address });
 
     return NextResponse.json({
       message: 'Address added successfully',
@@ -109,10 +111,10 @@ export async function POST(request: Request) {
     });
 
   } catch (error) {
-    console.error('Error adding address:', error);
+    log.error('Error adding address', { error });
     
     // Check for unique constraint violation
-    if (error && typeof error === 'object' && 'code' in error && error.code === 'SQLITE_CONSTRAINT_UNIQUE') {
+    if (isUniqueViolation(error)) {
       return NextResponse.json(
         { error: 'Address already exists' },
         { status: 409 }
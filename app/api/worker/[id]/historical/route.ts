@@ -0,0 +1,132 @@
+import { NextResponse } from 'next/server';
+import { getDb } from '@/lib/db';
+import { isValidBitcoinAddress } from '@/app/utils/validators';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger({ route: '/api/worker/[id]/historical' });
+
+// Enable caching based on interval
+export const revalidate = 60;
+
+export interface HistoricalWorkerStats {
+  timestamp: string;
+  hashrate: number;
+  shares: number;
+  bestshare: number;
+}
+
+// Bucket size, hashrate column and longest period for each interval, matching
+// /api/user/[address]/historical
+const INTERVALS: Record<string, { seconds: number; column: string; maxPeriodDays: number }> = {
+  '1m': { seconds: 60, column: 'hashrate1m_hs', maxPeriodDays: 2 },
+  '5m': { seconds: 5 * 60, column: 'hashrate5m_hs', maxPeriodDays: 10 },
+  '15m': { seconds: 15 * 60, column: 'hashrate5m_hs', maxPeriodDays: 30 },
+  '30m': { seconds: 30 * 60, column: 'hashrate5m_hs', maxPeriodDays: 30 },
+  '1h': { seconds: 60 * 60, column: 'hashrate1hr_hs', maxPeriodDays: 30 },
+};
+
+/**
+ * One row per interval: the last hashrate and share count sampled in it and
+ * the highest best share. Parameters, in order: start, interval, user id,
+ * worker name, start, end.
+ */
+function workerHistoryQuery(column: string): string {
+  return `
+    WITH samples AS (
+      SELECT (created_at - ?) / ? AS bucket, created_at, ${column} AS hashrate, shares, bestshare
+      FROM worker_stats_history
+      WHERE user_id = ? AND worker_name = ? AND created_at >= ? AND created_at < ?
+    ),
+    ranked AS (
+      SELECT
+        bucket,
+        hashrate,
+        shares,
+        MAX(bestshare) OVER (PARTITION BY bucket) AS bestshare,
+        ROW_NUMBER() OVER (PARTITION BY bucket ORDER BY created_at DESC) AS position
+      FROM samples
+    )
+    SELECT bucket, hashrate, shares, bestshare
+    FROM ranked
+    WHERE position = 1
+    ORDER BY bucket ASC
+  `;
+}
+
+function badRequest(error: string) {
+  return NextResponse.json({ error }, { status: 400, headers: { 'Cache-Control': 'no-store' } });
+}
+
+export async function GET(
+  request: Request,
+  { params }: { params: Promise<{ id: string }> }
+) {
+  try {
+    const { id } = await params;
+    const workerName = decodeURIComponent(id);
+    const address = workerName.split('.')[0];
+
+    if (!isValidBitcoinAddress(address)) {
+      return badRequest("Invalid worker id");
+    }
+
+    const { searchParams } = new URL(request.url);
+    const period = searchParams.get('period') || '24h';
+    const interval = searchParams.get('interval') || '5m';
+
+    const intervalConfig = INTERVALS[interval];
+    if (!intervalConfig) {
+      return badRequest(`Interval must be one of ${Object.keys(INTERVALS).join(', ')}`);
+    }
+
+    // Parse period format (e.g., "7d" or "6h")
+    const periodMatch = period.match(/^(\d+)([dh])$/);
+    if (!periodMatch || parseInt(periodMatch[1], 10) <= 0) {
+      return badRequest("Period must be a positive value (e.g., '24h' or '7d')");
+    }
+
+    const value = parseInt(periodMatch[1], 10);
+    const unit = periodMatch[2];
+    const totalDays = unit === 'd' ? value : value / 24;
+    if (totalDays > intervalConfig.maxPeriodDays) {
+      return badRequest(`For ${interval} interval, period cannot exceed ${intervalConfig.maxPeriodDays} days`);
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    const startTime = now - value * (unit === 'd' ? 24 * 60 * 60 : 60 * 60);
+
+    const db = await getDb();
+
+    const user = await db.get<{ id: number }>('SELECT id FROM monitored_users WHERE address = ? AND is_active = 1', [address]);
+    if (!user) {
+      return NextResponse.json(
+        { error: "User not found or not active" },
+        { status: 404 }
+      );
+    }
+
+    const rows = await db.all<{ bucket: number; hashrate: number | null; shares: number; bestshare: number }>(
+      workerHistoryQuery(intervalConfig.column),
+      [startTime, intervalConfig.seconds, user.id, workerName, startTime, now]
+    );
+
+    const results: HistoricalWorkerStats[] = rows.map(row => ({
+      timestamp: new Date((startTime + row.bucket * intervalConfig.seconds) * 1000).toISOString(),
+      hashrate: row.hashrate ?? 0,
+      shares: row.shares,
+      bestshare: row.bestshare,
+    }));
+
+    return NextResponse.json(results, {
+      headers: {
+        'Cache-Control': `s-maxage=${intervalConfig.seconds}, stale-while-revalidate=${intervalConfig.seconds * 2}`
+      }
+    });
+  } catch (error) {
+    log.error('Error fetching historical worker stats', { error });
+    return NextResponse.json(
+      { error: "Failed to fetch historical worker stats" },
+      { status: 500, headers: { 'Cache-Control': 'no-store' } }
+    );
+  }
+}
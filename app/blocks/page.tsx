@@ -0,0 +1,157 @@
+'use client';
+
+import { useState, useEffect, useCallback } from 'react';
+import Link from 'next/link';
+import { formatDifficulty, formatRelativeTime } from '@/app/utils/formatters';
+import type { BlockFound } from '@/app/api/blocks/types';
+
+const PAGE_SIZE = 50;
+
+function formatDuration(seconds: number): string {
+  const days = Math.floor(seconds / 86400);
+  const hours = Math.floor((seconds % 86400) / 3600);
+  const minutes = Math.floor((seconds % 3600) / 60);
+
+  if (days > 0) return `${days}d ${hours}h`;
+  if (hours > 0) return `${hours}h ${minutes}m`;
+  return `${minutes}m`;
+}
+
+function formatBtc(sats: number | null): string {
+  return sats !== null ? `${(sats / 1e8).toFixed(8)} BTC` : '-';
+}
+
+export default function BlocksPage() {
+  const [blocks, setBlocks] = useState<BlockFound[]>([]);
+  const [loading, setLoading] = useState(true);
+  const [loadingMore, setLoadingMore] = useState(false);
+  const [hasMore, setHasMore] = useState(false);
+
+  const fetchBlocks = useCallback(async (before?: number) => {
+    const query = new URLSearchParams({ limit: String(PAGE_SIZE) });
+    if (before !== undefined) query.set('before', String(before));
+
+    const response = await fetch(`/api/blocks?${query}`);
+    if (!response.ok) throw new Error('Failed to fetch blocks');
+    return await response.json() as BlockFound[];
+  }, []);
+
+  useEffect(() => {
+    fetchBlocks()
+      .then((data) => {
+        setBlocks(data);
+        setHasMore(data.length === PAGE_SIZE);
+      })
+      .catch((error) => console.error('Error fetching blocks:', error))
+      .finally(() => setLoading(false));
+  }, [fetchBlocks]);
+
+  const loadMore = async () => {
+    const oldest = blocks[blocks.length - 1];
+    if (!oldest) return;
+
+    setLoadingMore(true);
+    try {
+      const data = await fetchBlocks(oldest.blockHeight);
+      setBlocks((current) => [...current, ...data]);
+      setHasMore(data.length === PAGE_SIZE);
+    } catch (error) {
+      console.error('Error fetching more blocks:', error);
+    } finally {
+      setLoadingMore(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <main className="flex-grow p-4">
+        <div className="text-foreground/60">Loading blocks...</div>
+      </main>
+    );
+  }
+
+  return (
+    <main className="flex-grow p-4">
+      <h1 className="text-3xl font-bold mb-6">Blocks Found</h1>
+
+      {blocks.length === 0 ? (
+        <div className="text-foreground/60">No blocks found yet.</div>
+      ) : (
+        <>
+          <div className="overflow-x-auto border border-border bg-background shadow-md">
+            <table className="w-full min-w-full divide-y divide-border text-sm">
+              <thead className="bg-foreground">
+                <tr>
+                  {['Height', 'Hash', 'Found', 'Time to Find', 'Finder', 'Diff', 'Reward', 'Fees', 'Jobs', 'Outputs', 'Branches', 'Version'].map((header) => (
+                    <th
+                      key={header}
+                      scope="col"
+                      className="px-3 py-2 text-left text-xs text-background uppercase tracking-wider"
+                    >
+                      {header}
+                    </th>
+                  ))}
+                </tr>
+              </thead>
+              <tbody className="divide-y divide-border">
+                {blocks.map((block) => (
+                  <tr key={block.blockHeight} className="hover:bg-foreground/5">
+                    <td className="px-3 py-2 font-mono font-semibold">
+                      <Link href={`/block/${block.blockHeight}`} className="text-accent-1 hover:underline">
+                        {block.blockHeight.toLocaleString()}
+                      </Link>
+                    </td>
+                    <td className="px-3 py-2 font-mono" title={block.blockHash}>
+                      <a
+                        href={`https://mempool.space/block/${block.blockHash}`}
+                        target="_blank"
+                        rel="noopener noreferrer"
+                        className="hover:underline"
+                      >
+                        …{block.blockHash.slice(-8)}
+                      </a>
+                    </td>
+                    <td
+                      className="px-3 py-2 text-accent-3 whitespace-nowrap"
+                      title={block.foundAtSource === 'block' ? 'Block header timestamp' : 'First template on top of the block'}
+                    >
+                      {block.foundAt !== null ? formatRelativeTime(block.foundAt) : '-'}
+                    </td>
+                    <td className="px-3 py-2 whitespace-nowrap">
+                      {block.timeToFind !== null ? formatDuration(block.timeToFind) : '-'}
+                    </td>
+                    <td className="px-3 py-2 font-mono">{block.finder ?? '-'}</td>
+                    <td className="px-3 py-2">{block.winnerDiff !== null ? formatDifficulty(block.winnerDiff) : '-'}</td>
+                    <td className="px-3 py-2 font-mono whitespace-nowrap">{formatBtc(block.reward)}</td>
+                    <td className="px-3 py-2 font-mono whitespace-nowrap">{formatBtc(block.template?.fees ?? null)}</td>
+                    <td className="px-3 py-2">{block.template?.jobCount ?? '-'}</td>
+                    <td className="px-3 py-2">{block.template?.outputCount ?? '-'}</td>
+                    <td className="px-3 py-2">{block.template?.merkleBranchCount ?? '-'}</td>
+                    <td className="px-3 py-2 font-mono">{block.template?.version ?? '-'}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+
+          {hasMore && (
+            <div className="mt-4 text-center">
+              <button
+                onClick={loadMore}
+                disabled={loadingMore}
+                className="px-4 py-2 bg-foreground text-background hover:bg-foreground/80 transition-colors disabled:opacity-50"
+              >
+                {loadingMore ? 'Loading...' : 'Load more'}
+              </button>
+            </div>
+          )}
+
+          <div className="text-xs text-accent-3 mt-2">
+            Template columns describe the last job the pool sent before the block was found, when the
+            stratum collector observed it.
+          </div>
+        </>
+      )}
+    </main>
+  );
+}
@@ -41,6 +41,11 @@ const FooterWithSearchParams = () => {
                     </Link>
                 </div>
                 <div className="flex-1 text-center italic break-all">
+                    <Link href="/blocks">
+                        <span className="cursor-pointer">Blocks</span>
+                    </Link>
+                </div>
+                <div className="flex-1 text-center italic break-all">
           <span className="cursor-pointer" onClick={() => setShowHelpModal(true)}>
             Help
           </span>
@@ -79,6 +84,11 @@ const FooterFallback = () => (
                 <span className="cursor-pointer">Rounds</span>
             </Link>
         </div>
+        <div className="flex-1 text-center italic break-all">
+            <Link href="/blocks">
+                <span className="cursor-pointer">Blocks</span>
+            </Link>
+        </div>
         <div className="flex-1 text-center italic break-all">
             <span className="cursor-pointer">Help</span>
         </div>
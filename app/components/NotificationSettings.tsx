@@ -0,0 +1,232 @@
+'use client';
+
+import { useCallback, useEffect, useState, type FormEvent, type MouseEvent } from 'react';
+import { useWallet } from '@/app/hooks/useWallet';
+import {
+  createNotificationSubscription,
+  deleteNotificationSubscription,
+  getNotificationSubscriptions,
+  type CreatedSubscription,
+  type SubscriptionsResponse,
+} from '@/app/utils/api';
+import type { NotificationChannel, NotificationEvent } from '@/lib/notifications/types';
+import { BellIcon } from '@/app/components/icons';
+import { getCollapsibleContainerClassName, shouldToggleCollapse } from './collapsible';
+import CardHeader from './CardHeader';
+
+interface NotificationSettingsProps {
+  userId: string;
+  className?: string;
+  collapsed: boolean;
+  onToggle: () => void;
+}
+
+const CHANNEL_LABELS: Record<NotificationChannel, string> = {
+  webhook: 'Webhook',
+  email: 'Email',
+  nostr: 'Nostr DM',
+};
+
+const TARGET_PLACEHOLDERS: Record<NotificationChannel, string> = {
+  webhook: 'https://example.com/parastats-webhook',
+  email: 'you@example.com',
+  nostr: 'npub1...',
+};
+
+const EVENT_LABELS: Record<NotificationEvent, string> = {
+  worker_offline: 'Worker goes offline',
+  top_diff: 'Top difficulty for a block',
+  block_found: 'Pool finds a block',
+};
+
+export default function NotificationSettings({ userId, className = '', collapsed, onToggle }: NotificationSettingsProps) {
+  const { lightningToken } = useWallet();
+  const [settings, setSettings] = useState<SubscriptionsResponse | null>(null);
+  const [channel, setChannel] = useState<NotificationChannel | null>(null);
+  const [target, setTarget] = useState('');
+  const [events, setEvents] = useState<NotificationEvent[]>(['worker_offline']);
+  const [created, setCreated] = useState<CreatedSubscription | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    if (!lightningToken) return;
+    try {
+      const data = await getNotificationSubscriptions(userId, lightningToken);
+      setSettings(data);
+      setChannel(current => current ?? data.channels[0] ?? null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load notifications');
+    }
+  }, [userId, lightningToken]);
+
+  useEffect(() => {
+    void load();
+  }, [load]);
+
+  const handleClick = (event: MouseEvent<HTMLDivElement>) => {
+    if (!shouldToggleCollapse(event, '[data-collapse-ignore]')) {
+      return;
+    }
+
+    onToggle();
+  };
+
+  const toggleEvent = (event: NotificationEvent) => {
+    setEvents(current => current.includes(event)
+      ? current.filter(value => value !== event)
+      : [...current, event]);
+  };
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!lightningToken || !channel) return;
+
+    setIsSaving(true);
+    setError(null);
+    setCreated(null);
+    try {
+      const subscription = await createNotificationSubscription(
+        { address: userId, channel, target, events },
+        lightningToken,
+      );
+      setCreated(subscription);
+      setTarget('');
+      await load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save subscription');
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleDelete = async (id: number) => {
+    if (!lightningToken) return;
+    setError(null);
+    try {
+      await deleteNotificationSubscription(userId, id, lightningToken);
+      if (created?.id === id) setCreated(null);
+      await load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to remove subscription');
+    }
+  };
+
+  const containerClassName = getCollapsibleContainerClassName(
+    `w-full bg-background border border-border p-4 sm:p-6 shadow-md ${className}`.trim(),
+    collapsed,
+    true,
+  );
+
+  return (
+    <div className={containerClassName} onClick={handleClick}>
+      <CardHeader
+        title="Notifications"
+        icon={<BellIcon />}
+        className={collapsed ? '' : 'mb-4 sm:mb-6'}
+        titleClassName="text-xl sm:text-2xl font-semibold"
+      />
+
+      {!collapsed && (
+        <div data-collapse-ignore className="space-y-4">
+          {!settings ? (
+            error ? (
+              <p className="text-sm text-red-500">{error}</p>
+            ) : (
+              <div className="h-10 bg-gray-200 dark:bg-gray-700 rounded animate-pulse"></div>
+            )
+          ) : (
+            <>
+              {settings.subscriptions.length > 0 ? (
+                <ul className="divide-y divide-border border border-border">
+                  {settings.subscriptions.map(subscription => (
+                    <li key={subscription.id} className="flex items-center justify-between gap-4 p-3">
+                      <div className="min-w-0">
+                        <p className="font-medium">
+                          {CHANNEL_LABELS[subscription.channel]}{' '}
+                          <span className="text-foreground/60 break-all">{subscription.target}</span>
+                        </p>
+                        <p className="text-sm text-foreground/60">
+                          {subscription.events.map(event => EVENT_LABELS[event]).join(', ')}
+                        </p>
+                      </div>
+                      <button
+                        onClick={() => handleDelete(subscription.id)}
+                        className="text-sm text-red-500 hover:underline shrink-0"
+                      >
+                        Remove
+                      </button>
+                    </li>
+                  ))}
+                </ul>
+              ) : (
+                <p className="text-foreground/60">No notifications set up yet.</p>
+              )}
+
+              {created?.secret && (
+                <div className="text-sm bg-secondary p-3 border border-border">
+                  <p className="mb-1">
+                    Webhook signing secret (shown once). Requests carry
+                    {' '}<code>X-Parastats-Signature: t=&lt;time&gt;,v1=&lt;hmac&gt;</code>, an HMAC-SHA256
+                    of <code>&lt;time&gt;.&lt;body&gt;</code> with this secret.
+                  </p>
+                  <code className="break-all">{created.secret}</code>
+                </div>
+              )}
+
+              {settings.channels.length > 0 ? (
+                <form onSubmit={handleSubmit} className="space-y-3">
+                  <div className="flex flex-col sm:flex-row gap-2">
+                    <select
+                      value={channel ?? ''}
+                      onChange={(e) => setChannel(e.target.value as NotificationChannel)}
+                      className="bg-secondary text-foreground px-3 py-2 border border-border focus:outline-none focus:border-accent-3"
+                    >
+                      {settings.channels.map(value => (
+                        <option key={value} value={value}>{CHANNEL_LABELS[value]}</option>
+                      ))}
+                    </select>
+                    <input
+                      type="text"
+                      value={target}
+                      onChange={(e) => setTarget(e.target.value)}
+                      placeholder={channel ? TARGET_PLACEHOLDERS[channel] : ''}
+                      className="flex-1 bg-secondary text-foreground px-3 py-2 border border-border focus:outline-none focus:border-accent-3"
+                    />
+                  </div>
+                  <div className="flex flex-wrap gap-4">
+                    {settings.events.map(event => (
+                      <label key={event} className="flex items-center gap-2 text-sm">
+                        <input
+                          type="checkbox"
+                          checked={events.includes(event)}
+                          onChange={() => toggleEvent(event)}
+                        />
+                        {EVENT_LABELS[event]}
+                      </label>
+                    ))}
+                  </div>
+                  <button
+                    type="submit"
+                    disabled={isSaving || !target || events.length === 0}
+                    className="px-4 py-2 bg-foreground text-background font-medium hover:bg-foreground/80 disabled:opacity-50 disabled:cursor-not-allowed transition-colors"
+                  >
+                    {isSaving ? 'Saving...' : 'Add notification'}
+                  </button>
+                </form>
+              ) : (
+                <p className="text-sm text-foreground/60">Notifications are not enabled on this server.</p>
+              )}
+
+              {error && (
+                <div className="text-sm text-red-500 bg-red-500/10 p-3 border border-red-500/20">
+                  {error}
+                </div>
+              )}
+            </>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
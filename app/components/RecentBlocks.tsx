@@ -7,6 +7,9 @@ import { Block } from "@mempool/mempool.js/lib/interfaces/bitcoin/blocks";
 import { getPreference, setPreference } from "../../lib/localStorage";
 import { getBlocksTipHeight, getRecentBlocks, getRecentBlockTopDiffs, type BlockTopDiff } from "../utils/api";
 import { PlusIcon, MinusIcon, ChevronLeftIcon, ChevronRightIcon } from "./icons";
+import { useStratumStream } from "../hooks/useStratumStream";
+import { DEFAULT_POOL } from "../api/stratum/types";
+import type { StratumNotification } from "../api/stratum/route";
 import Link from "next/link";
 
 // Combined Mining card that connects both chains
@@ -44,8 +47,18 @@ export default function RecentBlocks() {
   const [isCompact, setIsCompact] = useState(true);
   const [highestDiffs, setHighestDiffs] = useState<Map<number, BlockTopDiff>>(new Map());
   const [initialBlockHeight, setInitialBlockHeight] = useState(0);
+  // Bumped on every clean job from the pool so the tip is refetched right away
+  const [newBlockSignal, setNewBlockSignal] = useState(0);
   const scrollContainerRef = useRef<HTMLDivElement>(null);
 
+  const handleStratumNotification = useCallback((notification: StratumNotification) => {
+    if (notification.cleanJobs) {
+      setNewBlockSignal((signal) => signal + 1);
+    }
+  }, []);
+
+  useStratumStream({ pool: DEFAULT_POOL, onNotification: handleStratumNotification });
+
   const updateArrows = useCallback(() => {
     const container = scrollContainerRef.current;
     if (container) {
@@ -103,7 +116,7 @@ export default function RecentBlocks() {
 
     // Clean up interval on component unmount
     return () => clearInterval(intervalId);
-  }, [currentTipHeight]);
+  }, [currentTipHeight, newBlockSignal]);
 
   // Separate useEffect for scrolling to handle new blocks
   useEffect(() => {
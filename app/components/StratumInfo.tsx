@@ -1,10 +1,15 @@
 "use client";
 
-import { useState, type MouseEvent } from "react";
+import { useCallback, useEffect, useMemo, useState, type MouseEvent } from "react";
 import { motion } from "framer-motion";
 import CardHeader from "@/app/components/CardHeader";
 import { getCollapsibleContainerClassName, shouldToggleCollapse } from "@/app/components/collapsible";
 import { StratumIcon, CopyIcon, CheckIcon } from "@/app/components/icons";
+import { useStratumStream } from "@/app/hooks/useStratumStream";
+import { DEFAULT_POOL } from "@/app/api/stratum/types";
+import type { StratumNotification } from "@/app/api/stratum/route";
+import { decodeCoinbaseScriptSigInfo, getTransaction } from "@/app/utils/bitcoinUtils";
+import { formatDifficulty, formatRelativeTime } from "@/app/utils/formatters";
 
 interface StratumInfoProps {
   userId: string;
@@ -21,6 +26,40 @@ export default function StratumInfo({
 }: StratumInfoProps) {
   const [copiedField, setCopiedField] = useState<string | null>(null);
   const [useHighDiff, setUseHighDiff] = useState(false);
+  const [latestJob, setLatestJob] = useState<StratumNotification | null>(null);
+
+  // Seed with the latest stored job, then follow the live stream
+  useEffect(() => {
+    fetch("/api/stratum")
+      .then((response) => (response.ok ? response.json() : []))
+      .then((data: StratumNotification[]) => {
+        if (data.length > 0) {
+          setLatestJob((current) => current ?? data[0]);
+        }
+      })
+      .catch((error) => console.error("Error fetching stratum data:", error));
+  }, []);
+
+  const handleStratumNotification = useCallback((notification: StratumNotification) => {
+    setLatestJob(notification);
+  }, []);
+
+  const streamStatus = useStratumStream({ pool: DEFAULT_POOL, onNotification: handleStratumNotification });
+
+  const latestJobHeight = useMemo(() => {
+    if (!latestJob) return undefined;
+    try {
+      const coinbaseRaw =
+        latestJob.coinbase1 +
+        (latestJob.extranonce1 || "00000000") +
+        "00".repeat(latestJob.extranonce2Size || 4) +
+        latestJob.coinbase2;
+      const tx = getTransaction(coinbaseRaw);
+      return decodeCoinbaseScriptSigInfo(Buffer.from(tx.ins[0].script)).height;
+    } catch {
+      return undefined;
+    }
+  }, [latestJob]);
 
   const stratumUrl = useHighDiff ? "parasite.wtf:42068" : "parasite.wtf:42069";
   const stratumUsername = `${userId}.WORKER_NAME`;
@@ -161,6 +200,22 @@ export default function StratumInfo({
               </button>
             </div>
           </div>
+
+          {latestJob && (
+            <div className="flex items-center gap-2 text-xs text-accent-3">
+              <div
+                className={`w-2 h-2 rounded-full ${
+                  streamStatus === "open" ? "bg-green-400 animate-pulse" : "bg-yellow-400"
+                }`}
+              ></div>
+              <span>
+                {latestJobHeight !== undefined ? `Mining block ${latestJobHeight} · ` : ""}
+                job {latestJob.jobId} · updated {formatRelativeTime(latestJob.timestamp)}
+                {latestJob.difficulty !== undefined && ` · pool diff ${formatDifficulty(latestJob.difficulty)}`}
+                {latestJob.versionRollingMask && ` · version rolling ${latestJob.versionRollingMask}`}
+              </span>
+            </div>
+          )}
         </div>
       )}
     </div>
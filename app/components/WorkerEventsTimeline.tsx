@@ -0,0 +1,103 @@
+'use client';
+
+import Link from 'next/link';
+import type { MouseEvent } from 'react';
+import type { WorkerEventEntry } from '@/app/api/user/[address]/worker-events/route';
+import { formatHashrate, formatRelativeTime } from '@/app/utils/formatters';
+import { ClockIcon } from '@/app/components/icons';
+import { getCollapsibleContainerClassName, shouldToggleCollapse } from './collapsible';
+import CardHeader from './CardHeader';
+
+interface WorkerEventsTimelineProps {
+  events?: WorkerEventEntry[] | null;
+  isLoading: boolean;
+  collapsed: boolean;
+  onToggle: () => void;
+}
+
+const EVENT_LABELS: Record<WorkerEventEntry['type'], string> = {
+  online: 'came online',
+  idle: 'went idle',
+  offline: 'went offline',
+  hashrate_drop: 'hashrate dropped',
+  hashrate_recovered: 'hashrate recovered',
+};
+
+const EVENT_COLORS: Record<WorkerEventEntry['type'], string> = {
+  online: 'bg-green-500',
+  idle: 'bg-yellow-500',
+  offline: 'bg-red-500',
+  hashrate_drop: 'bg-orange-500',
+  hashrate_recovered: 'bg-green-500',
+};
+
+function describeHashrate(event: WorkerEventEntry): string | null {
+  if (event.hashrate === null) return null;
+  if (event.type === 'hashrate_drop' || event.type === 'hashrate_recovered') {
+    return `${formatHashrate(event.hashrate)} (1d avg ${formatHashrate(event.baseline_hashrate ?? 0)})`;
+  }
+  return formatHashrate(event.hashrate);
+}
+
+export default function WorkerEventsTimeline({ events, isLoading, collapsed, onToggle }: WorkerEventsTimelineProps) {
+  const containerClassName = getCollapsibleContainerClassName(
+    'w-full bg-background border border-border p-4 sm:p-6 shadow-md',
+    collapsed,
+    true,
+  );
+
+  const handleClick = (event: MouseEvent<HTMLDivElement>) => {
+    if (!shouldToggleCollapse(event, '[data-collapse-ignore]')) {
+      return;
+    }
+
+    onToggle();
+  };
+
+  return (
+    <div className={containerClassName} onClick={handleClick}>
+      <CardHeader
+        title="Worker Activity"
+        icon={<ClockIcon />}
+        className={collapsed ? '' : 'mb-4 sm:mb-6'}
+        titleClassName="text-xl sm:text-2xl font-semibold"
+      />
+
+      {!collapsed && (
+        <div data-collapse-ignore>
+          {isLoading ? (
+            <div className="space-y-3">
+              <div className="h-10 bg-gray-200 dark:bg-gray-700 rounded animate-pulse"></div>
+              <div className="h-10 bg-gray-200 dark:bg-gray-700 rounded animate-pulse"></div>
+              <div className="h-10 bg-gray-200 dark:bg-gray-700 rounded animate-pulse"></div>
+            </div>
+          ) : events && events.length > 0 ? (
+            <ol className="relative border-l border-border ml-2">
+              {events.map((event, index) => {
+                const hashrate = describeHashrate(event);
+                return (
+                  <li key={`${event.worker}-${event.timestamp}-${index}`} className="mb-4 ml-4">
+                    <span className={`absolute -left-1.5 mt-1.5 h-3 w-3 rounded-full ${EVENT_COLORS[event.type]}`}></span>
+                    <p className="text-sm text-foreground/60">{formatRelativeTime(event.timestamp)}</p>
+                    <p>
+                      <Link
+                        href={`/worker/${encodeURIComponent(event.worker)}`}
+                        className="font-bold hover:underline"
+                      >
+                        {event.name}
+                      </Link>{' '}
+                      {EVENT_LABELS[event.type]}
+                      {hashrate && <span className="text-foreground/60"> · {hashrate}</span>}
+                    </p>
+                  </li>
+                );
+              })}
+            </ol>
+          ) : (
+            <p className="text-foreground/60">No worker activity recorded yet.</p>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
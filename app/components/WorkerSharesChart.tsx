@@ -0,0 +1,155 @@
+"use client";
+
+import { useEffect, useRef } from "react";
+import * as echarts from "echarts";
+
+interface WorkerSharesChartProps {
+  data?: {
+    dates: string[];
+    shares: number[];
+  };
+  loading?: boolean;
+}
+
+export default function WorkerSharesChart({
+  data,
+  loading = false,
+}: WorkerSharesChartProps) {
+  const chartRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    if (!chartRef.current) return;
+
+    const chart = echarts.init(chartRef.current);
+
+    // Get theme colors from CSS variables
+    const foregroundColor = getComputedStyle(document.documentElement)
+      .getPropertyValue("--foreground")
+      .trim();
+    const borderColor = getComputedStyle(document.documentElement)
+      .getPropertyValue("--border")
+      .trim();
+    const secondaryColor = getComputedStyle(document.documentElement)
+      .getPropertyValue("--secondary")
+      .trim();
+
+    const sharesColor = "#CCCCCC";
+    const fontFamily = '"Courier New", Courier, monospace';
+
+    chart.setOption({
+      backgroundColor: "transparent",
+      animation: false,
+      textStyle: {
+        color: foregroundColor,
+        fontFamily,
+      },
+      tooltip: {
+        trigger: "axis",
+        backgroundColor: secondaryColor,
+        borderColor: borderColor,
+        textStyle: {
+          color: foregroundColor,
+          fontFamily,
+        },
+      },
+      grid: {
+        left: "2%",
+        right: "2%",
+        bottom: "5%",
+        containLabel: true,
+      },
+      xAxis: {
+        type: "category",
+        boundaryGap: false,
+        data: [],
+        axisLine: {
+          lineStyle: {
+            color: borderColor,
+          },
+        },
+        axisLabel: {
+          color: foregroundColor,
+          fontFamily,
+        },
+      },
+      yAxis: {
+        type: "value",
+        name: "Shares",
+        scale: true,
+        nameTextStyle: {
+          color: sharesColor,
+          fontFamily,
+          fontWeight: "bold",
+        },
+        axisLabel: {
+          color: sharesColor,
+          fontFamily,
+          fontWeight: "bold",
+        },
+        splitLine: {
+          lineStyle: {
+            color: borderColor,
+            opacity: 0.2,
+          },
+        },
+      },
+      series: [
+        {
+          name: "Shares",
+          type: "line",
+          data: [],
+          smooth: true,
+          sampling: "average",
+          lineStyle: {
+            color: sharesColor,
+            width: 3,
+          },
+          itemStyle: {
+            color: sharesColor,
+          },
+          showSymbol: false,
+        },
+      ],
+    });
+
+    const handleResize = () => {
+      chart.resize();
+    };
+    window.addEventListener("resize", handleResize);
+
+    return () => {
+      chart.dispose();
+      window.removeEventListener("resize", handleResize);
+    };
+  }, []); // Empty dependency array for initialization
+
+  // Separate effect for data updates
+  useEffect(() => {
+    if (!chartRef.current || !data) return;
+
+    const chart = echarts.getInstanceByDom(chartRef.current);
+    if (chart) {
+      chart.setOption(
+        {
+          xAxis: {
+            data: data.dates,
+          },
+          series: [
+            {
+              data: data.shares,
+            },
+          ],
+        },
+        { notMerge: false }
+      );
+    }
+  }, [data]); // Only run when data changes
+
+  return (
+    <div className="bg-background py-6 shadow-md border border-border">
+      <h2 className="text-2xl font-semibold mb-4 px-6">Shares</h2>
+      {loading && <p className="text-center">Loading data...</p>}
+      <div ref={chartRef} style={{ width: "100%", height: "300px" }}></div>
+    </div>
+  );
+}
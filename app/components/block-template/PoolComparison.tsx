@@ -0,0 +1,154 @@
+"use client";
+
+import { useMemo } from "react";
+import { StratumNotification } from "../../api/stratum/route";
+import {
+  computeCoinbaseOutputs,
+  computeCoinbaseOutputValue,
+  decodeCoinbaseScriptSigInfo,
+  getFormattedCoinbaseAsciiTag,
+  getTransaction,
+} from "../../utils/bitcoinUtils";
+import { formatRelativeTime } from "../../utils/formatters";
+
+interface PoolComparisonProps {
+  notifications: StratumNotification[];
+  referencePool: string;
+}
+
+interface PoolTemplateSummary {
+  pool: string;
+  jobId: string;
+  height?: number;
+  prevBlockHash: string;
+  coinbaseValue: number;
+  outputCount: number;
+  merkleBranchCount: number;
+  version: string;
+  nTime: string;
+  asciiTag: string;
+  timestamp: number;
+}
+
+function summarize(notification: StratumNotification): PoolTemplateSummary {
+  const extranonce1 = notification.extranonce1 || "00000000";
+  const extranonce2Size = notification.extranonce2Size || 4;
+  const coinbaseRaw =
+    notification.coinbase1 +
+    extranonce1 +
+    "00".repeat(extranonce2Size) +
+    notification.coinbase2;
+
+  let height: number | undefined;
+  try {
+    const tx = getTransaction(coinbaseRaw);
+    height = decodeCoinbaseScriptSigInfo(Buffer.from(tx.ins[0].script)).height;
+  } catch (error) {
+    console.error(`Error parsing ${notification.pool} coinbase:`, error);
+  }
+
+  return {
+    pool: notification.pool,
+    jobId: notification.jobId,
+    height,
+    prevBlockHash: notification.prevBlockHash,
+    coinbaseValue: computeCoinbaseOutputValue(coinbaseRaw),
+    outputCount: computeCoinbaseOutputs(coinbaseRaw).length,
+    merkleBranchCount: notification.merkleBranches.length,
+    version: notification.version,
+    nTime: notification.nTime,
+    asciiTag: getFormattedCoinbaseAsciiTag(
+      notification.coinbase1,
+      extranonce1,
+      extranonce2Size,
+      notification.coinbase2
+    ),
+    timestamp: notification.timestamp,
+  };
+}
+
+export default function PoolComparison({
+  notifications,
+  referencePool,
+}: PoolComparisonProps) {
+  const summaries = useMemo(() => notifications.map(summarize), [notifications]);
+  const reference = summaries.find((s) => s.pool === referencePool);
+
+  if (summaries.length < 2) {
+    return null;
+  }
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-lg font-semibold">Pool Comparison</h3>
+      <div className="overflow-x-auto border border-border">
+        <table className="w-full min-w-full divide-y divide-border text-sm">
+          <thead className="bg-foreground">
+            <tr>
+              {["Pool", "Height", "Prev Block", "Reward", "Outputs", "Branches", "Version", "nTime", "Tag", "Received"].map((header) => (
+                <th
+                  key={header}
+                  scope="col"
+                  className="px-3 py-2 text-left text-xs text-background uppercase tracking-wider"
+                >
+                  {header}
+                </th>
+              ))}
+            </tr>
+          </thead>
+          <tbody className="divide-y divide-border">
+            {summaries.map((summary) => {
+              const isReference = summary.pool === referencePool;
+              // A different prev hash means one of the pools hasn't switched to the new tip yet
+              const onDifferentTip =
+                reference !== undefined && summary.prevBlockHash !== reference.prevBlockHash;
+              const valueDelta =
+                reference !== undefined && !isReference
+                  ? summary.coinbaseValue - reference.coinbaseValue
+                  : 0;
+
+              return (
+                <tr
+                  key={summary.pool}
+                  className={isReference ? "bg-foreground/10" : "hover:bg-foreground/5"}
+                >
+                  <td className="px-3 py-2 font-semibold whitespace-nowrap">{summary.pool}</td>
+                  <td className="px-3 py-2 font-mono">{summary.height?.toLocaleString() ?? "-"}</td>
+                  <td
+                    className={`px-3 py-2 font-mono ${onDifferentTip ? "text-yellow-400" : ""}`}
+                    title={summary.prevBlockHash}
+                  >
+                    …{summary.prevBlockHash.slice(-8)}
+                  </td>
+                  <td className="px-3 py-2 font-mono whitespace-nowrap">
+                    {(summary.coinbaseValue / 100000000).toFixed(8)}
+                    {valueDelta !== 0 && (
+                      <span className={`ml-1 text-xs ${valueDelta > 0 ? "text-green-400" : "text-red-400"}`}>
+                        ({valueDelta > 0 ? "+" : ""}
+                        {valueDelta.toLocaleString()} sats)
+                      </span>
+                    )}
+                  </td>
+                  <td className="px-3 py-2">{summary.outputCount}</td>
+                  <td className="px-3 py-2">{summary.merkleBranchCount}</td>
+                  <td className="px-3 py-2 font-mono">{summary.version}</td>
+                  <td className="px-3 py-2 font-mono">{summary.nTime}</td>
+                  <td className="px-3 py-2 text-accent-2 max-w-48 truncate" title={summary.asciiTag}>
+                    {summary.asciiTag || "-"}
+                  </td>
+                  <td className="px-3 py-2 text-accent-3 whitespace-nowrap">
+                    {formatRelativeTime(summary.timestamp)}
+                  </td>
+                </tr>
+              );
+            })}
+          </tbody>
+        </table>
+      </div>
+      <div className="text-xs text-accent-3">
+        Latest mining.notify job from every observed pool. Reward differences are relative to{" "}
+        {referencePool}; a highlighted previous block means that pool is building on a different tip.
+      </div>
+    </div>
+  );
+}
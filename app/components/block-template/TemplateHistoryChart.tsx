@@ -0,0 +1,145 @@
+'use client';
+
+import { useEffect, useRef } from 'react';
+import * as echarts from 'echarts';
+import type { BlockTemplateSummary } from '../../api/stratum/types';
+
+interface TemplateHistoryChartProps {
+  summaries: BlockTemplateSummary[];
+}
+
+export default function TemplateHistoryChart({ summaries }: TemplateHistoryChartProps) {
+  const chartRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    if (chartRef.current) {
+      const chart = echarts.init(chartRef.current);
+
+      // Get theme colors from CSS variables
+      const foregroundColor = getComputedStyle(document.documentElement).getPropertyValue('--foreground').trim();
+      const accentColor1 = getComputedStyle(document.documentElement).getPropertyValue('--accent-1').trim();
+      const accentColor2 = getComputedStyle(document.documentElement).getPropertyValue('--accent-2').trim();
+      const borderColor = getComputedStyle(document.documentElement).getPropertyValue('--border').trim();
+      const secondaryColor = getComputedStyle(document.documentElement).getPropertyValue('--secondary').trim();
+
+      const axisStyle = {
+        axisLine: {
+          lineStyle: {
+            color: borderColor
+          }
+        },
+        axisLabel: {
+          color: foregroundColor,
+          fontFamily: '"Courier New", Courier, monospace'
+        },
+        splitLine: {
+          lineStyle: {
+            color: borderColor,
+            opacity: 0.2
+          }
+        }
+      };
+
+      const option = {
+        backgroundColor: 'transparent',
+        textStyle: {
+          color: foregroundColor,
+          fontFamily: '"Courier New", Courier, monospace'
+        },
+        tooltip: {
+          trigger: 'axis',
+          backgroundColor: secondaryColor,
+          borderColor: borderColor,
+          textStyle: {
+            color: foregroundColor,
+            fontFamily: '"Courier New", Courier, monospace'
+          },
+          formatter: (params: Array<{ dataIndex: number }>) => {
+            const summary = summaries[params[0]?.dataIndex];
+            if (!summary) return '';
+            return [
+              `Block ${summary.blockHeight.toLocaleString()}`,
+              `Fees: ${(summary.maxFees / 100000000).toFixed(8)} BTC`,
+              `Fee share: ${(summary.feeShare * 100).toFixed(2)}%`,
+              `Jobs: ${summary.jobCount}`,
+              `Clean job latency: ${summary.cleanJobLatency !== null ? `${summary.cleanJobLatency}s` : '-'}`,
+            ].join('<br/>');
+          }
+        },
+        legend: {
+          data: ['Fees (BTC)', 'Fee Share (%)'],
+          textStyle: {
+            color: foregroundColor,
+            fontFamily: '"Courier New", Courier, monospace'
+          }
+        },
+        grid: {
+          left: '3%',
+          right: '4%',
+          bottom: '3%',
+          containLabel: true,
+        },
+        xAxis: {
+          type: 'category',
+          data: summaries.map(summary => summary.blockHeight.toString()),
+          ...axisStyle
+        },
+        yAxis: [
+          {
+            type: 'value',
+            name: 'BTC',
+            ...axisStyle
+          },
+          {
+            type: 'value',
+            name: '%',
+            min: 0,
+            max: 100,
+            ...axisStyle,
+            splitLine: { show: false }
+          }
+        ],
+        series: [
+          {
+            name: 'Fees (BTC)',
+            type: 'bar',
+            data: summaries.map(summary => summary.maxFees / 100000000),
+            itemStyle: {
+              color: accentColor1
+            }
+          },
+          {
+            name: 'Fee Share (%)',
+            type: 'line',
+            yAxisIndex: 1,
+            showSymbol: false,
+            data: summaries.map(summary => Number((summary.feeShare * 100).toFixed(2))),
+            lineStyle: {
+              color: accentColor2
+            },
+            itemStyle: {
+              color: accentColor2
+            }
+          }
+        ],
+      };
+
+      chart.setOption(option);
+
+      const handleResize = () => {
+        chart.resize();
+      };
+
+      window.addEventListener('resize', handleResize);
+
+      return () => {
+        chart.dispose();
+        window.removeEventListener('resize', handleResize);
+      };
+    }
+  }, [summaries]);
+
+  return (
+    <div ref={chartRef} style={{ width: '100%', height: '360px' }}></div>
+  );
+}
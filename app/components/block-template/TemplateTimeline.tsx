@@ -0,0 +1,151 @@
+"use client";
+
+import type { TemplateTimeline as TemplateTimelineData, TimelineOutput } from "../../api/stratum/types";
+import { formatAddress } from "../../utils/formatters";
+
+interface TemplateTimelineProps {
+  timeline: TemplateTimelineData;
+  selectedHeight: number | null;
+  onSelectHeight: (height: number | null) => void;
+}
+
+function formatSatsDelta(delta: number | null) {
+  if (delta === null || delta === 0) return null;
+  return (
+    <span className={`ml-1 text-xs ${delta > 0 ? "text-green-400" : "text-red-400"}`}>
+      ({delta > 0 ? "+" : ""}
+      {delta.toLocaleString()} sats)
+    </span>
+  );
+}
+
+function formatCountDelta(delta: number | null) {
+  if (delta === null || delta === 0) return null;
+  return (
+    <span className={`ml-1 text-xs ${delta > 0 ? "text-green-400" : "text-red-400"}`}>
+      ({delta > 0 ? "+" : ""}
+      {delta})
+    </span>
+  );
+}
+
+function describeOutput(output: TimelineOutput): string {
+  const label = output.address ? formatAddress(output.address) : `${output.script.slice(0, 16)}…`;
+  return `${label} (${(output.value / 100000000).toFixed(8)} BTC)`;
+}
+
+function formatTime(timestamp: number): string {
+  return new Date(timestamp * 1000).toLocaleTimeString();
+}
+
+export default function TemplateTimeline({
+  timeline,
+  selectedHeight,
+  onSelectHeight,
+}: TemplateTimelineProps) {
+  return (
+    <div className="space-y-4">
+      <div className="flex flex-wrap items-center justify-between gap-2">
+        <h3 className="text-lg font-semibold">
+          Template Timeline
+          {timeline.blockHeight !== null && (
+            <span className="ml-2 text-accent-2 font-normal">
+              block {timeline.blockHeight.toLocaleString()}
+            </span>
+          )}
+        </h3>
+        {timeline.recentBlocks.length > 1 && (
+          <select
+            value={selectedHeight ?? ""}
+            onChange={(event) =>
+              onSelectHeight(event.target.value ? parseInt(event.target.value, 10) : null)
+            }
+            className="bg-secondary border border-border px-2 py-1 text-sm"
+          >
+            <option value="">Current block</option>
+            {timeline.recentBlocks
+              .filter((block) => block.blockHeight !== null)
+              .map((block) => (
+                <option key={block.prevBlockHash} value={block.blockHeight!}>
+                  {block.blockHeight!.toLocaleString()} ({block.jobCount} jobs)
+                </option>
+              ))}
+          </select>
+        )}
+      </div>
+
+      {timeline.jobs.length === 0 ? (
+        <p className="text-sm text-accent-3">No jobs recorded for this block yet.</p>
+      ) : (
+        <div className="overflow-x-auto border border-border">
+          <table className="w-full min-w-full divide-y divide-border text-sm">
+            <thead className="bg-foreground">
+              <tr>
+                {["Received", "Job", "Fees", "Outputs", "Branches", "nTime", "Version", "Output Changes"].map((header) => (
+                  <th
+                    key={header}
+                    scope="col"
+                    className="px-3 py-2 text-left text-xs text-background uppercase tracking-wider"
+                  >
+                    {header}
+                  </th>
+                ))}
+              </tr>
+            </thead>
+            <tbody className="divide-y divide-border">
+              {/* Newest job first */}
+              {[...timeline.jobs].reverse().map((job) => (
+                <tr key={`${job.jobId}-${job.timestamp}`} className="hover:bg-foreground/5">
+                  <td className="px-3 py-2 text-accent-3 whitespace-nowrap">
+                    {formatTime(job.timestamp)}
+                    {job.cleanJobs && (
+                      <span className="ml-1 text-xs text-accent-1">clean</span>
+                    )}
+                  </td>
+                  <td className="px-3 py-2 font-mono">{job.jobId}</td>
+                  <td className="px-3 py-2 font-mono whitespace-nowrap">
+                    {job.fees !== null ? `${(job.fees / 100000000).toFixed(8)}` : "-"}
+                    {formatSatsDelta(job.coinbaseValueDelta)}
+                  </td>
+                  <td className="px-3 py-2">
+                    {job.outputCount}
+                    {formatCountDelta(job.outputCountDelta)}
+                  </td>
+                  <td className="px-3 py-2">
+                    {job.merkleBranchCount}
+                    {formatCountDelta(job.merkleBranchDelta)}
+                  </td>
+                  <td className={`px-3 py-2 font-mono ${job.nTimeChanged ? "text-yellow-400" : ""}`}>
+                    {job.nTime}
+                  </td>
+                  <td className={`px-3 py-2 font-mono ${job.versionChanged ? "text-yellow-400" : ""}`}>
+                    {job.version}
+                  </td>
+                  <td className="px-3 py-2 text-xs">
+                    {job.outputsAdded.map((output) => (
+                      <div key={`+${output.script}`} className="text-green-400 whitespace-nowrap">
+                        + {describeOutput(output)}
+                      </div>
+                    ))}
+                    {job.outputsRemoved.map((output) => (
+                      <div key={`-${output.script}`} className="text-red-400 whitespace-nowrap">
+                        − {describeOutput(output)}
+                      </div>
+                    ))}
+                    {job.outputsAdded.length === 0 && job.outputsRemoved.length === 0 && (
+                      <span className="text-accent-3">-</span>
+                    )}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+      )}
+      <div className="text-xs text-accent-3">
+        Every job the pool sent on this previous block hash. Deltas compare each job with the one
+        before it; fees are the coinbase value minus the block subsidy.
+      </div>
+    </div>
+  );
+}
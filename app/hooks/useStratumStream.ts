@@ -0,0 +1,56 @@
+'use client';
+
+import { useEffect, useRef, useState } from 'react';
+import type { StratumNotification } from '../api/stratum/route';
+
+export type StratumStreamStatus = 'connecting' | 'open' | 'error';
+
+interface UseStratumStreamOptions {
+  // Only receive notifications from this pool
+  pool?: string;
+  onNotification: (notification: StratumNotification) => void;
+  // Called whenever the stream (re)opens, so callers can refetch anything missed
+  onOpen?: () => void;
+}
+
+/**
+ * Subscribe to /api/stratum/stream. EventSource reconnects on its own, so
+ * the returned status only reflects the current connection attempt.
+ */
+export function useStratumStream({ pool, onNotification, onOpen }: UseStratumStreamOptions): StratumStreamStatus {
+  const [status, setStatus] = useState<StratumStreamStatus>('connecting');
+
+  // Keep the latest callbacks without reopening the stream on every render
+  const onNotificationRef = useRef(onNotification);
+  const onOpenRef = useRef(onOpen);
+  useEffect(() => {
+    onNotificationRef.current = onNotification;
+    onOpenRef.current = onOpen;
+  }, [onNotification, onOpen]);
+
+  useEffect(() => {
+    const url = pool ? `/api/stratum/stream?pool=${encodeURIComponent(pool)}` : '/api/stratum/stream';
+    const source = new EventSource(url);
+
+    source.onopen = () => {
+      setStatus('open');
+      onOpenRef.current?.();
+    };
+
+    source.onerror = () => {
+      setStatus(source.readyState === EventSource.CLOSED ? 'error' : 'connecting');
+    };
+
+    source.addEventListener('notification', (event) => {
+      try {
+        onNotificationRef.current(JSON.parse((event as MessageEvent<string>).data));
+      } catch (error) {
+        console.error('Error parsing stratum stream event:', error);
+      }
+    });
+
+    return () => source.close();
+  }, [pool]);
+
+  return status;
+}
@@ -17,6 +17,21 @@ export function validateEnv() {
     process.exit(1);
   }
 
+  if (process.env.DB_BACKEND === 'postgres' && !process.env.DATABASE_URL) {
+    console.error('❌ DB_BACKEND=postgres requires DATABASE_URL');
+    process.exit(1);
+  }
+
+  if (process.env.NOSTR_PRIVATE_KEY && !/^[0-9a-f]{64}$/i.test(process.env.NOSTR_PRIVATE_KEY.trim())) {
+    console.error('❌ NOSTR_PRIVATE_KEY must be a 64-character hex private key');
+    process.exit(1);
+  }
+
+  if (process.env.SMTP_URL && !process.env.SMTP_FROM) {
+    console.error('❌ SMTP_URL requires SMTP_FROM');
+    process.exit(1);
+  }
+
   console.log('✅ All required environment variables are set');
 }
 
@@ -1,54 +1,149 @@
 "use client";
 
-import { useEffect, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import NotificationDetails from "../components/block-template/NotificationDetails";
+import PoolComparison from "../components/block-template/PoolComparison";
+import TemplateTimeline from "../components/block-template/TemplateTimeline";
+import TemplateHistoryChart from "../components/block-template/TemplateHistoryChart";
 import type { StratumNotification } from "../api/stratum/route";
+import {
+  DEFAULT_POOL,
+  type BlockTemplateSummary,
+  type TemplateTimeline as TemplateTimelineData,
+} from "../api/stratum/types";
+import { useStratumStream } from "../hooks/useStratumStream";
 
 export default function BlockTemplatePage() {
   const [notification, setNotification] = useState<StratumNotification | null>(
     null
   );
+  const [poolNotifications, setPoolNotifications] = useState<StratumNotification[]>([]);
+  const [timeline, setTimeline] = useState<TemplateTimelineData | null>(null);
+  // null follows the block currently being mined
+  const [timelineHeight, setTimelineHeight] = useState<number | null>(null);
+  const [history, setHistory] = useState<BlockTemplateSummary[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [connectionStatus, setConnectionStatus] = useState<
     "connecting" | "connected" | "error"
   >("connecting");
 
-  useEffect(() => {
-    async function fetchStratumData() {
-      try {
-        const response = await fetch("/api/stratum");
-        if (!response.ok) {
-          throw new Error("Failed to fetch stratum data");
-        }
-        const data = await response.json();
-
-        if (data.length > 0) {
-          setNotification(data[0]); // Get the most recent notification
-          setConnectionStatus("connected");
-          setError(null);
-        } else {
-          setNotification(null);
-          setError("No real stratum data available yet");
-          setConnectionStatus("connecting"); // Show as connecting since we're waiting for real data
-        }
-      } catch (error) {
-        console.error("Error fetching stratum data:", error);
-        setError(error instanceof Error ? error.message : "Unknown error");
-        setConnectionStatus("error");
-      } finally {
-        setLoading(false);
+  const fetchStratumData = useCallback(async () => {
+    try {
+      const response = await fetch("/api/stratum");
+      if (!response.ok) {
+        throw new Error("Failed to fetch stratum data");
+      }
+      const data = await response.json();
+
+      if (data.length > 0) {
+        setNotification(data[0]); // Get the most recent notification
+        setConnectionStatus("connected");
+        setError(null);
+      } else {
+        setNotification(null);
+        setError("No real stratum data available yet");
+        setConnectionStatus("connecting"); // Show as connecting since we're waiting for real data
+      }
+    } catch (error) {
+      console.error("Error fetching stratum data:", error);
+      setError(error instanceof Error ? error.message : "Unknown error");
+      setConnectionStatus("error");
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const fetchPoolNotifications = useCallback(async () => {
+    try {
+      const response = await fetch("/api/stratum/pools");
+      if (!response.ok) {
+        throw new Error("Failed to fetch stratum pools");
       }
+      setPoolNotifications(await response.json());
+    } catch (error) {
+      console.error("Error fetching stratum pools:", error);
     }
+  }, []);
+
+  const fetchTimeline = useCallback(async () => {
+    try {
+      const query = timelineHeight !== null ? `?height=${timelineHeight}` : "";
+      const response = await fetch(`/api/stratum/timeline${query}`);
+      if (!response.ok) {
+        throw new Error("Failed to fetch template timeline");
+      }
+      setTimeline(await response.json());
+    } catch (error) {
+      console.error("Error fetching template timeline:", error);
+    }
+  }, [timelineHeight]);
+
+  const fetchHistory = useCallback(async () => {
+    try {
+      const response = await fetch("/api/stratum/history");
+      if (!response.ok) {
+        throw new Error("Failed to fetch template history");
+      }
+      setHistory(await response.json());
+    } catch (error) {
+      console.error("Error fetching template history:", error);
+    }
+  }, []);
 
+  const refreshAll = useCallback(() => {
     fetchStratumData();
+    fetchPoolNotifications();
+    fetchHistory();
+  }, [fetchStratumData, fetchPoolNotifications, fetchHistory]);
 
-    // Set up interval to refresh data every 5 seconds for real-time updates
-    const intervalId = setInterval(fetchStratumData, 5000);
+  const handleStreamOpen = useCallback(() => {
+    refreshAll();
+    fetchTimeline();
+  }, [refreshAll, fetchTimeline]);
 
-    // Clean up the interval when component unmounts
+  const handleStreamNotification = useCallback((update: StratumNotification) => {
+    if (update.pool === DEFAULT_POOL) {
+      setNotification(update);
+      setConnectionStatus("connected");
+      setError(null);
+      if (timelineHeight === null) {
+        fetchTimeline();
+      }
+      // A clean job means the previous height's summary is final
+      if (update.cleanJobs) {
+        fetchHistory();
+      }
+    }
+    // Replace that pool's entry in the comparison, keeping the others
+    setPoolNotifications((current) => [
+      ...current.filter((n) => n.pool !== update.pool),
+      update,
+    ]);
+  }, [timelineHeight, fetchTimeline, fetchHistory]);
+
+  // New jobs are pushed over SSE; refetch whenever the stream (re)opens to fill any gap
+  const streamStatus = useStratumStream({
+    onNotification: handleStreamNotification,
+    onOpen: handleStreamOpen,
+  });
+
+  useEffect(() => {
+    refreshAll();
+  }, [refreshAll]);
+
+  // Also refetches when a different block is picked in the timeline
+  useEffect(() => {
+    fetchTimeline();
+  }, [fetchTimeline]);
+
+  // Fall back to polling if the stream can't be established
+  useEffect(() => {
+    if (streamStatus !== "error") return;
+
+    const intervalId = setInterval(handleStreamOpen, 10000);
     return () => clearInterval(intervalId);
-  }, []);
+  }, [streamStatus, handleStreamOpen]);
 
   const getStatusColor = () => {
     switch (connectionStatus) {
@@ -157,8 +252,38 @@ export default function BlockTemplatePage() {
           )} */}
         </div>
 
+        {poolNotifications.length > 1 && (
+          <div className="bg-background border border-border shadow-lg p-6 mb-4">
+            <PoolComparison
+              notifications={poolNotifications}
+              referencePool={notification?.pool ?? DEFAULT_POOL}
+            />
+          </div>
+        )}
+
         {notification ? (
-          <NotificationDetails notification={notification} />
+          <>
+            <NotificationDetails notification={notification} />
+            {timeline && (
+              <div className="bg-background border border-border shadow-lg p-6 mt-4">
+                <TemplateTimeline
+                  timeline={timeline}
+                  selectedHeight={timelineHeight}
+                  onSelectHeight={setTimelineHeight}
+                />
+              </div>
+            )}
+            {history.length > 1 && (
+              <div className="bg-background border border-border shadow-lg p-6 mt-4">
+                <h3 className="text-lg font-semibold mb-4">Template History</h3>
+                <TemplateHistoryChart summaries={history} />
+                <div className="text-xs text-accent-3 mt-2">
+                  Highest template fees seen per block height over the last 30 days, and their share of
+                  the coinbase reward.
+                </div>
+              </div>
+            )}
+          </>
         ) : (
           <div className="text-center py-12">
             <div className="mb-4">
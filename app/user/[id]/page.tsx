@@ -5,7 +5,7 @@ import Link from 'next/link';
 import { useState, useEffect, useMemo } from 'react';
 import HashrateChart from '../../components/HashrateChart';
 import { isValidBitcoinAddress } from '@/app/utils/validators';
-import { getUserData, getHistoricalUserStats, getHashrate, updateAccountMetadata, getUserBlockDiffs, getUserRounds, getUserRefineryOperatorBadge, type UserBlockDiffEntry, type UserRoundsResponse } from '@/app/utils/api';
+import { getUserData, getHistoricalUserStats, getHashrate, updateAccountMetadata, getUserBlockDiffs, getUserRounds, getUserRefineryOperatorBadge, getUserWorkerEvents, type UserBlockDiffEntry, type UserRoundsResponse, type WorkerEventEntry } from '@/app/utils/api';
 import { ProcessedUserData } from '@/app/api/user/[address]/route';
 import { HistoricalUserStats } from '@/app/api/user/[address]/historical/route';
 import { Hashrate } from '@mempool/mempool.js/lib/interfaces/bitcoin/difficulty';
@@ -24,6 +24,8 @@ import BadgeDisplay from '@/app/components/badges/BadgeDisplay';
 import Refinery from '@/app/components/Refinery';
 import ErrorBoundary from '@/app/components/ErrorBoundary';
 import UserMiners from '@/app/components/UserMiners';
+import WorkerEventsTimeline from '@/app/components/WorkerEventsTimeline';
+import NotificationSettings from '@/app/components/NotificationSettings';
 import CardHeader from '@/app/components/CardHeader';
 import { getCollapsibleContainerClassName, shouldToggleCollapse } from '@/app/components/collapsible';
 
@@ -37,6 +39,8 @@ interface CollapsedSections {
   hashrateChart: boolean;
   rounds: boolean;
   miners: boolean;
+  workerEvents: boolean;
+  notifications: boolean;
 }
 
 const defaultCollapsedSections: CollapsedSections = {
@@ -46,6 +50,8 @@ const defaultCollapsedSections: CollapsedSections = {
   hashrateChart: false,
   rounds: false,
   miners: false,
+  workerEvents: false,
+  notifications: false,
 };
 
 function parseCollapsedSections(value: string | null): CollapsedSections {
@@ -62,6 +68,8 @@ function parseCollapsedSections(value: string | null): CollapsedSections {
     hashrateChart: Boolean(parsedState.hashrateChart),
     rounds: Boolean(parsedState.rounds),
     miners: Boolean(parsedState.miners),
+    workerEvents: Boolean(parsedState.workerEvents),
+    notifications: Boolean(parsedState.notifications),
   };
 }
 
@@ -82,6 +90,8 @@ export default function UserDashboard() {
   const [isConnecting, setIsConnecting] = useState(false);
   const [userBlockDiffs, setUserBlockDiffs] = useState<UserBlockDiffEntry[]>([]);
   const [roundsData, setRoundsData] = useState<UserRoundsResponse | null>(null);
+  // undefined until loaded, null when the profile is private
+  const [workerEvents, setWorkerEvents] = useState<WorkerEventEntry[] | null | undefined>(undefined);
   const [hasRefineryOperatorBadge, setHasRefineryOperatorBadge] = useState(false);
   const [collapsedSections, setCollapsedSections] = useState<CollapsedSections>(defaultCollapsedSections);
   const [loadedCollapsePreferencesUserId, setLoadedCollapsePreferencesUserId] = useState<string | null>(null);
@@ -337,6 +347,29 @@ export default function UserDashboard() {
     };
   }, [userId, isValidAddress]);
 
+  useEffect(() => {
+    if (!isValidAddress) return;
+
+    let mounted = true;
+
+    const fetchWorkerEvents = async () => {
+      try {
+        const data = await getUserWorkerEvents(userId);
+        if (mounted) setWorkerEvents(data);
+      } catch (err) {
+        console.error('Error fetching worker events:', err);
+      }
+    };
+
+    fetchWorkerEvents();
+    const intervalId = setInterval(fetchWorkerEvents, 60000);
+
+    return () => {
+      mounted = false;
+      clearInterval(intervalId);
+    };
+  }, [userId, isValidAddress]);
+
   useEffect(() => {
     if (!isValidAddress) return;
 
@@ -784,6 +817,12 @@ export default function UserDashboard() {
                       collapsed={collapsedSections.dispenser}
                       onToggle={() => toggleCollapsedSection('dispenser')}
                     />
+                    <NotificationSettings
+                      userId={userId}
+                      className="mt-4"
+                      collapsed={collapsedSections.notifications}
+                      onToggle={() => toggleCollapsedSection('notifications')}
+                    />
                   </div>
               )}
             </div>
@@ -829,6 +868,16 @@ export default function UserDashboard() {
               />
           )}
 
+          {/* Worker state changes, hidden for private profiles */}
+          {(workerEvents === undefined || (workerEvents && workerEvents.length > 0)) && (
+              <WorkerEventsTimeline
+                events={workerEvents}
+                isLoading={workerEvents === undefined}
+                collapsed={collapsedSections.workerEvents}
+                onToggle={() => toggleCollapsedSection('workerEvents')}
+              />
+          )}
+
           {/* Rounds Section */}
           {(!hasInitiallyLoaded || allRounds.length > 0) && (
               <div
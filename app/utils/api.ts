@@ -4,6 +4,9 @@ import { Block } from "@mempool/mempool.js/lib/interfaces/bitcoin/blocks";
 import { ProcessedUserData } from "../api/user/[address]/route";
 import { HistoricalPoolStats } from "../api/pool-stats/historical/route";
 import { HistoricalUserStats } from "../api/user/[address]/historical/route";
+import { HistoricalWorkerStats } from "../api/worker/[id]/historical/route";
+import { HttpError } from "@/lib/http-errors";
+import { withRetry, type RetryOptions } from "@/lib/retry";
 
 // Initialize mempoolJS
 const { bitcoin } = mempoolJS();
@@ -20,36 +23,13 @@ export type PoolStats = {
   workSinceLastBlock: number | null;
 }
 
-// Helper function to create a delay
-const delay = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
-
-// Retry helper function for API calls
-async function withRetry<T>(
-  operation: () => Promise<T>,
-  maxRetries = 2,
-  baseDelay = 500
-): Promise<T> {
-  let lastError;
-
-  for (let attempt = 0; attempt <= maxRetries; attempt++) {
-    try {
-      return await operation();
-    } catch (error) {
-      lastError = error;
-      if (attempt < maxRetries) {
-        await delay(baseDelay * (attempt + 1));
-        continue;
-      }
-      throw error;
-    }
-  }
-  throw lastError;
-}
+// Requests from the UI give up sooner than the collectors do
+const RETRY_OPTIONS: RetryOptions = { maxRetries: 2 };
 
 // Hashrate and difficulty APIs
 export async function getHashrate(interval = "1m"): Promise<Hashrate> {
   try {
-    return await withRetry(() => difficulty.getHashrate({ interval }));
+    return await withRetry(() => difficulty.getHashrate({ interval }), RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching hashrate:", error);
     throw error;
@@ -58,7 +38,7 @@ export async function getHashrate(interval = "1m"): Promise<Hashrate> {
 
 export async function getDifficultyAdjustment(): Promise<Adjustment> {
   try {
-    return await withRetry(() => difficulty.getDifficultyAdjustment());
+    return await withRetry(() => difficulty.getDifficultyAdjustment(), RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching difficulty adjustment:", error);
     throw error;
@@ -68,7 +48,7 @@ export async function getDifficultyAdjustment(): Promise<Adjustment> {
 // Blocks APIs
 export async function getBlocksTipHeight(): Promise<number> {
   try {
-    return await withRetry(() => blocks.getBlocksTipHeight());
+    return await withRetry(() => blocks.getBlocksTipHeight(), RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching blocks tip height:", error);
     throw error;
@@ -79,7 +59,7 @@ export async function getRecentBlocks(tipHeight: number): Promise<Block[]> {
   try {
     return await withRetry(() => blocks.getBlocks({
       start_height: tipHeight,
-    }));
+    }), RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching recent blocks:", error);
     throw error;
@@ -93,7 +73,7 @@ export async function getBitcoinPrice(): Promise<number | null> {
       const response = await fetch("https://mempool.space/api/v1/prices");
       const data = await response.json();
       return data.USD;
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching Bitcoin price:", error);
     return null;
@@ -106,10 +86,10 @@ export async function getPoolStats(): Promise<PoolStats> {
     return await withRetry(async () => {
       const response = await fetch("/api/pool-stats");
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching pool stats:", error);
     throw error;
@@ -122,10 +102,10 @@ export async function getUserData(address: string): Promise<ProcessedUserData> {
     return await withRetry(async () => {
       const response = await fetch(`/api/user/${address}`);
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error(`Error fetching data for user ${address}:`, error);
     throw error;
@@ -138,10 +118,10 @@ export async function getHistoricalPoolStats(period: string = "24h", interval: s
     return await withRetry(async () => {
       const response = await fetch(`/api/pool-stats/historical?period=${period}&interval=${interval}`);
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching historical pool stats:", error);
     throw error;
@@ -154,33 +134,48 @@ export async function getHistoricalUserStats(address: string, period: string = "
     return await withRetry(async () => {
       const response = await fetch(`/api/user/${address}/historical?period=${period}&interval=${interval}`);
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error(`Error fetching historical stats for user ${address}:`, error);
     throw error;
   }
 }
 
+// Historical worker stats API
+export async function getHistoricalWorkerStats(workerId: string, period: string = "24h", interval: string = "5m"): Promise<HistoricalWorkerStats[]> {
+  try {
+    return await withRetry(async () => {
+      const response = await fetch(`/api/worker/${encodeURIComponent(workerId)}/historical?period=${period}&interval=${interval}`);
+      if (!response.ok) {
+        throw new HttpError(response.status, response.statusText, response.url);
+      }
+      return await response.json();
+    }, RETRY_OPTIONS);
+  } catch (error) {
+    console.error(`Error fetching historical stats for worker ${workerId}:`, error);
+    throw error;
+  }
+}
+
 export async function updateAccountMetadata(
   btc_address: string,
   metadata: Record<string, unknown>,
   signature: string
 ): Promise<unknown> {
+  // Not retried: a write that timed out may still have been applied
   try {
-    return await withRetry(async () => {
-      const response = await fetch('/api/account/metadata', {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify({ btc_address, metadata, signature }),
-      });
-      if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
-      }
-      return await response.json();
+    const response = await fetch('/api/account/metadata', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ btc_address, metadata, signature }),
     });
+    if (!response.ok) {
+      throw new HttpError(response.status, response.statusText, response.url);
+    }
+    return await response.json();
   } catch (error) {
     console.error(`Error updating account metadata for ${btc_address}:`, error);
     throw error;
@@ -208,10 +203,10 @@ export async function getRecentBlockTopDiffs(limit: number = 10): Promise<BlockT
     return await withRetry(async () => {
       const response = await fetch(`/api/highest-diff?limit=${limit}`);
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error("Error fetching recent block top diffs:", error);
     throw error;
@@ -241,25 +236,46 @@ export async function getUserRounds(address: string, limit: number = 20): Promis
         return null;
       }
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error(`Error fetching rounds for user ${address}:`, error);
     throw error;
   }
 }
 
+export type { WorkerEventEntry } from '../api/user/[address]/worker-events/route';
+import type { WorkerEventEntry } from '../api/user/[address]/worker-events/route';
+
+export async function getUserWorkerEvents(address: string, limit: number = 50): Promise<WorkerEventEntry[] | null> {
+  try {
+    return await withRetry(async () => {
+      const response = await fetch(`/api/user/${address}/worker-events?limit=${limit}`);
+      if (response.status === 403) {
+        return null;
+      }
+      if (!response.ok) {
+        throw new HttpError(response.status, response.statusText, response.url);
+      }
+      return await response.json();
+    }, RETRY_OPTIONS);
+  } catch (error) {
+    console.error(`Error fetching worker events for user ${address}:`, error);
+    throw error;
+  }
+}
+
 export async function getUserBlockDiffs(address: string, limit: number = 50): Promise<UserBlockDiffEntry[]> {
   try {
     return await withRetry(async () => {
       const response = await fetch(`/api/highest-diff?address=${address}&type=user-diffs&limit=${limit}`);
       if (!response.ok) {
-        throw new Error(`HTTP error! status: ${response.status}`);
+        throw new HttpError(response.status, response.statusText, response.url);
       }
       return await response.json();
-    });
+    }, RETRY_OPTIONS);
   } catch (error) {
     console.error(`Error fetching block diffs for user ${address}:`, error);
     throw error;
@@ -276,3 +292,54 @@ export async function getUserRefineryOperatorBadge(address: string): Promise<boo
     return false;
   }
 }
+
+export type { SubscriptionsResponse, SubscriptionEntry, CreatedSubscription } from '../api/notifications/types';
+import type { SubscriptionsResponse, CreatedSubscription, CreateSubscriptionRequest } from '../api/notifications/types';
+
+async function notificationError(response: Response): Promise<Error> {
+  const data = await response.json().catch(() => null) as { error?: string } | null;
+  return new Error(data?.error ?? `HTTP error! status: ${response.status}`);
+}
+
+export async function getNotificationSubscriptions(address: string, lightningToken: string): Promise<SubscriptionsResponse> {
+  try {
+    return await withRetry(async () => {
+      const response = await fetch(`/api/notifications/subscriptions?address=${encodeURIComponent(address)}`, {
+        headers: { 'X-Lightning-Token': lightningToken },
+        cache: 'no-store',
+      });
+      if (!response.ok) {
+        throw await notificationError(response);
+      }
+      return await response.json();
+    }, RETRY_OPTIONS);
+  } catch (error) {
+    console.error(`Error fetching notification subscriptions for ${address}:`, error);
+    throw error;
+  }
+}
+
+export async function createNotificationSubscription(
+  subscription: CreateSubscriptionRequest,
+  lightningToken: string
+): Promise<CreatedSubscription> {
+  const response = await fetch('/api/notifications/subscriptions', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', 'X-Lightning-Token': lightningToken },
+    body: JSON.stringify(subscription),
+  });
+  if (!response.ok) {
+    throw await notificationError(response);
+  }
+  return await response.json();
+}
+
+export async function deleteNotificationSubscription(address: string, id: number, lightningToken: string): Promise<void> {
+  const response = await fetch(`/api/notifications/subscriptions/${id}?address=${encodeURIComponent(address)}`, {
+    method: 'DELETE',
+    headers: { 'X-Lightning-Token': lightningToken },
+  });
+  if (!response.ok) {
+    throw await notificationError(response);
+  }
+}
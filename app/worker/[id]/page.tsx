@@ -1,10 +1,13 @@
 'use client';
 
-import { useEffect, useState } from 'react';
+import { useEffect, useMemo, useState } from 'react';
 import { useParams } from 'next/navigation';
 import Link from 'next/link';
-import { getUserData } from '@/app/utils/api';
+import { getUserData, getHistoricalWorkerStats } from '@/app/utils/api';
 import { ProcessedWorkerData } from '@/app/api/user/[address]/route';
+import { HistoricalWorkerStats } from '@/app/api/worker/[id]/historical/route';
+import HashrateChart from '@/app/components/HashrateChart';
+import WorkerSharesChart from '@/app/components/WorkerSharesChart';
 import { formatHashrate, formatDifficulty, formatRelativeTime } from '@/app/utils/formatters';
 import { TrendingUpIcon, CheckIcon, ClockIcon } from '@/app/components/icons';
 
@@ -15,6 +18,7 @@ export default function WorkerDashboard() {
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [currentTimeSeconds, setCurrentTimeSeconds] = useState(0);
+  const [historicalData, setHistoricalData] = useState<HistoricalWorkerStats[] | null>(null);
 
   // Extract user address from worker ID (format: address.workername)
   const userAddress = workerId.split('.')[0];
@@ -60,6 +64,72 @@ export default function WorkerDashboard() {
     };
   }, [workerId, userAddress]);
 
+  // Fetch historical data on mount and every minute
+  useEffect(() => {
+    let mounted = true;
+
+    const fetchHistoricalData = async () => {
+      try {
+        const data = await getHistoricalWorkerStats(workerId, '3d', '5m');
+        if (mounted) {
+          setHistoricalData(data);
+        }
+      } catch (error) {
+        console.error('Error fetching historical worker data:', error);
+      }
+    };
+
+    fetchHistoricalData();
+    const intervalId = setInterval(fetchHistoricalData, 60000); // Update every minute
+
+    return () => {
+      mounted = false;
+      clearInterval(intervalId);
+    };
+  }, [workerId]);
+
+  // Memoize so the charts only rebuild when the data actually changes, not on every poll
+  const chartDates = useMemo(() => (
+    historicalData?.map(d => new Date(d.timestamp).toLocaleString("en-US", {
+      year: undefined,
+      month: "2-digit",
+      day: "2-digit",
+      hour: "2-digit",
+      minute: "2-digit",
+      hour12: false,
+    }))
+  ), [historicalData]);
+
+  const hashrateChartData = useMemo(() => (
+    historicalData && chartDates ? {
+      timestamps: chartDates,
+      rawTimestamps: historicalData.map(d => new Date(d.timestamp).getTime()),
+      series: [
+        {
+          data: historicalData.map(d => d.hashrate),
+          title: "Hashrate",
+        },
+      ],
+    } : undefined
+  ), [historicalData, chartDates]);
+
+  const hashrateChartBestDiffs = useMemo(() => (
+    historicalData && chartDates ? historicalData
+      .map((d, index) => ({
+        timestamp: chartDates[index],
+        rawTimestamp: new Date(d.timestamp).getTime(),
+        difficulty: d.bestshare,
+      }))
+      .filter(point => point.difficulty > 0) : undefined
+  ), [historicalData, chartDates]);
+
+  const sharesChartData = useMemo(() => (
+    historicalData && chartDates ? {
+      dates: chartDates,
+      shares: historicalData.map(d => d.shares),
+    } : undefined
+  ), [historicalData, chartDates]);
+
   useEffect(() => {
     const updateCurrentTime = () => setCurrentTimeSeconds(Date.now() / 1000);
     updateCurrentTime();
@@ -174,6 +244,19 @@ export default function WorkerDashboard() {
         </div>
       </div>
 
+      <div className="w-full mt-8">
+        <HashrateChart
+          title="Hashrate & Best Share"
+          data={hashrateChartData}
+          bestDiffs={hashrateChartBestDiffs}
+          loading={!historicalData}
+        />
+      </div>
+
+      <div className="w-full mt-8">
+        <WorkerSharesChart data={sharesChartData} loading={!historicalData} />
+      </div>
+
       {/* Worker Details */}
       <div className="w-full mt-8">
         <div className="bg-background p-6 rounded-lg shadow-md border border-border">
@@ -1,5 +1,10 @@
+import { CircuitOpenError } from './circuit-breaker';
 import { isRetryableError } from './http-client';
 import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+import { recordAggregatorCache, routeLabel } from './metrics';
+
+const log = createLogger({ module: 'aggregator-cache' });
 
 interface CacheEntry<T> {
   data: T;
@@ -35,23 +40,27 @@ export async function fetchWithCache<T>(
       }
     }
     cache.set(key, { data, cachedAt: Date.now() });
+    recordAggregatorCache(key, 'fresh');
     return { data, fromCache: false };
   } catch (error) {
     const cached = cache.get(key) as CacheEntry<T> | undefined;
     if (cached && Date.now() - cached.cachedAt > MAX_STALE_AGE_MS) {
       cache.delete(key);
-    } else if (cached && isRetryableError(error)) {
+    } else if (cached && (isRetryableError(error) || error instanceof CircuitOpenError)) {
       // Serve stale data only for transient failures (timeouts, network
-      // errors, 5xx). Authoritative answers like a 404 must propagate —
+      // errors, 5xx, an open circuit). Authoritative answers like a 404 must propagate —
       // masking them with stale data would show departed users forever.
       const ageSeconds = Math.round((Date.now() - cached.cachedAt) / 1000);
-      console.warn(
-        `[aggregator-cache] Fetch failed for "${key}", using cached data (${ageSeconds}s old):`,
-        error instanceof Error ? error.message : error,
-      );
+      log.warn('Fetch failed, using cached data', {
+        upstream: routeLabel(key),
+        ageSeconds,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      recordAggregatorCache(key, 'stale');
       return { data: cached.data, fromCache: true };
     }
     // No cached fallback available (or non-transient error) — rethrow
+    recordAggregatorCache(key, 'error');
     throw error;
   }
 }
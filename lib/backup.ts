@@ -0,0 +1,173 @@
+import fs from 'fs';
+import path from 'path';
+import Database from 'better-sqlite3';
+import { DATABASE_FILE_NAME, getDatabasePath, getDb } from './db';
+import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+import { SqliteDb } from './storage/sqlite';
+
+/**
+ * Database backups
+ *
+ * Copies the live SQLite database with the online backup API, checks the
+ * copy with `PRAGMA integrity_check` and keeps the newest BACKUP_RETENTION_COUNT
+ * files. A backup is written under a temporary name and only renamed once it
+ * passes the check, so every `pool-stats-*.db` in the backup dir is usable.
+ */
+
+const CONFIG = {
+  DIR: process.env.BACKUP_DIR || path.join(path.dirname(getDatabasePath()), 'backups'),
+  RETENTION_COUNT: parsePositiveInt(process.env.BACKUP_RETENTION_COUNT, 7),
+};
+
+const log = createLogger({ module: 'backup' });
+
+const BACKUP_FILE_PATTERN = /^pool-stats-\d{8}T\d{6}Z\.db$/;
+
+export interface BackupInfo {
+  file: string;
+  sizeBytes: number;
+  createdAt: Date;
+}
+
+export interface BackupResult extends BackupInfo {
+  durationMs: number;
+  removed: string[];
+}
+
+export function getBackupDir(): string {
+  return CONFIG.DIR;
+}
+
+function backupFileName(date: Date): string {
+  // 2026-10-18T03:30:00.000Z -> pool-stats-20261018T033000Z.db
+  const stamp = date.toISOString().replace(/[-:]/g, '').replace(/\.\d{3}Z$/, 'Z');
+  return `pool-stats-${stamp}.db`;
+}
+
+/**
+ * Run `PRAGMA integrity_check` on a database file. Returns the problems
+ * found, or an empty array when the file is intact.
+ */
+export function verifyBackup(file: string): string[] {
+  const db = new Database(file, { readonly: true, fileMustExist: true });
+  try {
+    const rows = db.pragma('integrity_check') as { integrity_check: string }[];
+    const problems = rows.map(row => row.integrity_check);
+    return problems.length === 1 && problems[0] === 'ok' ? [] : problems;
+  } finally {
+    db.close();
+  }
+}
+
+/**
+ * Backups in the backup dir, newest first
+ */
+export function listBackups(): BackupInfo[] {
+  if (!fs.existsSync(CONFIG.DIR)) return [];
+
+  return fs.readdirSync(CONFIG.DIR)
+    .filter(name => BACKUP_FILE_PATTERN.test(name))
+    .map(name => {
+      const file = path.join(CONFIG.DIR, name);
+      const stat = fs.statSync(file);
+      return { file, sizeBytes: stat.size, createdAt: stat.mtime };
+    })
+    // File names sort by creation time
+    .sort((a, b) => b.file.localeCompare(a.file));
+}
+
+// The copy inherits WAL mode; switch it back so the backup is one
+// self-contained file that can be opened read-only
+function useRollbackJournal(file: string): void {
+  const db = new Database(file);
+  try {
+    db.pragma('journal_mode = DELETE');
+  } finally {
+    db.close();
+  }
+}
+
+function rotateBackups(): string[] {
+  const removed = listBackups().slice(CONFIG.RETENTION_COUNT).map(backup => backup.file);
+  for (const file of removed) {
+    fs.rmSync(file, { force: true });
+  }
+  return removed;
+}
+
+/**
+ * Take a consistent online backup of the stats database, verify it and
+ * rotate old backups
+ */
+export async function createBackup(): Promise<BackupResult> {
+  const db = await getDb();
+  if (!(db instanceof SqliteDb)) {
+    throw new Error('Backups cover the SQLite backend only; use pg_dump for Postgres');
+  }
+
+  fs.mkdirSync(CONFIG.DIR, { recursive: true });
+
+  const startedAt = Date.now();
+  const file = path.join(CONFIG.DIR, backupFileName(new Date(startedAt)));
+  const partial = `${file}.partial`;
+
+  try {
+    await db.backup(partial);
+    useRollbackJournal(partial);
+
+    const problems = verifyBackup(partial);
+    if (problems.length > 0) {
+      throw new Error(`Backup failed integrity check: ${problems.slice(0, 5).join('; ')}`);
+    }
+
+    fs.renameSync(partial, file);
+  } catch (error) {
+    for (const leftover of [partial, `${partial}-wal`, `${partial}-shm`]) {
+      fs.rmSync(leftover, { force: true });
+    }
+    throw error;
+  }
+
+  const removed = rotateBackups();
+  const durationMs = Date.now() - startedAt;
+  const { size } = fs.statSync(file);
+
+  log.info('Backed up database', { file, sizeBytes: size, durationMs });
+  if (removed.length > 0) {
+    log.info('Removed old backups', { backups: removed.length });
+  }
+
+  return { file, sizeBytes: size, createdAt: new Date(startedAt), durationMs, removed };
+}
+
+/**
+ * Restore a backup into `dataDir` so a process started with
+ * PARASTATS_DATA_DIR=dataDir picks it up. The backup is verified first, and
+ * the target must not already hold a database, so a live one is never
+ * overwritten. Pending migrations run when the restored database is opened.
+ */
+export function restoreBackup(file: string, dataDir: string): string {
+  if (!fs.existsSync(file)) {
+    throw new Error(`Backup ${file} does not exist`);
+  }
+
+  const problems = verifyBackup(file);
+  if (problems.length > 0) {
+    throw new Error(`Backup ${file} failed integrity check: ${problems.slice(0, 5).join('; ')}`);
+  }
+
+  const target = path.join(dataDir, DATABASE_FILE_NAME);
+  for (const existing of [target, `${target}-wal`, `${target}-shm`]) {
+    if (fs.existsSync(existing)) {
+      throw new Error(`${existing} already exists; restore into an empty data dir`);
+    }
+  }
+
+  fs.mkdirSync(dataDir, { recursive: true });
+  const partial = `${target}.partial`;
+  fs.copyFileSync(file, partial);
+  fs.renameSync(partial, target);
+
+  return target;
+}
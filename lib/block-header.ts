@@ -0,0 +1,174 @@
+import { createHash } from 'crypto';
+import { decodeCoinbaseScriptSigInfo, getTransaction } from '@/app/utils/bitcoinUtils';
+import { createLogger } from './logger';
+
+/**
+ * Block header reconstruction
+ *
+ * Rebuilds the coinbase, merkle root and 80-byte header a miner would hash for
+ * a stratum V1 job, given the miner's extranonce2 and nonce, so work on any
+ * stored job can be verified server-side.
+ *
+ * Stratum sends version, nBits and nTime as big-endian hex and the prev hash as
+ * the header's bytes with each 4-byte word reversed; merkle branches are
+ * already in header (internal) byte order.
+ */
+
+// Target of a difficulty-1 share: 0xffff * 2^208
+const DIFF1_TARGET = BigInt(0xffff) << BigInt(208);
+
+const log = createLogger({ module: 'block-header' });
+
+export interface HeaderJob {
+  prevBlockHash: string;
+  coinbase1: string;
+  coinbase2: string;
+  merkleBranches: string[];
+  version: string;
+  nBits: string;
+  nTime: string;
+}
+
+export interface HeaderWork {
+  extranonce1: string;
+  extranonce2: string;
+  nonce: string;
+  /** Overrides the job's nTime when the miner rolled it */
+  nTime?: string;
+  /** BIP 310 rolled version bits, applied under versionRollingMask */
+  versionBits?: string;
+  versionRollingMask?: string;
+}
+
+export interface ReconstructedHeader {
+  blockHeight: number | null;
+  coinbase: string;
+  coinbaseTxid: string;
+  merkleRoot: string;
+  version: string;
+  nTime: string;
+  nonce: string;
+  header: string;
+  hash: string;
+  shareDifficulty: number;
+  networkDifficulty: number;
+  meetsNetworkTarget: boolean;
+}
+
+function sha256d(data: Buffer): Buffer {
+  return createHash('sha256').update(createHash('sha256').update(data).digest()).digest();
+}
+
+function reverseHex(hex: string): string {
+  return Buffer.from(hex, 'hex').reverse().toString('hex');
+}
+
+function swapWords(hex: string): string {
+  return (hex.match(/.{8}/g) ?? []).map(reverseHex).join('');
+}
+
+/**
+ * Stratum prev hash for a display (RPC/explorer) block hash: the display hash
+ * with its 4-byte word order reversed
+ */
+export function toStratumPrevHash(blockHash: string): string {
+  const words = blockHash.toLowerCase().match(/.{8}/g) ?? [];
+  return words.reverse().join('');
+}
+
+/** Display block hash for a stratum prev hash; the inverse of toStratumPrevHash */
+export function fromStratumPrevHash(prevHash: string): string {
+  return toStratumPrevHash(prevHash);
+}
+
+export function buildCoinbase(job: Pick<HeaderJob, 'coinbase1' | 'coinbase2'>, extranonce1: string, extranonce2: string): string {
+  return (job.coinbase1 + extranonce1 + extranonce2 + job.coinbase2).toLowerCase();
+}
+
+/**
+ * Fold the coinbase txid up the branches. Returns the root in internal byte
+ * order, as it appears in the header.
+ */
+export function computeMerkleRoot(coinbase: string, merkleBranches: string[]): string {
+  let node = sha256d(Buffer.from(coinbase, 'hex'));
+  for (const branch of merkleBranches) {
+    node = sha256d(Buffer.concat([node, Buffer.from(branch, 'hex')]));
+  }
+  return node.toString('hex');
+}
+
+/** Apply rolled version bits under the negotiated mask; both big-endian hex */
+export function rollVersion(version: string, versionBits: string, mask: string): string {
+  const base = parseInt(version, 16) >>> 0;
+  const bits = parseInt(versionBits, 16) >>> 0;
+  const rollingMask = parseInt(mask, 16) >>> 0;
+  const rolled = ((base & ~rollingMask) | (bits & rollingMask)) >>> 0;
+  return rolled.toString(16).padStart(8, '0');
+}
+
+export function nBitsToTarget(nBits: string): bigint {
+  const compact = parseInt(nBits, 16) >>> 0;
+  const exponent = compact >>> 24;
+  const mantissa = BigInt(compact & 0x007fffff);
+  return exponent <= 3
+    ? mantissa >> BigInt(8 * (3 - exponent))
+    : mantissa << BigInt(8 * (exponent - 3));
+}
+
+/** Difficulty of a hash (display byte order) or target relative to difficulty 1 */
+export function targetToDifficulty(target: bigint): number {
+  if (target === BigInt(0)) return Infinity;
+  // Keep 8 decimal places of precision through the bigint division
+  const scale = BigInt(100_000_000);
+  return Number((DIFF1_TARGET * scale) / target) / 100_000_000;
+}
+
+/**
+ * Serialize the 80-byte header for a job and the miner's work, then hash it
+ */
+export function reconstructHeader(job: HeaderJob, work: HeaderWork): ReconstructedHeader {
+  const coinbase = buildCoinbase(job, work.extranonce1, work.extranonce2);
+
+  const version = work.versionBits && work.versionRollingMask
+    ? rollVersion(job.version, work.versionBits, work.versionRollingMask)
+    : job.version.toLowerCase();
+  const nTime = (work.nTime ?? job.nTime).toLowerCase();
+  const nonce = work.nonce.toLowerCase();
+  const merkleRoot = computeMerkleRoot(coinbase, job.merkleBranches);
+
+  const header = [
+    reverseHex(version),
+    swapWords(job.prevBlockHash.toLowerCase()),
+    merkleRoot,
+    reverseHex(nTime),
+    reverseHex(job.nBits.toLowerCase()),
+    reverseHex(nonce),
+  ].join('');
+
+  const hash = sha256d(Buffer.from(header, 'hex')).reverse().toString('hex');
+  const hashValue = BigInt(`0x${hash}`);
+  const networkTarget = nBitsToTarget(job.nBits);
+
+  let blockHeight: number | null = null;
+  try {
+    const tx = getTransaction(coinbase);
+    blockHeight = decodeCoinbaseScriptSigInfo(Buffer.from(tx.ins[0].script)).height ?? null;
+  } catch (error) {
+    log.error('Error decoding coinbase height', { error });
+  }
+
+  return {
+    blockHeight,
+    coinbase,
+    coinbaseTxid: reverseHex(sha256d(Buffer.from(coinbase, 'hex')).toString('hex')),
+    merkleRoot: reverseHex(merkleRoot),
+    version,
+    nTime,
+    nonce,
+    header,
+    hash,
+    shareDifficulty: targetToDifficulty(hashValue),
+    networkDifficulty: targetToDifficulty(networkTarget),
+    meetsNetworkTarget: hashValue <= networkTarget,
+  };
+}
@@ -0,0 +1,219 @@
+import { getDb, type Db } from './db';
+import { toStratumPrevHash } from './block-header';
+import { DEFAULT_POOL } from '@/app/api/stratum/types';
+import { enqueueNotification } from './notifications';
+import { createLogger } from './logger';
+import { assertLeaseHeld } from './leader-lease';
+
+/**
+ * Block-found registry
+ *
+ * Every round in the `rounds` table is a block the pool found. This joins each
+ * one with what the stratum collector saw around it: the last template the
+ * pool handed out at that height, and the moment the first template built on
+ * top of the block arrived (our best estimate of when it was found).
+ */
+
+const log = createLogger({ collector: 'blocks-found' });
+
+const CONFIG = {
+  // Rows still missing stratum data are retried for this long after being recorded
+  INCOMPLETE_RETRY_SECONDS: 24 * 60 * 60,
+} as const;
+
+interface RoundRow {
+  block_height: number;
+  block_hash: string;
+  coinbase_value: number | null;
+  winner_diff: number | null;
+  winner_username: string | null;
+  recorded_height: number | null;
+}
+
+interface TemplateSummaryRow {
+  prev_block_hash: string;
+  first_job_at: number;
+  job_count: number;
+  max_coinbase_value: number;
+  max_fees: number;
+}
+
+interface LastJobRow {
+  job_id: string;
+  coinbase_value: number;
+  fees: number | null;
+  output_count: number;
+  merkle_branch_count: number;
+  version: string;
+  n_time: string;
+}
+
+async function getPrimaryPool(db: Db): Promise<string> {
+  const row = await db.get<{ pool: string }>(
+    'SELECT pool FROM stratum_health WHERE is_primary = 1 ORDER BY pool LIMIT 1'
+  );
+  return row?.pool ?? DEFAULT_POOL;
+}
+
+/**
+ * Record any rounds not yet in blocks_found, and retry recent rows that were
+ * recorded before the stratum data they need was available. Runs as part
+ * of the rounds job. Returns the number of rows written.
+ */
+export async function syncBlocksFound(): Promise<number> {
+  try {
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+    const pool = await getPrimaryPool(db);
+
+    const rounds = await db.all<RoundRow>(`
+      SELECT r.block_height, r.block_hash, r.coinbase_value, r.winner_diff, r.winner_username,
+        b.block_height AS recorded_height
+      FROM rounds r
+      LEFT JOIN blocks_found b ON b.block_height = r.block_height
+      WHERE r.block_height != 0
+        AND (
+          b.block_height IS NULL
+          OR ((b.found_at_source IS NULL OR b.found_at_source != 'stratum' OR b.template_job_count IS NULL)
+              AND b.created_at >= ?)
+        )
+      ORDER BY r.block_height ASC
+    `, [now - CONFIG.INCOMPLETE_RETRY_SECONDS]);
+
+    if (rounds.length === 0) return 0;
+
+    const getTemplateSummary = (blockHeight: number) => db.get<TemplateSummaryRow>(`
+      SELECT prev_block_hash, first_job_at, job_count, max_coinbase_value, max_fees
+      FROM stratum_block_summaries
+      WHERE pool = ? AND block_height = ?
+    `, [pool, blockHeight]);
+
+    // Only blocks past the newest one already recorded are news; the first
+    // sync into an empty table is history
+    const latest = await db.get<{ height: number | null }>('SELECT MAX(block_height) AS height FROM blocks_found');
+    const latestRecorded = latest?.height ?? null;
+
+    let written = 0;
+
+    await db.transaction(async () => {
+      await assertLeaseHeld(db);
+      for (const round of rounds) {
+        // The template the pool was mining when it found the block
+        const template = await getTemplateSummary(round.block_height);
+        const lastJob = template
+          ? await db.get<LastJobRow>(`
+              SELECT job_id, coinbase_value, fees, output_count, merkle_branch_count, version, n_time
+              FROM stratum_job_diffs
+              WHERE pool = ? AND block_height = ? AND prev_block_hash = ?
+              ORDER BY id DESC
+              LIMIT 1
+            `, [pool, round.block_height, template.prev_block_hash])
+          : undefined;
+
+        // The first template on top of the block, if it really builds on this hash
+        const next = await getTemplateSummary(round.block_height + 1);
+        let foundAt: number | null = null;
+        let foundAtSource: 'stratum' | 'block' | null = null;
+        if (next && next.prev_block_hash === toStratumPrevHash(round.block_hash)) {
+          foundAt = next.first_job_at;
+          foundAtSource = 'stratum';
+        } else {
+          const block = await db.get<{ block_timestamp: number }>(
+            'SELECT block_timestamp FROM block_highest_diff WHERE block_height = ? AND block_timestamp IS NOT NULL',
+            [round.block_height]
+          );
+          if (block) {
+            foundAt = block.block_timestamp;
+            foundAtSource = 'block';
+          }
+        }
+
+        const previous = await db.get<{ found_at: number }>(`
+          SELECT found_at FROM blocks_found
+          WHERE block_height < ? AND found_at IS NOT NULL
+          ORDER BY block_height DESC
+          LIMIT 1
+        `, [round.block_height]);
+        const timeToFind = foundAt !== null && previous ? foundAt - previous.found_at : null;
+
+        await db.run(`
+          INSERT INTO blocks_found (
+            block_height, block_hash, reward, finder_username, winner_diff,
+            found_at, found_at_source, time_to_find,
+            template_pool, template_job_id, template_job_count, template_first_job_at,
+            template_coinbase_value, template_fees, template_output_count,
+            template_merkle_branch_count, template_version, template_n_time,
+            created_at, updated_at
+          ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+          ON CONFLICT(block_height) DO UPDATE SET
+            block_hash = excluded.block_hash,
+            reward = excluded.reward,
+            finder_username = excluded.finder_username,
+            winner_diff = excluded.winner_diff,
+            found_at = excluded.found_at,
+            found_at_source = excluded.found_at_source,
+            time_to_find = excluded.time_to_find,
+            template_pool = excluded.template_pool,
+            template_job_id = excluded.template_job_id,
+            template_job_count = excluded.template_job_count,
+            template_first_job_at = excluded.template_first_job_at,
+            template_coinbase_value = excluded.template_coinbase_value,
+            template_fees = excluded.template_fees,
+            template_output_count = excluded.template_output_count,
+            template_merkle_branch_count = excluded.template_merkle_branch_count,
+            template_version = excluded.template_version,
+            template_n_time = excluded.template_n_time,
+            updated_at = excluded.updated_at
+        `, [
+          round.block_height,
+          round.block_hash,
+          round.coinbase_value,
+          round.winner_username,
+          round.winner_diff,
+          foundAt,
+          foundAtSource,
+          timeToFind,
+          template ? pool : null,
+          lastJob?.job_id ?? null,
+          template?.job_count ?? null,
+          template?.first_job_at ?? null,
+          lastJob?.coinbase_value ?? template?.max_coinbase_value ?? null,
+          lastJob?.fees ?? template?.max_fees ?? null,
+          lastJob?.output_count ?? null,
+          lastJob?.merkle_branch_count ?? null,
+          lastJob?.version ?? null,
+          lastJob?.n_time ?? null,
+          now,
+          now,
+        ]);
+        written++;
+
+        if (round.recorded_height === null && latestRecorded !== null && round.block_height > latestRecorded) {
+          await enqueueNotification(db, {
+            event: 'block_found',
+            address: null,
+            key: `block_found:${round.block_height}`,
+            title: `Block ${round.block_height} found`,
+            message: `The pool found block ${round.block_height}${round.winner_username ? ` (top share from ${round.winner_username})` : ''}.`,
+            data: {
+              block_height: round.block_height,
+              block_hash: round.block_hash,
+              reward: round.coinbase_value,
+              winner: round.winner_username,
+            },
+            timestamp: foundAt ?? now,
+          });
+        }
+      }
+    });
+
+    if (written > 0) {
+      log.info('Recorded found blocks', { blocks: written });
+    }
+
+    return written;
+  } catch (error) {
+    log.error('Error syncing found blocks', { error });
+    return 0;
+  }
+}
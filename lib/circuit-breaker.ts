@@ -0,0 +1,219 @@
+import { parsePositiveInt } from './env';
+import { CircuitOpenError } from './http-errors';
+import { createLogger } from './logger';
+import { recordCircuitRejection, recordCircuitState } from './metrics';
+
+/**
+ * Circuit breakers for upstream services
+ *
+ * lib/http-client's fetch maps each request to an upstream by origin and asks
+ * that upstream's breaker first. After CIRCUIT_BREAKER_FAILURE_THRESHOLD
+ * consecutive failures (network errors, timeouts or 5xx) the circuit opens
+ * and requests fail at once with CircuitOpenError instead of piling retries
+ * onto a service that is down. After CIRCUIT_BREAKER_OPEN_SECONDS one probe
+ * request is let through (half-open): success closes the circuit, failure
+ * opens it again. Any other response, including 4xx and 429, counts as the
+ * upstream being up.
+ *
+ * Breakers are per process and survive HMR reloads via globalThis.
+ */
+
+export { CircuitOpenError };
+
+export const UPSTREAMS = ['api', 'mempool', 'router', 'dispenser', 'lightning'] as const;
+
+export type Upstream = (typeof UPSTREAMS)[number];
+
+export type CircuitState = 'closed' | 'open' | 'half_open';
+
+export interface CircuitStatus {
+  upstream: Upstream;
+  state: CircuitState;
+  consecutiveFailures: number;
+  /** Unix time the circuit last opened */
+  openedAt: number | null;
+  /** Unix time an open circuit lets its next probe through */
+  retryAt: number | null;
+  lastFailureAt: number | null;
+  lastError: string | null;
+  /** Times the circuit has opened since the process started */
+  opens: number;
+}
+
+const CONFIG = {
+  FAILURE_THRESHOLD: parsePositiveInt(process.env.CIRCUIT_BREAKER_FAILURE_THRESHOLD, 5),
+  OPEN_MS: parsePositiveInt(process.env.CIRCUIT_BREAKER_OPEN_SECONDS, 30) * 1000,
+};
+
+// Base URL of each upstream; requests are matched by origin
+const UPSTREAM_URLS: Record<Upstream, () => string | undefined> = {
+  api: () => process.env.API_URL,
+  mempool: () => 'https://mempool.space',
+  router: () => process.env.ROUTER_API_URL,
+  dispenser: () => process.env.DISPENSER_API_URL,
+  lightning: () => process.env.LIGHTNING_API_URL,
+};
+
+const log = createLogger({ module: 'circuit-breaker' });
+
+export class CircuitBreaker {
+  private state: CircuitState = 'closed';
+  private consecutiveFailures = 0;
+  private openedAt: number | null = null;
+  private probeInFlight = false;
+  private lastFailureAt: number | null = null;
+  private lastError: string | null = null;
+  private opens = 0;
+
+  constructor(
+    public readonly upstream: Upstream,
+    private readonly failureThreshold = CONFIG.FAILURE_THRESHOLD,
+    private readonly openMs = CONFIG.OPEN_MS
+  ) {
+    recordCircuitState(upstream, 'closed');
+  }
+
+  /**
+   * Whether a request may be sent now. Moves an open circuit to half-open
+   * once openMs has passed and admits that request as the only probe.
+   */
+  tryAcquire(now = Date.now()): boolean {
+    if (this.state === 'closed') return true;
+
+    if (this.state === 'open' && now >= (this.openedAt ?? 0) + this.openMs) {
+      this.transition('half_open');
+    }
+    if (this.state === 'half_open' && !this.probeInFlight) {
+      this.probeInFlight = true;
+      return true;
+    }
+    return false;
+  }
+
+  recordSuccess(): void {
+    this.probeInFlight = false;
+    this.consecutiveFailures = 0;
+    if (this.state !== 'closed') {
+      log.info('Circuit closed', { upstream: this.upstream });
+      this.transition('closed');
+    }
+  }
+
+  recordFailure(error: unknown, now = Date.now()): void {
+    this.probeInFlight = false;
+    this.consecutiveFailures++;
+    this.lastFailureAt = now;
+    this.lastError = error instanceof Error ? error.message : String(error);
+
+    if (this.state === 'half_open' || (this.state === 'closed' && this.consecutiveFailures >= this.failureThreshold)) {
+      this.openedAt = now;
+      this.opens++;
+      log.warn('Circuit opened', {
+        upstream: this.upstream,
+        consecutiveFailures: this.consecutiveFailures,
+        openSeconds: this.openMs / 1000,
+        error: this.lastError,
+      });
+      this.transition('open');
+    }
+  }
+
+  /** A request ended without saying anything about the upstream (e.g. the caller aborted it) */
+  release(): void {
+    this.probeInFlight = false;
+  }
+
+  /** Whether requests are currently being refused */
+  isOpen(now = Date.now()): boolean {
+    if (this.state === 'open') return now < (this.openedAt ?? 0) + this.openMs;
+    return this.state === 'half_open' && this.probeInFlight;
+  }
+
+  /** When a refused request could next get through */
+  retryAt(): number {
+    return (this.openedAt ?? Date.now()) + this.openMs;
+  }
+
+  status(): CircuitStatus {
+    const toSeconds = (ms: number | null) => (ms === null ? null : Math.floor(ms / 1000));
+    return {
+      upstream: this.upstream,
+      state: this.state,
+      consecutiveFailures: this.consecutiveFailures,
+      openedAt: toSeconds(this.openedAt),
+      retryAt: this.state === 'closed' ? null : toSeconds(this.retryAt()),
+      lastFailureAt: toSeconds(this.lastFailureAt),
+      lastError: this.lastError,
+      opens: this.opens,
+    };
+  }
+
+  private transition(state: CircuitState): void {
+    this.state = state;
+    recordCircuitState(this.upstream, state);
+  }
+}
+
+const globalForCircuits = globalThis as typeof globalThis & {
+  __circuitBreakers?: Map<Upstream, CircuitBreaker>;
+};
+
+export function getCircuitBreaker(upstream: Upstream): CircuitBreaker {
+  globalForCircuits.__circuitBreakers ??= new Map();
+  let breaker = globalForCircuits.__circuitBreakers.get(upstream);
+  if (!breaker) {
+    breaker = new CircuitBreaker(upstream);
+    globalForCircuits.__circuitBreakers.set(upstream, breaker);
+  }
+  return breaker;
+}
+
+function originOf(url: string): string | null {
+  try {
+    return new URL(url).origin;
+  } catch {
+    return null;
+  }
+}
+
+/** The upstream a request URL belongs to, or null for anything else */
+export function upstreamForUrl(url: string): Upstream | null {
+  const origin = originOf(url);
+  if (!origin) return null;
+
+  for (const upstream of UPSTREAMS) {
+    const base = UPSTREAM_URLS[upstream]();
+    if (base && originOf(base) === origin) return upstream;
+  }
+  return null;
+}
+
+/**
+ * Whether requests to `upstream` are being refused right now. Collectors
+ * check this between batches to stop a fan-out early.
+ */
+export function isCircuitOpen(upstream: Upstream): boolean {
+  return getCircuitBreaker(upstream).isOpen();
+}
+
+/**
+ * Ask the breaker for `url`'s upstream whether a request may go out; throws
+ * CircuitOpenError when it may not. Returns the breaker to report the
+ * outcome to, or null when the URL isn't a known upstream.
+ */
+export function acquireCircuit(url: string): CircuitBreaker | null {
+  const upstream = upstreamForUrl(url);
+  if (!upstream) return null;
+
+  const breaker = getCircuitBreaker(upstream);
+  if (!breaker.tryAcquire()) {
+    recordCircuitRejection(upstream);
+    throw new CircuitOpenError(upstream, breaker.retryAt());
+  }
+  return breaker;
+}
+
+/** State of every upstream's breaker in this process, for health output */
+export function getCircuitStates(): CircuitStatus[] {
+  return UPSTREAMS.map(upstream => getCircuitBreaker(upstream).status());
+}
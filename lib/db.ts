@@ -1,290 +1,141 @@
-import Database from 'better-sqlite3';
 import path from 'path';
 import fs from 'fs';
+import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+import { SqliteDb } from './storage/sqlite';
+import { PostgresDb } from './storage/postgres';
+import type { Db } from './storage/types';
+
+export type { Db, SqlValue } from './storage/types';
 
-// Ensure the data directory exists - configurable via environment variable
 const dataDir = process.env.PARASTATS_DATA_DIR || path.join(process.cwd(), 'data');
-if (!fs.existsSync(dataDir)) {
-  fs.mkdirSync(dataDir, { recursive: true });
-}
 
-const dbPath = path.join(dataDir, 'pool-stats.db');
+export const DATABASE_FILE_NAME = 'pool-stats.db';
+
+// Metrics kept by the pool_stats rollup tables (hashrates parsed to H/s)
+export const POOL_STATS_ROLLUP_METRICS = [
+  'users',
+  'workers',
+  'hashrate1m',
+  'hashrate5m',
+  'hashrate15m',
+  'hashrate1hr',
+  'hashrate6hr',
+  'hashrate1d',
+  'hashrate7d',
+] as const;
+
+export const POOL_STATS_ROLLUP_RESOLUTIONS = ['5m', '1h', '1d'] as const;
+
+const CONFIG = {
+  // 'sqlite' keeps everything in one file under PARASTATS_DATA_DIR; 'postgres'
+  // connects to DATABASE_URL so several web instances can share the data
+  BACKEND: process.env.DB_BACKEND === 'postgres' ? 'postgres' : 'sqlite',
+  DATABASE_URL: process.env.DATABASE_URL,
+  POOL_SIZE: parsePositiveInt(process.env.DB_POOL_SIZE, 10),
+  // Apply pending schema migrations when the first connection opens. Disable
+  // to run them explicitly with `pnpm migrate apply` as a deploy step.
+  AUTO_MIGRATE: process.env.DB_AUTO_MIGRATE !== 'false',
+} as const;
+
+const log = createLogger({ module: 'db' });
 
 // Singleton database instance
-let db: Database.Database | null = null;
+let db: Db | null = null;
+let schemaCheck: Promise<void> | null = null;
 
-export function getDb(): Database.Database {
-  if (!db) {
-    db = new Database(dbPath, { timeout: 10000 });
-    
-    // Enable foreign keys
-    db.pragma('journal_mode = WAL');
-    db.pragma('synchronous = NORMAL');
-    db.pragma('foreign_keys = ON');
-    
-    // Create tables if they don't exist
-    initializeTables();
-  }
-  return db;
+// The SQLite database file; unused with DB_BACKEND=postgres
+export function getDatabasePath(): string {
+  return path.join(dataDir, DATABASE_FILE_NAME);
 }
 
-function addColumnIfNotExists(db: Database.Database, alterStatement: string) {
-  try {
-    db.exec(alterStatement);
-  } catch (error: unknown) {
-    if (error instanceof Error && !error.message.includes('duplicate column name')) {
-      console.error(`Error running "${alterStatement}":`, error);
+function openDb(): Db {
+  if (CONFIG.BACKEND === 'postgres') {
+    if (!CONFIG.DATABASE_URL) {
+      throw new Error('DB_BACKEND=postgres requires DATABASE_URL');
     }
+    return PostgresDb.open(CONFIG.DATABASE_URL, CONFIG.POOL_SIZE);
   }
-}
-
-function initializeTables() {
-  const db = getDb();
-  
-  // Create pool stats table
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS pool_stats (
-      id INTEGER PRIMARY KEY AUTOINCREMENT,
-      timestamp INTEGER NOT NULL,
-      runtime INTEGER NOT NULL,
-      users INTEGER NOT NULL,
-      workers INTEGER NOT NULL,
-      idle INTEGER NOT NULL,
-      disconnected INTEGER NOT NULL,
-      hashrate1m TEXT NOT NULL,
-      hashrate5m TEXT NOT NULL,
-      hashrate15m TEXT NOT NULL,
-      hashrate1hr TEXT NOT NULL,
-      hashrate6hr TEXT NOT NULL,
-      hashrate1d TEXT NOT NULL,
-      hashrate7d TEXT NOT NULL
-    )
-  `);
-  
-  // Create index on timestamp for faster queries
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_pool_stats_timestamp ON pool_stats(timestamp)
-  `);
-
-  // Create monitored users table
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS monitored_users (
-      id INTEGER PRIMARY KEY AUTOINCREMENT,
-      address TEXT UNIQUE NOT NULL,
-      is_active BOOLEAN NOT NULL DEFAULT 1,
-      is_public BOOLEAN NOT NULL DEFAULT 1,
-      bestever REAL DEFAULT 0,
-      authorised_at INTEGER DEFAULT 0,
-      failed_attempts INTEGER NOT NULL DEFAULT 0,
-      total_blocks INTEGER NOT NULL DEFAULT 0,
-      created_at INTEGER NOT NULL,
-      updated_at INTEGER NOT NULL
-    )
-  `);
-
-  addColumnIfNotExists(db, `ALTER TABLE monitored_users ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0`);
-  addColumnIfNotExists(db, `ALTER TABLE monitored_users ADD COLUMN total_blocks INTEGER NOT NULL DEFAULT 0`);
-
-  // Create index on address for faster lookups
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_monitored_users_address ON monitored_users(address)
-  `);
-
-  // Create index on is_active for efficient filtering during collection
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_monitored_users_active ON monitored_users(is_active)
-  `);
-
-  // Index to speed up loyalty leaderboard queries (filter active/public, sort by total_blocks)
-  // Note: Composite index only helps when filtering left-to-right (is_active first, then is_public).
-  // Won't help queries filtering on is_public alone or sorting by total_blocks without the filters.
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_monitored_users_loyalty
-    ON monitored_users(is_active, is_public, total_blocks DESC)
-  `);
-
-  // Create user stats history table
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS user_stats_history (
-      id INTEGER PRIMARY KEY AUTOINCREMENT,
-      user_id INTEGER NOT NULL,
-      hashrate1m TEXT NOT NULL,
-      hashrate5m TEXT NOT NULL,
-      hashrate1hr TEXT NOT NULL,
-      hashrate1d TEXT NOT NULL,
-      hashrate7d TEXT NOT NULL,
-      workers INTEGER NOT NULL,
-      bestshare REAL NOT NULL,
-      bestever REAL NOT NULL,
-      created_at INTEGER NOT NULL,
-      FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
-    )
-  `);
-
-  // Create indexes for efficient querying of historical data
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_user_stats_user_time ON user_stats_history(user_id, created_at);
-    CREATE INDEX IF NOT EXISTS idx_user_stats_created_at ON user_stats_history(created_at);
-  `);
-
-  // Create stratum notifications table
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS stratum_notifications (
-      id INTEGER PRIMARY KEY AUTOINCREMENT,
-      notification_id TEXT NOT NULL,
-      timestamp INTEGER NOT NULL,
-      pool TEXT NOT NULL,
-      job_id TEXT NOT NULL,
-      prev_block_hash TEXT NOT NULL,
-      coinbase1 TEXT NOT NULL,
-      coinbase2 TEXT NOT NULL,
-      merkle_branches TEXT NOT NULL,
-      version TEXT NOT NULL,
-      n_bits TEXT NOT NULL,
-      n_time TEXT NOT NULL,
-      clean_jobs BOOLEAN NOT NULL,
-      extranonce1 TEXT,
-      extranonce2_size INTEGER,
-      raw_message TEXT NOT NULL,
-      created_at INTEGER NOT NULL,
-      UNIQUE(notification_id)
-    )
-  `);
-
-  // Create indexes for efficient querying of stratum data
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_stratum_timestamp ON stratum_notifications(timestamp);
-    CREATE INDEX IF NOT EXISTS idx_stratum_pool ON stratum_notifications(pool);
-  `);
-
-  // Create block highest diff table (pool-wide winner per block)
-  // block_timestamp is the actual Bitcoin block timestamp from mempool.space
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS block_highest_diff (
-      block_height INTEGER PRIMARY KEY,
-      winner_address TEXT NOT NULL,
-      difficulty REAL NOT NULL,
-      block_timestamp INTEGER
-    )
-  `);
 
-  // Index for leaderboard queries by winner
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_block_highest_diff_winner ON block_highest_diff(winner_address);
-  `);
-
-  // Create per-user block diff table with foreign key to block_highest_diff
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS user_block_diff (
-      block_height INTEGER NOT NULL,
-      address TEXT NOT NULL,
-      difficulty REAL NOT NULL,
-      PRIMARY KEY (block_height, address),
-      FOREIGN KEY (block_height) REFERENCES block_highest_diff(block_height) ON DELETE CASCADE
-    )
-  `);
-
-  // Index for user-specific queries
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_user_block_diff_address ON user_block_diff(address);
-  `);
-
-  // Create rounds table (caches completed round metadata from GET /rounds)
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS rounds (
-      block_height INTEGER PRIMARY KEY,
-      block_hash TEXT NOT NULL,
-      coinbase_value INTEGER,
-      winner_diff REAL,
-      winner_username TEXT,
-      participant_status TEXT NOT NULL DEFAULT 'pending',
-      participant_fetched_at INTEGER,
-      error_message TEXT,
-      created_at INTEGER NOT NULL
-    )
-  `);
-
-  // Create round participants table (per-user stats per round)
-  // block_height = 0 is a sentinel for current-round data from /rounds/current
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS round_participants (
-      block_height INTEGER NOT NULL,
-      username TEXT NOT NULL,
-      top_diff REAL NOT NULL DEFAULT 0,
-      blocks_participated INTEGER NOT NULL DEFAULT 0,
-      total_work REAL NOT NULL DEFAULT 0,
-      PRIMARY KEY (block_height, username)
-    )
-  `);
-
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_round_participants_diff
-      ON round_participants(block_height, top_diff DESC);
-    CREATE INDEX IF NOT EXISTS idx_round_participants_blocks
-      ON round_participants(block_height, blocks_participated DESC);
-    CREATE INDEX IF NOT EXISTS idx_round_participants_username
-      ON round_participants(username, block_height DESC);
-  `);
+  // Ensure the data directory exists - configurable via environment variable
+  if (!fs.existsSync(dataDir)) {
+    fs.mkdirSync(dataDir, { recursive: true });
+  }
+  return SqliteDb.open(getDatabasePath());
+}
 
-  // Create block participants table (users who submitted shares at the exact block height)
-  db.exec(`
-    CREATE TABLE IF NOT EXISTS block_participants (
-      block_height INTEGER NOT NULL,
-      username TEXT NOT NULL,
-      PRIMARY KEY (block_height, username)
-    )
-  `);
+/**
+ * The shared connection, without checking the schema. Only the migrate CLI
+ * should need this; everything else goes through getDb.
+ */
+export function getDbWithoutMigrations(): Db {
+  if (!db) {
+    db = openDb();
+  }
+  return db;
+}
 
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_block_participants_username
-      ON block_participants(username, block_height DESC)
-  `);
+async function checkSchema(connection: Db): Promise<void> {
+  if (CONFIG.AUTO_MIGRATE) {
+    await connection.migrate();
+    return;
+  }
 
-  addColumnIfNotExists(db, `ALTER TABLE monitored_users ADD COLUMN has_refinery_badge BOOLEAN NOT NULL DEFAULT 0`);
+  const { currentVersion, latestVersion } = await connection.getMigrationStatus();
+  if (currentVersion < latestVersion) {
+    log.warn('Database schema is behind, run `pnpm migrate apply`', { currentVersion, latestVersion });
+  }
+}
 
-  addColumnIfNotExists(db, `ALTER TABLE rounds ADD COLUMN block_participant_status TEXT NOT NULL DEFAULT 'pending'`);
-  addColumnIfNotExists(db, `ALTER TABLE rounds ADD COLUMN block_participant_fetched_at INTEGER`);
+export async function getDb(): Promise<Db> {
+  const connection = getDbWithoutMigrations();
 
-  // Add total_work to existing round_participants caches. When newly added,
-  // re-mark completed rounds as pending so the collector refetches real work.
-  const hadTotalWork = (db.prepare(`PRAGMA table_info(round_participants)`).all() as { name: string }[])
-    .some((column) => column.name === 'total_work');
-  addColumnIfNotExists(db, `ALTER TABLE round_participants ADD COLUMN total_work REAL NOT NULL DEFAULT 0`);
-  if (!hadTotalWork) {
-    db.prepare(`UPDATE rounds SET participant_status = 'pending' WHERE participant_status = 'complete'`).run();
+  if (!schemaCheck) {
+    schemaCheck = checkSchema(connection).catch(error => {
+      // Let the next caller retry
+      schemaCheck = null;
+      throw error;
+    });
   }
+  await schemaCheck;
 
-  // Created after the total_work column is guaranteed to exist (the column is
-  // only added to pre-existing round_participants tables by the migration above).
-  db.exec(`
-    CREATE INDEX IF NOT EXISTS idx_round_participants_work
-      ON round_participants(block_height, total_work DESC);
-  `);
+  return connection;
 }
 
-export function checkpointWal() {
+// SQLite only; Postgres manages its own WAL
+export async function checkpointWal() {
   try {
-    const [result] = getDb().pragma('wal_checkpoint(TRUNCATE)') as {
-      busy: number;
-      log: number;
-      checkpointed: number;
-    }[];
+    const connection = await getDb();
+    if (!(connection instanceof SqliteDb)) return null;
+
+    const result = connection.checkpointWal();
     if (result.busy === 1) {
-      console.warn(
-        `WAL checkpoint could not complete (reader holding the WAL): ${result.checkpointed}/${result.log} frames checkpointed`
-      );
+      log.warn('WAL checkpoint could not complete, a reader is holding the WAL', {
+        checkpointed: result.checkpointed,
+        frames: result.log,
+      });
     }
     return result;
   } catch (error) {
-    console.warn('WAL checkpoint failed:', error);
+    log.warn('WAL checkpoint failed', { error });
     return null;
   }
 }
 
 // Close the database connection when the app is shutting down
-export function closeDb() {
+export async function closeDb() {
   if (db) {
-    db.close();
+    const connection = db;
     db = null;
+    schemaCheck = null;
+    await connection.close();
   }
 }
+
+// True when an insert hit a UNIQUE or PRIMARY KEY constraint, on either backend
+export function isUniqueViolation(error: unknown): boolean {
+  if (!error || typeof error !== 'object' || !('code' in error)) return false;
+  return error.code === 'SQLITE_CONSTRAINT_UNIQUE'
+    || error.code === 'SQLITE_CONSTRAINT_PRIMARYKEY'
+    || error.code === '23505';
+}
@@ -1,3 +1,7 @@
+import { createLogger } from './logger';
+
+const log = createLogger({ module: 'env' });
+
 /**
  * Parse an environment variable as a positive integer.
  *
@@ -10,7 +14,7 @@ export function parsePositiveInt(raw: string | undefined, fallback: number): num
 
   const parsed = Number(raw);
   if (!Number.isInteger(parsed) || parsed <= 0) {
-    console.warn(`Ignoring invalid value "${raw}" for positive integer, using ${fallback}`);
+    log.warn('Ignoring invalid value for positive integer', { value: raw, fallback });
     return fallback;
   }
   return parsed;
@@ -1,6 +1,13 @@
-import cron from 'node-cron';
 import { getDb } from './db';
-import { fetch, HttpError, isRetryableError } from './http-client';
+import { parsePositiveInt } from './env';
+import { fetch, HttpError } from './http-client';
+import { withRetry } from './retry';
+import { isCircuitOpen } from './circuit-breaker';
+import { createLogger, newCycleId } from './logger';
+import { recordCollectorCycle } from './metrics';
+import { triggerJob, type JobDefinition } from './job-supervisor';
+import { assertLeaseHeld } from './leader-lease';
+import { enqueueNotification } from './notifications';
 
 // Types for API responses
 interface HighestDiffResponse {
@@ -22,17 +29,30 @@ interface MempoolBlockDetails {
 // Configuration
 const CONFIG = {
   BACKFILL_BLOCKS: 500, // Number of blocks to backfill on startup (3 days worth: 3 * 144)
-  MAX_BLOCKS_TO_KEEP: 500, // Only keep this many blocks in the database (3 days worth)
+  // Only keep this many blocks in the database (default 3 days worth); null keeps everything
+  MAX_BLOCKS_TO_KEEP: process.env.HIGHEST_DIFF_RETENTION_BLOCKS === 'unlimited'
+    ? null
+    : parsePositiveInt(process.env.HIGHEST_DIFF_RETENTION_BLOCKS, 500),
+  // Historical backfill below the startup window: lowest height to walk down to,
+  // delay between blocks, and how many empty blocks in a row mean the pool's
+  // history has run out
+  HISTORY_MIN_HEIGHT: parsePositiveInt(process.env.HIGHEST_DIFF_BACKFILL_MIN_HEIGHT, 1),
+  HISTORY_DELAY_MS: parsePositiveInt(process.env.HIGHEST_DIFF_BACKFILL_DELAY_MS, 1000),
+  HISTORY_MAX_EMPTY_BLOCKS: parsePositiveInt(process.env.HIGHEST_DIFF_BACKFILL_MAX_EMPTY_BLOCKS, 2016),
+  // Each historical backfill run stops after this long, inside its hourly job's timeout
+  HISTORY_RUN_MS: 50 * 60_000,
   COLLECTION_DELAY_MS: 90_000, // 90 seconds delay after clean_jobs
+  // Backfilled blocks older than this don't notify their top-diff winner
+  TOP_DIFF_NOTIFY_MAX_AGE_SECONDS: 6 * 60 * 60,
   MEMPOOL_API_URL: 'https://mempool.space/api/blocks/tip/height',
   MEMPOOL_BLOCK_URL: 'https://mempool.space/api/block-height',
   MEMPOOL_BLOCK_DETAILS_URL: 'https://mempool.space/api/block',
-  MAX_RETRIES: 4,
-  RETRY_BASE_DELAY: 500,
   MAX_PENDING_COLLECTIONS: 50, // Prevent memory leak from too many pending timeouts
-} as const;
+};
 
-let isCollecting = false;
+const log = createLogger({ collector: 'highest-diff' });
+
+let isStopped = false;
 const pendingCollections: Map<number, NodeJS.Timeout> = new Map();
 
 /**
@@ -52,45 +72,6 @@ function isValidBlockDetails(data: unknown): data is MempoolBlockDetails {
   );
 }
 
-/**
- * Retry helper function with backoff and jitter
- */
-async function withRetry<T>(
-  operation: () => Promise<T>,
-  context?: string
-): Promise<T> {
-  let lastError;
-
-  for (let attempt = 0; attempt <= CONFIG.MAX_RETRIES; attempt++) {
-    try {
-      return await operation();
-    } catch (error) {
-      lastError = error;
-
-      const retryable = isRetryableError(error);
-      if (!retryable) {
-        const contextMsg = context ? ` [${context}]` : '';
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        console.log(`Non-retryable error${contextMsg} - ${errorMsg}`);
-        throw error;
-      }
-
-      if (attempt < CONFIG.MAX_RETRIES) {
-        const backoffDelay = CONFIG.RETRY_BASE_DELAY + (attempt * CONFIG.RETRY_BASE_DELAY);
-        const jitter = Math.random() + 0.5;
-        const delayWithJitter = Math.floor(backoffDelay * jitter);
-        const contextMsg = context ? ` [${context}]` : '';
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        console.log(`Retry attempt ${attempt + 1}/${CONFIG.MAX_RETRIES} after ${delayWithJitter}ms${contextMsg} - Error: ${errorMsg}`);
-        await delay(delayWithJitter);
-        continue;
-      }
-      throw error;
-    }
-  }
-  throw lastError;
-}
-
 /**
  * Get the current Bitcoin block height from mempool.space
  */
@@ -102,7 +83,7 @@ export async function getCurrentBlockHeight(): Promise<number> {
     }
     const height = await response.text();
     return parseInt(height, 10);
-  }, 'GET mempool block height');
+  }, { context: 'GET mempool block height', log });
 }
 
 /**
@@ -138,9 +119,9 @@ async function fetchBlockTimestamp(blockHeight: number): Promise<number | null>
       }
       
       return blockDetails.timestamp;
-    }, `GET block timestamp for ${blockHeight}`);
+    }, { context: `GET block timestamp for ${blockHeight}`, log });
   } catch (error) {
-    console.error(`Error fetching timestamp for block ${blockHeight}:`, error);
+    log.error('Error fetching block timestamp', { blockHeight, error });
     return null;
   }
 }
@@ -148,32 +129,34 @@ async function fetchBlockTimestamp(blockHeight: number): Promise<number | null>
 /**
  * Check if we have a timestamp for a block
  */
-function hasBlockTimestamp(blockHeight: number): boolean {
-  const db = getDb();
-  const result = db.prepare(
-    'SELECT block_timestamp FROM block_highest_diff WHERE block_height = ? AND block_timestamp IS NOT NULL'
-  ).get(blockHeight);
+async function hasBlockTimestamp(blockHeight: number): Promise<boolean> {
+  const db = await getDb();
+  const result = await db.get(
+    'SELECT block_timestamp FROM block_highest_diff WHERE block_height = ? AND block_timestamp IS NOT NULL',
+    [blockHeight]
+  );
   return result !== undefined;
 }
 
 /**
  * Store block timestamp (updates existing record)
  */
-function storeBlockTimestamp(blockHeight: number, timestamp: number): void {
-  const db = getDb();
-  db.prepare(
-    'UPDATE block_highest_diff SET block_timestamp = ? WHERE block_height = ?'
-  ).run(timestamp, blockHeight);
+async function storeBlockTimestamp(blockHeight: number, timestamp: number): Promise<void> {
+  const db = await getDb();
+  await db.run(
+    'UPDATE block_highest_diff SET block_timestamp = ? WHERE block_height = ?',
+    [timestamp, blockHeight]
+  );
 }
 
 /**
- * Fetch highest diff for a block (pool winner)
+ * Fetch highest diff for a block (pool winner). Returns null when the pool
+ * has no data for the block and throws when it can't be fetched.
  */
 async function fetchHighestDiff(blockHeight: number): Promise<HighestDiffResponse | null> {
   const apiUrl = process.env.API_URL;
   if (!apiUrl) {
-    console.error('Failed to fetch highest diff: No API_URL defined in env');
-    return null;
+    throw new Error('Failed to fetch highest diff: No API_URL defined in env');
   }
 
   const headers: Record<string, string> = {};
@@ -181,39 +164,31 @@ async function fetchHighestDiff(blockHeight: number): Promise<HighestDiffRespons
     headers['Authorization'] = `Bearer ${process.env.API_TOKEN}`;
   }
 
-  try {
-    return await withRetry(async () => {
-      const url = `${apiUrl}/highestdiff/${blockHeight}`;
-      const response = await fetch(url, { headers, timeout: 10_000 });
-      
-      if (response.status === 404) {
-        // No data for this block (might be too old or no shares submitted)
-        return null;
-      }
-      
-      if (!response.ok) {
-        throw new HttpError(response.status, response.statusText, url);
-      }
-      
-      return await response.json() as HighestDiffResponse;
-    }, `GET /highestdiff/${blockHeight}`);
-  } catch (error) {
-    if (error instanceof HttpError && error.status === 404) {
+  return withRetry(async () => {
+    const url = `${apiUrl}/highestdiff/${blockHeight}`;
+    const response = await fetch(url, { headers, timeout: 10_000 });
+    
+    if (response.status === 404) {
+      // No data for this block (might be too old or no shares submitted)
       return null;
     }
-    console.error(`Error fetching highest diff for block ${blockHeight}:`, error);
-    return null;
-  }
+    
+    if (!response.ok) {
+      throw new HttpError(response.status, response.statusText, url);
+    }
+    
+    return await response.json() as HighestDiffResponse;
+  }, { context: `GET /highestdiff/${blockHeight}`, log });
 }
 
 /**
- * Fetch all users' highest diffs for a block
+ * Fetch all users' highest diffs for a block. Throws when they can't be
+ * fetched, so a block is never stored with its user diffs missing.
  */
 async function fetchAllUserDiffs(blockHeight: number): Promise<UserDiffEntry[]> {
   const apiUrl = process.env.API_URL;
   if (!apiUrl) {
-    console.error('Failed to fetch user diffs: No API_URL defined in env');
-    return [];
+    throw new Error('Failed to fetch user diffs: No API_URL defined in env');
   }
 
   const headers: Record<string, string> = {};
@@ -221,38 +196,31 @@ async function fetchAllUserDiffs(blockHeight: number): Promise<UserDiffEntry[]>
     headers['Authorization'] = `Bearer ${process.env.API_TOKEN}`;
   }
 
-  try {
-    return await withRetry(async () => {
-      const url = `${apiUrl}/highestdiff/${blockHeight}/all`;
-      const response = await fetch(url, { headers, timeout: 30_000 });
-      
-      if (response.status === 404) {
-        return [];
-      }
-      
-      if (!response.ok) {
-        throw new HttpError(response.status, response.statusText, url);
-      }
-      
-      return await response.json() as UserDiffEntry[];
-    }, `GET /highestdiff/${blockHeight}/all`);
-  } catch (error) {
-    if (error instanceof HttpError && error.status === 404) {
+  return withRetry(async () => {
+    const url = `${apiUrl}/highestdiff/${blockHeight}/all`;
+    const response = await fetch(url, { headers, timeout: 30_000 });
+    
+    if (response.status === 404) {
       return [];
     }
-    console.error(`Error fetching all user diffs for block ${blockHeight}:`, error);
-    return [];
-  }
+    
+    if (!response.ok) {
+      throw new HttpError(response.status, response.statusText, url);
+    }
+    
+    return await response.json() as UserDiffEntry[];
+  }, { context: `GET /highestdiff/${blockHeight}/all`, log });
 }
 
 /**
  * Check if we already have data for a block
  */
-function hasBlockData(blockHeight: number): boolean {
-  const db = getDb();
-  const result = db.prepare(
-    'SELECT 1 FROM block_highest_diff WHERE block_height = ?'
-  ).get(blockHeight);
+async function hasBlockData(blockHeight: number): Promise<boolean> {
+  const db = await getDb();
+  const result = await db.get(
+    'SELECT 1 FROM block_highest_diff WHERE block_height = ?',
+    [blockHeight]
+  );
   return result !== undefined;
 }
 
@@ -260,14 +228,16 @@ function hasBlockData(blockHeight: number): boolean {
  * Clean up old block data, keeping only the last MAX_BLOCKS_TO_KEEP blocks
  * Deletes from block_highest_diff (cascades to user_block_diff via FK)
  */
-function cleanupOldBlocks(): void {
-  const db = getDb();
-  
+async function cleanupOldBlocks(): Promise<void> {
+  if (CONFIG.MAX_BLOCKS_TO_KEEP === null) return;
+
   try {
+    const db = await getDb();
+
     // Get the current max block height
-    const result = db.prepare(
+    const result = await db.get<{ max_height: number | null }>(
       'SELECT MAX(block_height) as max_height FROM block_highest_diff'
-    ).get() as { max_height: number | null } | undefined;
+    );
     
     const maxHeight = result?.max_height;
     if (!maxHeight) return;
@@ -275,29 +245,31 @@ function cleanupOldBlocks(): void {
     const cutoffHeight = maxHeight - CONFIG.MAX_BLOCKS_TO_KEEP;
     
     // Delete old records - user_block_diff cascades via foreign key
-    const deleted = db.prepare(
-      'DELETE FROM block_highest_diff WHERE block_height < ?'
-    ).run(cutoffHeight);
+    const deleted = await db.run(
+      'DELETE FROM block_highest_diff WHERE block_height < ?',
+      [cutoffHeight]
+    );
     
     if (deleted.changes > 0) {
-      console.log(`🧹 Cleaned up old block diff data: ${deleted.changes} blocks (keeping blocks > ${cutoffHeight})`);
+      log.info('Cleaned up old block diff data', { blocks: deleted.changes, cutoffHeight });
     }
   } catch (error) {
-    console.error('Error cleaning up old block diff data:', error);
+    log.error('Error cleaning up old block diff data', { error });
   }
 }
 
 /**
- * Collect and store highest diff data for a specific block
+ * Collect and store highest diff data for a specific block. Resolves to
+ * false when the pool has no data for it and throws when the pool API fails.
  */
-export async function collectHighestDiff(blockHeight: number): Promise<boolean> {
+async function collectBlock(blockHeight: number): Promise<boolean> {
   // Skip if we already have data for this block
-  if (hasBlockData(blockHeight)) {
+  if (await hasBlockData(blockHeight)) {
     // Still ensure we have the timestamp
-    if (!hasBlockTimestamp(blockHeight)) {
+    if (!(await hasBlockTimestamp(blockHeight))) {
       const timestamp = await fetchBlockTimestamp(blockHeight);
       if (timestamp) {
-        storeBlockTimestamp(blockHeight, timestamp);
+        await storeBlockTimestamp(blockHeight, timestamp);
       }
     }
     return true;
@@ -316,45 +288,77 @@ export async function collectHighestDiff(blockHeight: number): Promise<boolean>
   // Fetch block timestamp from mempool.space
   const blockTimestamp = await fetchBlockTimestamp(blockHeight);
 
-  const db = getDb();
+  const db = await getDb();
 
-  db.transaction(() => {
+  await db.transaction(async () => {
+    await assertLeaseHeld(db);
     // Store pool winner with optional block timestamp
-    const winnerStmt = db.prepare(`
-      INSERT OR REPLACE INTO block_highest_diff (
+    await db.run(`
+      INSERT INTO block_highest_diff (
         block_height, winner_address, difficulty, block_timestamp
       ) VALUES (?, ?, ?, ?)
-    `);
-    winnerStmt.run(blockHeight, poolWinner.username, poolWinner.diff, blockTimestamp);
+      ON CONFLICT(block_height) DO UPDATE SET
+        winner_address = excluded.winner_address,
+        difficulty = excluded.difficulty,
+        block_timestamp = excluded.block_timestamp
+    `, [blockHeight, poolWinner.username, poolWinner.diff, blockTimestamp]);
+
+    if (blockTimestamp !== null && Date.now() / 1000 - blockTimestamp <= CONFIG.TOP_DIFF_NOTIFY_MAX_AGE_SECONDS) {
+      await enqueueNotification(db, {
+        event: 'top_diff',
+        address: poolWinner.username,
+        key: `top_diff:${blockHeight}`,
+        title: `Top difficulty for block ${blockHeight}`,
+        message: `Your share of difficulty ${poolWinner.diff.toExponential(2)} was the highest in the pool for block ${blockHeight}.`,
+        data: { block_height: blockHeight, difficulty: poolWinner.diff },
+        timestamp: blockTimestamp,
+      });
+    }
 
     // Store all user diffs
-    if (userDiffs.length > 0) {
-      const userStmt = db.prepare(`
-        INSERT OR REPLACE INTO user_block_diff (
+    for (const entry of userDiffs) {
+      await db.run(`
+        INSERT INTO user_block_diff (
           block_height, address, difficulty
         ) VALUES (?, ?, ?)
-      `);
-
-      for (const entry of userDiffs) {
-        userStmt.run(blockHeight, entry.username, entry.diff);
-      }
+        ON CONFLICT(block_height, address) DO UPDATE SET difficulty = excluded.difficulty
+      `, [blockHeight, entry.username, entry.diff]);
     }
-  })();
+  });
 
-  console.log(`📊 Block ${blockHeight}: winner=${poolWinner.username.substring(0, 12)}... (${poolWinner.diff.toExponential(2)}) + ${userDiffs.length} user diffs`);
+  log.info('Collected block highest diff', {
+    blockHeight,
+    address: poolWinner.username,
+    diff: poolWinner.diff,
+    userDiffs: userDiffs.length,
+  });
   
   // Clean up old block data to keep only the last MAX_BLOCKS_TO_KEEP blocks
-  cleanupOldBlocks();
+  await cleanupOldBlocks();
   
   return true;
 }
 
+/**
+ * Collect and store highest diff data for a specific block
+ */
+export async function collectHighestDiff(blockHeight: number): Promise<boolean> {
+  try {
+    return await collectBlock(blockHeight);
+  } catch (error) {
+    log.error('Error collecting highest diff', { blockHeight, error });
+    return false;
+  }
+}
+
 /**
  * Backfill historical data on startup
  * Checks the last BACKFILL_BLOCKS blocks and fills in any missing entries
  */
-export async function backfillHighestDiff(): Promise<void> {
-  console.log(`🔄 Starting highest diff backfill (last ${CONFIG.BACKFILL_BLOCKS} blocks)...`);
+export async function backfillHighestDiff(signal?: AbortSignal): Promise<void> {
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'backfill' });
+  cycleLog.info('Starting highest diff backfill', { blocks: CONFIG.BACKFILL_BLOCKS });
 
   try {
     const currentHeight = await getCurrentBlockHeight();
@@ -365,10 +369,14 @@ export async function backfillHighestDiff(): Promise<void> {
     let failed = 0;
 
     for (let height = startHeight; height <= currentHeight; height++) {
-      if (hasBlockData(height)) {
+      signal?.throwIfAborted();
+      if (await hasBlockData(height)) {
         skipped++;
         continue;
       }
+      if (isCircuitOpen('api')) {
+        throw new Error(`API circuit open, backfill stopped at block ${height}`);
+      }
 
       const success = await collectHighestDiff(height);
       if (success) {
@@ -381,9 +389,135 @@ export async function backfillHighestDiff(): Promise<void> {
       await delay(100);
     }
 
-    console.log(`✅ Backfill complete: ${collected} collected, ${skipped} skipped (already had), ${failed} failed/empty`);
+    cycleLog.info('Backfill complete', { collected, skipped, failed, durationMs: Date.now() - startedAtMs });
+  } catch (error) {
+    cycleLog.error('Error during highest diff backfill', { error });
+    throw error;
+  }
+}
+
+interface HistoryBackfillRow {
+  next_height: number;
+  floor_height: number;
+  consecutive_empty: number;
+  completed_at: number | null;
+}
+
+/**
+ * Lowest block the historical backfill walks down to: HISTORY_MIN_HEIGHT, or
+ * the retention boundary so it never stores blocks cleanup would delete
+ */
+function getHistoryFloor(currentHeight: number): number {
+  const retentionFloor = CONFIG.MAX_BLOCKS_TO_KEEP === null ? 0 : currentHeight - CONFIG.MAX_BLOCKS_TO_KEEP;
+  return Math.max(CONFIG.HISTORY_MIN_HEIGHT, retentionFloor);
+}
+
+/**
+ * Historical backfill below the startup window
+ *
+ * Walks down one block at a time from just below the last BACKFILL_BLOCKS,
+ * persisting its cursor in highest_diff_backfill after every block so a
+ * restart picks up where it stopped. It finishes at the floor or after
+ * HISTORY_MAX_EMPTY_BLOCKS blocks in a row without data. Each run walks for
+ * at most HISTORY_RUN_MS and the hourly job picks it up from there; a pool
+ * API failure fails the run with the cursor left on the failed block.
+ * Raising the retention or the empty-block limit later resumes a finished
+ * backfill.
+ */
+export async function backfillHighestDiffHistory(signal?: AbortSignal): Promise<void> {
+  const startedAtMs = Date.now();
+  try {
+    const db = await getDb();
+    const currentHeight = await getCurrentBlockHeight();
+    const floorHeight = getHistoryFloor(currentHeight);
+    const now = Math.floor(Date.now() / 1000);
+
+    let state = await db.get<HistoryBackfillRow>(
+      'SELECT next_height, floor_height, consecutive_empty, completed_at FROM highest_diff_backfill WHERE id = 1'
+    );
+    if (!state) {
+      const startHeight = currentHeight - CONFIG.BACKFILL_BLOCKS;
+      await db.run(`
+        INSERT INTO highest_diff_backfill (id, start_height, next_height, floor_height, started_at, updated_at)
+        VALUES (1, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO NOTHING
+      `, [startHeight, startHeight, floorHeight, now, now]);
+      state = { next_height: startHeight, floor_height: floorHeight, consecutive_empty: 0, completed_at: null };
+    }
+
+    let { next_height: height, consecutive_empty: consecutiveEmpty } = state;
+    const isFinished = () =>
+      height < floorHeight || consecutiveEmpty >= CONFIG.HISTORY_MAX_EMPTY_BLOCKS;
+
+    if (isFinished()) {
+      if (state.completed_at === null || state.floor_height !== floorHeight) {
+        await db.run(
+          'UPDATE highest_diff_backfill SET floor_height = ?, completed_at = COALESCE(completed_at, ?) WHERE id = 1',
+          [floorHeight, now]
+        );
+      }
+      return;
+    }
+
+    log.info('Resuming historical highest diff backfill', { blockHeight: height, floorHeight });
+    await db.run(
+      'UPDATE highest_diff_backfill SET floor_height = ?, completed_at = NULL WHERE id = 1',
+      [floorHeight]
+    );
+
+    let checked = 0;
+    let stored = 0;
+    while (!isStopped && !isFinished() && Date.now() - startedAtMs < CONFIG.HISTORY_RUN_MS) {
+      signal?.throwIfAborted();
+      let found: boolean;
+      try {
+        found = await collectBlock(height);
+      } catch (error) {
+        if (signal?.aborted) throw error;
+        const message = error instanceof Error ? error.message : String(error);
+        await db.run(
+          'UPDATE highest_diff_backfill SET last_error = ?, updated_at = ? WHERE id = 1',
+          [`Block ${height}: ${message}`, Math.floor(Date.now() / 1000)]
+        );
+        log.error('Historical highest diff backfill paused', { blockHeight: height });
+        throw error;
+      }
+
+      consecutiveEmpty = found ? 0 : consecutiveEmpty + 1;
+      height--;
+      checked++;
+      if (found) stored++;
+
+      await assertLeaseHeld(db);
+      await db.run(`
+        UPDATE highest_diff_backfill SET
+          next_height = ?,
+          consecutive_empty = ?,
+          blocks_checked = blocks_checked + 1,
+          blocks_stored = blocks_stored + ?,
+          blocks_empty = blocks_empty + ?,
+          last_error = NULL,
+          updated_at = ?
+        WHERE id = 1
+      `, [height, consecutiveEmpty, found ? 1 : 0, found ? 0 : 1, Math.floor(Date.now() / 1000)]);
+
+      if (checked % 500 === 0) {
+        log.info('Historical highest diff backfill progress', { blockHeight: height + 1, stored, checked });
+      }
+
+      await delay(CONFIG.HISTORY_DELAY_MS);
+    }
+
+    if (isFinished()) {
+      await db.run(
+        'UPDATE highest_diff_backfill SET completed_at = ? WHERE id = 1',
+        [Math.floor(Date.now() / 1000)]
+      );
+      log.info('Historical highest diff backfill complete', { blockHeight: height + 1, stored });
+    }
   } catch (error) {
-    console.error('Error during highest diff backfill:', error);
+    log.error('Error during historical highest diff backfill', { error });
+    throw error;
   }
 }
 
@@ -401,7 +535,7 @@ export function triggerDelayedCollection(blockHeight: number): void {
 
   // Prevent memory leak: if too many pending collections, clear oldest ones
   if (pendingCollections.size >= CONFIG.MAX_PENDING_COLLECTIONS) {
-    console.warn(`⚠️ Too many pending collections (${pendingCollections.size}), clearing oldest entries`);
+    log.warn('Too many pending collections, clearing oldest entries', { pending: pendingCollections.size });
     
     // Get the oldest entries (lowest block heights) and remove them
     const sortedEntries = [...pendingCollections.entries()].sort((a, b) => a[0] - b[0]);
@@ -413,14 +547,14 @@ export function triggerDelayedCollection(blockHeight: number): void {
     }
   }
 
-  console.log(`⏳ Scheduling highest diff collection for block ${blockHeight} in ${CONFIG.COLLECTION_DELAY_MS / 1000}s`);
+  log.info('Scheduling highest diff collection', { blockHeight, delayMs: CONFIG.COLLECTION_DELAY_MS });
 
   const timeout = setTimeout(async () => {
     pendingCollections.delete(blockHeight);
     try {
       await collectHighestDiff(blockHeight);
     } catch (error) {
-      console.error(`Error collecting highest diff for block ${blockHeight}:`, error);
+      log.error('Error collecting highest diff', { blockHeight, error });
     }
   }, CONFIG.COLLECTION_DELAY_MS);
 
@@ -432,14 +566,9 @@ export function triggerDelayedCollection(blockHeight: number): void {
  * Runs every 10 minutes to catch any missed blocks
  */
 async function periodicCollection(): Promise<void> {
-  if (isCollecting) {
-    console.warn('⚠️ Highest diff collection already running, skipping this cycle');
-    return;
-  }
-
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'periodic' });
   try {
-    isCollecting = true;
-
     const currentHeight = await getCurrentBlockHeight();
     
     // Check last 10 blocks for any missing data
@@ -448,7 +577,7 @@ async function periodicCollection(): Promise<void> {
 
     for (let i = 0; i < blocksToCheck; i++) {
       const height = currentHeight - i;
-      if (!hasBlockData(height)) {
+      if (!(await hasBlockData(height))) {
         const success = await collectHighestDiff(height);
         if (success) {
           collected++;
@@ -457,42 +586,60 @@ async function periodicCollection(): Promise<void> {
       }
     }
 
-    if (collected > 0) {
-      console.log(`📊 Periodic collection: collected ${collected} missing blocks`);
-    }
+    cycleLog.info('Periodic collection complete', { collected, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('highest_diff', startedAtMs, 'success');
   } catch (error) {
-    console.error('Error in periodic highest diff collection:', error);
-  } finally {
-    isCollecting = false;
+    cycleLog.error('Error in periodic highest diff collection', { error, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('highest_diff', startedAtMs, 'error');
+    throw error;
   }
 }
 
 /**
- * Start the highest diff collector
- * - Runs backfill on startup
- * - Sets up periodic collection every 10 minutes
+ * Jobs for the supervisor
+ * - The startup backfill of the last BACKFILL_BLOCKS blocks, repeated hourly
+ *   so a failed startup backfill doesn't hold back periodic collection for long
+ * - The historical backfill below it, started after each startup backfill and
+ *   resumed hourly; it never goes stale, since it finishes
+ * - Periodic collection every 10 minutes, once the backfill has succeeded
  */
-export function startHighestDiffCollector(): { job: ReturnType<typeof cron.schedule> } {
-  // Run backfill on startup
-  backfillHighestDiff();
-
-  // Schedule periodic collection every 10 minutes
-  const job = cron.schedule('*/10 * * * *', async () => {
-    await periodicCollection();
-  });
-
-  console.log('📊 Highest diff collector started (periodic collection every 10 minutes)');
-
-  return { job };
-}
+export const highestDiffJobs: JobDefinition[] = [
+  {
+    name: 'highest_diff_backfill',
+    schedule: '0 * * * *',
+    timeoutMs: 30 * 60_000,
+    staleAfterSeconds: null,
+    runOnStart: true,
+    run: async signal => {
+      await backfillHighestDiff(signal);
+      triggerJob('highest_diff_history');
+    },
+  },
+  {
+    name: 'highest_diff_history',
+    schedule: '30 * * * *',
+    timeoutMs: 60 * 60_000,
+    staleAfterSeconds: null,
+    run: backfillHighestDiffHistory,
+  },
+  {
+    name: 'highest_diff',
+    schedule: '*/10 * * * *',
+    timeoutMs: 10 * 60_000,
+    staleAfterSeconds: 60 * 60,
+    dependsOn: ['highest_diff_backfill'],
+    run: periodicCollection,
+  },
+];
 
 /**
  * Stop the collector and clear pending collections
  */
 export function stopHighestDiffCollector(): void {
+  isStopped = true;
   for (const timeout of pendingCollections.values()) {
     clearTimeout(timeout);
   }
   pendingCollections.clear();
-  console.log('📊 Highest diff collector stopped');
+  log.info('Highest diff collector stopped');
 }
@@ -1,4 +1,15 @@
 import { Agent, Pool, fetch as undiciFetch, type Dispatcher } from 'undici';
+import { acquireCircuit } from './circuit-breaker';
+import {
+  HttpError,
+  TIMEOUT_ABORT_REASON,
+  TimeoutError,
+  isClientDestroyedError,
+  isNetworkError,
+  isTimeoutError,
+} from './http-errors';
+import { createLogger } from './logger';
+import { recordAgentRecreation, recordUpstreamRequest } from './metrics';
 
 /**
  * HTTP/2-enabled HTTP client using Undici
@@ -11,58 +22,24 @@ import { Agent, Pool, fetch as undiciFetch, type Dispatcher } from 'undici';
  * - Connection pooling: Reuse TCP/TLS connections across requests
  * - Reduced latency: No repeated TLS handshakes
  * - Better resource utilization: Fewer connections needed for high concurrency
+ *
+ * Requests to known upstreams (pool API, mempool.space, router, dispenser,
+ * lightning) go through that upstream's circuit breaker in lib/circuit-breaker.
  */
 
-/**
- * Custom error class for request timeouts
- * Allows distinguishing timeout errors from other abort/network errors
- */
-export class TimeoutError extends Error {
-  public readonly timeoutMs: number;
-  public readonly url: string | undefined;
-
-  constructor(timeoutMs: number, url?: string) {
-    const urlInfo = url ? ` for ${url}` : '';
-    super(`Request timed out after ${timeoutMs}ms${urlInfo}`);
-    this.name = 'TimeoutError';
-    this.timeoutMs = timeoutMs;
-    this.url = url;
-  }
-}
+// Error types and retry classification live in lib/http-errors so browser
+// code can share them; re-exported for existing server-side imports
+export {
+  HttpError,
+  RateLimitError,
+  TimeoutError,
+  isClientDestroyedError,
+  isRetryableError,
+  isTimeoutError,
+  parseRetryAfter,
+} from './http-errors';
 
-/**
- * Custom error class for HTTP errors with status codes
- * Provides structured error information for better error handling
- */
-export class HttpError extends Error {
-  public readonly status: number;
-  public readonly statusText: string;
-  public readonly url: string | undefined;
-  public readonly detail: string | undefined;
-
-  constructor(status: number, statusText: string, url?: string, detail?: string) {
-    const urlInfo = url ? ` for ${url}` : '';
-    const detailInfo = detail ? `: ${detail}` : '';
-    super(`HTTP ${status} ${statusText}${urlInfo}${detailInfo}`);
-    this.name = 'HttpError';
-    this.status = status;
-    this.statusText = statusText;
-    this.url = url;
-    this.detail = detail;
-  }
-
-  /**
-   * Check if this error is retryable based on HTTP status code
-   * 
-   * Retryable: 429 (rate limit), 5xx (server errors)
-   * Non-retryable: 4xx client errors (except 429)
-   */
-  get isRetryable(): boolean {
-    if (this.status === 429) return true;
-    if (this.status >= 500 && this.status < 600) return true;
-    return false;
-  }
-}
+const log = createLogger({ module: 'http-client' });
 
 /**
  * Parse integer from environment variable with validation and fallback
@@ -71,7 +48,7 @@ function parseEnvInt(envValue: string | undefined, defaultValue: number): number
   if (!envValue) return defaultValue;
   const parsed = parseInt(envValue, 10);
   if (Number.isNaN(parsed) || parsed < 0) {
-    console.warn(`Invalid env value "${envValue}", using default ${defaultValue}`);
+    log.warn('Invalid env value, using default', { value: envValue, default: defaultValue });
     return defaultValue;
   }
   return parsed;
@@ -162,7 +139,8 @@ function getAgent(): Dispatcher {
  * consecutive connection failures indicate a wedged pool)
  */
 function recreateAgent(reason: string): Dispatcher {
-  console.warn(`[http-client] recreating HTTP agent: ${reason}`);
+  log.warn('Recreating HTTP agent', { reason });
+  recordAgentRecreation();
   // Close the old agent if it exists (ignore errors)
   if (globalForHttp2.__http2Agent) {
     try {
@@ -203,106 +181,6 @@ export type ExtendedRequestInit = RequestInit & {
   throwOnTimeout?: boolean;
 };
 
-// Unique symbol to identify our timeout aborts
-const TIMEOUT_ABORT_REASON = Symbol('http-client-timeout');
-
-/**
- * Check if an error is a timeout error from this client
- * Handles multiple cases:
- * - TimeoutError instance (our wrapped error)
- * - Raw TIMEOUT_ABORT_REASON symbol (undici may throw the abort reason directly)
- * - AbortError with our symbol as cause
- */
-export function isTimeoutError(error: unknown): boolean {
-  if (error instanceof TimeoutError) return true;
-  // Handle case where undici throws the raw abort reason symbol
-  if (error === TIMEOUT_ABORT_REASON) return true;
-  if (error instanceof Error) {
-    // Check abort errors with our symbol as cause
-    if (error.name === 'AbortError') {
-      const cause = (error as Error & { cause?: unknown }).cause;
-      return cause === TIMEOUT_ABORT_REASON;
-    }
-    // Check if the error message contains our symbol's description
-    if (error.message.includes('http-client-timeout')) return true;
-  }
-  // Check string representation of the error
-  if (typeof error === 'symbol' && error.description === 'http-client-timeout') return true;
-  return false;
-}
-
-/**
- * Check if an error is a connection/client destroyed error from undici
- * This happens when HTTP/2 sessions are closed while requests are in-flight
- */
-export function isClientDestroyedError(error: unknown): boolean {
-  if (error instanceof Error) {
-    // Check error code (undici sets this)
-    const code = (error as Error & { code?: string }).code;
-    if (code === 'UND_ERR_DESTROYED') return true;
-    
-    // Check error name
-    if (error.name === 'ClientDestroyedError') return true;
-    
-    // Check message as fallback
-    const msg = error.message.toLowerCase();
-    if (msg.includes('client is destroyed') || msg.includes('clientdestroyederror')) {
-      return true;
-    }
-  }
-  return false;
-}
-
-/**
- * Check if an error is a network/connection-level error (fetch failed, socket
- * errors, undici error codes such as UND_ERR_HEADERS_TIMEOUT)
- */
-function isNetworkError(error: unknown): boolean {
-  if (error instanceof Error) {
-    const msg = error.message.toLowerCase();
-    if (msg.includes('fetch failed') ||
-        msg.includes('network') ||
-        msg.includes('econnrefused') ||
-        msg.includes('econnreset') ||
-        msg.includes('etimedout') ||
-        msg.includes('enotfound') ||
-        msg.includes('socket hang up') ||
-        msg.includes('aborted')) {
-      return true;
-    }
-
-    // Check undici error codes
-    const code = (error as Error & { code?: string }).code;
-    if (code && (
-      code.startsWith('UND_ERR_') ||  // All undici errors are generally retryable
-      code === 'ECONNRESET' ||
-      code === 'ETIMEDOUT' ||
-      code === 'ENOTFOUND'
-    )) {
-      return true;
-    }
-  }
-
-  return false;
-}
-
-/**
- * Check if an error is retryable (network errors, timeouts, 5xx, 429, client destroyed)
- */
-export function isRetryableError(error: unknown): boolean {
-  // Timeout errors are retryable
-  if (isTimeoutError(error)) return true;
-
-  // Client destroyed errors are retryable (HTTP/2 session was closed)
-  if (isClientDestroyedError(error)) return true;
-
-  // HTTP errors - delegate to the error's own logic
-  if (error instanceof HttpError) return error.isRetryable;
-
-  // Network/connection errors are retryable
-  return isNetworkError(error);
-}
-
 /**
  * Check if an error suggests the pooled connection itself is broken rather
  * than the request. A run of these indicates a wedged HTTP/2 session that
@@ -416,27 +294,60 @@ function describeError(error: unknown): string {
  * When ClientDestroyedError occurs, or repeated timeouts/network errors
  * indicate a wedged pool, the agent is recreated and the request is retried
  * automatically.
+ *
+ * Throws CircuitOpenError without sending anything while the upstream's
+ * circuit is open. A response below 500 counts as the upstream being up;
+ * a 5xx or a connection error counts against it.
  */
 export async function fetch(
   input: string | URL | Request,
   init?: ExtendedRequestInit
 ): Promise<Response> {
-  let lastError: unknown;
-  const maxRetries = CONFIG.CONNECTION_RETRIES;
   const urlString = typeof input === 'string'
     ? input
     : input instanceof URL
       ? input.toString()
       : input.url;
 
+  const breaker = acquireCircuit(urlString);
+  try {
+    const response = await fetchWithAgentRecovery(input, init, urlString);
+    if (response.status >= 500) {
+      breaker?.recordFailure(new HttpError(response.status, response.statusText, urlString));
+    } else {
+      breaker?.recordSuccess();
+    }
+    return response;
+  } catch (error) {
+    // A caller giving up says nothing about the upstream
+    if (init?.signal?.aborted) {
+      breaker?.release();
+    } else {
+      breaker?.recordFailure(error);
+    }
+    throw error;
+  }
+}
+
+async function fetchWithAgentRecovery(
+  input: string | URL | Request,
+  init: ExtendedRequestInit | undefined,
+  urlString: string
+): Promise<Response> {
+  let lastError: unknown;
+  const maxRetries = CONFIG.CONNECTION_RETRIES;
+
   for (let attempt = 0; attempt <= maxRetries; attempt++) {
+    const startedAt = Date.now();
     try {
       const agent = getAgent();
       const response = await doFetch(input, init, agent);
       consecutivePoisonFailures = 0;
+      recordUpstreamRequest(urlString, response.status, Date.now() - startedAt);
       return response;
     } catch (error) {
       lastError = error;
+      recordUpstreamRequest(urlString, isTimeoutError(error) ? 'timeout' : 'error', Date.now() - startedAt);
 
       // Only retry on connection-level errors, not on HTTP errors
       if (isClientDestroyedError(error)) {
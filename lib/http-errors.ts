@@ -0,0 +1,218 @@
+import type { Upstream } from './circuit-breaker';
+
+/**
+ * Errors raised by upstream requests, and which of them are worth retrying
+ *
+ * Kept free of Node-only imports so lib/retry can be used from browser code
+ * as well as by lib/http-client.
+ */
+
+/**
+ * Custom error class for request timeouts
+ * Allows distinguishing timeout errors from other abort/network errors
+ */
+export class TimeoutError extends Error {
+  public readonly timeoutMs: number;
+  public readonly url: string | undefined;
+
+  constructor(timeoutMs: number, url?: string) {
+    const urlInfo = url ? ` for ${url}` : '';
+    super(`Request timed out after ${timeoutMs}ms${urlInfo}`);
+    this.name = 'TimeoutError';
+    this.timeoutMs = timeoutMs;
+    this.url = url;
+  }
+}
+
+/**
+ * Custom error class for HTTP errors with status codes
+ * Provides structured error information for better error handling
+ */
+export class HttpError extends Error {
+  public readonly status: number;
+  public readonly statusText: string;
+  public readonly url: string | undefined;
+  public readonly detail: string | undefined;
+
+  constructor(status: number, statusText: string, url?: string, detail?: string) {
+    const urlInfo = url ? ` for ${url}` : '';
+    const detailInfo = detail ? `: ${detail}` : '';
+    super(`HTTP ${status} ${statusText}${urlInfo}${detailInfo}`);
+    this.name = 'HttpError';
+    this.status = status;
+    this.statusText = statusText;
+    this.url = url;
+    this.detail = detail;
+  }
+
+  /**
+   * Check if this error is retryable based on HTTP status code
+   * 
+   * Retryable: 429 (rate limit), 5xx (server errors)
+   * Non-retryable: 4xx client errors (except 429)
+   */
+  get isRetryable(): boolean {
+    if (this.status === 429) return true;
+    if (this.status >= 500 && this.status < 600) return true;
+    return false;
+  }
+}
+
+/**
+ * HTTP 429 with the delay the server asked for. Not retryable: callers back
+ * off for retryAfterSeconds rather than retrying straight away.
+ */
+export class RateLimitError extends HttpError {
+  public readonly retryAfterSeconds: number;
+
+  constructor(retryAfterSeconds: number, url?: string, detail?: string) {
+    super(429, 'Too Many Requests', url, detail);
+    this.name = 'RateLimitError';
+    this.retryAfterSeconds = retryAfterSeconds;
+  }
+
+  override get isRetryable(): boolean {
+    return false;
+  }
+}
+
+/**
+ * Seconds to wait from a Retry-After header (delay in seconds or an HTTP
+ * date), or the fallback when it is missing or unparseable
+ */
+export function parseRetryAfter(value: string | null, fallbackSeconds: number, now = Date.now()): number {
+  if (!value) return fallbackSeconds;
+
+  const trimmed = value.trim();
+  if (/^\d+$/.test(trimmed)) return parseInt(trimmed, 10);
+
+  const date = Date.parse(trimmed);
+  if (Number.isNaN(date)) return fallbackSeconds;
+  return Math.max(Math.ceil((date - now) / 1000), 0);
+}
+
+/**
+ * Thrown instead of sending a request while the upstream's circuit is open.
+ * Not retryable: retrying before retryAt would only be refused again.
+ */
+export class CircuitOpenError extends Error {
+  public readonly upstream: Upstream;
+  public readonly retryAt: number;
+
+  constructor(upstream: Upstream, retryAt: number) {
+    super(`Circuit open for upstream ${upstream} until ${new Date(retryAt).toISOString()}`);
+    this.name = 'CircuitOpenError';
+    this.upstream = upstream;
+    this.retryAt = retryAt;
+  }
+}
+
+// Unique symbol to identify our timeout aborts
+export const TIMEOUT_ABORT_REASON = Symbol('http-client-timeout');
+
+/**
+ * Check if an error is a timeout error from this client
+ * Handles multiple cases:
+ * - TimeoutError instance (our wrapped error)
+ * - Raw TIMEOUT_ABORT_REASON symbol (undici may throw the abort reason directly)
+ * - AbortError with our symbol as cause
+ */
+export function isTimeoutError(error: unknown): boolean {
+  if (error instanceof TimeoutError) return true;
+  // Handle case where undici throws the raw abort reason symbol
+  if (error === TIMEOUT_ABORT_REASON) return true;
+  if (error instanceof Error) {
+    // Check abort errors with our symbol as cause
+    if (error.name === 'AbortError') {
+      const cause = (error as Error & { cause?: unknown }).cause;
+      return cause === TIMEOUT_ABORT_REASON;
+    }
+    // Check if the error message contains our symbol's description
+    if (error.message.includes('http-client-timeout')) return true;
+  }
+  // Check string representation of the error
+  if (typeof error === 'symbol' && error.description === 'http-client-timeout') return true;
+  return false;
+}
+
+/**
+ * Check if an error is a connection/client destroyed error from undici
+ * This happens when HTTP/2 sessions are closed while requests are in-flight
+ */
+export function isClientDestroyedError(error: unknown): boolean {
+  if (error instanceof Error) {
+    // Check error code (undici sets this)
+    const code = (error as Error & { code?: string }).code;
+    if (code === 'UND_ERR_DESTROYED') return true;
+    
+    // Check error name
+    if (error.name === 'ClientDestroyedError') return true;
+    
+    // Check message as fallback
+    const msg = error.message.toLowerCase();
+    if (msg.includes('client is destroyed') || msg.includes('clientdestroyederror')) {
+      return true;
+    }
+  }
+  return false;
+}
+
+/**
+ * Check if an error is a network/connection-level error (fetch failed, socket
+ * errors, undici error codes such as UND_ERR_HEADERS_TIMEOUT, and the
+ * browsers' fetch failures)
+ */
+export function isNetworkError(error: unknown): boolean {
+  if (error instanceof Error) {
+    const msg = error.message.toLowerCase();
+    if (msg.includes('fetch failed') ||
+        msg.includes('failed to fetch') ||
+        msg.includes('load failed') ||
+        msg.includes('network') ||
+        msg.includes('econnrefused') ||
+        msg.includes('econnreset') ||
+        msg.includes('etimedout') ||
+        msg.includes('enotfound') ||
+        msg.includes('socket hang up') ||
+        msg.includes('aborted')) {
+      return true;
+    }
+
+    // Check undici error codes
+    const code = (error as Error & { code?: string }).code;
+    if (code && (
+      code.startsWith('UND_ERR_') ||  // All undici errors are generally retryable
+      code === 'ECONNRESET' ||
+      code === 'ETIMEDOUT' ||
+      code === 'ENOTFOUND'
+    )) {
+      return true;
+    }
+  }
+
+  return false;
+}
+
+/**
+ * Check if an error is retryable (network errors, timeouts, 5xx, 429, client destroyed)
+ */
+export function isRetryableError(error: unknown): boolean {
+  // The upstream is known to be down; the breaker decides when to try again
+  if (error instanceof CircuitOpenError) return false;
+
+  // Timeout errors are retryable
+  if (isTimeoutError(error)) return true;
+
+  // Client destroyed errors are retryable (HTTP/2 session was closed)
+  if (isClientDestroyedError(error)) return true;
+
+  // HTTP errors - delegate to the error's own logic
+  if (error instanceof HttpError) return error.isRetryable;
+
+  // Errors from clients like axios (mempool.js) carry the response instead
+  const status = (error as { response?: { status?: unknown } } | null)?.response?.status;
+  if (typeof status === 'number') return status === 429 || (status >= 500 && status < 600);
+
+  // Network/connection errors are retryable
+  return isNetworkError(error);
+}
@@ -0,0 +1,428 @@
+import cron from 'node-cron';
+import { getDb, type Db, type SqlValue } from './db';
+import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+
+/**
+ * Job supervisor for the jobs process
+ *
+ * Collectors describe their jobs (name, cron schedule, timeout, dependencies)
+ * and the supervisor schedules and runs them. A run that fires while the
+ * previous one is still going is skipped and counted, a job whose
+ * dependencies haven't succeeded yet in this process is skipped, and a run
+ * past its timeout is reported as hung while it keeps its slot until it
+ * settles. Every run is recorded in job_status so the web app can serve
+ * /api/health/jobs without talking to the jobs process.
+ *
+ * Jobs signal failure by throwing; the supervisor logs and records it. Each
+ * run gets an AbortSignal that abortJobs() fires, which long jobs check
+ * between batches and pass to withRetry.
+ */
+
+export interface JobDefinition {
+  name: string;
+  /** Cron expression, or null for a job that only runs at startup */
+  schedule: string | null;
+  timeoutMs: number;
+  /** Jobs that must have succeeded once in this process before this one runs */
+  dependsOn?: string[];
+  /** Unhealthy when the last success is older than this; null never goes stale */
+  staleAfterSeconds?: number | null;
+  /** Run once as soon as the supervisor starts */
+  runOnStart?: boolean;
+  run: (signal: AbortSignal) => Promise<unknown>;
+}
+
+interface JobState {
+  definition: JobDefinition;
+  task: ReturnType<typeof cron.schedule> | null;
+  running: Promise<void> | null;
+  controller: AbortController | null;
+  hasSucceeded: boolean;
+}
+
+const CONFIG = {
+  // How long shutdown waits for running jobs before closing the database
+  SHUTDOWN_GRACE_MS: parsePositiveInt(process.env.JOB_SHUTDOWN_GRACE_MS, 30_000),
+};
+
+const log = createLogger({ module: 'jobs' });
+
+const jobs = new Map<string, JobState>();
+let isStarted = false;
+let isStopping = false;
+
+const nowSeconds = () => Math.floor(Date.now() / 1000);
+
+/**
+ * Add job definitions. Names must be unique; call before startJobs().
+ */
+export function registerJobs(definitions: JobDefinition[]): void {
+  for (const definition of definitions) {
+    if (jobs.has(definition.name)) {
+      throw new Error(`Job ${definition.name} is already registered`);
+    }
+    if (definition.schedule !== null && !cron.validate(definition.schedule)) {
+      throw new Error(`Job ${definition.name} has an invalid schedule: ${definition.schedule}`);
+    }
+    jobs.set(definition.name, { definition, task: null, running: null, controller: null, hasSucceeded: false });
+  }
+}
+
+/**
+ * Record the registered jobs, schedule them and kick off the runOnStart
+ * ones. Rows for jobs that are no longer registered are removed so they
+ * don't keep failing the health check.
+ */
+export async function startJobs(): Promise<void> {
+  if (isStarted) return;
+
+  for (const { definition } of jobs.values()) {
+    for (const dependency of definition.dependsOn ?? []) {
+      if (!jobs.has(dependency)) {
+        throw new Error(`Job ${definition.name} depends on unknown job ${dependency}`);
+      }
+    }
+  }
+
+  isStarted = true;
+  isStopping = false;
+  await persistRegistrations();
+
+  for (const state of jobs.values()) {
+    const { schedule } = state.definition;
+    if (schedule !== null) {
+      state.task = cron.schedule(schedule, () => runJob(state.definition.name));
+    }
+  }
+
+  for (const state of jobs.values()) {
+    if (state.definition.runOnStart) void runJob(state.definition.name);
+  }
+
+  log.info('Jobs started', {
+    jobs: [...jobs.values()].map(({ definition }) => `${definition.name}@${definition.schedule ?? 'startup'}`),
+  });
+}
+
+function stopScheduling(): void {
+  isStopping = true;
+  for (const state of jobs.values()) {
+    state.task?.stop();
+    state.task = null;
+  }
+}
+
+/**
+ * Stop scheduling and wait up to SHUTDOWN_GRACE_MS for running jobs
+ */
+export async function stopJobs(): Promise<void> {
+  stopScheduling();
+
+  const running = [...jobs.values()].filter(state => state.running);
+  if (running.length === 0) return;
+
+  log.info('Waiting for running jobs', { jobs: running.map(state => state.definition.name) });
+  let timer: NodeJS.Timeout | undefined;
+  const settled = await Promise.race([
+    Promise.all(running.map(state => state.running)).then(() => true),
+    new Promise<false>(resolve => { timer = setTimeout(() => resolve(false), CONFIG.SHUTDOWN_GRACE_MS); }),
+  ]);
+  clearTimeout(timer);
+
+  if (!settled) {
+    log.warn('Jobs still running at shutdown', {
+      jobs: running.filter(state => state.running).map(state => state.definition.name),
+      graceMs: CONFIG.SHUTDOWN_GRACE_MS,
+    });
+  }
+}
+
+/**
+ * Stop scheduling and abort every running job at once, for a leader that has
+ * lost its lease: a standby is already collecting, so letting the runs finish
+ * would collect twice. stopJobs() still waits for the aborted runs to settle.
+ */
+export function abortJobs(reason: string): void {
+  stopScheduling();
+  const running = [...jobs.values()].filter(state => state.controller);
+  for (const state of running) {
+    state.controller?.abort(new Error(reason));
+  }
+  if (running.length > 0) {
+    log.warn('Aborted running jobs', { jobs: running.map(state => state.definition.name), reason });
+  }
+}
+
+/**
+ * Run a job now, subject to the same overlap and dependency rules as a
+ * scheduled run. Resolves when the run finishes or is skipped.
+ */
+export async function runJob(name: string): Promise<void> {
+  const state = jobs.get(name);
+  if (!state) throw new Error(`Unknown job ${name}`);
+  if (isStopping) return;
+
+  const { definition } = state;
+  if (state.running) {
+    log.warn('Job still running, skipping this run', { job: name });
+    await incrementCounter(name, 'overlap_skips');
+    return;
+  }
+
+  const waitingOn = (definition.dependsOn ?? []).filter(dependency => !jobs.get(dependency)?.hasSucceeded);
+  if (waitingOn.length > 0) {
+    log.info('Job waiting on dependencies, skipping this run', { job: name, waitingOn });
+    await incrementCounter(name, 'dependency_skips');
+    return;
+  }
+
+  state.running = execute(state);
+  try {
+    await state.running;
+  } finally {
+    state.running = null;
+  }
+}
+
+/**
+ * Run a job in the background between its scheduled runs, for collectors
+ * reacting to an event such as a new round. Ignored until the supervisor has
+ * started, so replays and tests that never register jobs can still fire it.
+ */
+export function triggerJob(name: string): void {
+  if (!isStarted || !jobs.has(name)) return;
+  void runJob(name);
+}
+
+async function execute(state: JobState): Promise<void> {
+  const { name, timeoutMs } = state.definition;
+  const startedAtMs = Date.now();
+  await recordStart(name, Math.floor(startedAtMs / 1000));
+
+  // A timeout only reports the run; the job keeps its slot until it settles
+  // and later runs are counted as overlap skips
+  const timer = setTimeout(() => {
+    log.error('Job exceeded its timeout', { job: name, timeoutMs });
+    void recordTimeout(name, timeoutMs);
+  }, timeoutMs);
+  timer.unref();
+
+  const controller = new AbortController();
+  state.controller = controller;
+  try {
+    await state.definition.run(controller.signal);
+    state.hasSucceeded = true;
+    await recordFinish(name, startedAtMs, null);
+  } catch (error) {
+    log.warn('Job failed', { job: name, error, durationMs: Date.now() - startedAtMs });
+    await recordFinish(name, startedAtMs, error instanceof Error ? error.message : String(error));
+  } finally {
+    clearTimeout(timer);
+    state.controller = null;
+  }
+}
+
+// Status writes never fail a job: a database hiccup only costs a stale row
+
+async function writeStatus(sql: string, params: SqlValue[]): Promise<void> {
+  try {
+    const db = await getDb();
+    await db.run(sql, params);
+  } catch (error) {
+    log.warn('Failed to record job status', { error });
+  }
+}
+
+async function persistRegistrations(): Promise<void> {
+  const now = nowSeconds();
+  const names = [...jobs.keys()];
+
+  try {
+    const db = await getDb();
+    await db.transaction(async () => {
+      if (names.length > 0) {
+        await db.run(
+          `DELETE FROM job_status WHERE name NOT IN (${names.map(() => '?').join(', ')})`,
+          names
+        );
+      }
+
+      for (const { definition } of jobs.values()) {
+        // A previous process that died mid-run left running = 1 behind
+        await db.run(`
+          INSERT INTO job_status (name, schedule, timeout_ms, stale_after_seconds, depends_on, registered_at, updated_at)
+          VALUES (?, ?, ?, ?, ?, ?, ?)
+          ON CONFLICT(name) DO UPDATE SET
+            schedule = excluded.schedule,
+            timeout_ms = excluded.timeout_ms,
+            stale_after_seconds = excluded.stale_after_seconds,
+            depends_on = excluded.depends_on,
+            registered_at = excluded.registered_at,
+            running = 0,
+            updated_at = excluded.updated_at
+        `, [
+          definition.name,
+          definition.schedule,
+          definition.timeoutMs,
+          definition.staleAfterSeconds ?? null,
+          (definition.dependsOn ?? []).join(','),
+          now,
+          now,
+        ]);
+      }
+    });
+  } catch (error) {
+    log.warn('Failed to record job registrations', { error });
+  }
+}
+
+function recordStart(name: string, startedAt: number): Promise<void> {
+  return writeStatus(`
+    UPDATE job_status SET running = 1, last_started_at = ?, runs = runs + 1, updated_at = ?
+    WHERE name = ?
+  `, [startedAt, startedAt, name]);
+}
+
+function recordFinish(name: string, startedAtMs: number, error: string | null): Promise<void> {
+  const now = nowSeconds();
+  const durationMs = Date.now() - startedAtMs;
+
+  if (error === null) {
+    return writeStatus(`
+      UPDATE job_status SET
+        running = 0, last_finished_at = ?, last_duration_ms = ?, last_success_at = ?, updated_at = ?
+      WHERE name = ?
+    `, [now, durationMs, now, now, name]);
+  }
+
+  return writeStatus(`
+    UPDATE job_status SET
+      running = 0, last_finished_at = ?, last_duration_ms = ?,
+      last_error = ?, last_error_at = ?, failures = failures + 1, updated_at = ?
+    WHERE name = ?
+  `, [now, durationMs, error, now, now, name]);
+}
+
+function recordTimeout(name: string, timeoutMs: number): Promise<void> {
+  const now = nowSeconds();
+  return writeStatus(`
+    UPDATE job_status SET timeouts = timeouts + 1, last_error = ?, last_error_at = ?, updated_at = ?
+    WHERE name = ?
+  `, [`Timed out after ${timeoutMs}ms`, now, now, name]);
+}
+
+function incrementCounter(name: string, column: 'overlap_skips' | 'dependency_skips'): Promise<void> {
+  const now = nowSeconds();
+  return writeStatus(
+    `UPDATE job_status SET ${column} = ${column} + 1, updated_at = ? WHERE name = ?`,
+    [now, name]
+  );
+}
+
+export interface JobStatusRow {
+  name: string;
+  schedule: string | null;
+  timeout_ms: number;
+  stale_after_seconds: number | null;
+  depends_on: string;
+  registered_at: number;
+  running: number;
+  last_started_at: number | null;
+  last_finished_at: number | null;
+  last_duration_ms: number | null;
+  last_success_at: number | null;
+  last_error: string | null;
+  last_error_at: number | null;
+  runs: number;
+  failures: number;
+  timeouts: number;
+  overlap_skips: number;
+  dependency_skips: number;
+  updated_at: number;
+}
+
+/**
+ * hung: running past its timeout. stale: no success within
+ * stale_after_seconds (counted from registration until the first success).
+ * failing: the latest run failed. Only hung and stale are unhealthy; a single
+ * failure is left to the job's next run.
+ */
+export type JobHealthStatus = 'ok' | 'running' | 'never_run' | 'failing' | 'stale' | 'hung';
+
+export interface JobHealth {
+  name: string;
+  schedule: string | null;
+  status: JobHealthStatus;
+  healthy: boolean;
+  dependsOn: string[];
+  timeoutMs: number;
+  staleAfterSeconds: number | null;
+  running: boolean;
+  lastStartedAt: number | null;
+  lastFinishedAt: number | null;
+  lastDurationMs: number | null;
+  lastSuccessAt: number | null;
+  lastSuccessAge: number | null;
+  lastError: string | null;
+  lastErrorAt: number | null;
+  runs: number;
+  failures: number;
+  timeouts: number;
+  overlapSkips: number;
+  dependencySkips: number;
+}
+
+export function evaluateJobHealth(row: JobStatusRow, now: number): JobHealth {
+  const running = Boolean(row.running);
+  const isHung = running && row.last_started_at !== null
+    && (now - row.last_started_at) * 1000 > row.timeout_ms;
+  const isStale = row.stale_after_seconds !== null
+    && now - (row.last_success_at ?? row.registered_at) > row.stale_after_seconds;
+  const isFailing = row.last_error_at !== null
+    && (row.last_success_at === null || row.last_error_at >= row.last_success_at);
+
+  let status: JobHealthStatus;
+  if (isHung) status = 'hung';
+  else if (isStale) status = 'stale';
+  else if (isFailing) status = 'failing';
+  else if (running) status = 'running';
+  else if (row.last_success_at === null) status = 'never_run';
+  else status = 'ok';
+
+  return {
+    name: row.name,
+    schedule: row.schedule,
+    status,
+    healthy: !isHung && !isStale,
+    dependsOn: row.depends_on ? row.depends_on.split(',') : [],
+    timeoutMs: row.timeout_ms,
+    staleAfterSeconds: row.stale_after_seconds,
+    running,
+    lastStartedAt: row.last_started_at,
+    lastFinishedAt: row.last_finished_at,
+    lastDurationMs: row.last_duration_ms,
+    lastSuccessAt: row.last_success_at,
+    lastSuccessAge: row.last_success_at !== null ? now - row.last_success_at : null,
+    lastError: row.last_error,
+    lastErrorAt: row.last_error_at,
+    runs: row.runs,
+    failures: row.failures,
+    timeouts: row.timeouts,
+    overlapSkips: row.overlap_skips,
+    dependencySkips: row.dependency_skips,
+  };
+}
+
+/**
+ * Health of every job the jobs process registered. Unhealthy when any job is
+ * hung or stale, or when no job has ever been registered.
+ */
+export async function getJobsHealth(db: Db, now = nowSeconds()): Promise<{ healthy: boolean; jobs: JobHealth[] }> {
+  const rows = await db.all<JobStatusRow>('SELECT * FROM job_status ORDER BY name');
+  const jobsHealth = rows.map(row => evaluateJobHealth(row, now));
+  return {
+    healthy: jobsHealth.length > 0 && jobsHealth.every(job => job.healthy),
+    jobs: jobsHealth,
+  };
+}
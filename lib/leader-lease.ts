@@ -0,0 +1,225 @@
+import { randomBytes } from 'crypto';
+import os from 'os';
+import { getDb, type Db } from './db';
+import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+
+/**
+ * Leader election for the jobs process
+ *
+ * Only one `pnpm collect-stats` may collect at a time, or pool_stats gets
+ * duplicate rows and rounds are fetched twice. Every process competes for a
+ * row in leader_lease: the holder renews it every LEADER_LEASE_RENEW_SECONDS
+ * and the others poll at the same interval, taking it over once it has gone
+ * LEADER_LEASE_TTL_SECONDS without a renewal. A leader that fails to renew
+ * before its lease runs out is demoted and must stop collecting; on a clean
+ * shutdown it releases the lease so a standby takes over on its next poll.
+ * Collectors also call assertLeaseHeld() before each write batch, so a
+ * leader that lost its lease between heartbeats stops writing at once.
+ *
+ * Expiry compares timestamps written by different processes, so hosts
+ * sharing a Postgres database need synchronised clocks.
+ */
+
+const CONFIG = {
+  LEASE_NAME: 'jobs',
+  TTL_SECONDS: parsePositiveInt(process.env.LEADER_LEASE_TTL_SECONDS, 30),
+  RENEW_SECONDS: parsePositiveInt(process.env.LEADER_LEASE_RENEW_SECONDS, 10),
+};
+
+// Renewing less often than the lease lasts would let it lapse between heartbeats
+const RENEW_MS = Math.min(CONFIG.RENEW_SECONDS, Math.max(1, Math.floor(CONFIG.TTL_SECONDS / 3))) * 1000;
+
+const log = createLogger({ module: 'leader' });
+
+export interface LeaderLease {
+  holder: string;
+  acquiredAt: number;
+  renewedAt: number;
+  expiresAt: number;
+}
+
+/**
+ * Take the lease if it's free, expired or already ours. Returns whether
+ * `holder` holds it afterwards.
+ */
+export async function tryAcquireLease(
+  db: Db,
+  name: string,
+  holder: string,
+  now: number,
+  ttlSeconds: number
+): Promise<boolean> {
+  const { changes } = await db.run(`
+    INSERT INTO leader_lease AS l (name, holder, acquired_at, renewed_at, expires_at)
+    VALUES (?, ?, ?, ?, ?)
+    ON CONFLICT(name) DO UPDATE SET
+      holder = excluded.holder,
+      acquired_at = CASE WHEN l.holder = excluded.holder THEN l.acquired_at ELSE excluded.acquired_at END,
+      renewed_at = excluded.renewed_at,
+      expires_at = excluded.expires_at
+    WHERE l.holder = excluded.holder OR l.expires_at <= ?
+  `, [name, holder, now, now, now + ttlSeconds, now]);
+  return changes > 0;
+}
+
+/**
+ * Extend a lease `holder` still owns. False when it has been taken over.
+ */
+export async function renewLease(
+  db: Db,
+  name: string,
+  holder: string,
+  now: number,
+  ttlSeconds: number
+): Promise<boolean> {
+  const { changes } = await db.run(
+    'UPDATE leader_lease SET renewed_at = ?, expires_at = ? WHERE name = ? AND holder = ?',
+    [now, now + ttlSeconds, name, holder]
+  );
+  return changes > 0;
+}
+
+/**
+ * Whether `holder` owns the lease and it hasn't expired
+ */
+export async function holdsLease(db: Db, name: string, holder: string, now: number): Promise<boolean> {
+  const row = await db.get<{ holder: string }>(
+    'SELECT holder FROM leader_lease WHERE name = ? AND holder = ? AND expires_at > ?',
+    [name, holder, now]
+  );
+  return row !== undefined;
+}
+
+export async function releaseLease(db: Db, name: string, holder: string): Promise<void> {
+  await db.run('DELETE FROM leader_lease WHERE name = ? AND holder = ?', [name, holder]);
+}
+
+export async function getLease(db: Db, name = CONFIG.LEASE_NAME): Promise<LeaderLease | null> {
+  const row = await db.get<{ holder: string; acquired_at: number; renewed_at: number; expires_at: number }>(
+    'SELECT holder, acquired_at, renewed_at, expires_at FROM leader_lease WHERE name = ?',
+    [name]
+  );
+  return row
+    ? { holder: row.holder, acquiredAt: row.acquired_at, renewedAt: row.renewed_at, expiresAt: row.expires_at }
+    : null;
+}
+
+export class LeaseLostError extends Error {
+  constructor(holder: string) {
+    super(`${holder} no longer holds the leader lease`);
+    this.name = 'LeaseLostError';
+  }
+}
+
+// The election this process runs, if any, for assertLeaseHeld
+let currentElection: { holder: string; isLeader: () => boolean; demote: (reason: string) => void } | null = null;
+
+/**
+ * Write fence for collectors: throws LeaseLostError, and demotes this
+ * process, unless it still holds an unexpired jobs lease. Call it inside the
+ * write batch's transaction. A no-op in processes that never start an
+ * election, such as the web app and one-off scripts.
+ */
+export async function assertLeaseHeld(db: Db): Promise<void> {
+  if (!currentElection) return;
+
+  const { holder } = currentElection;
+  if (!currentElection.isLeader()) throw new LeaseLostError(holder);
+  if (!(await holdsLease(db, CONFIG.LEASE_NAME, holder, Math.floor(Date.now() / 1000)))) {
+    currentElection.demote('lease lost before a write');
+    throw new LeaseLostError(holder);
+  }
+}
+
+export interface LeaderElection {
+  /** This process's id in leader_lease.holder */
+  holder: string;
+  isLeader(): boolean;
+  /** Stop heartbeating and release the lease if held */
+  stop(): Promise<void>;
+}
+
+/**
+ * Compete for the jobs lease. `onElected` runs once this process holds the
+ * lease; `onDemoted` runs if it then loses it, after which the election
+ * stops and the caller is expected to abort running jobs and exit. From then
+ * on assertLeaseHeld() fails every write.
+ */
+export function startLeaderElection(handlers: {
+  onElected: () => void | Promise<void>;
+  onDemoted: () => void;
+}): LeaderElection {
+  const holder = `${os.hostname()}:${process.pid}:${randomBytes(3).toString('hex')}`;
+  let isLeader = false;
+  let isStopped = false;
+  let leaseExpiresAt = 0;
+  let timer: NodeJS.Timeout | null = null;
+  let pending: Promise<void> = Promise.resolve();
+
+  const demote = (reason: string) => {
+    if (!isLeader) return;
+    isLeader = false;
+    isStopped = true;
+    log.error('Lost leadership, stopping collection', { holder, reason });
+    handlers.onDemoted();
+  };
+
+  const heartbeat = async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      const db = await getDb();
+      if (isLeader) {
+        if (await renewLease(db, CONFIG.LEASE_NAME, holder, now, CONFIG.TTL_SECONDS)) {
+          leaseExpiresAt = now + CONFIG.TTL_SECONDS;
+        } else {
+          demote('lease taken over');
+        }
+        return;
+      }
+
+      if (await tryAcquireLease(db, CONFIG.LEASE_NAME, holder, now, CONFIG.TTL_SECONDS)) {
+        isLeader = true;
+        leaseExpiresAt = now + CONFIG.TTL_SECONDS;
+        log.info('Elected leader', { holder, ttlSeconds: CONFIG.TTL_SECONDS });
+        await handlers.onElected();
+      }
+    } catch (error) {
+      // Keep leading through a database hiccup only while the lease is still ours
+      if (isLeader && now >= leaseExpiresAt) {
+        demote('lease expired before it could be renewed');
+      } else {
+        log.warn('Leader lease heartbeat failed', { holder, error });
+      }
+    }
+  };
+
+  const tick = () => {
+    pending = heartbeat().finally(() => {
+      if (!isStopped) timer = setTimeout(tick, RENEW_MS);
+    });
+  };
+
+  currentElection = { holder, isLeader: () => isLeader, demote };
+  log.info('Waiting for the leader lease', { holder, renewMs: RENEW_MS, ttlSeconds: CONFIG.TTL_SECONDS });
+  tick();
+
+  return {
+    holder,
+    isLeader: () => isLeader,
+    async stop() {
+      isStopped = true;
+      if (timer) clearTimeout(timer);
+      await pending;
+      if (!isLeader) return;
+
+      isLeader = false;
+      try {
+        await releaseLease(await getDb(), CONFIG.LEASE_NAME, holder);
+        log.info('Released leader lease', { holder });
+      } catch (error) {
+        log.warn('Failed to release leader lease', { holder, error });
+      }
+    },
+  };
+}
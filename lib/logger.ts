@@ -0,0 +1,138 @@
+import { randomBytes } from 'crypto';
+import { formatAddress } from '@/app/utils/formatters';
+
+/**
+ * Structured logger
+ *
+ * Emits one JSON object per line with `time`, `level`, `msg` and the
+ * logger's context merged with the call's fields, so the log pipeline can
+ * filter by level, collector, cycle, block height or address. Set
+ * LOG_FORMAT=pretty (the default outside production) for a readable
+ * single-line format instead, and LOG_LEVEL to debug, info, warn or error.
+ *
+ * Addresses in an `address` field are truncated with formatAddress, and an
+ * `error` field is expanded to its name, message and stack.
+ *
+ * @example
+ * const log = createLogger({ collector: 'rounds' });
+ * const cycleLog = log.child({ cycle: newCycleId() });
+ * cycleLog.info('Synced rounds', { rounds: 12, durationMs: 340 });
+ */
+
+export type LogLevel = 'debug' | 'info' | 'warn' | 'error';
+
+export type LogFields = Record<string, unknown>;
+
+export interface Logger {
+  debug(message: string, fields?: LogFields): void;
+  info(message: string, fields?: LogFields): void;
+  warn(message: string, fields?: LogFields): void;
+  error(message: string, fields?: LogFields): void;
+  /** A logger that adds `fields` to every entry */
+  child(fields: LogFields): Logger;
+}
+
+const LEVELS: Record<LogLevel, number> = { debug: 10, info: 20, warn: 30, error: 40 };
+
+function parseLevel(raw: string | undefined): LogLevel {
+  const value = raw?.toLowerCase();
+  return value && value in LEVELS ? (value as LogLevel) : 'info';
+}
+
+const CONFIG = {
+  LEVEL: parseLevel(process.env.LOG_LEVEL),
+  PRETTY: (process.env.LOG_FORMAT ?? (process.env.NODE_ENV === 'production' ? 'json' : 'pretty')) === 'pretty',
+};
+
+/** Short id tying together the entries of one collector cycle */
+export function newCycleId(): string {
+  return randomBytes(4).toString('hex');
+}
+
+/** An error as plain fields; JSON.stringify drops everything on Error */
+export function serializeError(error: unknown): LogFields | string {
+  if (!(error instanceof Error)) return String(error);
+
+  const cause = (error as Error & { cause?: unknown }).cause;
+  return {
+    name: error.name,
+    message: error.message,
+    ...(error.stack ? { stack: error.stack } : {}),
+    ...(cause !== undefined ? { cause: serializeError(cause) } : {}),
+  };
+}
+
+function normalizeFields(fields: LogFields): LogFields {
+  const normalized: LogFields = {};
+  for (const [key, value] of Object.entries(fields)) {
+    if (value === undefined) continue;
+    if (key === 'address' && typeof value === 'string') {
+      normalized[key] = formatAddress(value);
+    } else if (key === 'error' || value instanceof Error) {
+      normalized[key] = serializeError(value);
+    } else if (typeof value === 'bigint') {
+      normalized[key] = value.toString();
+    } else {
+      normalized[key] = value;
+    }
+  }
+  return normalized;
+}
+
+function formatPretty(level: LogLevel, message: string, time: Date, fields: LogFields): string {
+  const parts = [time.toISOString().substring(11, 23), level.toUpperCase().padEnd(5)];
+  const scope = fields.collector ?? fields.route ?? fields.module;
+  if (typeof scope === 'string') parts.push(`[${scope}]`);
+  parts.push(message);
+
+  let stack: string | undefined;
+  for (const [key, value] of Object.entries(fields)) {
+    if (key === 'collector' || key === 'route' || key === 'module') continue;
+    if (key === 'error' && typeof value === 'object' && value !== null) {
+      const error = value as { name?: string; message?: string; stack?: string };
+      parts.push(`error="${error.name}: ${error.message}"`);
+      stack = error.stack;
+      continue;
+    }
+    parts.push(`${key}=${typeof value === 'string' ? value : JSON.stringify(value)}`);
+  }
+
+  return stack && level === 'error' ? `${parts.join(' ')}\n${stack}` : parts.join(' ');
+}
+
+function write(level: LogLevel, message: string, context: LogFields, fields: LogFields | undefined): void {
+  if (LEVELS[level] < LEVELS[CONFIG.LEVEL]) return;
+
+  const time = new Date();
+  const merged = normalizeFields({ ...context, ...fields });
+  let line: string;
+  if (CONFIG.PRETTY) {
+    line = formatPretty(level, message, time, merged);
+  } else {
+    try {
+      line = JSON.stringify({ time: time.toISOString(), level, msg: message, ...merged });
+    } catch {
+      // Circular or otherwise unserializable fields
+      line = JSON.stringify({ time: time.toISOString(), level, msg: message, fields: '[unserializable]' });
+    }
+  }
+
+  const isWarning = LEVELS[level] >= LEVELS.warn;
+  if (!process.stdout) {
+    // In the browser, where app/utils/api.ts retries through lib/retry
+    (isWarning ? console.warn : console.log)(line);
+    return;
+  }
+  const stream = isWarning ? process.stderr : process.stdout;
+  stream.write(`${line}\n`);
+}
+
+export function createLogger(context: LogFields = {}): Logger {
+  return {
+    debug: (message, fields) => write('debug', message, context, fields),
+    info: (message, fields) => write('info', message, context, fields),
+    warn: (message, fields) => write('warn', message, context, fields),
+    error: (message, fields) => write('error', message, context, fields),
+    child: fields => createLogger({ ...context, ...fields }),
+  };
+}
@@ -0,0 +1,194 @@
+import { timingSafeEqual } from 'crypto';
+import http from 'http';
+import { getDb } from './db';
+import { createLogger } from './logger';
+import { gauge, METRICS_CONTENT_TYPE, renderMetrics } from './metrics';
+
+/**
+ * Metrics scrape for both processes
+ *
+ * The web app's /api/metrics route and the jobs process's METRICS_PORT server
+ * both answer with collectMetrics(): the process's own counters and histograms
+ * (HTTP client, aggregator cache, rate limiter, collector cycles) plus gauges
+ * read from the database at scrape time, which are the same in either process.
+ * When METRICS_TOKEN is set, scrapes need `Authorization: Bearer <token>`.
+ */
+
+const log = createLogger({ module: 'metrics' });
+
+const HASHRATE_WINDOWS = ['1m', '5m', '15m', '1hr', '6hr', '1d', '7d'] as const;
+const STRATUM_STATUSES = ['connecting', 'connected', 'disconnected', 'stopped'] as const;
+
+const poolHashrate = gauge('parastats_pool_hashrate_hs', 'Pool hashrate in H/s by averaging window, from the latest pool stats sample');
+const poolWorkers = gauge('parastats_pool_workers', 'Pool workers by state (active, idle, disconnected) from the latest pool stats sample');
+const poolUsers = gauge('parastats_pool_users', 'Users reported by the pool in the latest pool stats sample');
+const poolStatsTimestamp = gauge('parastats_pool_stats_timestamp_seconds', 'Unix time of the latest pool stats sample');
+const monitoredUsers = gauge('parastats_monitored_users', 'Users tracked by the collector, by active flag');
+const stratumStatus = gauge('parastats_stratum_status', 'Stratum collector connection state (1 for the current status) by pool');
+const stratumLastMessage = gauge('parastats_stratum_last_message_timestamp_seconds', 'Unix time of the last message from each stratum pool');
+const stratumReconnects = gauge('parastats_stratum_reconnects', 'Reconnections since each stratum collector started');
+const stratumFailures = gauge('parastats_stratum_consecutive_failures', 'Consecutive failed connection attempts per stratum pool');
+const userCycleGauge = gauge('parastats_user_collection_last_cycle', 'Figures from the last user collection cycle (eligible, due, collected, failed, deferred, rate_limited, duration_seconds)');
+const userCycleCoverage = gauge('parastats_user_collection_coverage_ratio', 'Share of eligible users on schedule after the last user collection cycle');
+const userCycleLag = gauge('parastats_user_collection_lag_seconds', 'How long users collected in the last cycle waited past their due time (avg, max)');
+const dbUp = gauge('parastats_metrics_db_up', 'Whether the database-derived metrics could be read on the last scrape');
+const notificationOutbox = gauge('parastats_notification_outbox', 'Notification outbox rows by status');
+
+async function refreshDbGauges(): Promise<void> {
+  const db = await getDb();
+
+  const pool = await db.get<Record<string, number | null>>(`
+    SELECT timestamp, users, workers, idle, disconnected,
+      ${HASHRATE_WINDOWS.map(window => `hashrate${window}_hs`).join(', ')}
+    FROM pool_stats
+    ORDER BY timestamp DESC
+    LIMIT 1
+  `);
+  if (pool) {
+    for (const window of HASHRATE_WINDOWS) {
+      const value = pool[`hashrate${window}_hs`];
+      if (value !== null) poolHashrate.set({ window }, value);
+    }
+    poolUsers.set({}, pool.users ?? 0);
+    poolWorkers.set({ state: 'active' }, pool.workers ?? 0);
+    poolWorkers.set({ state: 'idle' }, pool.idle ?? 0);
+    poolWorkers.set({ state: 'disconnected' }, pool.disconnected ?? 0);
+    poolStatsTimestamp.set({}, pool.timestamp ?? 0);
+  }
+
+  const users = await db.all<{ is_active: number; count: number }>(
+    'SELECT is_active, COUNT(*) AS count FROM monitored_users GROUP BY is_active'
+  );
+  monitoredUsers.set({ active: 'true' }, users.find(row => Number(row.is_active) === 1)?.count ?? 0);
+  monitoredUsers.set({ active: 'false' }, users.find(row => Number(row.is_active) !== 1)?.count ?? 0);
+
+  const stratum = await db.all<{
+    pool: string;
+    status: string;
+    last_message_at: number | null;
+    reconnect_count: number;
+    consecutive_failures: number;
+  }>('SELECT pool, status, last_message_at, reconnect_count, consecutive_failures FROM stratum_health');
+  // Pools can be removed from STRATUM_ENDPOINTS; don't keep exporting them
+  for (const metric of [stratumStatus, stratumLastMessage, stratumReconnects, stratumFailures]) {
+    metric.reset();
+  }
+  for (const row of stratum) {
+    for (const status of STRATUM_STATUSES) {
+      stratumStatus.set({ pool: row.pool, status }, row.status === status ? 1 : 0);
+    }
+    if (row.last_message_at !== null) {
+      stratumLastMessage.set({ pool: row.pool }, row.last_message_at);
+    }
+    stratumReconnects.set({ pool: row.pool }, row.reconnect_count);
+    stratumFailures.set({ pool: row.pool }, row.consecutive_failures);
+  }
+
+  const cycle = await db.get<{
+    duration_ms: number;
+    eligible_users: number;
+    due_users: number;
+    collected: number;
+    failed: number;
+    deferred: number;
+    rate_limited: number;
+    coverage: number;
+    avg_lag_seconds: number;
+    max_lag_seconds: number;
+  }>('SELECT * FROM user_collection_cycles ORDER BY started_at DESC LIMIT 1');
+  if (cycle) {
+    userCycleGauge.set({ figure: 'eligible' }, cycle.eligible_users);
+    userCycleGauge.set({ figure: 'due' }, cycle.due_users);
+    userCycleGauge.set({ figure: 'collected' }, cycle.collected);
+    userCycleGauge.set({ figure: 'failed' }, cycle.failed);
+    userCycleGauge.set({ figure: 'deferred' }, cycle.deferred);
+    userCycleGauge.set({ figure: 'rate_limited' }, cycle.rate_limited);
+    userCycleGauge.set({ figure: 'duration_seconds' }, cycle.duration_ms / 1000);
+    userCycleCoverage.set({}, cycle.coverage);
+    userCycleLag.set({ stat: 'avg' }, cycle.avg_lag_seconds);
+    userCycleLag.set({ stat: 'max' }, cycle.max_lag_seconds);
+  }
+
+  const outbox = await db.all<{ status: string; count: number }>(
+    'SELECT status, COUNT(*) AS count FROM notification_outbox GROUP BY status'
+  );
+  notificationOutbox.reset();
+  for (const row of outbox) {
+    notificationOutbox.set({ status: row.status }, row.count);
+  }
+}
+
+/**
+ * Render this process's metrics. A database failure is reported through
+ * parastats_metrics_db_up rather than failing the scrape, so the in-process
+ * metrics stay visible while the database is down.
+ */
+export async function collectMetrics(): Promise<string> {
+  try {
+    await refreshDbGauges();
+    dbUp.set({}, 1);
+  } catch (error) {
+    log.error('Error reading metrics from the database', { error });
+    dbUp.set({}, 0);
+  }
+  return renderMetrics();
+}
+
+/** Check a scrape's Authorization header against METRICS_TOKEN, if set */
+export function isMetricsRequestAuthorized(authorization: string | null | undefined): boolean {
+  const token = process.env.METRICS_TOKEN;
+  if (!token) return true;
+
+  const expected = Buffer.from(`Bearer ${token}`);
+  const received = Buffer.from(authorization ?? '');
+  return received.length === expected.length && timingSafeEqual(received, expected);
+}
+
+/**
+ * Serve GET /metrics on the given port, for the jobs process, and GET /health
+ * from the optional health check (JSON, 503 when unhealthy) for liveness
+ * probes. Resolves once listening.
+ */
+export function startMetricsServer(
+  port: number,
+  healthCheck?: () => Promise<{ healthy: boolean }>
+): Promise<http.Server> {
+  const server = http.createServer(async (req, res) => {
+    const path = (req.url ?? '').split('?')[0];
+    if (req.method === 'GET' && path === '/health' && healthCheck) {
+      try {
+        const health = await healthCheck();
+        res.writeHead(health.healthy ? 200 : 503, { 'Content-Type': 'application/json', 'Cache-Control': 'no-store' })
+          .end(JSON.stringify(health));
+      } catch (error) {
+        log.error('Error serving health check', { error });
+        res.writeHead(500).end();
+      }
+      return;
+    }
+    if (req.method !== 'GET' || path !== '/metrics') {
+      res.writeHead(404).end();
+      return;
+    }
+    if (!isMetricsRequestAuthorized(req.headers.authorization)) {
+      res.writeHead(401).end();
+      return;
+    }
+
+    try {
+      const body = await collectMetrics();
+      res.writeHead(200, { 'Content-Type': METRICS_CONTENT_TYPE }).end(body);
+    } catch (error) {
+      log.error('Error serving metrics', { error });
+      res.writeHead(500).end();
+    }
+  });
+
+  return new Promise((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(port, () => {
+      server.off('error', reject);
+      resolve(server);
+    });
+  });
+}
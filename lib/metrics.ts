@@ -0,0 +1,301 @@
+/**
+ * In-process metrics in the Prometheus text exposition format
+ *
+ * Counters, gauges and histograms live in one registry per process, kept on
+ * globalThis so Next.js route bundles and HMR reloads share it. The web app
+ * serves it at /api/metrics and the jobs process on METRICS_PORT; both add
+ * database-derived gauges at scrape time (see metrics-exporter.ts).
+ */
+
+type Labels = Record<string, string>;
+
+interface MetricFamily {
+  name: string;
+  help: string;
+  type: 'counter' | 'gauge' | 'histogram';
+  render(): string[];
+}
+
+const globalForMetrics = globalThis as typeof globalThis & {
+  __metricsRegistry?: Map<string, MetricFamily>;
+};
+
+function getRegistry(): Map<string, MetricFamily> {
+  if (!globalForMetrics.__metricsRegistry) {
+    globalForMetrics.__metricsRegistry = new Map();
+  }
+  return globalForMetrics.__metricsRegistry;
+}
+
+function escapeLabelValue(value: string): string {
+  return value.replace(/\\/g, '\\\\').replace(/\n/g, '\\n').replace(/"/g, '\\"');
+}
+
+function formatLabels(labels: Labels): string {
+  const entries = Object.entries(labels);
+  if (entries.length === 0) return '';
+  return `{${entries.map(([key, value]) => `${key}="${escapeLabelValue(value)}"`).join(',')}}`;
+}
+
+function formatValue(value: number): string {
+  if (Number.isNaN(value)) return 'NaN';
+  if (value === Infinity) return '+Inf';
+  if (value === -Infinity) return '-Inf';
+  return String(value);
+}
+
+/** Stable key for a label set, independent of property order */
+function labelKey(labels: Labels): string {
+  return JSON.stringify(Object.keys(labels).sort().map(key => [key, labels[key]]));
+}
+
+export class Counter implements MetricFamily {
+  readonly type = 'counter' as const;
+  private values = new Map<string, { labels: Labels; value: number }>();
+
+  constructor(readonly name: string, readonly help: string) {}
+
+  inc(labels: Labels = {}, value = 1): void {
+    const key = labelKey(labels);
+    const entry = this.values.get(key);
+    if (entry) {
+      entry.value += value;
+    } else {
+      this.values.set(key, { labels, value });
+    }
+  }
+
+  get(labels: Labels = {}): number {
+    return this.values.get(labelKey(labels))?.value ?? 0;
+  }
+
+  render(): string[] {
+    return [...this.values.values()].map(({ labels, value }) =>
+      `${this.name}${formatLabels(labels)} ${formatValue(value)}`
+    );
+  }
+}
+
+export class Gauge implements MetricFamily {
+  readonly type = 'gauge' as const;
+  private values = new Map<string, { labels: Labels; value: number }>();
+
+  constructor(readonly name: string, readonly help: string) {}
+
+  set(labels: Labels, value: number): void {
+    this.values.set(labelKey(labels), { labels, value });
+  }
+
+  get(labels: Labels = {}): number | undefined {
+    return this.values.get(labelKey(labels))?.value;
+  }
+
+  /** Drop every series, for gauges rebuilt from scratch on each scrape */
+  reset(): void {
+    this.values.clear();
+  }
+
+  render(): string[] {
+    return [...this.values.values()].map(({ labels, value }) =>
+      `${this.name}${formatLabels(labels)} ${formatValue(value)}`
+    );
+  }
+}
+
+export class Histogram implements MetricFamily {
+  readonly type = 'histogram' as const;
+  private values = new Map<string, { labels: Labels; buckets: number[]; sum: number; count: number }>();
+
+  constructor(readonly name: string, readonly help: string, readonly bucketBounds: number[]) {}
+
+  observe(labels: Labels, value: number): void {
+    const key = labelKey(labels);
+    const entry = this.values.get(key) ?? { labels, buckets: this.bucketBounds.map(() => 0), sum: 0, count: 0 };
+    this.values.set(key, entry);
+    this.bucketBounds.forEach((bound, index) => {
+      if (value <= bound) entry.buckets[index]++;
+    });
+    entry.sum += value;
+    entry.count++;
+  }
+
+  render(): string[] {
+    const lines: string[] = [];
+    for (const { labels, buckets, sum, count } of this.values.values()) {
+      this.bucketBounds.forEach((bound, index) => {
+        lines.push(`${this.name}_bucket${formatLabels({ ...labels, le: formatValue(bound) })} ${buckets[index]}`);
+      });
+      lines.push(`${this.name}_bucket${formatLabels({ ...labels, le: '+Inf' })} ${count}`);
+      lines.push(`${this.name}_sum${formatLabels(labels)} ${formatValue(sum)}`);
+      lines.push(`${this.name}_count${formatLabels(labels)} ${count}`);
+    }
+    return lines;
+  }
+}
+
+function register<T extends MetricFamily>(metric: T): T {
+  const registry = getRegistry();
+  const existing = registry.get(metric.name);
+  if (existing) {
+    if (existing.type !== metric.type) {
+      throw new Error(`Metric ${metric.name} is already registered as a ${existing.type}`);
+    }
+    return existing as T;
+  }
+  registry.set(metric.name, metric);
+  return metric;
+}
+
+export function counter(name: string, help: string): Counter {
+  return register(new Counter(name, help));
+}
+
+export function gauge(name: string, help: string): Gauge {
+  return register(new Gauge(name, help));
+}
+
+export function histogram(name: string, help: string, buckets: number[]): Histogram {
+  return register(new Histogram(name, help, buckets));
+}
+
+/** Every registered metric in the Prometheus text format (version 0.0.4) */
+export function renderMetrics(): string {
+  const lines: string[] = [];
+  for (const metric of getRegistry().values()) {
+    const samples = metric.render();
+    if (samples.length === 0) continue;
+    lines.push(`# HELP ${metric.name} ${metric.help.replace(/\\/g, '\\\\').replace(/\n/g, '\\n')}`);
+    lines.push(`# TYPE ${metric.name} ${metric.type}`);
+    lines.push(...samples);
+  }
+  return lines.length > 0 ? `${lines.join('\n')}\n` : '';
+}
+
+export const METRICS_CONTENT_TYPE = 'text/plain; version=0.0.4; charset=utf-8';
+
+/**
+ * Collapse a URL or path into a route template so labels stay low
+ * cardinality: numeric segments (block heights, ids) become `:n` and long
+ * segments (addresses, hashes) become `:id`. The query string is dropped.
+ */
+export function routeLabel(urlOrPath: string): string {
+  let path = urlOrPath;
+  try {
+    path = new URL(urlOrPath, 'http://localhost').pathname;
+  } catch {
+    // Not a URL; label the raw string
+  }
+
+  const route = path
+    .split('/')
+    .map(segment => {
+      if (/^\d+$/.test(segment)) return ':n';
+      if (segment.length >= 20) return ':id';
+      return segment;
+    })
+    .join('/');
+  return route || '/';
+}
+
+function hostLabel(url: string): string {
+  try {
+    return new URL(url).host;
+  } catch {
+    return 'unknown';
+  }
+}
+
+// Instruments shared by several modules
+
+const upstreamDuration = histogram(
+  'parastats_upstream_request_duration_seconds',
+  'Time to response headers for upstream HTTP requests, by host and route',
+  [0.05, 0.1, 0.25, 0.5, 1, 2.5, 5, 10, 30],
+);
+
+const upstreamRequests = counter(
+  'parastats_upstream_requests_total',
+  'Upstream HTTP requests by host, route and status (HTTP code, "timeout" or "error")',
+);
+
+const upstreamAgentRecreations = counter(
+  'parastats_upstream_agent_recreations_total',
+  'Times the shared HTTP agent was recreated after connection failures',
+);
+
+const upstreamCircuitState = gauge(
+  'parastats_upstream_circuit_state',
+  'Circuit breaker state per upstream (1 for the current state: closed, open or half_open)',
+);
+
+const upstreamCircuitRejections = counter(
+  'parastats_upstream_circuit_rejections_total',
+  'Upstream requests refused without being sent because the circuit was open, by upstream',
+);
+
+const aggregatorCacheRequests = counter(
+  'parastats_aggregator_cache_requests_total',
+  'Aggregator cache lookups by route and result: fresh (upstream answered), stale (cached data served after a failure) or error',
+);
+
+const rateLimitRejections = counter(
+  'parastats_rate_limit_rejections_total',
+  'API requests rejected by the in-memory rate limiter, by route',
+);
+
+const collectorCycleDuration = histogram(
+  'parastats_collector_cycle_duration_seconds',
+  'Duration of collector cycles',
+  [0.1, 0.5, 1, 5, 10, 30, 60, 120, 300],
+);
+
+const collectorCycles = counter(
+  'parastats_collector_cycles_total',
+  'Collector cycles by collector and result (success or error)',
+);
+
+const collectorLastSuccess = gauge(
+  'parastats_collector_last_success_timestamp_seconds',
+  'Unix time of the last successful cycle of each collector',
+);
+
+export function recordUpstreamRequest(url: string, status: number | 'timeout' | 'error', durationMs: number): void {
+  const labels = { host: hostLabel(url), route: routeLabel(url) };
+  upstreamDuration.observe(labels, durationMs / 1000);
+  upstreamRequests.inc({ ...labels, status: String(status) });
+}
+
+export function recordAgentRecreation(): void {
+  upstreamAgentRecreations.inc();
+}
+
+export function recordCircuitState(upstream: string, state: 'closed' | 'open' | 'half_open'): void {
+  for (const candidate of ['closed', 'open', 'half_open'] as const) {
+    upstreamCircuitState.set({ upstream, state: candidate }, candidate === state ? 1 : 0);
+  }
+}
+
+export function recordCircuitRejection(upstream: string): void {
+  upstreamCircuitRejections.inc({ upstream });
+}
+
+export function recordAggregatorCache(key: string, result: 'fresh' | 'stale' | 'error'): void {
+  aggregatorCacheRequests.inc({ route: routeLabel(key), result });
+}
+
+export function recordRateLimitRejection(route: string): void {
+  rateLimitRejections.inc({ route });
+}
+
+/**
+ * Record the end of a collector cycle that started at `startedAtMs`.
+ * Cycles skipped because one is already running aren't recorded.
+ */
+export function recordCollectorCycle(collector: string, startedAtMs: number, result: 'success' | 'error'): void {
+  const now = Date.now();
+  collectorCycleDuration.observe({ collector }, Math.max(now - startedAtMs, 0) / 1000);
+  collectorCycles.inc({ collector, result });
+  if (result === 'success') {
+    collectorLastSuccess.set({ collector }, Math.floor(now / 1000));
+  }
+}
@@ -0,0 +1,332 @@
+import type { Migration } from './index';
+import { addColumnIfNotExists, hasColumn } from './helpers';
+
+/**
+ * Pool stats, monitored users and their history, stratum notifications, the
+ * highest diff leaderboards and the rounds cache, as built before schema
+ * versioning
+ */
+export const migration: Migration = {
+  version: 1,
+  name: 'initial_schema',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS pool_stats (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        runtime INTEGER NOT NULL,
+        users INTEGER NOT NULL,
+        workers INTEGER NOT NULL,
+        idle INTEGER NOT NULL,
+        disconnected INTEGER NOT NULL,
+        hashrate1m TEXT NOT NULL,
+        hashrate5m TEXT NOT NULL,
+        hashrate15m TEXT NOT NULL,
+        hashrate1hr TEXT NOT NULL,
+        hashrate6hr TEXT NOT NULL,
+        hashrate1d TEXT NOT NULL,
+        hashrate7d TEXT NOT NULL
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_pool_stats_timestamp ON pool_stats(timestamp);
+    `);
+
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS monitored_users (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        address TEXT UNIQUE NOT NULL,
+        is_active BOOLEAN NOT NULL DEFAULT 1,
+        is_public BOOLEAN NOT NULL DEFAULT 1,
+        bestever REAL DEFAULT 0,
+        authorised_at INTEGER DEFAULT 0,
+        failed_attempts INTEGER NOT NULL DEFAULT 0,
+        total_blocks INTEGER NOT NULL DEFAULT 0,
+        has_refinery_badge BOOLEAN NOT NULL DEFAULT 0,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+      )
+    `);
+
+    addColumnIfNotExists(db, 'monitored_users', 'failed_attempts', 'INTEGER NOT NULL DEFAULT 0');
+    addColumnIfNotExists(db, 'monitored_users', 'total_blocks', 'INTEGER NOT NULL DEFAULT 0');
+    addColumnIfNotExists(db, 'monitored_users', 'has_refinery_badge', 'BOOLEAN NOT NULL DEFAULT 0');
+
+    // The loyalty index only helps when filtering left-to-right (is_active
+    // first, then is_public), then sorting by total_blocks
+    db.exec(`
+      CREATE INDEX IF NOT EXISTS idx_monitored_users_address ON monitored_users(address);
+      CREATE INDEX IF NOT EXISTS idx_monitored_users_active ON monitored_users(is_active);
+      CREATE INDEX IF NOT EXISTS idx_monitored_users_loyalty
+        ON monitored_users(is_active, is_public, total_blocks DESC);
+    `);
+
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS user_stats_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER NOT NULL,
+        hashrate1m TEXT NOT NULL,
+        hashrate5m TEXT NOT NULL,
+        hashrate1hr TEXT NOT NULL,
+        hashrate1d TEXT NOT NULL,
+        hashrate7d TEXT NOT NULL,
+        workers INTEGER NOT NULL,
+        bestshare REAL NOT NULL,
+        bestever REAL NOT NULL,
+        created_at INTEGER NOT NULL,
+        FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_user_stats_user_time ON user_stats_history(user_id, created_at);
+      CREATE INDEX IF NOT EXISTS idx_user_stats_created_at ON user_stats_history(created_at);
+    `);
+
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS stratum_notifications (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        notification_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        pool TEXT NOT NULL,
+        job_id TEXT NOT NULL,
+        prev_block_hash TEXT NOT NULL,
+        coinbase1 TEXT NOT NULL,
+        coinbase2 TEXT NOT NULL,
+        merkle_branches TEXT NOT NULL,
+        version TEXT NOT NULL,
+        n_bits TEXT NOT NULL,
+        n_time TEXT NOT NULL,
+        clean_jobs BOOLEAN NOT NULL,
+        extranonce1 TEXT,
+        extranonce2_size INTEGER,
+        raw_message TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        UNIQUE(notification_id)
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_stratum_timestamp ON stratum_notifications(timestamp);
+      CREATE INDEX IF NOT EXISTS idx_stratum_pool ON stratum_notifications(pool);
+    `);
+
+    // Pool-wide winner per block. block_timestamp is the Bitcoin block
+    // timestamp from mempool.space.
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS block_highest_diff (
+        block_height INTEGER PRIMARY KEY,
+        winner_address TEXT NOT NULL,
+        difficulty REAL NOT NULL,
+        block_timestamp INTEGER
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_block_highest_diff_winner ON block_highest_diff(winner_address);
+
+      CREATE TABLE IF NOT EXISTS user_block_diff (
+        block_height INTEGER NOT NULL,
+        address TEXT NOT NULL,
+        difficulty REAL NOT NULL,
+        PRIMARY KEY (block_height, address),
+        FOREIGN KEY (block_height) REFERENCES block_highest_diff(block_height) ON DELETE CASCADE
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_user_block_diff_address ON user_block_diff(address);
+    `);
+
+    // Completed round metadata cached from GET /rounds
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS rounds (
+        block_height INTEGER PRIMARY KEY,
+        block_hash TEXT NOT NULL,
+        coinbase_value INTEGER,
+        winner_diff REAL,
+        winner_username TEXT,
+        participant_status TEXT NOT NULL DEFAULT 'pending',
+        participant_fetched_at INTEGER,
+        block_participant_status TEXT NOT NULL DEFAULT 'pending',
+        block_participant_fetched_at INTEGER,
+        error_message TEXT,
+        created_at INTEGER NOT NULL
+      )
+    `);
+
+    addColumnIfNotExists(db, 'rounds', 'block_participant_status', `TEXT NOT NULL DEFAULT 'pending'`);
+    addColumnIfNotExists(db, 'rounds', 'block_participant_fetched_at', 'INTEGER');
+
+    // Per-user stats per round. block_height = 0 is a sentinel for
+    // current-round data from /rounds/current.
+    const hadRoundParticipants = db.prepare(
+      `SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'round_participants'`
+    ).get() !== undefined;
+
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS round_participants (
+        block_height INTEGER NOT NULL,
+        username TEXT NOT NULL,
+        top_diff REAL NOT NULL DEFAULT 0,
+        blocks_participated INTEGER NOT NULL DEFAULT 0,
+        total_work REAL NOT NULL DEFAULT 0,
+        PRIMARY KEY (block_height, username)
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_round_participants_diff
+        ON round_participants(block_height, top_diff DESC);
+      CREATE INDEX IF NOT EXISTS idx_round_participants_blocks
+        ON round_participants(block_height, blocks_participated DESC);
+      CREATE INDEX IF NOT EXISTS idx_round_participants_username
+        ON round_participants(username, block_height DESC);
+    `);
+
+    // Caches from before total_work was tracked hold zero work for every
+    // participant, so re-mark completed rounds as pending to refetch them
+    if (hadRoundParticipants && !hasColumn(db, 'round_participants', 'total_work')) {
+      db.exec(`ALTER TABLE round_participants ADD COLUMN total_work REAL NOT NULL DEFAULT 0`);
+      db.prepare(`UPDATE rounds SET participant_status = 'pending' WHERE participant_status = 'complete'`).run();
+    }
+
+    db.exec(`
+      CREATE INDEX IF NOT EXISTS idx_round_participants_work
+        ON round_participants(block_height, total_work DESC);
+    `);
+
+    // Users who submitted shares at the exact block height
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS block_participants (
+        block_height INTEGER NOT NULL,
+        username TEXT NOT NULL,
+        PRIMARY KEY (block_height, username)
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_block_participants_username
+        ON block_participants(username, block_height DESC);
+    `);
+  },
+  postgres: `
+    CREATE TABLE pool_stats (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      timestamp BIGINT NOT NULL,
+      runtime BIGINT NOT NULL,
+      users BIGINT NOT NULL,
+      workers BIGINT NOT NULL,
+      idle BIGINT NOT NULL,
+      disconnected BIGINT NOT NULL,
+      hashrate1m TEXT NOT NULL,
+      hashrate5m TEXT NOT NULL,
+      hashrate15m TEXT NOT NULL,
+      hashrate1hr TEXT NOT NULL,
+      hashrate6hr TEXT NOT NULL,
+      hashrate1d TEXT NOT NULL,
+      hashrate7d TEXT NOT NULL
+    );
+
+    CREATE INDEX idx_pool_stats_timestamp ON pool_stats(timestamp);
+
+    CREATE TABLE monitored_users (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      address TEXT UNIQUE NOT NULL,
+      is_active SMALLINT NOT NULL DEFAULT 1,
+      is_public SMALLINT NOT NULL DEFAULT 1,
+      bestever DOUBLE PRECISION DEFAULT 0,
+      authorised_at BIGINT DEFAULT 0,
+      failed_attempts BIGINT NOT NULL DEFAULT 0,
+      total_blocks BIGINT NOT NULL DEFAULT 0,
+      has_refinery_badge SMALLINT NOT NULL DEFAULT 0,
+      created_at BIGINT NOT NULL,
+      updated_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_monitored_users_address ON monitored_users(address);
+    CREATE INDEX idx_monitored_users_active ON monitored_users(is_active);
+    CREATE INDEX idx_monitored_users_loyalty ON monitored_users(is_active, is_public, total_blocks DESC);
+
+    CREATE TABLE user_stats_history (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      user_id BIGINT NOT NULL REFERENCES monitored_users(id) ON DELETE CASCADE,
+      hashrate1m TEXT NOT NULL,
+      hashrate5m TEXT NOT NULL,
+      hashrate1hr TEXT NOT NULL,
+      hashrate1d TEXT NOT NULL,
+      hashrate7d TEXT NOT NULL,
+      workers BIGINT NOT NULL,
+      bestshare DOUBLE PRECISION NOT NULL,
+      bestever DOUBLE PRECISION NOT NULL,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_user_stats_user_time ON user_stats_history(user_id, created_at);
+    CREATE INDEX idx_user_stats_created_at ON user_stats_history(created_at);
+
+    CREATE TABLE stratum_notifications (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      notification_id TEXT NOT NULL UNIQUE,
+      timestamp BIGINT NOT NULL,
+      pool TEXT NOT NULL,
+      job_id TEXT NOT NULL,
+      prev_block_hash TEXT NOT NULL,
+      coinbase1 TEXT NOT NULL,
+      coinbase2 TEXT NOT NULL,
+      merkle_branches TEXT NOT NULL,
+      version TEXT NOT NULL,
+      n_bits TEXT NOT NULL,
+      n_time TEXT NOT NULL,
+      clean_jobs SMALLINT NOT NULL,
+      extranonce1 TEXT,
+      extranonce2_size BIGINT,
+      raw_message TEXT NOT NULL,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_stratum_timestamp ON stratum_notifications(timestamp);
+    CREATE INDEX idx_stratum_pool ON stratum_notifications(pool);
+
+    CREATE TABLE block_highest_diff (
+      block_height BIGINT PRIMARY KEY,
+      winner_address TEXT NOT NULL,
+      difficulty DOUBLE PRECISION NOT NULL,
+      block_timestamp BIGINT
+    );
+
+    CREATE INDEX idx_block_highest_diff_winner ON block_highest_diff(winner_address);
+
+    CREATE TABLE user_block_diff (
+      block_height BIGINT NOT NULL REFERENCES block_highest_diff(block_height) ON DELETE CASCADE,
+      address TEXT NOT NULL,
+      difficulty DOUBLE PRECISION NOT NULL,
+      PRIMARY KEY (block_height, address)
+    );
+
+    CREATE INDEX idx_user_block_diff_address ON user_block_diff(address);
+
+    CREATE TABLE rounds (
+      block_height BIGINT PRIMARY KEY,
+      block_hash TEXT NOT NULL,
+      coinbase_value BIGINT,
+      winner_diff DOUBLE PRECISION,
+      winner_username TEXT,
+      participant_status TEXT NOT NULL DEFAULT 'pending',
+      participant_fetched_at BIGINT,
+      block_participant_status TEXT NOT NULL DEFAULT 'pending',
+      block_participant_fetched_at BIGINT,
+      error_message TEXT,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE round_participants (
+      block_height BIGINT NOT NULL,
+      username TEXT NOT NULL,
+      top_diff DOUBLE PRECISION NOT NULL DEFAULT 0,
+      blocks_participated BIGINT NOT NULL DEFAULT 0,
+      total_work DOUBLE PRECISION NOT NULL DEFAULT 0,
+      PRIMARY KEY (block_height, username)
+    );
+
+    CREATE INDEX idx_round_participants_diff ON round_participants(block_height, top_diff DESC);
+    CREATE INDEX idx_round_participants_blocks ON round_participants(block_height, blocks_participated DESC);
+    CREATE INDEX idx_round_participants_username ON round_participants(username, block_height DESC);
+    CREATE INDEX idx_round_participants_work ON round_participants(block_height, total_work DESC);
+
+    CREATE TABLE block_participants (
+      block_height BIGINT NOT NULL,
+      username TEXT NOT NULL,
+      PRIMARY KEY (block_height, username)
+    );
+
+    CREATE INDEX idx_block_participants_username ON block_participants(username, block_height DESC);
+  `,
+};
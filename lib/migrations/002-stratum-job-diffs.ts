@@ -0,0 +1,75 @@
+import type { Migration } from './index';
+
+/**
+ * Per-job template summaries with deltas against the previous job on the
+ * same prev_block_hash. Kept separately from stratum_notifications, which is
+ * trimmed to the latest 100 rows per pool.
+ */
+export const migration: Migration = {
+  version: 2,
+  name: 'stratum_job_diffs',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS stratum_job_diffs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        notification_id TEXT NOT NULL UNIQUE,
+        pool TEXT NOT NULL,
+        prev_block_hash TEXT NOT NULL,
+        block_height INTEGER,
+        job_id TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        clean_jobs BOOLEAN NOT NULL,
+        merkle_branch_count INTEGER NOT NULL,
+        output_count INTEGER NOT NULL,
+        coinbase_value INTEGER NOT NULL,
+        fees INTEGER,
+        n_time TEXT NOT NULL,
+        version TEXT NOT NULL,
+        outputs TEXT NOT NULL,
+        previous_job_id TEXT,
+        merkle_branch_delta INTEGER,
+        output_count_delta INTEGER,
+        coinbase_value_delta INTEGER,
+        n_time_changed BOOLEAN,
+        version_changed BOOLEAN,
+        outputs_added TEXT,
+        outputs_removed TEXT
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_stratum_job_diffs_pool_prev ON stratum_job_diffs(pool, prev_block_hash);
+      CREATE INDEX IF NOT EXISTS idx_stratum_job_diffs_pool_height ON stratum_job_diffs(pool, block_height);
+      CREATE INDEX IF NOT EXISTS idx_stratum_job_diffs_timestamp ON stratum_job_diffs(timestamp);
+    `);
+  },
+  postgres: `
+    CREATE TABLE stratum_job_diffs (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      notification_id TEXT NOT NULL UNIQUE,
+      pool TEXT NOT NULL,
+      prev_block_hash TEXT NOT NULL,
+      block_height BIGINT,
+      job_id TEXT NOT NULL,
+      timestamp BIGINT NOT NULL,
+      clean_jobs SMALLINT NOT NULL,
+      merkle_branch_count BIGINT NOT NULL,
+      output_count BIGINT NOT NULL,
+      coinbase_value BIGINT NOT NULL,
+      fees BIGINT,
+      n_time TEXT NOT NULL,
+      version TEXT NOT NULL,
+      outputs TEXT NOT NULL,
+      previous_job_id TEXT,
+      merkle_branch_delta BIGINT,
+      output_count_delta BIGINT,
+      coinbase_value_delta BIGINT,
+      n_time_changed SMALLINT,
+      version_changed SMALLINT,
+      outputs_added TEXT,
+      outputs_removed TEXT
+    );
+
+    CREATE INDEX idx_stratum_job_diffs_pool_prev ON stratum_job_diffs(pool, prev_block_hash);
+    CREATE INDEX idx_stratum_job_diffs_pool_height ON stratum_job_diffs(pool, block_height);
+    CREATE INDEX idx_stratum_job_diffs_timestamp ON stratum_job_diffs(timestamp);
+  `,
+};
@@ -0,0 +1,46 @@
+import type { Migration } from './index';
+
+/**
+ * One row per pool per block height summarizing every template seen for it.
+ * Compact enough to keep indefinitely for long-term template quality trends.
+ */
+export const migration: Migration = {
+  version: 3,
+  name: 'stratum_block_summaries',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS stratum_block_summaries (
+        pool TEXT NOT NULL,
+        block_height INTEGER NOT NULL,
+        prev_block_hash TEXT NOT NULL,
+        first_job_at INTEGER NOT NULL,
+        last_job_at INTEGER NOT NULL,
+        first_clean_job_at INTEGER,
+        job_count INTEGER NOT NULL,
+        max_coinbase_value INTEGER NOT NULL,
+        max_fees INTEGER NOT NULL,
+        subsidy INTEGER NOT NULL,
+        PRIMARY KEY (pool, block_height)
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_stratum_block_summaries_first_job ON stratum_block_summaries(first_job_at);
+    `);
+  },
+  postgres: `
+    CREATE TABLE stratum_block_summaries (
+      pool TEXT NOT NULL,
+      block_height BIGINT NOT NULL,
+      prev_block_hash TEXT NOT NULL,
+      first_job_at BIGINT NOT NULL,
+      last_job_at BIGINT NOT NULL,
+      first_clean_job_at BIGINT,
+      job_count BIGINT NOT NULL,
+      max_coinbase_value BIGINT NOT NULL,
+      max_fees BIGINT NOT NULL,
+      subsidy BIGINT NOT NULL,
+      PRIMARY KEY (pool, block_height)
+    );
+
+    CREATE INDEX idx_stratum_block_summaries_first_job ON stratum_block_summaries(first_job_at);
+  `,
+};
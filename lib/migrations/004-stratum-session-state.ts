@@ -0,0 +1,42 @@
+import type { Migration } from './index';
+import { addColumnIfNotExists } from './helpers';
+
+/**
+ * The version rolling mask and share difficulty in effect when each job
+ * arrived, plus every difficulty the pool assigns (mining.set_difficulty /
+ * SV2 SetTarget)
+ */
+export const migration: Migration = {
+  version: 4,
+  name: 'stratum_session_state',
+  sqlite(db) {
+    addColumnIfNotExists(db, 'stratum_notifications', 'version_rolling_mask', 'TEXT');
+    addColumnIfNotExists(db, 'stratum_notifications', 'difficulty', 'REAL');
+
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS stratum_difficulty_changes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        pool TEXT NOT NULL,
+        difficulty REAL NOT NULL,
+        previous_difficulty REAL,
+        created_at INTEGER NOT NULL
+      );
+
+      CREATE INDEX IF NOT EXISTS idx_stratum_difficulty_changes_pool ON stratum_difficulty_changes(pool, created_at);
+    `);
+  },
+  postgres: `
+    ALTER TABLE stratum_notifications ADD COLUMN version_rolling_mask TEXT;
+    ALTER TABLE stratum_notifications ADD COLUMN difficulty DOUBLE PRECISION;
+
+    CREATE TABLE stratum_difficulty_changes (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      pool TEXT NOT NULL,
+      difficulty DOUBLE PRECISION NOT NULL,
+      previous_difficulty DOUBLE PRECISION,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_stratum_difficulty_changes_pool ON stratum_difficulty_changes(pool, created_at);
+  `,
+};
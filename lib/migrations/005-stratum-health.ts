@@ -0,0 +1,44 @@
+import type { Migration } from './index';
+
+/**
+ * Connection health per stratum endpoint, written by the collector so the
+ * web server can report a dead feed
+ */
+export const migration: Migration = {
+  version: 5,
+  name: 'stratum_health',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS stratum_health (
+        pool TEXT PRIMARY KEY,
+        host TEXT NOT NULL,
+        port INTEGER NOT NULL,
+        is_primary BOOLEAN NOT NULL,
+        status TEXT NOT NULL,
+        connected_at INTEGER,
+        last_message_at INTEGER,
+        last_notification_at INTEGER,
+        reconnect_count INTEGER NOT NULL DEFAULT 0,
+        consecutive_failures INTEGER NOT NULL DEFAULT 0,
+        last_error TEXT,
+        updated_at INTEGER NOT NULL
+      )
+    `);
+  },
+  postgres: `
+    CREATE TABLE stratum_health (
+      pool TEXT PRIMARY KEY,
+      host TEXT NOT NULL,
+      port BIGINT NOT NULL,
+      is_primary SMALLINT NOT NULL,
+      status TEXT NOT NULL,
+      connected_at BIGINT,
+      last_message_at BIGINT,
+      last_notification_at BIGINT,
+      reconnect_count BIGINT NOT NULL DEFAULT 0,
+      consecutive_failures BIGINT NOT NULL DEFAULT 0,
+      last_error TEXT,
+      updated_at BIGINT NOT NULL
+    );
+  `,
+};
@@ -0,0 +1,62 @@
+import type { Migration } from './index';
+
+/**
+ * Blocks found by the pool, correlating rounds with the stratum templates
+ * that preceded them. found_at_source is 'stratum' when found_at is when the
+ * collector first saw a template on top of the block, 'block' when it falls
+ * back to the block header timestamp.
+ */
+export const migration: Migration = {
+  version: 6,
+  name: 'blocks_found',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS blocks_found (
+        block_height INTEGER PRIMARY KEY,
+        block_hash TEXT NOT NULL,
+        reward INTEGER,
+        finder_username TEXT,
+        winner_diff REAL,
+        found_at INTEGER,
+        found_at_source TEXT,
+        time_to_find INTEGER,
+        template_pool TEXT,
+        template_job_id TEXT,
+        template_job_count INTEGER,
+        template_first_job_at INTEGER,
+        template_coinbase_value INTEGER,
+        template_fees INTEGER,
+        template_output_count INTEGER,
+        template_merkle_branch_count INTEGER,
+        template_version TEXT,
+        template_n_time TEXT,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+      )
+    `);
+  },
+  postgres: `
+    CREATE TABLE blocks_found (
+      block_height BIGINT PRIMARY KEY,
+      block_hash TEXT NOT NULL,
+      reward BIGINT,
+      finder_username TEXT,
+      winner_diff DOUBLE PRECISION,
+      found_at BIGINT,
+      found_at_source TEXT,
+      time_to_find BIGINT,
+      template_pool TEXT,
+      template_job_id TEXT,
+      template_job_count BIGINT,
+      template_first_job_at BIGINT,
+      template_coinbase_value BIGINT,
+      template_fees BIGINT,
+      template_output_count BIGINT,
+      template_merkle_branch_count BIGINT,
+      template_version TEXT,
+      template_n_time TEXT,
+      created_at BIGINT NOT NULL,
+      updated_at BIGINT NOT NULL
+    );
+  `,
+};
@@ -0,0 +1,39 @@
+import type { Migration } from './index';
+
+// Spelled out rather than taken from POOL_STATS_ROLLUP_METRICS so this
+// migration keeps building the same tables if the metrics change later
+const METRICS = [
+  'users', 'workers', 'hashrate1m', 'hashrate5m', 'hashrate15m', 'hashrate1hr', 'hashrate6hr', 'hashrate1d', 'hashrate7d',
+];
+const RESOLUTIONS = ['5m', '1h', '1d'];
+
+function rollupTables(types: { integer: string; real: string }, ifNotExists: boolean): string {
+  const metricColumns = METRICS
+    .flatMap(metric => ['min', 'max', 'avg', 'last'].map(stat => `${metric}_${stat} ${types.real} NOT NULL`))
+    .join(',\n      ');
+
+  return RESOLUTIONS.map(resolution => `
+    CREATE TABLE ${ifNotExists ? 'IF NOT EXISTS ' : ''}pool_stats_${resolution} (
+      bucket_start ${types.integer} PRIMARY KEY,
+      sample_count ${types.integer} NOT NULL,
+      last_timestamp ${types.integer} NOT NULL,
+      ${metricColumns},
+      idle_last ${types.integer} NOT NULL,
+      disconnected_last ${types.integer} NOT NULL,
+      updated_at ${types.integer} NOT NULL
+    );
+  `).join('');
+}
+
+/**
+ * Downsampled pool stats, one table per resolution, keyed by bucket start.
+ * Every metric keeps its min, max, average and last value over the bucket.
+ */
+export const migration: Migration = {
+  version: 7,
+  name: 'pool_stats_rollups',
+  sqlite(db) {
+    db.exec(rollupTables({ integer: 'INTEGER', real: 'REAL' }, true));
+  },
+  postgres: rollupTables({ integer: 'BIGINT', real: 'DOUBLE PRECISION' }, false),
+};
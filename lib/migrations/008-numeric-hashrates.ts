@@ -0,0 +1,62 @@
+import type Database from 'better-sqlite3';
+import type { Migration } from './index';
+import { addColumnIfNotExists } from './helpers';
+import { createLogger } from '../logger';
+
+const POOL_STATS_COLUMNS = [
+  'hashrate1m', 'hashrate5m', 'hashrate15m', 'hashrate1hr', 'hashrate6hr', 'hashrate1d', 'hashrate7d',
+];
+const USER_STATS_COLUMNS = ['hashrate1m', 'hashrate5m', 'hashrate1hr', 'hashrate1d', 'hashrate7d'];
+
+const log = createLogger({ module: 'migrations' });
+
+const HASHRATE_UNIT_EXPONENTS: Record<string, number> = {
+  K: 3, M: 6, G: 9, T: 12, P: 15, E: 18, Z: 21, Y: 24,
+};
+
+/**
+ * SQL equivalent of parseHashrate for a suffixed TEXT column like "1.2P",
+ * used to backfill the numeric hashrate columns in a single UPDATE
+ */
+function hashrateTextToNumberSql(column: string): string {
+  const value = `TRIM(REPLACE(${column}, '/s', ''))`;
+  const units = Object.entries(HASHRATE_UNIT_EXPONENTS)
+    .map(([unit, exponent]) => `WHEN '${unit}' THEN CAST(SUBSTR(${value}, 1, LENGTH(${value}) - 1) AS REAL) * 1e${exponent}`)
+    .join('\n      ');
+  return `CASE SUBSTR(${value}, -1)
+      ${units}
+      ELSE CAST(${value} AS REAL)
+    END`;
+}
+
+/**
+ * Add a numeric (H/s) column next to each suffixed TEXT hashrate column and
+ * fill it from the text. Columns that already exist were written by the
+ * collector since they were added, so only new ones are backfilled.
+ */
+function addNumericHashrateColumns(db: Database.Database, table: string, columns: string[]) {
+  const added = columns.filter(column => addColumnIfNotExists(db, table, `${column}_hs`, 'REAL'));
+  if (added.length === 0) return;
+
+  const assignments = added.map(column => `${column}_hs = ${hashrateTextToNumberSql(column)}`).join(',\n    ');
+  const result = db.prepare(`UPDATE ${table} SET\n    ${assignments}`).run();
+  log.info('Backfilled numeric hashrates', { table, rows: result.changes });
+}
+
+/**
+ * Numeric H/s copies of the suffixed TEXT hashrates, so readers can aggregate
+ * in SQL
+ */
+export const migration: Migration = {
+  version: 8,
+  name: 'numeric_hashrates',
+  sqlite(db) {
+    addNumericHashrateColumns(db, 'pool_stats', POOL_STATS_COLUMNS);
+    addNumericHashrateColumns(db, 'user_stats_history', USER_STATS_COLUMNS);
+  },
+  // Postgres databases start empty, so there's nothing to backfill
+  postgres: [
+    ...POOL_STATS_COLUMNS.map(column => `ALTER TABLE pool_stats ADD COLUMN ${column}_hs DOUBLE PRECISION;`),
+    ...USER_STATS_COLUMNS.map(column => `ALTER TABLE user_stats_history ADD COLUMN ${column}_hs DOUBLE PRECISION;`),
+  ].join('\n'),
+};
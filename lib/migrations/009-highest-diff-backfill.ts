@@ -0,0 +1,45 @@
+import type { Migration } from './index';
+
+/**
+ * Cursor of the historical highest diff backfill, a single row. The backfill
+ * walks down from below the startup window one block at a time; next_height
+ * is the next block it will check and floor_height the lowest it will go.
+ */
+export const migration: Migration = {
+  version: 9,
+  name: 'highest_diff_backfill',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE highest_diff_backfill (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        start_height INTEGER NOT NULL,
+        next_height INTEGER NOT NULL,
+        floor_height INTEGER NOT NULL,
+        blocks_checked INTEGER NOT NULL DEFAULT 0,
+        blocks_stored INTEGER NOT NULL DEFAULT 0,
+        blocks_empty INTEGER NOT NULL DEFAULT 0,
+        consecutive_empty INTEGER NOT NULL DEFAULT 0,
+        last_error TEXT,
+        started_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        completed_at INTEGER
+      )
+    `);
+  },
+  postgres: `
+    CREATE TABLE highest_diff_backfill (
+      id BIGINT PRIMARY KEY CHECK (id = 1),
+      start_height BIGINT NOT NULL,
+      next_height BIGINT NOT NULL,
+      floor_height BIGINT NOT NULL,
+      blocks_checked BIGINT NOT NULL DEFAULT 0,
+      blocks_stored BIGINT NOT NULL DEFAULT 0,
+      blocks_empty BIGINT NOT NULL DEFAULT 0,
+      consecutive_empty BIGINT NOT NULL DEFAULT 0,
+      last_error TEXT,
+      started_at BIGINT NOT NULL,
+      updated_at BIGINT NOT NULL,
+      completed_at BIGINT
+    );
+  `,
+};
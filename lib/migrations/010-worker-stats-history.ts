@@ -0,0 +1,54 @@
+import type { Migration } from './index';
+
+/**
+ * Per-worker samples taken alongside user_stats_history. worker_name is the
+ * full `address.name` the pool reports; hashrates are H/s and shares is the
+ * pool's running share count for the worker.
+ */
+export const migration: Migration = {
+  version: 10,
+  name: 'worker_stats_history',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE worker_stats_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER NOT NULL,
+        worker_name TEXT NOT NULL,
+        hashrate1m_hs REAL,
+        hashrate5m_hs REAL,
+        hashrate1hr_hs REAL,
+        hashrate1d_hs REAL,
+        hashrate7d_hs REAL,
+        shares REAL NOT NULL,
+        bestshare REAL NOT NULL,
+        bestever REAL NOT NULL,
+        lastshare INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
+      );
+
+      CREATE INDEX idx_worker_stats_worker_time ON worker_stats_history(user_id, worker_name, created_at);
+      CREATE INDEX idx_worker_stats_created_at ON worker_stats_history(created_at);
+    `);
+  },
+  postgres: `
+    CREATE TABLE worker_stats_history (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      user_id BIGINT NOT NULL REFERENCES monitored_users(id) ON DELETE CASCADE,
+      worker_name TEXT NOT NULL,
+      hashrate1m_hs DOUBLE PRECISION,
+      hashrate5m_hs DOUBLE PRECISION,
+      hashrate1hr_hs DOUBLE PRECISION,
+      hashrate1d_hs DOUBLE PRECISION,
+      hashrate7d_hs DOUBLE PRECISION,
+      shares DOUBLE PRECISION NOT NULL,
+      bestshare DOUBLE PRECISION NOT NULL,
+      bestever DOUBLE PRECISION NOT NULL,
+      lastshare BIGINT NOT NULL,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_worker_stats_worker_time ON worker_stats_history(user_id, worker_name, created_at);
+    CREATE INDEX idx_worker_stats_created_at ON worker_stats_history(created_at);
+  `,
+};
@@ -0,0 +1,69 @@
+import type { Migration } from './index';
+
+/**
+ * Worker state transitions derived by the user collector. worker_state holds
+ * each worker's last derived state so the next sample can be compared with
+ * it; worker_events keeps one row per transition.
+ */
+export const migration: Migration = {
+  version: 11,
+  name: 'worker_events',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE worker_state (
+        user_id INTEGER NOT NULL,
+        worker_name TEXT NOT NULL,
+        state TEXT NOT NULL,
+        hashrate_dropped INTEGER NOT NULL DEFAULT 0,
+        lastshare INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (user_id, worker_name),
+        FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
+      );
+
+      CREATE TABLE worker_events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        user_id INTEGER NOT NULL,
+        worker_name TEXT NOT NULL,
+        event_type TEXT NOT NULL,
+        previous_state TEXT,
+        state TEXT NOT NULL,
+        hashrate_hs REAL,
+        baseline_hashrate_hs REAL,
+        lastshare INTEGER,
+        created_at INTEGER NOT NULL,
+        FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
+      );
+
+      CREATE INDEX idx_worker_events_user_time ON worker_events(user_id, created_at);
+      CREATE INDEX idx_worker_events_created_at ON worker_events(created_at);
+    `);
+  },
+  postgres: `
+    CREATE TABLE worker_state (
+      user_id BIGINT NOT NULL REFERENCES monitored_users(id) ON DELETE CASCADE,
+      worker_name TEXT NOT NULL,
+      state TEXT NOT NULL,
+      hashrate_dropped SMALLINT NOT NULL DEFAULT 0,
+      lastshare BIGINT NOT NULL,
+      updated_at BIGINT NOT NULL,
+      PRIMARY KEY (user_id, worker_name)
+    );
+
+    CREATE TABLE worker_events (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      user_id BIGINT NOT NULL REFERENCES monitored_users(id) ON DELETE CASCADE,
+      worker_name TEXT NOT NULL,
+      event_type TEXT NOT NULL,
+      previous_state TEXT,
+      state TEXT NOT NULL,
+      hashrate_hs DOUBLE PRECISION,
+      baseline_hashrate_hs DOUBLE PRECISION,
+      lastshare BIGINT,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_worker_events_user_time ON worker_events(user_id, created_at);
+    CREATE INDEX idx_worker_events_created_at ON worker_events(created_at);
+  `,
+};
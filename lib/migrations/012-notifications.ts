@@ -0,0 +1,102 @@
+import type { Migration } from './index';
+
+/**
+ * Notification subscriptions and their delivery outbox. events is a
+ * comma-separated list of event types; secret signs webhook payloads.
+ * Outbox rows are written in the same transaction as the change that
+ * triggers them and event_key keeps each event to one row per subscription.
+ * wallet_sessions binds a Lightning login token (by hash) to the address
+ * whose signature the login verified, so subscription changes can be
+ * authorized for that address.
+ */
+export const migration: Migration = {
+  version: 12,
+  name: 'notifications',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE notification_subscriptions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        address TEXT NOT NULL,
+        channel TEXT NOT NULL,
+        target TEXT NOT NULL,
+        events TEXT NOT NULL,
+        secret TEXT,
+        is_active INTEGER NOT NULL DEFAULT 1,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        UNIQUE(address, channel, target)
+      );
+
+      CREATE INDEX idx_notification_subscriptions_address ON notification_subscriptions(address);
+
+      CREATE TABLE notification_outbox (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        subscription_id INTEGER NOT NULL,
+        event_type TEXT NOT NULL,
+        event_key TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        status TEXT NOT NULL DEFAULT 'pending',
+        attempts INTEGER NOT NULL DEFAULT 0,
+        next_attempt_at INTEGER NOT NULL,
+        last_error TEXT,
+        created_at INTEGER NOT NULL,
+        delivered_at INTEGER,
+        UNIQUE(subscription_id, event_key),
+        FOREIGN KEY (subscription_id) REFERENCES notification_subscriptions(id) ON DELETE CASCADE
+      );
+
+      CREATE INDEX idx_notification_outbox_pending ON notification_outbox(status, next_attempt_at);
+      CREATE INDEX idx_notification_outbox_created_at ON notification_outbox(created_at);
+
+      CREATE TABLE wallet_sessions (
+        token_hash TEXT PRIMARY KEY,
+        address TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+      );
+
+      CREATE INDEX idx_wallet_sessions_created_at ON wallet_sessions(created_at);
+    `);
+  },
+  postgres: `
+    CREATE TABLE notification_subscriptions (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      address TEXT NOT NULL,
+      channel TEXT NOT NULL,
+      target TEXT NOT NULL,
+      events TEXT NOT NULL,
+      secret TEXT,
+      is_active SMALLINT NOT NULL DEFAULT 1,
+      created_at BIGINT NOT NULL,
+      updated_at BIGINT NOT NULL,
+      UNIQUE(address, channel, target)
+    );
+
+    CREATE INDEX idx_notification_subscriptions_address ON notification_subscriptions(address);
+
+    CREATE TABLE notification_outbox (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      subscription_id BIGINT NOT NULL REFERENCES notification_subscriptions(id) ON DELETE CASCADE,
+      event_type TEXT NOT NULL,
+      event_key TEXT NOT NULL,
+      payload TEXT NOT NULL,
+      status TEXT NOT NULL DEFAULT 'pending',
+      attempts BIGINT NOT NULL DEFAULT 0,
+      next_attempt_at BIGINT NOT NULL,
+      last_error TEXT,
+      created_at BIGINT NOT NULL,
+      delivered_at BIGINT,
+      UNIQUE(subscription_id, event_key)
+    );
+
+    CREATE INDEX idx_notification_outbox_pending ON notification_outbox(status, next_attempt_at);
+    CREATE INDEX idx_notification_outbox_created_at ON notification_outbox(created_at);
+
+    CREATE TABLE wallet_sessions (
+      token_hash TEXT PRIMARY KEY,
+      address TEXT NOT NULL,
+      created_at BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_wallet_sessions_created_at ON wallet_sessions(created_at);
+  `,
+};
@@ -0,0 +1,74 @@
+import type { Migration } from './index';
+
+/**
+ * Adaptive user collection. user_collection_schedule holds when each user is
+ * next due and the activity their interval is derived from (1h hashrate,
+ * last share, last page view). user_collection_cycles keeps one report per
+ * collection cycle: how many users were due, collected or deferred and how
+ * late they were.
+ */
+export const migration: Migration = {
+  version: 13,
+  name: 'user_collection_schedule',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE user_collection_schedule (
+        user_id INTEGER PRIMARY KEY,
+        next_collect_at INTEGER NOT NULL DEFAULT 0,
+        last_collected_at INTEGER,
+        hashrate_hs REAL NOT NULL DEFAULT 0,
+        last_share_at INTEGER,
+        last_viewed_at INTEGER,
+        FOREIGN KEY (user_id) REFERENCES monitored_users(id) ON DELETE CASCADE
+      );
+
+      CREATE INDEX idx_user_collection_schedule_next ON user_collection_schedule(next_collect_at);
+
+      CREATE TABLE user_collection_cycles (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        started_at INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        eligible_users INTEGER NOT NULL,
+        due_users INTEGER NOT NULL,
+        collected INTEGER NOT NULL,
+        failed INTEGER NOT NULL,
+        deferred INTEGER NOT NULL,
+        rate_limited INTEGER NOT NULL DEFAULT 0,
+        coverage REAL NOT NULL,
+        avg_lag_seconds REAL NOT NULL,
+        max_lag_seconds INTEGER NOT NULL
+      );
+
+      CREATE INDEX idx_user_collection_cycles_started_at ON user_collection_cycles(started_at);
+    `);
+  },
+  postgres: `
+    CREATE TABLE user_collection_schedule (
+      user_id BIGINT PRIMARY KEY REFERENCES monitored_users(id) ON DELETE CASCADE,
+      next_collect_at BIGINT NOT NULL DEFAULT 0,
+      last_collected_at BIGINT,
+      hashrate_hs DOUBLE PRECISION NOT NULL DEFAULT 0,
+      last_share_at BIGINT,
+      last_viewed_at BIGINT
+    );
+
+    CREATE INDEX idx_user_collection_schedule_next ON user_collection_schedule(next_collect_at);
+
+    CREATE TABLE user_collection_cycles (
+      id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+      started_at BIGINT NOT NULL,
+      duration_ms BIGINT NOT NULL,
+      eligible_users BIGINT NOT NULL,
+      due_users BIGINT NOT NULL,
+      collected BIGINT NOT NULL,
+      failed BIGINT NOT NULL,
+      deferred BIGINT NOT NULL,
+      rate_limited SMALLINT NOT NULL DEFAULT 0,
+      coverage DOUBLE PRECISION NOT NULL,
+      avg_lag_seconds DOUBLE PRECISION NOT NULL,
+      max_lag_seconds BIGINT NOT NULL
+    );
+
+    CREATE INDEX idx_user_collection_cycles_started_at ON user_collection_cycles(started_at);
+  `,
+};
@@ -0,0 +1,59 @@
+import type { Migration } from './index';
+
+/**
+ * Job supervisor state. One row per registered job with its configuration
+ * and the outcome of its latest runs, written by the jobs process so the web
+ * app can serve /api/health/jobs.
+ */
+export const migration: Migration = {
+  version: 14,
+  name: 'job_status',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE job_status (
+        name TEXT PRIMARY KEY,
+        schedule TEXT,
+        timeout_ms INTEGER NOT NULL,
+        stale_after_seconds INTEGER,
+        depends_on TEXT NOT NULL DEFAULT '',
+        registered_at INTEGER NOT NULL,
+        running INTEGER NOT NULL DEFAULT 0,
+        last_started_at INTEGER,
+        last_finished_at INTEGER,
+        last_duration_ms INTEGER,
+        last_success_at INTEGER,
+        last_error TEXT,
+        last_error_at INTEGER,
+        runs INTEGER NOT NULL DEFAULT 0,
+        failures INTEGER NOT NULL DEFAULT 0,
+        timeouts INTEGER NOT NULL DEFAULT 0,
+        overlap_skips INTEGER NOT NULL DEFAULT 0,
+        dependency_skips INTEGER NOT NULL DEFAULT 0,
+        updated_at INTEGER NOT NULL
+      );
+    `);
+  },
+  postgres: `
+    CREATE TABLE job_status (
+      name TEXT PRIMARY KEY,
+      schedule TEXT,
+      timeout_ms BIGINT NOT NULL,
+      stale_after_seconds BIGINT,
+      depends_on TEXT NOT NULL DEFAULT '',
+      registered_at BIGINT NOT NULL,
+      running SMALLINT NOT NULL DEFAULT 0,
+      last_started_at BIGINT,
+      last_finished_at BIGINT,
+      last_duration_ms BIGINT,
+      last_success_at BIGINT,
+      last_error TEXT,
+      last_error_at BIGINT,
+      runs BIGINT NOT NULL DEFAULT 0,
+      failures BIGINT NOT NULL DEFAULT 0,
+      timeouts BIGINT NOT NULL DEFAULT 0,
+      overlap_skips BIGINT NOT NULL DEFAULT 0,
+      dependency_skips BIGINT NOT NULL DEFAULT 0,
+      updated_at BIGINT NOT NULL
+    );
+  `,
+};
@@ -0,0 +1,30 @@
+import type { Migration } from './index';
+
+/**
+ * Leader lease for the jobs process. The holder renews expires_at on every
+ * heartbeat; a standby takes the row over once it has expired.
+ */
+export const migration: Migration = {
+  version: 15,
+  name: 'leader_lease',
+  sqlite(db) {
+    db.exec(`
+      CREATE TABLE leader_lease (
+        name TEXT PRIMARY KEY,
+        holder TEXT NOT NULL,
+        acquired_at INTEGER NOT NULL,
+        renewed_at INTEGER NOT NULL,
+        expires_at INTEGER NOT NULL
+      );
+    `);
+  },
+  postgres: `
+    CREATE TABLE leader_lease (
+      name TEXT PRIMARY KEY,
+      holder TEXT NOT NULL,
+      acquired_at BIGINT NOT NULL,
+      renewed_at BIGINT NOT NULL,
+      expires_at BIGINT NOT NULL
+    );
+  `,
+};
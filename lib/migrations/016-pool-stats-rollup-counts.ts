@@ -0,0 +1,64 @@
+import type Database from 'better-sqlite3';
+import type { Migration } from './index';
+
+// Spelled out for the same reason as in 007
+const METRICS = [
+  'users', 'workers', 'hashrate1m', 'hashrate5m', 'hashrate15m', 'hashrate1hr', 'hashrate6hr', 'hashrate1d', 'hashrate7d',
+];
+const RESOLUTIONS = ['5m', '1h', '1d'];
+const STATS = ['min', 'max', 'avg', 'last'];
+
+/**
+ * SQLite can't drop NOT NULL in place, so each table is rebuilt with nullable
+ * stats and copied over
+ */
+function rebuildRollupTable(db: Database.Database, resolution: string) {
+  const table = `pool_stats_${resolution}`;
+  const statColumns = METRICS.flatMap(metric => STATS.map(stat => `${metric}_${stat}`));
+  const countColumns = METRICS.map(metric => `${metric}_count`);
+
+  db.exec(`
+    CREATE TABLE ${table}_new (
+      bucket_start INTEGER PRIMARY KEY,
+      sample_count INTEGER NOT NULL,
+      last_timestamp INTEGER NOT NULL,
+      ${countColumns.map(column => `${column} INTEGER NOT NULL`).join(',\n      ')},
+      ${statColumns.map(column => `${column} REAL`).join(',\n      ')},
+      idle_last INTEGER NOT NULL,
+      disconnected_last INTEGER NOT NULL,
+      updated_at INTEGER NOT NULL
+    );
+    INSERT INTO ${table}_new (
+      bucket_start, sample_count, last_timestamp, ${countColumns.join(', ')}, ${statColumns.join(', ')},
+      idle_last, disconnected_last, updated_at
+    )
+    SELECT
+      bucket_start, sample_count, last_timestamp, ${countColumns.map(() => 'sample_count').join(', ')},
+      ${statColumns.join(', ')}, idle_last, disconnected_last, updated_at
+    FROM ${table};
+    DROP TABLE ${table};
+    ALTER TABLE ${table}_new RENAME TO ${table};
+  `);
+}
+
+/**
+ * Per-metric sample counts on the pool stats rollups, so a bucket's stats
+ * only cover the samples that had the metric. A metric with no samples in a
+ * bucket has NULL stats. Existing buckets count every sample for every
+ * metric, as they were built.
+ */
+export const migration: Migration = {
+  version: 16,
+  name: 'pool_stats_rollup_counts',
+  sqlite(db) {
+    for (const resolution of RESOLUTIONS) rebuildRollupTable(db, resolution);
+  },
+  postgres: RESOLUTIONS.map(resolution => `
+    ALTER TABLE pool_stats_${resolution}
+      ${[
+        ...METRICS.map(metric => `ADD COLUMN ${metric}_count BIGINT NOT NULL DEFAULT 0`),
+        ...METRICS.flatMap(metric => STATS.map(stat => `ALTER COLUMN ${metric}_${stat} DROP NOT NULL`)),
+      ].join(',\n      ')};
+    UPDATE pool_stats_${resolution} SET ${METRICS.map(metric => `${metric}_count = sample_count`).join(', ')};
+  `).join(''),
+};
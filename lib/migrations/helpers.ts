@@ -0,0 +1,23 @@
+import type Database from 'better-sqlite3';
+
+export function hasColumn(db: Database.Database, table: string, column: string): boolean {
+  return (db.prepare(`PRAGMA table_info(${table})`).all() as { name: string }[])
+    .some(info => info.name === column);
+}
+
+/**
+ * Add a column unless the table already has it. Only for the pre-versioning
+ * migrations, which must tolerate databases where the column was already
+ * added; later migrations should ALTER unconditionally. Returns true when the
+ * column was added by this call.
+ */
+export function addColumnIfNotExists(
+  db: Database.Database,
+  table: string,
+  column: string,
+  definition: string
+): boolean {
+  if (hasColumn(db, table, column)) return false;
+  db.exec(`ALTER TABLE ${table} ADD COLUMN ${column} ${definition}`);
+  return true;
+}
@@ -0,0 +1,211 @@
+import type Database from 'better-sqlite3';
+import { createLogger } from '../logger';
+import { migration as initialSchema } from './001-initial-schema';
+import { migration as stratumJobDiffs } from './002-stratum-job-diffs';
+import { migration as stratumBlockSummaries } from './003-stratum-block-summaries';
+import { migration as stratumSessionState } from './004-stratum-session-state';
+import { migration as stratumHealth } from './005-stratum-health';
+import { migration as blocksFound } from './006-blocks-found';
+import { migration as poolStatsRollups } from './007-pool-stats-rollups';
+import { migration as numericHashrates } from './008-numeric-hashrates';
+import { migration as highestDiffBackfill } from './009-highest-diff-backfill';
+import { migration as workerStatsHistory } from './010-worker-stats-history';
+import { migration as workerEvents } from './011-worker-events';
+import { migration as notifications } from './012-notifications';
+import { migration as userCollectionSchedule } from './013-user-collection-schedule';
+import { migration as jobStatus } from './014-job-status';
+import { migration as leaderLease } from './015-leader-lease';
+import { migration as poolStatsRollupCounts } from './016-pool-stats-rollup-counts';
+
+/**
+ * Schema migrations
+ *
+ * Numbered, forward-only migrations. The database's `PRAGMA user_version` is
+ * the last applied version, and `schema_migrations` keeps an audit row per
+ * migration. Each migration runs in its own IMMEDIATE transaction together
+ * with its bookkeeping, so a failure leaves the database at the previous
+ * version and concurrent processes can't apply the same migration twice.
+ *
+ * Migrations 1-8 capture the schema as it was built before versioning, so
+ * on SQLite they tolerate databases where some or all of it already exists.
+ * Postgres databases always start empty. Later migrations can rely on the
+ * exact schema of the version before them.
+ *
+ * Postgres columns mirror SQLite's storage: INTEGER becomes BIGINT, REAL
+ * DOUBLE PRECISION, and BOOLEAN SMALLINT so flags stay 0/1 on both.
+ *
+ * To change the schema, add the next numbered file and append it here. Never
+ * edit a migration that has shipped.
+ */
+
+export interface Migration {
+  version: number;
+  name: string;
+  sqlite: (db: Database.Database) => void;
+  /** The same change for Postgres, applied by ./postgres */
+  postgres: string;
+}
+
+export const MIGRATIONS: Migration[] = [
+  initialSchema,
+  stratumJobDiffs,
+  stratumBlockSummaries,
+  stratumSessionState,
+  stratumHealth,
+  blocksFound,
+  poolStatsRollups,
+  numericHashrates,
+  highestDiffBackfill,
+  workerStatsHistory,
+  workerEvents,
+  notifications,
+  userCollectionSchedule,
+  jobStatus,
+  leaderLease,
+  poolStatsRollupCounts,
+];
+
+export const LATEST_SCHEMA_VERSION = MIGRATIONS[MIGRATIONS.length - 1].version;
+
+const log = createLogger({ module: 'migrations' });
+
+export interface AppliedMigration {
+  version: number;
+  name: string;
+  applied_at: number;
+  duration_ms: number;
+}
+
+export interface MigrationStatus {
+  currentVersion: number;
+  latestVersion: number;
+  applied: AppliedMigration[];
+  pending: Migration[];
+}
+
+export interface MigrateOptions {
+  /** Run pending migrations, then roll everything back */
+  dryRun?: boolean;
+  /** Stop after this version instead of the latest */
+  targetVersion?: number;
+}
+
+export interface MigrationResult {
+  version: number;
+  name: string;
+  durationMs: number;
+}
+
+// Thrown inside a dry run's transaction to roll it back
+class DryRunRollback extends Error {}
+
+function validateMigrations(): void {
+  MIGRATIONS.forEach((migration, index) => {
+    if (migration.version !== index + 1) {
+      throw new Error(`Migration ${migration.name} has version ${migration.version}, expected ${index + 1}`);
+    }
+  });
+}
+
+function ensureMigrationsTable(db: Database.Database): void {
+  db.exec(`
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+      version INTEGER PRIMARY KEY,
+      name TEXT NOT NULL,
+      applied_at INTEGER NOT NULL,
+      duration_ms INTEGER NOT NULL
+    )
+  `);
+}
+
+export function getSchemaVersion(db: Database.Database): number {
+  return db.pragma('user_version', { simple: true }) as number;
+}
+
+export function getMigrationStatus(db: Database.Database): MigrationStatus {
+  const currentVersion = getSchemaVersion(db);
+  const hasMigrationsTable = db.prepare(
+    `SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'`
+  ).get() !== undefined;
+
+  const applied = hasMigrationsTable
+    ? db.prepare(`SELECT * FROM schema_migrations ORDER BY version ASC`).all() as AppliedMigration[]
+    : [];
+
+  return {
+    currentVersion,
+    latestVersion: LATEST_SCHEMA_VERSION,
+    applied,
+    pending: MIGRATIONS.filter(migration => migration.version > currentVersion),
+  };
+}
+
+function applyMigration(db: Database.Database, migration: Migration): MigrationResult {
+  const startedAt = Date.now();
+  migration.sqlite(db);
+  const durationMs = Date.now() - startedAt;
+
+  db.prepare(`
+    INSERT INTO schema_migrations (version, name, applied_at, duration_ms)
+    VALUES (?, ?, ?, ?)
+  `).run(migration.version, migration.name, Math.floor(startedAt / 1000), durationMs);
+  db.pragma(`user_version = ${migration.version}`);
+
+  return { version: migration.version, name: migration.name, durationMs };
+}
+
+/**
+ * Apply pending migrations up to targetVersion (default the latest).
+ * Returns the migrations applied, or that would have been in a dry run.
+ */
+export function migrate(db: Database.Database, options: MigrateOptions = {}): MigrationResult[] {
+  const { dryRun = false, targetVersion = LATEST_SCHEMA_VERSION } = options;
+
+  validateMigrations();
+  if (!Number.isInteger(targetVersion) || targetVersion < 0 || targetVersion > LATEST_SCHEMA_VERSION) {
+    throw new Error(`Target version must be between 0 and ${LATEST_SCHEMA_VERSION}`);
+  }
+
+  const currentVersion = getSchemaVersion(db);
+  if (currentVersion > LATEST_SCHEMA_VERSION) {
+    throw new Error(
+      `Database schema version ${currentVersion} is newer than this build supports (${LATEST_SCHEMA_VERSION})`
+    );
+  }
+
+  const results: MigrationResult[] = [];
+
+  if (dryRun) {
+    try {
+      db.transaction(() => {
+        ensureMigrationsTable(db);
+        for (const migration of MIGRATIONS) {
+          if (migration.version <= currentVersion || migration.version > targetVersion) continue;
+          results.push(applyMigration(db, migration));
+        }
+        throw new DryRunRollback();
+      }).immediate();
+    } catch (error) {
+      if (!(error instanceof DryRunRollback)) throw error;
+    }
+    return results;
+  }
+
+  for (const migration of MIGRATIONS) {
+    if (migration.version <= currentVersion || migration.version > targetVersion) continue;
+
+    const result = db.transaction(() => {
+      // Another process may have applied it while we waited for the lock
+      if (getSchemaVersion(db) >= migration.version) return null;
+      ensureMigrationsTable(db);
+      return applyMigration(db, migration);
+    }).immediate();
+
+    if (result) {
+      log.info('Applied migration', { version: result.version, name: result.name, durationMs: result.durationMs });
+      results.push(result);
+    }
+  }
+
+  return results;
+}
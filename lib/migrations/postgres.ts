@@ -0,0 +1,137 @@
+import type { Pool, PoolClient } from 'pg';
+import { createLogger } from '../logger';
+import {
+  LATEST_SCHEMA_VERSION,
+  MIGRATIONS,
+  type AppliedMigration,
+  type Migration,
+  type MigrateOptions,
+  type MigrationResult,
+  type MigrationStatus,
+} from './index';
+
+/**
+ * Postgres runner for the same numbered migrations, applying each one's
+ * `postgres` SQL. Postgres has no user_version, so `schema_migrations` is the
+ * source of truth, and a transaction-scoped advisory lock keeps concurrent
+ * processes from applying the same migration twice.
+ */
+
+// Arbitrary key for pg_advisory_xact_lock, shared by every parastats process
+const MIGRATION_LOCK_KEY = 7_245_031;
+
+const log = createLogger({ module: 'migrations' });
+
+const CREATE_MIGRATIONS_TABLE = `
+  CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    applied_at BIGINT NOT NULL,
+    duration_ms INTEGER NOT NULL
+  )
+`;
+
+/**
+ * Apply one migration inside the caller's transaction. Returns null when
+ * another process applied it while we waited for the lock.
+ */
+async function applyMigration(client: PoolClient, migration: Migration): Promise<MigrationResult | null> {
+  await client.query('SELECT pg_advisory_xact_lock($1)', [MIGRATION_LOCK_KEY]);
+  await client.query(CREATE_MIGRATIONS_TABLE);
+
+  const applied = await client.query('SELECT 1 FROM schema_migrations WHERE version = $1', [migration.version]);
+  if (applied.rowCount) return null;
+
+  const startedAt = Date.now();
+  await client.query(migration.postgres);
+  const durationMs = Date.now() - startedAt;
+
+  await client.query(
+    `INSERT INTO schema_migrations (version, name, applied_at, duration_ms) VALUES ($1, $2, $3, $4)`,
+    [migration.version, migration.name, Math.floor(startedAt / 1000), durationMs]
+  );
+
+  return { version: migration.version, name: migration.name, durationMs };
+}
+
+async function loadApplied(pool: Pool): Promise<AppliedMigration[]> {
+  const exists = await pool.query(`SELECT to_regclass('schema_migrations') IS NOT NULL AS exists`);
+  if (!exists.rows[0].exists) return [];
+
+  const result = await pool.query(`SELECT * FROM schema_migrations ORDER BY version ASC`);
+  return result.rows as AppliedMigration[];
+}
+
+export async function getPostgresMigrationStatus(pool: Pool): Promise<MigrationStatus> {
+  const applied = await loadApplied(pool);
+  const currentVersion = applied.length > 0 ? applied[applied.length - 1].version : 0;
+
+  return {
+    currentVersion,
+    latestVersion: LATEST_SCHEMA_VERSION,
+    applied,
+    pending: MIGRATIONS.filter(migration => migration.version > currentVersion),
+  };
+}
+
+export async function migratePostgres(pool: Pool, options: MigrateOptions = {}): Promise<MigrationResult[]> {
+  const { dryRun = false, targetVersion = LATEST_SCHEMA_VERSION } = options;
+
+  if (!Number.isInteger(targetVersion) || targetVersion < 0 || targetVersion > LATEST_SCHEMA_VERSION) {
+    throw new Error(`Target version must be between 0 and ${LATEST_SCHEMA_VERSION}`);
+  }
+
+  const { currentVersion } = await getPostgresMigrationStatus(pool);
+  if (currentVersion > LATEST_SCHEMA_VERSION) {
+    throw new Error(
+      `Database schema version ${currentVersion} is newer than this build supports (${LATEST_SCHEMA_VERSION})`
+    );
+  }
+
+  const pending = MIGRATIONS.filter(
+    migration => migration.version > currentVersion && migration.version <= targetVersion
+  );
+  if (pending.length === 0) return [];
+
+  const results: MigrationResult[] = [];
+  const client = await pool.connect();
+
+  try {
+    // A dry run applies everything in one transaction and rolls it back
+    if (dryRun) {
+      await client.query('BEGIN');
+      try {
+        for (const migration of pending) {
+          const result = await applyMigration(client, migration);
+          if (result) results.push(result);
+        }
+      } finally {
+        await client.query('ROLLBACK');
+      }
+      return results;
+    }
+
+    for (const migration of pending) {
+      await client.query('BEGIN');
+      try {
+        const result = await applyMigration(client, migration);
+        await client.query('COMMIT');
+        if (result) {
+          log.info('Applied migration', {
+            version: result.version,
+            name: result.name,
+            durationMs: result.durationMs,
+          });
+          results.push(result);
+        }
+      } catch (error) {
+        await client.query('ROLLBACK');
+        throw error;
+      }
+    }
+  } finally {
+    client.release();
+  }
+
+  return results;
+}
@@ -0,0 +1,349 @@
+import { randomBytes } from 'crypto';
+import { getDb, type Db } from '../db';
+import { parsePositiveInt } from '../env';
+import { createLogger } from '../logger';
+import { assertLeaseHeld } from '../leader-lease';
+import { recordCollectorCycle } from '../metrics';
+import { emailDeliverer } from './email';
+import { nostrDeliverer } from './nostr';
+import { webhookDeliverer } from './webhook';
+import {
+  NOTIFICATION_EVENTS,
+  type Deliverer,
+  type NotificationChannel,
+  type NotificationEvent,
+  type NotificationPayload,
+  type NotificationSubscription,
+} from './types';
+
+export * from './types';
+
+/**
+ * Notification outbox
+ *
+ * Collectors call enqueueNotification inside the transaction that records the
+ * event, writing one outbox row per matching subscription. The jobs process
+ * drains the outbox with deliverPendingNotifications: each row goes to its
+ * channel's deliverer, and a failed attempt is retried with exponential
+ * backoff until NOTIFICATION_MAX_ATTEMPTS, after which the row is marked
+ * failed.
+ */
+
+const CONFIG = {
+  BATCH_SIZE: parsePositiveInt(process.env.NOTIFICATION_BATCH_SIZE, 100),
+  CONCURRENCY: 10,
+  MAX_ATTEMPTS: parsePositiveInt(process.env.NOTIFICATION_MAX_ATTEMPTS, 8),
+  RETRY_BASE_SECONDS: 60,
+  RETRY_MAX_SECONDS: 6 * 60 * 60,
+  // A claimed row isn't picked up again for this long, so a slow delivery
+  // isn't sent twice by overlapping runs
+  CLAIM_SECONDS: 5 * 60,
+  RETENTION_DAYS: parsePositiveInt(process.env.NOTIFICATION_RETENTION_DAYS, 30),
+  MAX_SUBSCRIPTIONS_PER_ADDRESS: 10,
+} as const;
+
+const log = createLogger({ module: 'notifications' });
+
+const DELIVERERS: Record<NotificationChannel, Deliverer> = {
+  webhook: webhookDeliverer,
+  email: emailDeliverer,
+  nostr: nostrDeliverer,
+};
+
+export function getDeliverer(channel: NotificationChannel): Deliverer {
+  return DELIVERERS[channel];
+}
+
+/** Channels the server has the configuration to deliver on */
+export function getConfiguredChannels(): NotificationChannel[] {
+  return Object.values(DELIVERERS)
+    .filter(deliverer => deliverer.isConfigured())
+    .map(deliverer => deliverer.channel);
+}
+
+/**
+ * Queue a notification for every active subscription to `event`. A null
+ * address reaches all subscribers (pool-wide events such as found blocks).
+ * `key` identifies the event: queueing the same key twice is a no-op.
+ * Returns the number of outbox rows written.
+ */
+export async function enqueueNotification(
+  db: Db,
+  notification: {
+    event: NotificationEvent;
+    address: string | null;
+    key: string;
+    title: string;
+    message: string;
+    data?: NotificationPayload['data'];
+    timestamp?: number;
+  }
+): Promise<number> {
+  const now = Math.floor(Date.now() / 1000);
+  const payload: NotificationPayload = {
+    event: notification.event,
+    title: notification.title,
+    message: notification.message,
+    data: notification.data ?? {},
+    timestamp: notification.timestamp ?? now,
+  };
+
+  const result = await db.run(`
+    INSERT INTO notification_outbox (
+      subscription_id, event_type, event_key, payload, next_attempt_at, created_at
+    )
+    SELECT id, ?, ?, ?, ?, ?
+    FROM notification_subscriptions
+    WHERE is_active = 1
+      AND (',' || events || ',') LIKE ?
+      ${notification.address === null ? '' : 'AND address = ?'}
+    ON CONFLICT(subscription_id, event_key) DO NOTHING
+  `, [
+    notification.event,
+    notification.key,
+    JSON.stringify(payload),
+    now,
+    now,
+    `%,${notification.event},%`,
+    ...(notification.address === null ? [] : [notification.address]),
+  ]);
+
+  return result.changes;
+}
+
+interface OutboxRow {
+  id: number;
+  payload: string;
+  attempts: number;
+  subscription_id: number;
+  address: string;
+  channel: NotificationChannel;
+  target: string;
+  events: string;
+  secret: string | null;
+}
+
+function toSubscription(row: {
+  id: number;
+  address: string;
+  channel: NotificationChannel;
+  target: string;
+  events: string;
+  secret: string | null;
+}): NotificationSubscription {
+  return {
+    id: row.id,
+    address: row.address,
+    channel: row.channel,
+    target: row.target,
+    events: row.events.split(',').filter((event): event is NotificationEvent =>
+      (NOTIFICATION_EVENTS as readonly string[]).includes(event)
+    ),
+    secret: row.secret,
+  };
+}
+
+/** Seconds to wait before retrying after the given number of failed attempts */
+export function retryDelaySeconds(attempts: number): number {
+  return Math.min(CONFIG.RETRY_BASE_SECONDS * 2 ** Math.max(attempts - 1, 0), CONFIG.RETRY_MAX_SECONDS);
+}
+
+async function deliverRow(db: Db, row: OutboxRow, now: number): Promise<boolean> {
+  // Claim the row; another run may have picked it up first
+  const claim = await db.run(
+    `UPDATE notification_outbox SET next_attempt_at = ? WHERE id = ? AND status = 'pending' AND next_attempt_at <= ?`,
+    [now + CONFIG.CLAIM_SECONDS, row.id, now]
+  );
+  if (claim.changes === 0) return false;
+
+  const subscription = toSubscription({ ...row, id: row.subscription_id });
+  const deliverer = DELIVERERS[subscription.channel];
+  const attempts = row.attempts + 1;
+
+  try {
+    if (!deliverer?.isConfigured()) {
+      throw new Error(`Channel ${subscription.channel} is not configured`);
+    }
+    await deliverer.deliver(subscription, {
+      ...(JSON.parse(row.payload) as NotificationPayload),
+      id: row.id,
+      address: subscription.address,
+    });
+
+    await db.run(`
+      UPDATE notification_outbox
+      SET status = 'delivered', attempts = ?, delivered_at = ?, last_error = NULL
+      WHERE id = ?
+    `, [attempts, Math.floor(Date.now() / 1000), row.id]);
+    return true;
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    const failed = attempts >= CONFIG.MAX_ATTEMPTS;
+
+    await db.run(`
+      UPDATE notification_outbox
+      SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?
+      WHERE id = ?
+    `, [
+      failed ? 'failed' : 'pending',
+      attempts,
+      Math.floor(Date.now() / 1000) + retryDelaySeconds(attempts),
+      message.substring(0, 500),
+      row.id,
+    ]);
+
+    const fields = { channel: subscription.channel, notificationId: row.id, attempts, error };
+    if (failed) {
+      log.error('Giving up on notification', fields);
+    } else {
+      log.warn('Notification delivery failed', { ...fields, maxAttempts: CONFIG.MAX_ATTEMPTS });
+    }
+    return false;
+  }
+}
+
+/**
+ * Deliver outbox rows that are due, as the notifications job. Returns the
+ * number delivered.
+ */
+export async function deliverPendingNotifications(): Promise<number> {
+  const startedAtMs = Date.now();
+  try {
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+
+    const rows = await db.all<OutboxRow>(`
+      SELECT o.id, o.payload, o.attempts,
+        s.id AS subscription_id, s.address, s.channel, s.target, s.events, s.secret
+      FROM notification_outbox o
+      JOIN notification_subscriptions s ON s.id = o.subscription_id
+      WHERE o.status = 'pending' AND o.next_attempt_at <= ?
+      ORDER BY o.next_attempt_at ASC, o.id ASC
+      LIMIT ?
+    `, [now, CONFIG.BATCH_SIZE]);
+
+    let delivered = 0;
+    for (let i = 0; i < rows.length; i += CONFIG.CONCURRENCY) {
+      // Another leader would send the same rows
+      await assertLeaseHeld(db);
+      const results = await Promise.all(
+        rows.slice(i, i + CONFIG.CONCURRENCY).map(row => deliverRow(db, row, now))
+      );
+      delivered += results.filter(Boolean).length;
+    }
+
+    if (delivered > 0) {
+      log.info('Delivered notifications', { delivered, due: rows.length, durationMs: Date.now() - startedAtMs });
+    }
+    recordCollectorCycle('notifications', startedAtMs, 'success');
+    return delivered;
+  } catch (error) {
+    log.error('Error delivering notifications', { error });
+    recordCollectorCycle('notifications', startedAtMs, 'error');
+    throw error;
+  }
+}
+
+export async function purgeOldNotifications(): Promise<void> {
+  try {
+    const db = await getDb();
+    const cutoff = Math.floor(Date.now() / 1000) - CONFIG.RETENTION_DAYS * 24 * 60 * 60;
+
+    const { changes } = await db.run(
+      `DELETE FROM notification_outbox WHERE status != 'pending' AND created_at < ?`,
+      [cutoff]
+    );
+    log.info('Purged old notifications', { rows: changes, retentionDays: CONFIG.RETENTION_DAYS });
+  } catch (error) {
+    log.error('Error purging notifications', { error });
+  }
+}
+
+export async function listSubscriptions(db: Db, address: string): Promise<NotificationSubscription[]> {
+  const rows = await db.all<{
+    id: number;
+    address: string;
+    channel: NotificationChannel;
+    target: string;
+    events: string;
+    secret: string | null;
+  }>(`
+    SELECT id, address, channel, target, events, secret
+    FROM notification_subscriptions
+    WHERE address = ? AND is_active = 1
+    ORDER BY id ASC
+  `, [address]);
+
+  return rows.map(toSubscription);
+}
+
+export class SubscriptionError extends Error {}
+
+/**
+ * Create a subscription, or replace the events of an existing one for the
+ * same channel and target. Webhooks get a new signing secret either way.
+ */
+export async function saveSubscription(
+  db: Db,
+  input: { address: string; channel: NotificationChannel; target: string; events: NotificationEvent[] }
+): Promise<NotificationSubscription> {
+  const deliverer = DELIVERERS[input.channel];
+  if (!deliverer.isConfigured()) {
+    throw new SubscriptionError(`${input.channel} notifications are not available on this server`);
+  }
+
+  const target = input.target.trim();
+  const targetError = deliverer.validateTarget(target);
+  if (targetError) {
+    throw new SubscriptionError(targetError);
+  }
+
+  const events = NOTIFICATION_EVENTS.filter(event => input.events.includes(event));
+  if (events.length === 0) {
+    throw new SubscriptionError('Choose at least one event');
+  }
+
+  const secret = input.channel === 'webhook' ? randomBytes(32).toString('hex') : null;
+  const now = Math.floor(Date.now() / 1000);
+
+  return db.transaction(async () => {
+    const existing = await db.all<{ id: number; channel: string; target: string }>(
+      'SELECT id, channel, target FROM notification_subscriptions WHERE address = ? AND is_active = 1',
+      [input.address]
+    );
+    const replacing = existing.some(row => row.channel === input.channel && row.target === target);
+    if (!replacing && existing.length >= CONFIG.MAX_SUBSCRIPTIONS_PER_ADDRESS) {
+      throw new SubscriptionError(`At most ${CONFIG.MAX_SUBSCRIPTIONS_PER_ADDRESS} subscriptions per address`);
+    }
+
+    const row = await db.get<{ id: number }>(`
+      INSERT INTO notification_subscriptions (
+        address, channel, target, events, secret, is_active, created_at, updated_at
+      ) VALUES (?, ?, ?, ?, ?, 1, ?, ?)
+      ON CONFLICT(address, channel, target) DO UPDATE SET
+        events = excluded.events,
+        secret = excluded.secret,
+        is_active = 1,
+        updated_at = excluded.updated_at
+      RETURNING id
+    `, [input.address, input.channel, target, events.join(','), secret, now, now]);
+
+    return {
+      id: row!.id,
+      address: input.address,
+      channel: input.channel,
+      target,
+      events,
+      secret,
+    };
+  });
+}
+
+/** Delete one of an address's subscriptions, with its queued notifications */
+export async function deleteSubscription(db: Db, address: string, id: number): Promise<boolean> {
+  const { changes } = await db.run(
+    'DELETE FROM notification_subscriptions WHERE id = ? AND address = ?',
+    [id, address]
+  );
+  return changes > 0;
+}
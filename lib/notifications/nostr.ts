@@ -0,0 +1,171 @@
+import { createCipheriv, createHash, randomBytes } from 'crypto';
+import { getSharedSecret, schnorr } from '@noble/secp256k1';
+import type { Deliverer } from './types';
+
+/**
+ * Nostr direct messages (NIP-04, kind 4) signed with NOSTR_PRIVATE_KEY and
+ * published to every relay in NOSTR_RELAYS
+ */
+
+const CONFIG = {
+  PRIVATE_KEY: process.env.NOSTR_PRIVATE_KEY?.trim().toLowerCase(),
+  RELAYS: (process.env.NOSTR_RELAYS || 'wss://relay.damus.io,wss://nos.lol')
+    .split(',')
+    .map(relay => relay.trim())
+    .filter(Boolean),
+  TIMEOUT_MS: 10_000,
+};
+
+const HEX_KEY_PATTERN = /^[0-9a-f]{64}$/;
+const BECH32_CHARSET = 'qpzry9x8gf2tvdw0s3jn54khce6mua7l';
+
+export interface NostrEvent {
+  id: string;
+  pubkey: string;
+  created_at: number;
+  kind: number;
+  tags: string[][];
+  content: string;
+  sig: string;
+}
+
+/**
+ * Decode an `npub1...` key to hex. Returns null for anything that isn't a
+ * valid bech32 npub.
+ */
+export function decodeNpub(npub: string): string | null {
+  const value = npub.toLowerCase();
+  const separator = value.lastIndexOf('1');
+  if (value.slice(0, separator) !== 'npub' || value.length - separator < 7) return null;
+
+  const words: number[] = [];
+  for (const char of value.slice(separator + 1)) {
+    const word = BECH32_CHARSET.indexOf(char);
+    if (word < 0) return null;
+    words.push(word);
+  }
+
+  const hrp = [...'npub'].map(c => c.charCodeAt(0) >> 5)
+    .concat(0, [...'npub'].map(c => c.charCodeAt(0) & 31));
+  if (bech32Polymod(hrp.concat(words)) !== 1) return null;
+
+  // 5-bit words to bytes, without the 6-word checksum
+  let accumulator = 0;
+  let bits = 0;
+  const bytes: number[] = [];
+  for (const word of words.slice(0, -6)) {
+    accumulator = (accumulator << 5) | word;
+    bits += 5;
+    if (bits >= 8) {
+      bits -= 8;
+      bytes.push((accumulator >> bits) & 0xff);
+    }
+  }
+  if (bytes.length !== 32) return null;
+  return Buffer.from(bytes).toString('hex');
+}
+
+function bech32Polymod(values: number[]): number {
+  const generators = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+  let checksum = 1;
+  for (const value of values) {
+    const top = checksum >> 25;
+    checksum = ((checksum & 0x1ffffff) << 5) ^ value;
+    for (let i = 0; i < 5; i++) {
+      if ((top >> i) & 1) checksum ^= generators[i];
+    }
+  }
+  return checksum;
+}
+
+/** Hex public key for an npub or hex target, or null if it is neither */
+export function parseNostrPubkey(target: string): string | null {
+  const value = target.trim();
+  if (HEX_KEY_PATTERN.test(value.toLowerCase())) return value.toLowerCase();
+  if (value.toLowerCase().startsWith('npub1')) return decodeNpub(value);
+  return null;
+}
+
+/** NIP-04 content: AES-256-CBC with the x coordinate of the ECDH shared point */
+export function encryptDirectMessage(privateKey: string, recipientPubkey: string, text: string): string {
+  const sharedPoint = getSharedSecret(privateKey, `02${recipientPubkey}`);
+  const key = Buffer.from(sharedPoint.slice(1, 33));
+  const iv = randomBytes(16);
+  const cipher = createCipheriv('aes-256-cbc', key, iv);
+  const encrypted = Buffer.concat([cipher.update(text, 'utf8'), cipher.final()]);
+  return `${encrypted.toString('base64')}?iv=${iv.toString('base64')}`;
+}
+
+export async function signEvent(
+  privateKey: string,
+  event: Pick<NostrEvent, 'created_at' | 'kind' | 'tags' | 'content'>
+): Promise<NostrEvent> {
+  const pubkey = Buffer.from(schnorr.getPublicKey(privateKey)).toString('hex');
+  const serialized = JSON.stringify([0, pubkey, event.created_at, event.kind, event.tags, event.content]);
+  const id = createHash('sha256').update(serialized).digest('hex');
+  const sig = Buffer.from(await schnorr.sign(id, privateKey)).toString('hex');
+  return { id, pubkey, ...event, sig };
+}
+
+/** Publish to one relay, resolving once it acknowledges the event */
+function publishToRelay(relay: string, event: NostrEvent): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const socket = new WebSocket(relay);
+    const timer = setTimeout(() => finish(new Error(`${relay} timed out`)), CONFIG.TIMEOUT_MS);
+
+    const finish = (error?: Error) => {
+      clearTimeout(timer);
+      socket.close();
+      if (error) reject(error); else resolve();
+    };
+
+    socket.addEventListener('open', () => socket.send(JSON.stringify(['EVENT', event])));
+    socket.addEventListener('message', message => {
+      try {
+        const [type, id, accepted, reason] = JSON.parse(String(message.data));
+        if (type !== 'OK' || id !== event.id) return;
+        finish(accepted ? undefined : new Error(`${relay} rejected event: ${reason}`));
+      } catch {
+        // Ignore anything that isn't an OK for this event
+      }
+    });
+    socket.addEventListener('error', () => finish(new Error(`${relay} connection failed`)));
+  });
+}
+
+export const nostrDeliverer: Deliverer = {
+  channel: 'nostr',
+
+  isConfigured() {
+    return Boolean(CONFIG.PRIVATE_KEY && HEX_KEY_PATTERN.test(CONFIG.PRIVATE_KEY) && CONFIG.RELAYS.length > 0);
+  },
+
+  validateTarget(target) {
+    return parseNostrPubkey(target) ? null : 'Not a valid npub or hex public key';
+  },
+
+  async deliver(subscription, notification) {
+    const recipient = parseNostrPubkey(subscription.target);
+    if (!recipient || !CONFIG.PRIVATE_KEY) {
+      throw new Error(`Nostr subscription ${subscription.id} can't be delivered`);
+    }
+
+    const event = await signEvent(CONFIG.PRIVATE_KEY, {
+      created_at: Math.floor(Date.now() / 1000),
+      kind: 4,
+      tags: [['p', recipient]],
+      content: encryptDirectMessage(
+        CONFIG.PRIVATE_KEY,
+        recipient,
+        `${notification.title}\n\n${notification.message}`
+      ),
+    });
+
+    // Delivered if any relay takes it
+    const results = await Promise.allSettled(CONFIG.RELAYS.map(relay => publishToRelay(relay, event)));
+    if (!results.some(result => result.status === 'fulfilled')) {
+      const reasons = results.map(result => result.status === 'rejected' ? String(result.reason?.message ?? result.reason) : '');
+      throw new Error(`No relay accepted the event: ${reasons.join('; ')}`);
+    }
+  },
+};
@@ -0,0 +1,42 @@
+export const NOTIFICATION_EVENTS = ['worker_offline', 'top_diff', 'block_found'] as const;
+export type NotificationEvent = typeof NOTIFICATION_EVENTS[number];
+
+export const NOTIFICATION_CHANNELS = ['webhook', 'email', 'nostr'] as const;
+export type NotificationChannel = typeof NOTIFICATION_CHANNELS[number];
+
+export interface NotificationSubscription {
+  id: number;
+  address: string;
+  channel: NotificationChannel;
+  target: string;
+  events: NotificationEvent[];
+  secret: string | null;
+}
+
+/** What gets delivered, stored as JSON in notification_outbox.payload */
+export interface NotificationPayload {
+  event: NotificationEvent;
+  title: string;
+  message: string;
+  data: Record<string, string | number | null>;
+  timestamp: number;
+}
+
+export interface Notification extends NotificationPayload {
+  /** Outbox id, stable across retries so receivers can deduplicate */
+  id: number;
+  address: string;
+}
+
+/**
+ * Sends notifications over one channel. Throwing marks the attempt failed
+ * and schedules a retry.
+ */
+export interface Deliverer {
+  channel: NotificationChannel;
+  /** Whether the channel has the server-side configuration it needs */
+  isConfigured(): boolean;
+  /** Reject a target this channel can't deliver to, with a message for the user */
+  validateTarget(target: string): string | null;
+  deliver(subscription: NotificationSubscription, notification: Notification): Promise<void>;
+}
@@ -0,0 +1,173 @@
+import { createHmac } from 'crypto';
+import dns, { type LookupAddress } from 'dns';
+import { BlockList, isIP, type LookupFunction } from 'net';
+import { Agent, fetch as undiciFetch } from 'undici';
+import { HttpError } from '../http-client';
+import type { Deliverer } from './types';
+
+const TIMEOUT_MS = 10_000;
+
+// Host names a webhook may not point at, so subscriptions can't probe the
+// server's own network. Only a pre-check: deliver vets the resolved addresses.
+const PRIVATE_HOST_PATTERNS = [
+  /^localhost$/i,
+  /\.localhost$/i,
+  /\.internal$/i,
+];
+
+// Addresses a webhook may not connect to: unspecified, loopback, private,
+// CGNAT, link-local, benchmarking, multicast and reserved ranges
+const PRIVATE_ADDRESSES = new BlockList();
+for (const [network, prefix] of [
+  ['0.0.0.0', 8], ['10.0.0.0', 8], ['100.64.0.0', 10], ['127.0.0.0', 8],
+  ['169.254.0.0', 16], ['172.16.0.0', 12], ['192.0.0.0', 24], ['192.168.0.0', 16],
+  ['198.18.0.0', 15], ['224.0.0.0', 4], ['240.0.0.0', 4],
+] as const) {
+  PRIVATE_ADDRESSES.addSubnet(network, prefix, 'ipv4');
+}
+for (const [network, prefix] of [
+  ['::', 96], ['64:ff9b::', 96], ['64:ff9b:1::', 48], ['fc00::', 7], ['fe80::', 10], ['ff00::', 8],
+] as const) {
+  PRIVATE_ADDRESSES.addSubnet(network, prefix, 'ipv6');
+}
+
+/**
+ * The IPv4 address inside an IPv4-mapped IPv6 address, in either the
+ * `::ffff:127.0.0.1` or the normalised `::ffff:7f00:1` form
+ */
+function unmapIPv4(address: string): string | null {
+  const match = /^(?:0{0,4}:){0,4}:?(?:0{0,4}:)?ffff:(.+)$/i.exec(address);
+  if (!match) return null;
+  const tail = match[1];
+  if (isIP(tail) === 4) return tail;
+
+  const hex = /^([0-9a-f]{1,4}):([0-9a-f]{1,4})$/i.exec(tail);
+  if (!hex) return null;
+  const high = parseInt(hex[1], 16);
+  const low = parseInt(hex[2], 16);
+  return [high >> 8, high & 0xff, low >> 8, low & 0xff].join('.');
+}
+
+/** Whether a webhook must not connect to this IP address */
+export function isPrivateAddress(address: string): boolean {
+  const bare = address.replace(/^\[|\]$/g, '');
+  const family = isIP(bare);
+  if (family === 4) return PRIVATE_ADDRESSES.check(bare, 'ipv4');
+  if (family !== 6) return true;
+
+  const mapped = unmapIPv4(bare);
+  if (mapped) return PRIVATE_ADDRESSES.check(mapped, 'ipv4');
+  return PRIVATE_ADDRESSES.check(bare, 'ipv6');
+}
+
+type Resolver = (hostname: string, options: { all: true }) => Promise<LookupAddress[]>;
+
+/**
+ * Resolve a webhook host and check every address it resolves to, so a public
+ * name pointing (or rebound) at an internal address is refused. Throws when
+ * any address is private.
+ */
+export async function resolvePublicAddresses(
+  hostname: string,
+  resolve: Resolver = dns.promises.lookup
+): Promise<LookupAddress[]> {
+  const host = hostname.replace(/^\[|\]$/g, '');
+  const family = isIP(host);
+  const addresses = family ? [{ address: host, family }] : await resolve(host, { all: true });
+
+  if (addresses.length === 0) {
+    throw new Error(`Webhook host ${hostname} did not resolve`);
+  }
+  const blocked = addresses.find(({ address }) => isPrivateAddress(address));
+  if (blocked) {
+    throw new Error(`Webhook host ${hostname} resolves to non-public address ${blocked.address}`);
+  }
+  return addresses;
+}
+
+/** A lookup that answers with the already vetted addresses instead of resolving again */
+function pinnedLookup(addresses: LookupAddress[]): LookupFunction {
+  return (_hostname, options, callback) => {
+    if (options.all) {
+      callback(null, addresses);
+    } else {
+      callback(null, addresses[0].address, addresses[0].family);
+    }
+  };
+}
+
+/**
+ * Signature of a webhook body: hex HMAC-SHA256 of `<timestamp>.<body>` with
+ * the subscription's secret, sent as `X-Parastats-Signature: t=<timestamp>,v1=<hex>`
+ */
+export function signWebhook(secret: string, timestamp: number, body: string): string {
+  return createHmac('sha256', secret).update(`${timestamp}.${body}`).digest('hex');
+}
+
+export const webhookDeliverer: Deliverer = {
+  channel: 'webhook',
+
+  isConfigured() {
+    return true;
+  },
+
+  validateTarget(target) {
+    let url: URL;
+    try {
+      url = new URL(target);
+    } catch {
+      return 'Webhook URL is not a valid URL';
+    }
+    if (url.protocol !== 'https:') {
+      return 'Webhook URL must use https';
+    }
+    const host = url.hostname.replace(/^\[|\]$/g, '');
+    const isPrivate = isIP(host) !== 0
+      ? isPrivateAddress(host)
+      : PRIVATE_HOST_PATTERNS.some(pattern => pattern.test(host)) || !host.includes('.');
+    if (isPrivate) {
+      return 'Webhook URL must point at a public host';
+    }
+    return null;
+  },
+
+  async deliver(subscription, notification) {
+    if (!subscription.secret) {
+      throw new Error(`Webhook subscription ${subscription.id} has no signing secret`);
+    }
+
+    const body = JSON.stringify(notification);
+    const timestamp = Math.floor(Date.now() / 1000);
+
+    // Connect only to addresses vetted here, so the name can't be rebound to
+    // an internal address between the check and the connection. A dedicated
+    // agent also keeps failures at a user's endpoint off the shared one.
+    const addresses = await resolvePublicAddresses(new URL(subscription.target).hostname);
+    const dispatcher = new Agent({ connect: { lookup: pinnedLookup(addresses) } });
+
+    try {
+      const response = await undiciFetch(subscription.target, {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+          'X-Parastats-Event': notification.event,
+          'X-Parastats-Delivery': String(notification.id),
+          'X-Parastats-Signature': `t=${timestamp},v1=${signWebhook(subscription.secret, timestamp, body)}`,
+        },
+        body,
+        redirect: 'manual',
+        signal: AbortSignal.timeout(TIMEOUT_MS),
+        dispatcher,
+      });
+
+      // Drain the body before the agent closes
+      await response.text().catch(() => undefined);
+
+      if (!response.ok) {
+        throw new HttpError(response.status, response.statusText, subscription.target);
+      }
+    } finally {
+      await dispatcher.close();
+    }
+  },
+};
@@ -1,8 +1,25 @@
-import cron from 'node-cron';
-import { getDb } from './db';
+import { getDb, type Db, type SqlValue } from './db';
 import { parsePositiveInt } from './env';
-import { fetch, HttpError, isRetryableError } from './http-client';
+import { getRolledUpBefore, updatePoolStatsRollups, purgeOldRollups } from './pool-stats-rollups';
+import { recordWorkerEvents } from './worker-events';
+import { enqueueNotification, purgeOldNotifications } from './notifications';
+import {
+  buildCycleReport,
+  loadSchedule,
+  recordCollection,
+  selectDueUsers,
+  storeCycleReport,
+  type ScheduledUser,
+} from './user-scheduler';
+import { fetch, HttpError, parseRetryAfter, RateLimitError } from './http-client';
+import { withRetry } from './retry';
+import { CircuitOpenError, isCircuitOpen } from './circuit-breaker';
+import { createLogger, newCycleId, type Logger } from './logger';
+import { recordCollectorCycle } from './metrics';
+import type { JobDefinition } from './job-supervisor';
+import { assertLeaseHeld } from './leader-lease';
 import { fetchWithTimeout } from '@/app/api/lib/fetch-with-timeout';
+import { parseHashrate } from '@/app/utils/formatters';
 
 interface StatsData {
   runtime: number;
@@ -23,6 +40,19 @@ interface HashrateData {
   hashrate7d: string;
 }
 
+interface WorkerData {
+  workername: string;
+  hashrate1m: string;
+  hashrate5m: string;
+  hashrate1hr: string;
+  hashrate1d: string;
+  hashrate7d: string;
+  lastshare: number;
+  shares: number;
+  bestshare: number;
+  bestever: number;
+}
+
 interface UserData {
   hashrate1m: string;
   hashrate5m: string;
@@ -33,6 +63,8 @@ interface UserData {
   bestshare: number;
   bestever: number;
   authorised: number;
+  lastshare?: number;
+  worker?: WorkerData[];
 }
 
 interface MonitoredUser {
@@ -42,7 +74,9 @@ interface MonitoredUser {
 
 interface UserStatsSuccess {
   status: 'success';
+  user: ScheduledUser;
   userId: number;
+  address: string;
   data: UserData;
   now: number;
 }
@@ -53,11 +87,22 @@ interface UserStatsFailure {
   address: string;
 }
 
-type UserStatsResult = UserStatsSuccess | UserStatsFailure;
+interface UserStatsRateLimited {
+  status: 'rate_limited';
+  userId: number;
+  retryAfterSeconds: number;
+}
+
+// Not sent: the API's circuit breaker was open
+interface UserStatsCircuitOpen {
+  status: 'circuit_open';
+  userId: number;
+}
+
+type UserStatsResult = UserStatsSuccess | UserStatsFailure | UserStatsRateLimited | UserStatsCircuitOpen;
 
-let isCollectorRunning = false;
-let isUserCollectorRunning = false;
-let isAccountJobRunning = false;
+// Upstream answered 429: no user collection before this time (unix seconds)
+let rateLimitedUntil = 0;
 
 // Configuration constants
 const CONFIG = {
@@ -71,69 +116,41 @@ const CONFIG = {
   PURGE_CHUNK_DELAY_MS: 100,
   POOL_STATS_RETENTION_DAYS: parsePositiveInt(process.env.POOL_STATS_RETENTION_DAYS, 90),
   USER_STATS_RETENTION_DAYS: parsePositiveInt(process.env.USER_STATS_RETENTION_DAYS, 30),
+  WORKER_EVENT_RETENTION_DAYS: parsePositiveInt(process.env.WORKER_EVENT_RETENTION_DAYS, 90),
+  // Back-off after a 429 without a usable Retry-After header
+  RATE_LIMIT_FALLBACK_SECONDS: 60,
+  COLLECTION_CYCLE_RETENTION_DAYS: 7,
 } as const;
 
-/**
- * Helper function to add delay between retries
- */
-const delay = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
+const log = createLogger({ collector: 'pool-stats' });
 
 /**
- * Retry helper function with backoff and jitter
- * Intelligently skips retries for non-retryable errors (4xx client errors)
+ * Helper function to add delay
  */
-async function withRetry<T>(
-  operation: () => Promise<T>,
-  maxRetries = 4,
-  baseDelay = 500,
-  context?: string // Optional context for logging which operation is retrying
-): Promise<T> {
-  let lastError;
-
-  for (let attempt = 0; attempt <= maxRetries; attempt++) {
-    try {
-      return await operation();
-    } catch (error) {
-      lastError = error;
-
-      // Check if error is retryable before attempting retry
-      const retryable = isRetryableError(error);
-
-      if (!retryable) {
-        // Non-retryable error (e.g., 404, 400, 403) - fail immediately
-        // Skip logging for 404s - they're handled by the caller with a cleaner message
-        if (!(error instanceof HttpError && error.status === 404)) {
-          const contextMsg = context ? ` [${context}]` : '';
-          const errorMsg = error instanceof Error ? error.message : String(error);
-          console.log(`Non-retryable error${contextMsg} - ${errorMsg}`);
-        }
-        throw error;
-      }
+const delay = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
 
-      if (attempt < maxRetries) {
-        const backoffDelay = baseDelay + (attempt * baseDelay);
-        // Add jitter: randomize delay between 50% and 150% of backoff delay
-        const jitter = Math.random() + 0.5; // Random value between 0.5 and 1.5
-        const delayWithJitter = Math.floor(backoffDelay * jitter);
-        const contextMsg = context ? ` [${context}]` : '';
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        console.log(`Retry attempt ${attempt + 1}/${maxRetries} after ${delayWithJitter}ms${contextMsg} - Error: ${errorMsg}`);
-        await delay(delayWithJitter);
-        continue;
-      }
-      throw error;
-    }
-  }
-  throw lastError;
+interface BatchedUserStatsResult {
+  collected: ScheduledUser[];
+  failed: number;
+  /** Users not attempted because upstream rate limited the cycle or its circuit opened */
+  skipped: number;
+  rateLimited: boolean;
 }
 
 /**
- * Process users in batches to avoid overwhelming the system
+ * Process users in batches to avoid overwhelming the system. A 429 from
+ * upstream ends the cycle after the current batch and pauses collection for
+ * the Retry-After delay; so does the API's circuit opening, until the
+ * next cycle. Users refused by the open circuit don't count as failures.
  */
-async function processBatchedUserStats(users: MonitoredUser[]): Promise<number> {
-  const db = getDb();
-
-  const historyStmt = db.prepare(`
+async function processBatchedUserStats(
+  users: ScheduledUser[],
+  cycleLog: Logger,
+  signal?: AbortSignal
+): Promise<BatchedUserStatsResult> {
+  const db = await getDb();
+
+  const historySql = `
     INSERT INTO user_stats_history (
       user_id,
       hashrate1m,
@@ -141,18 +158,40 @@ async function processBatchedUserStats(users: MonitoredUser[]): Promise<number>
       hashrate1hr,
       hashrate1d,
       hashrate7d,
+      hashrate1m_hs,
+      hashrate5m_hs,
+      hashrate1hr_hs,
+      hashrate1d_hs,
+      hashrate7d_hs,
       workers,
       bestshare,
       bestever,
       created_at
-    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-  `);
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `;
+
+  const workerHistorySql = `
+    INSERT INTO worker_stats_history (
+      user_id,
+      worker_name,
+      hashrate1m_hs,
+      hashrate5m_hs,
+      hashrate1hr_hs,
+      hashrate1d_hs,
+      hashrate7d_hs,
+      shares,
+      bestshare,
+      bestever,
+      lastshare,
+      created_at
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `;
 
   // Update monitored_users with latest bestever and earliest authorised_at
   // bestever: only increase (higher is better)
   // authorised_at: only decrease to earlier timestamp (earlier = more loyal), but never to 0
   // Also reset failed_attempts since we succeeded
-  const successStmt = db.prepare(`
+  const successSql = `
     UPDATE monitored_users
     SET
       bestever = CASE WHEN bestever < ? THEN ? ELSE bestever END,
@@ -164,46 +203,101 @@ async function processBatchedUserStats(users: MonitoredUser[]): Promise<number>
       failed_attempts = 0,
       updated_at = ?
     WHERE id = ?
-  `);
+  `;
 
-  const applyBatch = db.transaction((successes: UserStatsSuccess[], failureIds: number[], now: number) => {
+  const applyBatch = (successes: UserStatsSuccess[], failureIds: number[], now: number) => db.transaction(async () => {
+    await assertLeaseHeld(db);
     const deactivatedIds: number[] = [];
     for (let i = 0; i < failureIds.length; i += CONFIG.SQL_BATCH_SIZE) {
       const chunk = failureIds.slice(i, i + CONFIG.SQL_BATCH_SIZE);
       const placeholders = chunk.map(() => '?').join(',');
-      const rows = db.prepare(
-        `SELECT id FROM monitored_users WHERE id IN (${placeholders}) AND failed_attempts + 1 >= ?`
-      ).all(...chunk, CONFIG.MAX_FAILED_ATTEMPTS) as { id: number }[];
+      const rows = await db.all<{ id: number }>(
+        `SELECT id FROM monitored_users WHERE id IN (${placeholders}) AND failed_attempts + 1 >= ?`,
+        [...chunk, CONFIG.MAX_FAILED_ATTEMPTS]
+      );
       deactivatedIds.push(...rows.map((row) => row.id));
     }
 
     for (const success of successes) {
-      historyStmt.run(
+      await db.run(historySql, [
         success.userId,
         success.data.hashrate1m,
         success.data.hashrate5m,
         success.data.hashrate1hr,
         success.data.hashrate1d,
         success.data.hashrate7d,
+        parseHashrate(success.data.hashrate1m),
+        parseHashrate(success.data.hashrate5m),
+        parseHashrate(success.data.hashrate1hr),
+        parseHashrate(success.data.hashrate1d),
+        parseHashrate(success.data.hashrate7d),
         success.data.workers,
         success.data.bestshare,
         success.data.bestever,
-        success.now
-      );
+        success.now,
+      ]);
+
+      for (const worker of success.data.worker ?? []) {
+        await db.run(workerHistorySql, [
+          success.userId,
+          worker.workername,
+          parseHashrate(worker.hashrate1m),
+          parseHashrate(worker.hashrate5m),
+          parseHashrate(worker.hashrate1hr),
+          parseHashrate(worker.hashrate1d),
+          parseHashrate(worker.hashrate7d),
+          worker.shares,
+          worker.bestshare,
+          worker.bestever,
+          worker.lastshare,
+          success.now,
+        ]);
+      }
 
-      successStmt.run(
+      // Without a worker list there's nothing to compare, rather than every worker gone
+      if (success.data.worker) {
+        const events = await recordWorkerEvents(db, success.userId, success.data.worker.map(worker => ({
+          workerName: worker.workername,
+          lastshare: worker.lastshare,
+          hashrate: parseHashrate(worker.hashrate1hr),
+          baselineHashrate: parseHashrate(worker.hashrate1d),
+        })), success.now);
+
+        for (const event of events) {
+          if (event.type !== 'offline') continue;
+          const lastSeen = event.lastshare > 0 ? new Date(event.lastshare * 1000).toISOString() : 'never';
+          await enqueueNotification(db, {
+            event: 'worker_offline',
+            address: success.address,
+            key: `worker_offline:${event.workerName}:${success.now}`,
+            title: `Worker ${event.workerName} is offline`,
+            message: `${event.workerName} went offline (last share: ${lastSeen}).`,
+            data: { worker: event.workerName, previous_state: event.previousState, lastshare: event.lastshare },
+            timestamp: success.now,
+          });
+        }
+      }
+
+      await db.run(successSql, [
         success.data.bestever,
         success.data.bestever,
         success.data.authorised,     // Check if API value is valid (> 0)
         success.data.authorised,     // Compare against existing value
         success.data.authorised,     // Set to this value if conditions met
         success.now,
-        success.userId
-      );
+        success.userId,
+      ]);
+
+      const lastShares = (success.data.worker ?? []).map(worker => worker.lastshare);
+      const lastShareAt = Math.max(success.data.lastshare ?? 0, ...lastShares);
+      await recordCollection(db, success.user, {
+        hashrateHs: parseHashrate(success.data.hashrate1hr),
+        lastShareAt: lastShareAt > 0 ? lastShareAt : null,
+      }, success.now);
     }
 
     if (failureIds.length > 0) {
-      updateUsersInChunks(
+      await updateUsersInChunks(
         db,
         failureIds,
         `UPDATE monitored_users
@@ -219,17 +313,22 @@ async function processBatchedUserStats(users: MonitoredUser[]): Promise<number>
     return deactivatedIds;
   });
 
+  const result: BatchedUserStatsResult = { collected: [], failed: 0, skipped: 0, rateLimited: false };
+
   for (let i = 0; i < users.length; i += CONFIG.BATCH_SIZE) {
+    signal?.throwIfAborted();
     const batch = users.slice(i, i + CONFIG.BATCH_SIZE);
     const results = await Promise.all(
-      batch.map(user => fetchUserStats(user.id, user.address))
+      batch.map(user => fetchUserStats(user, cycleLog, signal))
     );
 
     const successes = results.filter((result) => result.status === 'success');
     const failures = results.filter((result) => result.status === 'failure');
+    const rateLimits = results.filter((result) => result.status === 'rate_limited');
+    const circuitOpen = results.filter((result) => result.status === 'circuit_open');
     const now = Math.floor(Date.now() / 1000);
 
-    const deactivatedIds = applyBatch.immediate(
+    const deactivatedIds = await applyBatch(
       successes,
       failures.map((failure) => failure.userId),
       now
@@ -237,24 +336,47 @@ async function processBatchedUserStats(users: MonitoredUser[]): Promise<number>
 
     const failureAddresses = new Map(failures.map((failure) => [failure.userId, failure.address]));
     for (const id of deactivatedIds) {
-      console.log(`Deactivating user ${failureAddresses.get(id)} after ${CONFIG.MAX_FAILED_ATTEMPTS} failed attempts`);
+      cycleLog.info('Deactivating user after repeated failures', { address: failureAddresses.get(id), failedAttempts: CONFIG.MAX_FAILED_ATTEMPTS });
+    }
+
+    result.collected.push(...successes.map((success) => success.user));
+    result.failed += failures.length;
+
+    cycleLog.debug('Processed user batch', {
+      batch: Math.floor(i / CONFIG.BATCH_SIZE) + 1,
+      batches: Math.ceil(users.length / CONFIG.BATCH_SIZE),
+      users: batch.length,
+    });
+
+    if (rateLimits.length > 0) {
+      const retryAfter = Math.max(...rateLimits.map((limit) => limit.retryAfterSeconds));
+      rateLimitedUntil = Math.max(rateLimitedUntil, now + retryAfter);
+      result.rateLimited = true;
+      result.skipped = rateLimits.length + users.length - (i + batch.length);
+      cycleLog.warn('Upstream rate limited user requests, pausing user collection', { requests: rateLimits.length, retryAfterSeconds: retryAfter });
+      break;
     }
 
-    console.log(`Processed batch ${Math.floor(i / CONFIG.BATCH_SIZE) + 1}/${Math.ceil(users.length / CONFIG.BATCH_SIZE)} (${batch.length} users)`);
+    const remaining = users.length - (i + batch.length);
+    if (circuitOpen.length > 0 || (remaining > 0 && isCircuitOpen('api'))) {
+      result.skipped = circuitOpen.length + remaining;
+      cycleLog.warn('API circuit open, deferring the rest of the cycle', { skipped: result.skipped });
+      break;
+    }
   }
-  return users.length;
+  return result;
 }
 
 /**
  * Update users in chunks to respect SQLite's 999 parameter limit
  * Executes UPDATE statements in batches for large user ID arrays
  */
-function updateUsersInChunks(
-  db: ReturnType<typeof getDb>,
+async function updateUsersInChunks(
+  db: Db,
   userIds: number[],
   sqlTemplate: string,
-  params: unknown[]
-): number {
+  params: SqlValue[]
+): Promise<number> {
   let totalUpdated = 0;
 
   // Process in chunks to stay well below SQLite's 999 parameter limit
@@ -264,7 +386,7 @@ function updateUsersInChunks(
     const sql = sqlTemplate.replace('${placeholders}', placeholders);
 
     // Run the update with params first, then the chunk IDs
-    const result = db.prepare(sql).run(...params, ...chunk);
+    const result = await db.run(sql, [...params, ...chunk]);
     totalUpdated += result.changes;
   }
 
@@ -274,13 +396,13 @@ function updateUsersInChunks(
 /**
  * Fetch user stats from the API
  */
-async function fetchUserStats(userId: number, address: string): Promise<UserStatsResult> {
+async function fetchUserStats(user: ScheduledUser, cycleLog: Logger, signal?: AbortSignal): Promise<UserStatsResult> {
+  const { id: userId, address } = user;
   try {
     // Include response.json() inside retry wrapper to handle connection errors during body reading
     const userData = await withRetry(async () => {
       const apiUrl = process.env.API_URL;
       if (!apiUrl) {
-        console.error("Failed to fetch user data: No API_URL defined in env");
         throw new Error(`Failed to fetch user data: No API_URL defined in env`);
       }
 
@@ -293,6 +415,13 @@ async function fetchUserStats(userId: number, address: string): Promise<UserStat
         const res = await fetch(`${apiUrl}/aggregator/users/${address}`, {
           headers,
         });
+        if (res.status === 429) {
+          await res.body?.cancel();
+          throw new RateLimitError(
+            parseRetryAfter(res.headers.get('retry-after'), CONFIG.RATE_LIMIT_FALLBACK_SECONDS),
+            `${apiUrl}/aggregator/users/${address}`
+          );
+        }
         if (!res.ok) {
           // Try to get response body for more details
           let responseBody: string | undefined;
@@ -315,15 +444,24 @@ async function fetchUserStats(userId: number, address: string): Promise<UserStat
         }
         throw error;
       }
-    }, 5, 300, `GET /users/${address}`);
+    }, { maxRetries: 5, baseDelayMs: 300, context: 'GET /users/:address', log: cycleLog.child({ address }), signal });
 
-    return { status: 'success', userId, data: userData, now: Math.floor(Date.now() / 1000) };
+    return { status: 'success', user, userId, address, data: userData, now: Math.floor(Date.now() / 1000) };
   } catch (error) {
+    // Cancelled, not a failure of this user
+    if (signal?.aborted) throw error;
+    if (error instanceof RateLimitError) {
+      return { status: 'rate_limited', userId, retryAfterSeconds: error.retryAfterSeconds };
+    }
+    if (error instanceof CircuitOpenError) {
+      return { status: 'circuit_open', userId };
+    }
+
     // Log 404s as simple one-liners (user not found is common, not critical)
     if (error instanceof HttpError && error.status === 404) {
-      console.log(`User ${address} not found (404)`);
+      cycleLog.info('User not found upstream', { address });
     } else {
-      console.error(`Error collecting stats for user ${address}:`, error);
+      cycleLog.error('Error collecting user stats', { address, error });
     }
 
     return { status: 'failure', userId, address };
@@ -331,23 +469,67 @@ async function fetchUserStats(userId: number, address: string): Promise<UserStat
 }
 
 /**
- * Fetch stats for all monitored users and manage user lifecycle
- * Handles auto-discovery, deactivation, and reactivation of users
+ * Collect the users among `eligible` that the adaptive schedule says are
+ * due, then record the cycle's coverage and lag
  */
-export async function collectAllUserStats() {
-  if (isUserCollectorRunning) {
-    console.warn('⚠️  User stats collection already running, skipping this cycle');
-    return;
-  }
+async function collectDueUsers(
+  db: Db,
+  eligible: MonitoredUser[],
+  startedAt: number,
+  cycleLog: Logger,
+  signal?: AbortSignal
+): Promise<void> {
+  const schedule = await loadSchedule(db, eligible);
+  const { selected, deferred, due } = selectDueUsers(schedule, startedAt);
+  const result = await processBatchedUserStats(selected, cycleLog, signal);
+
+  const report = buildCycleReport({
+    startedAt,
+    finishedAtMs: Date.now(),
+    eligible: schedule.length,
+    due,
+    collected: result.collected,
+    failed: result.failed,
+    deferred: deferred.length + result.skipped,
+    rateLimited: result.rateLimited,
+  });
+  await storeCycleReport(db, report);
+
+  cycleLog.info('Collected due users', {
+    collected: report.collected,
+    due: report.due,
+    eligible: report.eligible,
+    failed: report.failed,
+    deferred: report.deferred,
+    rateLimited: report.rateLimited,
+    coverage: report.coverage,
+    avgLagSeconds: Math.round(report.avgLagSeconds),
+    maxLagSeconds: report.maxLagSeconds,
+    durationMs: report.durationMs,
+  });
+}
 
+/**
+ * Fetch stats for due monitored users and manage user lifecycle
+ * Handles auto-discovery, deactivation, and reactivation of users
+ */
+export async function collectAllUserStats(signal?: AbortSignal) {
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'users' });
   try {
-    isUserCollectorRunning = true;
-    const db = getDb();
+    const db = await getDb();
     const now = Math.floor(Date.now() / 1000);
+
+    if (now < rateLimitedUntil) {
+      cycleLog.info('User collection paused by upstream rate limit', { remainingSeconds: rateLimitedUntil - now });
+      return;
+    }
+
     const backoffThreshold = now - (CONFIG.FAILED_USER_BACKOFF_MINUTES * 60);
 
     // Fetch current pool users list directly from API
     let poolUsers: Set<string>;
+    let poolUsersError: unknown = null;
     try {
       poolUsers = await withRetry(async () => {
         const apiUrl = process.env.API_URL;
@@ -361,6 +543,13 @@ export async function collectAllUserStats() {
         }
 
         const response = await fetch(`${apiUrl}/aggregator/users`, { headers });
+        if (response.status === 429) {
+          await response.body?.cancel();
+          throw new RateLimitError(
+            parseRetryAfter(response.headers.get('retry-after'), CONFIG.RATE_LIMIT_FALLBACK_SECONDS),
+            `${apiUrl}/aggregator/users`
+          );
+        }
         if (!response.ok) {
           throw new HttpError(response.status, response.statusText, `${apiUrl}/aggregator/users`);
         }
@@ -368,30 +557,41 @@ export async function collectAllUserStats() {
         const data = await response.json();
         // API returns array of address strings
         return new Set<string>(data as string[]);
-      }, 5, 300, 'GET /users');
+      }, { maxRetries: 5, baseDelayMs: 300, context: 'GET /users', log: cycleLog, signal });
     } catch (error) {
-      console.error('Error fetching pool users:', error);
+      if (error instanceof RateLimitError) {
+        rateLimitedUntil = now + error.retryAfterSeconds;
+        cycleLog.warn('Upstream rate limited the pool users list, pausing user collection', { retryAfterSeconds: error.retryAfterSeconds });
+        return;
+      }
+      // Includes an open API circuit
+      poolUsersError = error;
       poolUsers = new Set<string>();
     }
 
     if (poolUsers.size === 0) {
-      console.warn('⚠️  Pool users API returned empty - either API failed or pool is actually empty');
-      console.log('Skipping auto-discovery and lifecycle management, collecting stats for known active users only');
+      cycleLog.warn(poolUsersError
+        ? 'Pool users API unavailable, collecting known active users only'
+        : 'Pool users API returned empty, collecting known active users only');
 
       // Collect stats for existing active users even when API is down
-      const knownUsers = db.prepare(`
+      const knownUsers = await db.all<{ id: number; address: string; updated_at: number; failed_attempts: number }>(`
         SELECT id, address, updated_at, failed_attempts
         FROM monitored_users
         WHERE is_active = 1
-      `).all() as Array<{ id: number; address: string; updated_at: number; failed_attempts: number }>;
+      `);
 
       // Apply backoff for users with recent failures
       const usersToCollect = knownUsers.filter(user => {
         return user.failed_attempts === 0 || user.updated_at < backoffThreshold;
       });
 
-      const collectedCount = await processBatchedUserStats(usersToCollect);
-      console.log(`Collected stats for ${collectedCount} known users at ${new Date().toISOString()}`);
+      await collectDueUsers(db, usersToCollect, now, cycleLog, signal);
+
+      // Fail the cycle when the list couldn't be fetched, so the outage shows
+      // in the cycle metrics and job health; a pool with no users is a success
+      if (poolUsersError) throw poolUsersError;
+      recordCollectorCycle('user_stats', startedAtMs, 'success');
       return;
     }
 
@@ -402,16 +602,16 @@ export async function collectAllUserStats() {
 
     // Get all monitored users (active and inactive) with all needed fields
     // We fetch this once and reuse for both auto-discovery and lifecycle management
-    const allUsers = db.prepare(`
-      SELECT id, address, is_active, updated_at, failed_attempts
-      FROM monitored_users
-    `).all() as Array<{
+    const allUsers = await db.all<{
       id: number;
       address: string;
       is_active: number;
       updated_at: number;
       failed_attempts: number;
-    }>;
+    }>(`
+      SELECT id, address, is_active, updated_at, failed_attempts
+      FROM monitored_users
+    `);
 
     // Auto-discover new users if enabled
     if (CONFIG.AUTO_DISCOVER_USERS) {
@@ -431,24 +631,24 @@ export async function collectAllUserStats() {
 
       // Insert new users in a transaction for better performance
       if (newAddresses.length > 0) {
-        const insertStmt = db.prepare(`
-          INSERT INTO monitored_users (
-            address,
-            is_active,
-            is_public,
-            created_at,
-            updated_at,
-            authorised_at
-          ) VALUES (?, ?, ?, ?, ?, ?)
-        `);
-
-        const insertTransaction = db.transaction(() => {
+        await db.transaction(async () => {
+          await assertLeaseHeld(db);
           for (const address of newAddresses) {
-            const result = insertStmt.run(address, 1, 1, now, now, 0);
+            const inserted = await db.get<{ id: number }>(`
+              INSERT INTO monitored_users (
+                address,
+                is_active,
+                is_public,
+                created_at,
+                updated_at,
+                authorised_at
+              ) VALUES (?, ?, ?, ?, ?, ?)
+              RETURNING id
+            `, [address, 1, 1, now, now, 0]);
             addedCount++;
             // Add to allUsers array so they get processed in this cycle
             allUsers.push({
-              id: result.lastInsertRowid as number,
+              id: inserted!.id,
               address,
               is_active: 1,
               updated_at: now,
@@ -457,11 +657,9 @@ export async function collectAllUserStats() {
           }
         });
 
-        insertTransaction();
-
         const totalNewUsers = Array.from(poolUsers).filter(addr => !existingAddresses.has(addr)).length;
         if (totalNewUsers > CONFIG.AUTO_DISCOVER_BATCH_LIMIT) {
-          console.log(`⚠️  Found ${totalNewUsers} new users, added ${addedCount} (limited to ${CONFIG.AUTO_DISCOVER_BATCH_LIMIT} per cycle)`);
+          cycleLog.info('Auto-discovery limited', { newUsers: totalNewUsers, added: addedCount, limit: CONFIG.AUTO_DISCOVER_BATCH_LIMIT });
         }
       }
     }
@@ -492,10 +690,11 @@ export async function collectAllUserStats() {
     // Update user states in a single transaction for better performance
     // Use chunked updates to respect SQLite's 999 parameter limit
     if (usersToReactivate.length > 0 || usersToDeactivate.length > 0) {
-      const updateTransaction = db.transaction(() => {
+      await db.transaction(async () => {
+        await assertLeaseHeld(db);
         // Reactivate users who came back
         if (usersToReactivate.length > 0) {
-          reactivatedCount = updateUsersInChunks(
+          reactivatedCount = await updateUsersInChunks(
             db,
             usersToReactivate,
             `UPDATE monitored_users
@@ -507,7 +706,7 @@ export async function collectAllUserStats() {
 
         // Deactivate users who left
         if (usersToDeactivate.length > 0) {
-          deactivatedCount = updateUsersInChunks(
+          deactivatedCount = await updateUsersInChunks(
             db,
             usersToDeactivate,
             `UPDATE monitored_users
@@ -517,23 +716,21 @@ export async function collectAllUserStats() {
           );
         }
       });
-
-      updateTransaction();
     }
 
-    const collectedCount = await processBatchedUserStats(usersToCollect);
-
-    // Log summary
+    // Log lifecycle summary
     const lifecycleChanges = addedCount + deactivatedCount + reactivatedCount;
     if (lifecycleChanges > 0) {
-      console.log(`User lifecycle: ${addedCount} added, ${deactivatedCount} deactivated, ${reactivatedCount} reactivated`);
+      cycleLog.info('User lifecycle changes', { added: addedCount, deactivated: deactivatedCount, reactivated: reactivatedCount });
     }
-    console.log(`Collected stats for ${collectedCount} users at ${new Date().toISOString()}`);
+
+    await collectDueUsers(db, usersToCollect, now, cycleLog, signal);
+    recordCollectorCycle('user_stats', startedAtMs, 'success');
 
   } catch (error) {
-    console.error("Error collecting user stats:", error);
-  } finally {
-    isUserCollectorRunning = false;
+    cycleLog.error('Error collecting user stats', { error, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('user_stats', startedAtMs, 'error');
+    throw error;
   }
 }
 
@@ -541,15 +738,12 @@ export async function collectAllUserStats() {
  * Fetch pool stats and store them in the database
  */
 export async function collectPoolStats() {
-  if (isCollectorRunning) return;
-
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'pool' });
   try {
-    isCollectorRunning = true;
-    
     const text = await withRetry(async () => {
       const apiUrl = process.env.API_URL;
       if (!apiUrl) {
-        console.error("Error fetching pool stats: No API_URL defined in env");
         throw new Error('API_URL is not defined in environment variables');
       }
 
@@ -566,7 +760,7 @@ export async function collectPoolStats() {
         throw new HttpError(response.status, response.statusText, url);
       }
       return response.text();
-    }, 5, 300, 'GET /pool/pool.status');
+    }, { maxRetries: 5, baseDelayMs: 300, context: 'GET /pool/pool.status', log: cycleLog });
     
     // Split the response into lines and parse each JSON object
     const jsonLines = text.trim().split('\n').map(line => JSON.parse(line));
@@ -576,22 +770,25 @@ export async function collectPoolStats() {
     const hashrateData = jsonLines[1] as HashrateData;
     
     // Insert data into the database
-    const db = getDb();
-    const stmt = db.prepare(`
+    const db = await getDb();
+    const timestamp = Math.floor(Date.now() / 1000);
+
+    await assertLeaseHeld(db);
+    await db.run(`
       INSERT INTO pool_stats (
         timestamp, runtime, users, workers, idle, disconnected,
         hashrate1m, hashrate5m, hashrate15m, hashrate1hr, 
-        hashrate6hr, hashrate1d, hashrate7d
+        hashrate6hr, hashrate1d, hashrate7d,
+        hashrate1m_hs, hashrate5m_hs, hashrate15m_hs, hashrate1hr_hs,
+        hashrate6hr_hs, hashrate1d_hs, hashrate7d_hs
       ) VALUES (
         ?, ?, ?, ?, ?, ?,
         ?, ?, ?, ?,
+        ?, ?, ?,
+        ?, ?, ?, ?,
         ?, ?, ?
       )
-    `);
-    
-    const timestamp = Math.floor(Date.now() / 1000);
-    
-    stmt.run(
+    `, [
       timestamp,
       statsData.runtime,
       statsData.Users,
@@ -604,68 +801,41 @@ export async function collectPoolStats() {
       hashrateData.hashrate1hr,
       hashrateData.hashrate6hr,
       hashrateData.hashrate1d,
-      hashrateData.hashrate7d
-    );
+      hashrateData.hashrate7d,
+      parseHashrate(hashrateData.hashrate1m),
+      parseHashrate(hashrateData.hashrate5m),
+      parseHashrate(hashrateData.hashrate15m),
+      parseHashrate(hashrateData.hashrate1hr),
+      parseHashrate(hashrateData.hashrate6hr),
+      parseHashrate(hashrateData.hashrate1d),
+      parseHashrate(hashrateData.hashrate7d),
+    ]);
     
-    console.log(`Pool stats collected and stored at ${new Date().toISOString()}`);
+    cycleLog.info('Pool stats collected', { timestamp, durationMs: Date.now() - startedAtMs });
+
+    await updatePoolStatsRollups();
+    recordCollectorCycle('pool_stats', startedAtMs, 'success');
     
   } catch (error) {
-    console.error("Error collecting pool stats:", error);
-  } finally {
-    isCollectorRunning = false;
+    cycleLog.error('Error collecting pool stats', { error, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('pool_stats', startedAtMs, 'error');
+    throw error;
   }
 }
 
-/**
- * Start the stats collection cron jobs
- */
-export function startPoolStatsCollector() {
-  // Run pool stats collection every minute
-  const poolJob = cron.schedule('* * * * *', async () => {
-    await collectPoolStats();
-  });
-
-  // Run user stats collection every 1 minute
-  const userJob = cron.schedule('* * * * *', async () => {
-    await collectAllUserStats();
-  });
-
-  // Run account data sync every 10 minutes (for total_blocks loyalty ranking)
-  const accountJob = cron.schedule('*/10 * * * *', async () => {
-    await syncAccountTotalBlocks();
-  });
-  
-  console.log('Stats collectors started');
-  
-  // Run initial collections immediately
-  collectPoolStats();
-  collectAllUserStats();
-  syncAccountTotalBlocks();
-  
-  return {
-    poolJob,
-    userJob,
-    accountJob
-  };
-}
-
 /**
  * Sync total_blocks from para server accounts to local SQLite
  * Used for loyalty ranking based on blocks mined
  */
-export async function syncAccountTotalBlocks() {
-  if (isAccountJobRunning) {
-    console.warn('⚠️  Account job already running, skipping this cycle');
-    return;
-  }
-
+export async function syncAccountTotalBlocks(signal?: AbortSignal) {
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'accounts' });
   try {
-    isAccountJobRunning = true;
-    const db = getDb();
+    const db = await getDb();
     const apiUrl = process.env.API_URL;
 
     if (!apiUrl) {
-      console.error('Failed to sync account data: No API_URL defined in env');
+      cycleLog.error('Failed to sync account data: No API_URL defined in env');
       return;
     }
 
@@ -675,22 +845,28 @@ export async function syncAccountTotalBlocks() {
     }
 
     // Get all active users
-    const users = db.prepare('SELECT id, address FROM monitored_users WHERE is_active = 1').all() as Array<{ id: number; address: string }>;
+    const users = await db.all<{ id: number; address: string }>('SELECT id, address FROM monitored_users WHERE is_active = 1');
 
     if (users.length === 0) {
-      console.log('No active users to sync total_blocks for');
+      cycleLog.info('No active users to sync total_blocks for');
       return;
     }
 
     let successCount = 0;
     let errorCount = 0;
-    const updateStmt = db.prepare('UPDATE monitored_users SET total_blocks = ?, is_public = ? WHERE id = ?');
-    const updateBatch = db.transaction((updates: Array<{ id: number; total_blocks: number; is_public: number }>) => {
-      for (const u of updates) updateStmt.run(u.total_blocks, u.is_public, u.id);
+    const updateBatch = (updates: Array<{ id: number; total_blocks: number; is_public: number }>) => db.transaction(async () => {
+      await assertLeaseHeld(db);
+      for (const u of updates) {
+        await db.run('UPDATE monitored_users SET total_blocks = ?, is_public = ? WHERE id = ?', [u.total_blocks, u.is_public, u.id]);
+      }
     });
 
     // Process in batches to avoid overwhelming the API
     for (let i = 0; i < users.length; i += CONFIG.BATCH_SIZE) {
+      signal?.throwIfAborted();
+      if (isCircuitOpen('api')) {
+        throw new Error(`API circuit open, account sync stopped after ${successCount + errorCount}/${users.length} users`);
+      }
       const batch = users.slice(i, i + CONFIG.BATCH_SIZE);
       
       const results = await Promise.allSettled(batch.map(async (user) => {
@@ -712,15 +888,19 @@ export async function syncAccountTotalBlocks() {
             // Sync visibility: upstream is_private -> local is_public (inverted)
             const pub = data.metadata?.is_private ? 0 : 1;
             return { totalBlocks: blocks, isPublic: pub };
-          }, 4, 500, `account total_blocks ${user.address}`);
+          }, { context: 'GET /account/:address', log: cycleLog.child({ address: user.address }), signal });
 
           return { success: true, update: { id: user.id, total_blocks: accountResult.totalBlocks, is_public: accountResult.isPublic } };
         } catch (err) {
-          console.warn(`Failed to fetch total_blocks for ${user.address}:`, err instanceof Error ? err.message : err);
+          if (signal?.aborted) throw err;
+          if (err instanceof CircuitOpenError) return { success: false as const };
+          cycleLog.warn('Failed to fetch total_blocks', { address: user.address, error: err instanceof Error ? err.message : String(err) });
           return { success: false as const };
         }
       }));
 
+      signal?.throwIfAborted();
+
       const updates: Array<{ id: number; total_blocks: number; is_public: number }> = [];
       for (const result of results) {
         if (result.status === 'fulfilled' && result.value.success && 'update' in result.value) {
@@ -729,7 +909,7 @@ export async function syncAccountTotalBlocks() {
       }
 
       if (updates.length > 0) {
-        updateBatch(updates);
+        await updateBatch(updates);
       }
 
       for (const result of results) {
@@ -741,25 +921,25 @@ export async function syncAccountTotalBlocks() {
       }
     }
 
-    console.log(`Synced total_blocks for ${successCount} users (${errorCount} errors) at ${new Date().toISOString()}`);
+    cycleLog.info('Synced account total_blocks', { users: successCount, errors: errorCount, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('accounts', startedAtMs, 'success');
 
   } catch (error) {
-    console.error('Error syncing account total_blocks:', error);
-  } finally {
-    isAccountJobRunning = false;
+    cycleLog.error('Error syncing account total_blocks', { error });
+    recordCollectorCycle('accounts', startedAtMs, 'error');
+    throw error;
   }
 }
 
 async function purgeInChunks(
-  db: ReturnType<typeof getDb>,
+  db: Db,
   sql: string,
   cutoffTimestamp: number
 ): Promise<number> {
-  const stmt = db.prepare(sql);
   let total = 0;
 
   for (;;) {
-    const result = stmt.run(cutoffTimestamp, CONFIG.PURGE_CHUNK_SIZE);
+    const result = await db.run(sql, [cutoffTimestamp, CONFIG.PURGE_CHUNK_SIZE]);
     total += result.changes;
     if (result.changes < CONFIG.PURGE_CHUNK_SIZE) {
       return total;
@@ -770,17 +950,93 @@ async function purgeInChunks(
 
 export async function purgeOldData() {
   try {
-    const db = getDb();
+    const db = await getDb();
     const now = Math.floor(Date.now() / 1000);
-    const poolCutoff = now - CONFIG.POOL_STATS_RETENTION_DAYS * 24 * 60 * 60;
     const userCutoff = now - CONFIG.USER_STATS_RETENTION_DAYS * 24 * 60 * 60;
 
-    const poolPurged = await purgeInChunks(db, 'DELETE FROM pool_stats WHERE timestamp < ? LIMIT ?', poolCutoff);
-    console.log(`Purged ${poolPurged} pool stats records older than ${CONFIG.POOL_STATS_RETENTION_DAYS} days`);
+    // Raw rows go only once the pool_stats job has rolled them up
+    const poolCutoff = Math.min(
+      now - CONFIG.POOL_STATS_RETENTION_DAYS * 24 * 60 * 60,
+      await getRolledUpBefore(db)
+    );
+
+    const poolPurged = await purgeInChunks(
+      db,
+      'DELETE FROM pool_stats WHERE id IN (SELECT id FROM pool_stats WHERE timestamp < ? LIMIT ?)',
+      poolCutoff
+    );
+    log.info('Purged old pool stats', { rows: poolPurged, retentionDays: CONFIG.POOL_STATS_RETENTION_DAYS });
+    await purgeOldRollups();
+
+    const userPurged = await purgeInChunks(
+      db,
+      'DELETE FROM user_stats_history WHERE id IN (SELECT id FROM user_stats_history WHERE created_at < ? LIMIT ?)',
+      userCutoff
+    );
+    log.info('Purged old user stats', { rows: userPurged, retentionDays: CONFIG.USER_STATS_RETENTION_DAYS });
+
+    const workerPurged = await purgeInChunks(
+      db,
+      'DELETE FROM worker_stats_history WHERE id IN (SELECT id FROM worker_stats_history WHERE created_at < ? LIMIT ?)',
+      userCutoff
+    );
+    log.info('Purged old worker stats', { rows: workerPurged, retentionDays: CONFIG.USER_STATS_RETENTION_DAYS });
+
+    const eventCutoff = now - CONFIG.WORKER_EVENT_RETENTION_DAYS * 24 * 60 * 60;
+    const eventsPurged = await purgeInChunks(
+      db,
+      'DELETE FROM worker_events WHERE id IN (SELECT id FROM worker_events WHERE created_at < ? LIMIT ?)',
+      eventCutoff
+    );
+    // Workers gone for that long are forgotten; if one returns it starts over as new
+    const { changes: statesPurged } = await db.run('DELETE FROM worker_state WHERE updated_at < ?', [eventCutoff]);
+    log.info('Purged old worker events', { events: eventsPurged, states: statesPurged, retentionDays: CONFIG.WORKER_EVENT_RETENTION_DAYS });
+
+    const cycleCutoff = now - CONFIG.COLLECTION_CYCLE_RETENTION_DAYS * 24 * 60 * 60;
+    const { changes: cyclesPurged } = await db.run('DELETE FROM user_collection_cycles WHERE started_at < ?', [cycleCutoff]);
+    log.info('Purged old user collection cycle reports', { rows: cyclesPurged, retentionDays: CONFIG.COLLECTION_CYCLE_RETENTION_DAYS });
 
-    const userPurged = await purgeInChunks(db, 'DELETE FROM user_stats_history WHERE created_at < ? LIMIT ?', userCutoff);
-    console.log(`Purged ${userPurged} user stats records older than ${CONFIG.USER_STATS_RETENTION_DAYS} days`);
+    await purgeOldNotifications();
   } catch (error) {
-    console.error("Error purging old stats:", error);
+    log.error('Error purging old stats', { error });
+    throw error;
   }
 }
+
+/**
+ * Jobs for the supervisor: pool and user stats every minute, account totals
+ * every 10 minutes and the daily purge at midnight
+ */
+export const poolStatsJobs: JobDefinition[] = [
+  {
+    name: 'pool_stats',
+    schedule: '* * * * *',
+    timeoutMs: 2 * 60_000,
+    staleAfterSeconds: 10 * 60,
+    runOnStart: true,
+    run: collectPoolStats,
+  },
+  {
+    name: 'user_stats',
+    schedule: '* * * * *',
+    timeoutMs: 10 * 60_000,
+    staleAfterSeconds: 20 * 60,
+    runOnStart: true,
+    run: collectAllUserStats,
+  },
+  {
+    name: 'accounts',
+    schedule: '*/10 * * * *',
+    timeoutMs: 20 * 60_000,
+    staleAfterSeconds: 60 * 60,
+    runOnStart: true,
+    run: syncAccountTotalBlocks,
+  },
+  {
+    name: 'purge',
+    schedule: '0 0 * * *',
+    timeoutMs: 60 * 60_000,
+    staleAfterSeconds: 26 * 60 * 60,
+    run: purgeOldData,
+  },
+];
@@ -0,0 +1,274 @@
+import { getDb, POOL_STATS_ROLLUP_METRICS, POOL_STATS_ROLLUP_RESOLUTIONS, type Db } from './db';
+import { parsePositiveInt } from './env';
+import { createLogger } from './logger';
+import { assertLeaseHeld } from './leader-lease';
+
+/**
+ * Pool stats rollups
+ *
+ * Downsamples the minute-level `pool_stats` rows into 5m, 1h and 1d tables so
+ * long-range charts come from a single query and history outlives the raw
+ * rows. Each resolution is built from the one below it (5m from raw rows),
+ * and each run recomputes from the newest existing bucket, which may have
+ * been written while still filling.
+ */
+
+type RollupMetric = typeof POOL_STATS_ROLLUP_METRICS[number];
+export type PoolStatsResolution = typeof POOL_STATS_ROLLUP_RESOLUTIONS[number];
+
+export const RESOLUTION_SECONDS: Record<PoolStatsResolution, number> = {
+  '5m': 5 * 60,
+  '1h': 60 * 60,
+  '1d': 24 * 60 * 60,
+};
+
+const SOURCE_RESOLUTION: Record<PoolStatsResolution, PoolStatsResolution | null> = {
+  '5m': null,
+  '1h': '5m',
+  '1d': '1h',
+};
+
+const CONFIG = {
+  // Daily rollups are kept forever
+  RETENTION_DAYS: {
+    '5m': parsePositiveInt(process.env.POOL_STATS_5M_RETENTION_DAYS, 180),
+    '1h': parsePositiveInt(process.env.POOL_STATS_1H_RETENTION_DAYS, 5 * 365),
+  } as Partial<Record<PoolStatsResolution, number>>,
+};
+
+const log = createLogger({ module: 'pool-stats-rollups' });
+
+// Over the samples that had the metric; min, max and last are null when none did
+interface MetricStats {
+  count: number;
+  min: number | null;
+  max: number | null;
+  sum: number;
+  last: number | null;
+}
+
+// A raw row or a lower-resolution bucket, in the same shape so both merge alike
+interface Sample {
+  timestamp: number;
+  count: number;
+  metrics: Record<RollupMetric, MetricStats>;
+  idle: number;
+  disconnected: number;
+}
+
+// Raw pool_stats row with the numeric hashrate columns aliased to the metric names
+type RawPoolStatsRow = {
+  timestamp: number;
+  idle: number;
+  disconnected: number;
+} & Record<RollupMetric, number | null>;
+
+export type PoolStatsRollupRow = {
+  bucket_start: number;
+  sample_count: number;
+  last_timestamp: number;
+  idle_last: number;
+  disconnected_last: number;
+  updated_at: number;
+} & Record<`${RollupMetric}_count`, number>
+  & Record<`${RollupMetric}_${'min' | 'max' | 'avg' | 'last'}`, number | null>;
+
+const EMPTY_STATS: MetricStats = { count: 0, min: null, max: null, sum: 0, last: null };
+
+function fromRawRow(row: RawPoolStatsRow): Sample {
+  const metrics = {} as Record<RollupMetric, MetricStats>;
+  for (const metric of POOL_STATS_ROLLUP_METRICS) {
+    // NULL is a hashrate that couldn't be parsed, not a zero
+    const value = row[metric];
+    metrics[metric] = value === null
+      ? { ...EMPTY_STATS }
+      : { count: 1, min: value, max: value, sum: value, last: value };
+  }
+  return { timestamp: row.timestamp, count: 1, metrics, idle: row.idle, disconnected: row.disconnected };
+}
+
+function fromRollupRow(row: PoolStatsRollupRow): Sample {
+  const metrics = {} as Record<RollupMetric, MetricStats>;
+  for (const metric of POOL_STATS_ROLLUP_METRICS) {
+    const count = row[`${metric}_count`];
+    metrics[metric] = {
+      count,
+      min: row[`${metric}_min`],
+      max: row[`${metric}_max`],
+      sum: (row[`${metric}_avg`] ?? 0) * count,
+      last: row[`${metric}_last`],
+    };
+  }
+  return {
+    timestamp: row.last_timestamp,
+    count: row.sample_count,
+    metrics,
+    idle: row.idle_last,
+    disconnected: row.disconnected_last,
+  };
+}
+
+async function loadSamples(db: Db, source: PoolStatsResolution | null, since: number): Promise<Sample[]> {
+  if (source === null) {
+    const rows = await db.all<RawPoolStatsRow>(`
+      SELECT timestamp, users, workers, idle, disconnected,
+        hashrate1m_hs AS hashrate1m, hashrate5m_hs AS hashrate5m, hashrate15m_hs AS hashrate15m,
+        hashrate1hr_hs AS hashrate1hr, hashrate6hr_hs AS hashrate6hr, hashrate1d_hs AS hashrate1d,
+        hashrate7d_hs AS hashrate7d
+      FROM pool_stats
+      WHERE timestamp >= ?
+      ORDER BY timestamp ASC
+    `, [since]);
+    return rows.map(fromRawRow);
+  }
+
+  const rows = await db.all<PoolStatsRollupRow>(`
+    SELECT * FROM pool_stats_${source}
+    WHERE bucket_start >= ?
+    ORDER BY bucket_start ASC
+  `, [since]);
+  return rows.map(fromRollupRow);
+}
+
+/**
+ * Merge time-ordered samples into buckets of bucketSeconds, keyed by the
+ * bucket's start
+ */
+function mergeSamples(samples: Sample[], bucketSeconds: number): Map<number, Sample> {
+  const buckets = new Map<number, Sample>();
+
+  for (const sample of samples) {
+    const start = Math.floor(sample.timestamp / bucketSeconds) * bucketSeconds;
+    const bucket = buckets.get(start);
+
+    if (!bucket) {
+      buckets.set(start, {
+        ...sample,
+        metrics: Object.fromEntries(
+          POOL_STATS_ROLLUP_METRICS.map(metric => [metric, { ...sample.metrics[metric] }])
+        ) as Record<RollupMetric, MetricStats>,
+      });
+      continue;
+    }
+
+    bucket.timestamp = sample.timestamp;
+    bucket.count += sample.count;
+    bucket.idle = sample.idle;
+    bucket.disconnected = sample.disconnected;
+    for (const metric of POOL_STATS_ROLLUP_METRICS) {
+      const stats = bucket.metrics[metric];
+      const next = sample.metrics[metric];
+      if (next.count === 0) continue;
+      if (stats.count === 0) {
+        bucket.metrics[metric] = { ...next };
+        continue;
+      }
+      stats.count += next.count;
+      stats.min = Math.min(stats.min!, next.min!);
+      stats.max = Math.max(stats.max!, next.max!);
+      stats.sum += next.sum;
+      stats.last = next.last;
+    }
+  }
+
+  return buckets;
+}
+
+async function writeBuckets(db: Db, resolution: PoolStatsResolution, buckets: Map<number, Sample>): Promise<void> {
+  const metricColumns = POOL_STATS_ROLLUP_METRICS.flatMap(metric =>
+    ['count', 'min', 'max', 'avg', 'last'].map(stat => `${metric}_${stat}`)
+  );
+  const columns = [
+    'bucket_start', 'sample_count', 'last_timestamp', ...metricColumns,
+    'idle_last', 'disconnected_last', 'updated_at',
+  ];
+  const sql = `
+    INSERT INTO pool_stats_${resolution} (${columns.join(', ')})
+    VALUES (${columns.map(() => '?').join(', ')})
+    ON CONFLICT(bucket_start) DO UPDATE SET
+      ${columns.slice(1).map(column => `${column} = excluded.${column}`).join(',\n      ')}
+  `;
+  const now = Math.floor(Date.now() / 1000);
+
+  await db.transaction(async () => {
+    await assertLeaseHeld(db);
+    for (const [start, bucket] of buckets) {
+      await db.run(sql, [
+        start,
+        bucket.count,
+        bucket.timestamp,
+        ...POOL_STATS_ROLLUP_METRICS.flatMap(metric => {
+          const stats = bucket.metrics[metric];
+          return [stats.count, stats.min, stats.max, stats.count > 0 ? stats.sum / stats.count : null, stats.last];
+        }),
+        bucket.idle,
+        bucket.disconnected,
+        now,
+      ]);
+    }
+  });
+}
+
+/**
+ * Bring every rollup table up to date with the rows below it. Runs after
+ * each pool_stats collection, which the supervisor never overlaps.
+ * Returns the number of buckets written per resolution.
+ */
+export async function updatePoolStatsRollups(): Promise<Partial<Record<PoolStatsResolution, number>>> {
+  const written: Partial<Record<PoolStatsResolution, number>> = {};
+
+  try {
+    const db = await getDb();
+
+    for (const resolution of POOL_STATS_ROLLUP_RESOLUTIONS) {
+      const latest = await db.get<{ bucket_start: number | null }>(
+        `SELECT MAX(bucket_start) AS bucket_start FROM pool_stats_${resolution}`
+      );
+
+      const samples = await loadSamples(db, SOURCE_RESOLUTION[resolution], latest?.bucket_start ?? 0);
+      if (samples.length === 0) continue;
+
+      const buckets = mergeSamples(samples, RESOLUTION_SECONDS[resolution]);
+      await writeBuckets(db, resolution, buckets);
+      written[resolution] = buckets.size;
+    }
+  } catch (error) {
+    log.error('Error updating pool stats rollups', { error });
+  }
+
+  return written;
+}
+
+/**
+ * Raw rows before this timestamp are in the 5m rollups for good: everything
+ * below the newest bucket, which the next run recomputes
+ */
+export async function getRolledUpBefore(db: Db): Promise<number> {
+  const latest = await db.get<{ bucket_start: number | null }>(
+    'SELECT MAX(bucket_start) AS bucket_start FROM pool_stats_5m'
+  );
+  return latest?.bucket_start ?? 0;
+}
+
+/**
+ * Drop rollup buckets past their resolution's retention
+ */
+export async function purgeOldRollups(): Promise<void> {
+  try {
+    const db = await getDb();
+    const now = Math.floor(Date.now() / 1000);
+
+    for (const resolution of POOL_STATS_ROLLUP_RESOLUTIONS) {
+      const retentionDays = CONFIG.RETENTION_DAYS[resolution];
+      if (retentionDays === undefined) continue;
+
+      const result = await db.run(
+        `DELETE FROM pool_stats_${resolution} WHERE bucket_start < ?`,
+        [now - retentionDays * 24 * 60 * 60]
+      );
+      log.info('Purged old pool stats rollups', { resolution, rows: result.changes, retentionDays });
+    }
+  } catch (error) {
+    log.error('Error purging pool stats rollups', { error });
+  }
+}
@@ -1,41 +1,45 @@
 import { getDb } from './db';
 import { parsePositiveInt } from './env';
+import { fetch } from './http-client';
+import { isCircuitOpen } from './circuit-breaker';
+import { createLogger } from './logger';
 import type { OrderSummary } from '../app/api/router/types';
 
 const BATCH_SIZE = parsePositiveInt(process.env.REFINERY_BADGE_BATCH_SIZE, 50);
 
+const log = createLogger({ module: 'refinery-badges' });
+
 export async function syncRefineryBadges() {
   const routerBase = process.env.ROUTER_API_URL;
   if (!routerBase) {
-    console.log('⏭️  Refinery badge sync skipped (no ROUTER_API_URL)');
+    log.info('Refinery badge sync skipped, ROUTER_API_URL not set');
     return;
   }
 
-  const db = getDb();
-  const users = db.prepare(
+  const db = await getDb();
+  const users = await db.all<{ address: string }>(
     'SELECT address FROM monitored_users WHERE has_refinery_badge = 0'
-  ).all() as { address: string }[];
+  );
 
   if (users.length === 0) {
-    console.log('⏭️  Refinery badge sync skipped (no unlatched users)');
+    log.info('Refinery badge sync skipped, no unlatched users');
     return;
   }
 
-  console.log(`🏭 Refinery badge sync starting for ${users.length} users`);
+  log.info('Refinery badge sync starting', { users: users.length });
 
   const headers: Record<string, string> = {};
   if (process.env.ROUTER_API_TOKEN) {
     headers.Authorization = `Bearer ${process.env.ROUTER_API_TOKEN}`;
   }
 
-  const latch = db.prepare(
-    'UPDATE monitored_users SET has_refinery_badge = 1 WHERE address = ?'
-  );
-
   let checked = 0;
   let latched = 0;
 
   for (let i = 0; i < users.length; i += BATCH_SIZE) {
+    if (isCircuitOpen('router')) {
+      throw new Error(`Router circuit open, refinery badge sync stopped after ${checked}/${users.length} users`);
+    }
     const batch = users.slice(i, i + BATCH_SIZE);
 
     const results = await Promise.allSettled(
@@ -55,17 +59,17 @@ export async function syncRefineryBadges() {
     for (let j = 0; j < results.length; j++) {
       const result = results[j];
       if (result.status === 'fulfilled' && result.value) {
-        latch.run(batch[j].address);
+        await db.run('UPDATE monitored_users SET has_refinery_badge = 1 WHERE address = ?', [batch[j].address]);
         latched++;
       }
     }
 
     checked += batch.length;
-    console.log(`🏭 Refinery badge sync: ${checked}/${users.length} checked, ${latched} newly latched`);
+    log.info('Refinery badge sync progress', { checked, users: users.length, latched });
   }
 
-  const total = db.prepare(
+  const total = await db.get<{ count: number }>(
     'SELECT COUNT(*) as count FROM monitored_users WHERE has_refinery_badge = 1'
-  ).get() as { count: number };
-  console.log(`🏭 Refinery badge sync complete: ${total.count} total badge holders`);
+  );
+  log.info('Refinery badge sync complete', { latched, badgeHolders: total?.count ?? 0 });
 }
@@ -0,0 +1,97 @@
+import { CircuitOpenError, HttpError, isRetryableError } from './http-errors';
+import { createLogger, type Logger } from './logger';
+
+/**
+ * Retry policy for upstream requests
+ *
+ * Retries retryable errors (network errors, timeouts, 5xx; see
+ * isRetryableError) with linear backoff and jitter: attempt n waits
+ * baseDelayMs * n, randomised between 50% and 150%. Everything else is thrown
+ * at once, including RateLimitError, which callers turn into a pause, and
+ * CircuitOpenError once the upstream's circuit breaker has opened, so a
+ * fan-out stops retrying as soon as the upstream is known to be down. An
+ * aborted `signal` ends it with the signal's reason, even mid-backoff.
+ *
+ * Only depends on lib/http-errors and lib/logger, so app/utils/api.ts uses it
+ * in the browser too.
+ */
+
+export interface RetryOptions {
+  /** Retries after the first attempt (default: 4) */
+  maxRetries?: number;
+  /** Backoff step in ms (default: 500) */
+  baseDelayMs?: number;
+  /** Names the operation in log entries */
+  context?: string;
+  /** Logger to report retries on, so entries carry the caller's context */
+  log?: Logger;
+  /** Stops retrying, and waiting to retry, once aborted */
+  signal?: AbortSignal;
+}
+
+const DEFAULTS = {
+  MAX_RETRIES: 4,
+  BASE_DELAY_MS: 500,
+};
+
+const defaultLog = createLogger({ module: 'retry' });
+
+function delay(ms: number, signal?: AbortSignal): Promise<void> {
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort);
+      resolve();
+    }, ms);
+    const onAbort = () => {
+      clearTimeout(timer);
+      reject(signal?.reason);
+    };
+    signal?.addEventListener('abort', onAbort, { once: true });
+  });
+}
+
+/** Delay before retry number `attempt` (1-based) */
+export function backoffDelay(attempt: number, baseDelayMs: number, random = Math.random()): number {
+  return Math.floor(baseDelayMs * attempt * (random + 0.5));
+}
+
+/**
+ * Whether a non-retryable error is routine enough not to log: the caller
+ * reports 404s and 429s itself, and an open circuit was logged when it opened
+ */
+function isQuietError(error: unknown): boolean {
+  if (error instanceof CircuitOpenError) return true;
+  return error instanceof HttpError && (error.status === 404 || error.status === 429);
+}
+
+export async function withRetry<T>(operation: () => Promise<T>, options: RetryOptions = {}): Promise<T> {
+  const maxRetries = options.maxRetries ?? DEFAULTS.MAX_RETRIES;
+  const baseDelayMs = options.baseDelayMs ?? DEFAULTS.BASE_DELAY_MS;
+  const log = options.log ?? defaultLog;
+
+  for (let attempt = 0; ; attempt++) {
+    options.signal?.throwIfAborted();
+    try {
+      return await operation();
+    } catch (error) {
+      if (options.signal?.aborted) throw options.signal.reason;
+      if (!isRetryableError(error)) {
+        if (!isQuietError(error)) {
+          log.warn('Non-retryable error', { operation: options.context, error });
+        }
+        throw error;
+      }
+      if (attempt >= maxRetries) throw error;
+
+      const delayMs = backoffDelay(attempt + 1, baseDelayMs);
+      log.warn('Retrying after error', {
+        operation: options.context,
+        attempt: attempt + 1,
+        maxRetries,
+        delayMs,
+        error: error instanceof Error ? error.message : String(error),
+      });
+      await delay(delayMs, options.signal);
+    }
+  }
+}
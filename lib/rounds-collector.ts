@@ -1,6 +1,12 @@
-import cron from 'node-cron';
 import { getDb } from './db';
-import { fetch, HttpError, isRetryableError } from './http-client';
+import { fetch, HttpError } from './http-client';
+import { withRetry } from './retry';
+import { isCircuitOpen } from './circuit-breaker';
+import { createLogger, newCycleId } from './logger';
+import { recordCollectorCycle } from './metrics';
+import { triggerJob, type JobDefinition } from './job-supervisor';
+import { assertLeaseHeld } from './leader-lease';
+import { syncBlocksFound } from './blocks-found-collector';
 
 // Types for API responses
 interface Round {
@@ -20,8 +26,6 @@ interface RoundParticipant {
 
 // Configuration
 const CONFIG = {
-  MAX_RETRIES: 4,
-  RETRY_BASE_DELAY: 500,
   CURRENT_ROUND_POLL_INTERVAL: '*/10 * * * *', // every 10 minutes
   CURRENT_ROUND_TIMEOUT: 30_000,
   PARTICIPANT_FETCH_TIMEOUT: 300_000, // 5 minutes
@@ -29,51 +33,7 @@ const CONFIG = {
   ROUNDS_SYNC_INTERVAL: '*/10 * * * *', // every 10 minutes
 } as const;
 
-let isCollectingCurrent = false;
-let isSyncingRounds = false;
-let isFetchingPendingParticipants = false;
-let syncJob: ReturnType<typeof cron.schedule> | null = null;
-let currentRoundJob: ReturnType<typeof cron.schedule> | null = null;
-
-const delay = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
-
-/**
- * Retry helper with backoff and jitter
- */
-async function withRetry<T>(
-  operation: () => Promise<T>,
-  context?: string
-): Promise<T> {
-  let lastError;
-
-  for (let attempt = 0; attempt <= CONFIG.MAX_RETRIES; attempt++) {
-    try {
-      return await operation();
-    } catch (error) {
-      lastError = error;
-
-      if (!isRetryableError(error)) {
-        const contextMsg = context ? ` [${context}]` : '';
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        console.log(`Non-retryable error${contextMsg} - ${errorMsg}`);
-        throw error;
-      }
-
-      if (attempt < CONFIG.MAX_RETRIES) {
-        const backoffDelay = CONFIG.RETRY_BASE_DELAY + (attempt * CONFIG.RETRY_BASE_DELAY);
-        const jitter = Math.random() + 0.5;
-        const delayWithJitter = Math.floor(backoffDelay * jitter);
-        const contextMsg = context ? ` [${context}]` : '';
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        console.log(`Retry attempt ${attempt + 1}/${CONFIG.MAX_RETRIES} after ${delayWithJitter}ms${contextMsg} - Error: ${errorMsg}`);
-        await delay(delayWithJitter);
-        continue;
-      }
-      throw error;
-    }
-  }
-  throw lastError;
-}
+const log = createLogger({ collector: 'rounds' });
 
 function getApiHeaders(): { url: string; headers: Record<string, string> } {
   const apiUrl = process.env.API_URL;
@@ -102,7 +62,7 @@ async function fetchRoundsList(): Promise<Round[]> {
     }
 
     return await response.json() as Round[];
-  }, 'GET /rounds');
+  }, { context: 'GET /rounds', log });
 }
 
 /**
@@ -120,13 +80,13 @@ async function fetchCurrentRound(): Promise<RoundParticipant[]> {
     }
 
     return await response.json() as RoundParticipant[];
-  }, 'GET /rounds/current');
+  }, { context: 'GET /rounds/current', log });
 }
 
 /**
  * Fetch block participants (users who submitted shares at the exact block height)
  */
-async function fetchBlockParticipantsFromApi(blockHeight: number): Promise<string[]> {
+async function fetchBlockParticipantsFromApi(blockHeight: number, signal?: AbortSignal): Promise<string[]> {
   const { url: apiUrl, headers } = getApiHeaders();
 
   return withRetry(async () => {
@@ -138,13 +98,13 @@ async function fetchBlockParticipantsFromApi(blockHeight: number): Promise<strin
     }
 
     return await response.json() as string[];
-  }, `GET /participants/${blockHeight}`);
+  }, { context: `GET /participants/${blockHeight}`, log, signal });
 }
 
 /**
  * Fetch participants for a completed round from the pool API
  */
-async function fetchRoundParticipantsFromApi(blockHeight: number): Promise<RoundParticipant[]> {
+async function fetchRoundParticipantsFromApi(blockHeight: number, signal?: AbortSignal): Promise<RoundParticipant[]> {
   const { url: apiUrl, headers } = getApiHeaders();
 
   return withRetry(async () => {
@@ -156,7 +116,7 @@ async function fetchRoundParticipantsFromApi(blockHeight: number): Promise<Round
     }
 
     return await response.json() as RoundParticipant[];
-  }, `GET /rounds/${blockHeight}`);
+  }, { context: `GET /rounds/${blockHeight}`, log, signal });
 }
 
 /**
@@ -164,58 +124,56 @@ async function fetchRoundParticipantsFromApi(blockHeight: number): Promise<Round
  * Returns the number of new rounds found.
  */
 export async function syncRounds(): Promise<number> {
-  if (isSyncingRounds) {
-    console.warn('Rounds sync already running, skipping');
-    return 0;
-  }
-
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'sync' });
   try {
-    isSyncingRounds = true;
     const rounds = await fetchRoundsList();
-    const db = getDb();
+    const db = await getDb();
     const now = Math.floor(Date.now() / 1000);
 
-    const insertNew = db.prepare(`
-      INSERT INTO rounds (block_height, block_hash, coinbase_value, winner_diff, winner_username, created_at)
-      VALUES (?, ?, ?, ?, ?, ?)
-      ON CONFLICT(block_height) DO UPDATE SET
-        block_hash = excluded.block_hash,
-        coinbase_value = excluded.coinbase_value,
-        winner_diff = excluded.winner_diff,
-        winner_username = excluded.winner_username
-    `);
-
     const existingHeights = new Set(
-      (db.prepare('SELECT block_height FROM rounds').all() as { block_height: number }[])
+      (await db.all<{ block_height: number }>('SELECT block_height FROM rounds'))
         .map(r => r.block_height)
     );
 
     let newRounds = 0;
 
-    db.transaction(() => {
+    await db.transaction(async () => {
+      await assertLeaseHeld(db);
       for (const round of rounds) {
         if (!existingHeights.has(round.blockheight)) newRounds++;
-        insertNew.run(
+        await db.run(`
+          INSERT INTO rounds (block_height, block_hash, coinbase_value, winner_diff, winner_username, created_at)
+          VALUES (?, ?, ?, ?, ?, ?)
+          ON CONFLICT(block_height) DO UPDATE SET
+            block_hash = excluded.block_hash,
+            coinbase_value = excluded.coinbase_value,
+            winner_diff = excluded.winner_diff,
+            winner_username = excluded.winner_username
+        `, [
           round.blockheight,
           round.blockhash,
           round.coinbasevalue ?? null,
           round.diff ?? null,
           round.username ?? null,
-          now
-        );
+          now,
+        ]);
       }
-    })();
+    });
 
     if (newRounds > 0) {
-      console.log(`🔄 Synced ${rounds.length} rounds (${newRounds} new)`);
+      cycleLog.info('Synced rounds', { rounds: rounds.length, newRounds, durationMs: Date.now() - startedAtMs });
     }
 
+    // Correlate new rounds with the stratum templates around them
+    await syncBlocksFound();
+
+    recordCollectorCycle('rounds', startedAtMs, 'success');
     return newRounds;
   } catch (error) {
-    console.error('Error syncing rounds:', error);
-    return 0;
-  } finally {
-    isSyncingRounds = false;
+    cycleLog.error('Error syncing rounds', { error, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('rounds', startedAtMs, 'error');
+    throw error;
   }
 }
 
@@ -224,149 +182,147 @@ export async function syncRounds(): Promise<number> {
  * Replaces all block_height=0 rows with fresh data.
  */
 export async function collectCurrentRound(): Promise<void> {
-  if (isCollectingCurrent) {
-    console.warn('Current round collection already running, skipping');
-    return;
-  }
-
+  const startedAtMs = Date.now();
+  const cycleLog = log.child({ cycle: newCycleId(), job: 'current_round' });
   try {
-    isCollectingCurrent = true;
     const participants = await fetchCurrentRound();
-    const db = getDb();
-
-    db.transaction(() => {
-      db.prepare('DELETE FROM round_participants WHERE block_height = 0').run();
+    const db = await getDb();
 
-      const insert = db.prepare(`
-        INSERT INTO round_participants (block_height, username, top_diff, blocks_participated, total_work)
-        VALUES (0, ?, ?, ?, ?)
-      `);
+    await db.transaction(async () => {
+      await assertLeaseHeld(db);
+      await db.run('DELETE FROM round_participants WHERE block_height = 0');
 
       for (const p of participants) {
-        insert.run(p.username, p.top_diff, p.blocks_participated, p.total_work ?? 0);
+        await db.run(`
+          INSERT INTO round_participants (block_height, username, top_diff, blocks_participated, total_work)
+          VALUES (0, ?, ?, ?, ?)
+        `, [p.username, p.top_diff, p.blocks_participated, p.total_work ?? 0]);
       }
-    })();
+    });
 
-    console.log(`🔄 Current round: ${participants.length} participants`);
+    cycleLog.info('Collected current round', { participants: participants.length, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('current_round', startedAtMs, 'success');
   } catch (error) {
-    console.error('Error collecting current round:', error);
-  } finally {
-    isCollectingCurrent = false;
+    cycleLog.error('Error collecting current round', { error, durationMs: Date.now() - startedAtMs });
+    recordCollectorCycle('current_round', startedAtMs, 'error');
+    throw error;
   }
 }
 
 /**
  * Fetch and cache participants for completed rounds that are pending or errored.
- * Runs async, never blocks cron cycle.
+ * Runs as the round_participants job after each rounds sync.
  */
-export async function fetchPendingRoundParticipants(): Promise<void> {
-  if (isFetchingPendingParticipants) {
-    console.warn('Pending participant fetch already running, skipping');
-    return;
-  }
-
-  isFetchingPendingParticipants = true;
-  try {
-    const db = getDb();
-    const now = Math.floor(Date.now() / 1000);
-
-    const pendingRounds = db.prepare(`
-      SELECT block_height, participant_status, participant_fetched_at
-      FROM rounds
-      WHERE participant_status IN ('pending', 'error', 'fetching')
-      ORDER BY block_height ASC
-    `).all() as { block_height: number; participant_status: string; participant_fetched_at: number | null }[];
-
-    for (const round of pendingRounds) {
-      // Skip if recently attempted (prevent double-fetches on restart)
-      if (round.participant_fetched_at && (now - round.participant_fetched_at) < CONFIG.PARTICIPANT_REFETCH_COOLDOWN / 1000) {
-        continue;
-      }
+export async function fetchPendingRoundParticipants(signal?: AbortSignal): Promise<void> {
+  const db = await getDb();
+  const now = Math.floor(Date.now() / 1000);
+
+  const pendingRounds = await db.all<{ block_height: number; participant_status: string; participant_fetched_at: number | null }>(`
+    SELECT block_height, participant_status, participant_fetched_at
+    FROM rounds
+    WHERE participant_status IN ('pending', 'error', 'fetching')
+    ORDER BY block_height ASC
+  `);
+
+  for (const round of pendingRounds) {
+    signal?.throwIfAborted();
+    // Skip if recently attempted (prevent double-fetches on restart)
+    if (round.participant_fetched_at && (now - round.participant_fetched_at) < CONFIG.PARTICIPANT_REFETCH_COOLDOWN / 1000) {
+      continue;
+    }
+    if (isCircuitOpen('api')) {
+      log.warn('API circuit open, leaving the remaining rounds pending');
+      return;
+    }
 
-      const blockHeight = round.block_height;
+    const blockHeight = round.block_height;
 
-      // Mark as fetching
-      db.prepare('UPDATE rounds SET participant_status = ?, participant_fetched_at = ? WHERE block_height = ?')
-        .run('fetching', now, blockHeight);
+    // Mark as fetching
+    await assertLeaseHeld(db);
+    await db.run('UPDATE rounds SET participant_status = ?, participant_fetched_at = ? WHERE block_height = ?',
+      ['fetching', now, blockHeight]);
 
-      try {
-        const participants = await fetchRoundParticipantsFromApi(blockHeight);
+    try {
+      const participants = await fetchRoundParticipantsFromApi(blockHeight, signal);
 
-        db.transaction(() => {
-          // Clear any existing data for this round
-          db.prepare('DELETE FROM round_participants WHERE block_height = ?').run(blockHeight);
+      await db.transaction(async () => {
+        await assertLeaseHeld(db);
+        // Clear any existing data for this round
+        await db.run('DELETE FROM round_participants WHERE block_height = ?', [blockHeight]);
 
-          const insert = db.prepare(`
+        for (const p of participants) {
+          await db.run(`
             INSERT INTO round_participants (block_height, username, top_diff, blocks_participated, total_work)
             VALUES (?, ?, ?, ?, ?)
-          `);
-
-          for (const p of participants) {
-            insert.run(blockHeight, p.username, p.top_diff, p.blocks_participated, p.total_work ?? 0);
-          }
-
-          db.prepare('UPDATE rounds SET participant_status = ?, error_message = NULL WHERE block_height = ?')
-            .run('complete', blockHeight);
-        })();
-
-        console.log(`✅ Round ${blockHeight}: ${participants.length} participants cached`);
-      } catch (error) {
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        db.prepare('UPDATE rounds SET participant_status = ?, error_message = ? WHERE block_height = ?')
-          .run('error', errorMsg, blockHeight);
-        console.error(`❌ Round ${blockHeight} participant fetch failed: ${errorMsg}`);
-      }
+          `, [blockHeight, p.username, p.top_diff, p.blocks_participated, p.total_work ?? 0]);
+        }
+
+        await db.run('UPDATE rounds SET participant_status = ?, error_message = NULL WHERE block_height = ?',
+          ['complete', blockHeight]);
+      });
+
+      log.info('Cached round participants', { blockHeight, participants: participants.length });
+    } catch (error) {
+      if (signal?.aborted) throw error;
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      await db.run('UPDATE rounds SET participant_status = ?, error_message = ? WHERE block_height = ?',
+        ['error', errorMsg, blockHeight]);
+      log.error('Round participant fetch failed', { blockHeight, error });
     }
+  }
 
-    const blockParticipantNow = Math.floor(Date.now() / 1000);
+  const blockParticipantNow = Math.floor(Date.now() / 1000);
 
-    const pendingBlockParticipants = db.prepare(`
-      SELECT block_height, block_participant_status, block_participant_fetched_at
-      FROM rounds
-      WHERE block_participant_status IN ('pending', 'error', 'fetching')
-        AND participant_status = 'complete'
-      ORDER BY block_height ASC
-    `).all() as { block_height: number; block_participant_status: string; block_participant_fetched_at: number | null }[];
+  const pendingBlockParticipants = await db.all<{ block_height: number; block_participant_status: string; block_participant_fetched_at: number | null }>(`
+    SELECT block_height, block_participant_status, block_participant_fetched_at
+    FROM rounds
+    WHERE block_participant_status IN ('pending', 'error', 'fetching')
+      AND participant_status = 'complete'
+    ORDER BY block_height ASC
+  `);
 
-    for (const round of pendingBlockParticipants) {
-      if (round.block_participant_fetched_at && (blockParticipantNow - round.block_participant_fetched_at) < CONFIG.PARTICIPANT_REFETCH_COOLDOWN / 1000) {
-        continue;
-      }
+  for (const round of pendingBlockParticipants) {
+    signal?.throwIfAborted();
+    if (round.block_participant_fetched_at && (blockParticipantNow - round.block_participant_fetched_at) < CONFIG.PARTICIPANT_REFETCH_COOLDOWN / 1000) {
+      continue;
+    }
+    if (isCircuitOpen('api')) {
+      log.warn('API circuit open, leaving the remaining block participants pending');
+      return;
+    }
 
-      const blockHeight = round.block_height;
+    const blockHeight = round.block_height;
 
-      db.prepare('UPDATE rounds SET block_participant_status = ?, block_participant_fetched_at = ? WHERE block_height = ?')
-        .run('fetching', blockParticipantNow, blockHeight);
+    await assertLeaseHeld(db);
+    await db.run('UPDATE rounds SET block_participant_status = ?, block_participant_fetched_at = ? WHERE block_height = ?',
+      ['fetching', blockParticipantNow, blockHeight]);
 
-      try {
-        const usernames = await fetchBlockParticipantsFromApi(blockHeight);
+    try {
+      const usernames = await fetchBlockParticipantsFromApi(blockHeight, signal);
 
-        db.transaction(() => {
-          db.prepare('DELETE FROM block_participants WHERE block_height = ?').run(blockHeight);
+      await db.transaction(async () => {
+        await assertLeaseHeld(db);
+        await db.run('DELETE FROM block_participants WHERE block_height = ?', [blockHeight]);
 
-          const insert = db.prepare(`
+        for (const username of usernames) {
+          await db.run(`
             INSERT INTO block_participants (block_height, username)
             VALUES (?, ?)
-          `);
-
-          for (const username of usernames) {
-            insert.run(blockHeight, username);
-          }
-
-          db.prepare('UPDATE rounds SET block_participant_status = ?, error_message = NULL WHERE block_height = ?')
-            .run('complete', blockHeight);
-        })();
-
-        console.log(`✅ Block ${blockHeight}: ${usernames.length} block participants cached`);
-      } catch (error) {
-        const errorMsg = error instanceof Error ? error.message : String(error);
-        db.prepare('UPDATE rounds SET block_participant_status = ?, error_message = ? WHERE block_height = ?')
-          .run('error', errorMsg, blockHeight);
-        console.error(`❌ Block ${blockHeight} participant fetch failed: ${errorMsg}`);
-      }
+          `, [blockHeight, username]);
+        }
+
+        await db.run('UPDATE rounds SET block_participant_status = ?, error_message = NULL WHERE block_height = ?',
+          ['complete', blockHeight]);
+      });
+
+      log.info('Cached block participants', { blockHeight, participants: usernames.length });
+    } catch (error) {
+      if (signal?.aborted) throw error;
+      const errorMsg = error instanceof Error ? error.message : String(error);
+      await db.run('UPDATE rounds SET block_participant_status = ?, error_message = ? WHERE block_height = ?',
+        ['error', errorMsg, blockHeight]);
+      log.error('Block participant fetch failed', { blockHeight, error });
     }
-  } finally {
-    isFetchingPendingParticipants = false;
   }
 }
 
@@ -374,74 +330,42 @@ export async function fetchPendingRoundParticipants(): Promise<void> {
  * Trigger a re-sync of rounds (e.g. from stratum clean_jobs)
  */
 export function triggerRoundsSync(): void {
-  syncRounds()
-    .then(newRounds => {
-      if (newRounds > 0) {
-        // New round detected — fetch participants in background
-        fetchPendingRoundParticipants().catch(err =>
-          console.error('Error fetching pending round participants:', err)
-        );
-        // Refresh the current round so work-since-last-block resets promptly
-        collectCurrentRound().catch(err =>
-          console.error('Error collecting current round after new round:', err)
-        );
-      }
-    })
-    .catch(err => console.error('Error in triggered rounds sync:', err));
+  triggerJob('rounds');
 }
 
 /**
- * Start the rounds collector:
- * - Sync round list on startup
- * - Collect current round data on startup
- * - Fetch pending round participants on startup
- * - Schedule periodic polling
+ * Jobs for the supervisor: round list sync, current round polling, and the
+ * participant fetch for pending or errored rounds that every sync kicks off.
+ * The stratum collector triggers the sync between runs through the
+ * supervisor, so triggered and scheduled runs never overlap.
  */
-export function startRoundsCollector(): void {
-  // Startup: sync rounds, collect current, fetch pending participants
-  syncRounds()
-    .then(() => {
-      // After syncing rounds, fetch any pending participant data in background
-      fetchPendingRoundParticipants().catch(err =>
-        console.error('Error fetching pending round participants on startup:', err)
-      );
-    })
-    .catch(err => console.error('Error syncing rounds on startup:', err));
-
-  collectCurrentRound().catch(err =>
-    console.error('Error collecting current round on startup:', err)
-  );
-
-  // Schedule periodic round list sync + retry any pending/errored participants
-  syncJob = cron.schedule(CONFIG.ROUNDS_SYNC_INTERVAL, async () => {
-    await syncRounds();
-    fetchPendingRoundParticipants().catch(err =>
-      console.error('Error fetching pending round participants:', err)
-    );
-  });
-
-  // Schedule current round polling
-  currentRoundJob = cron.schedule(CONFIG.CURRENT_ROUND_POLL_INTERVAL, async () => {
-    await collectCurrentRound();
-  });
-
-  console.log('🔄 Rounds collector started (sync + current round polling every 10 minutes)');
-}
-
-/**
- * Stop the rounds collector and clear scheduled jobs
- */
-export function stopRoundsCollector(): void {
-  if (syncJob) {
-    syncJob.stop();
-    syncJob = null;
-  }
-  if (currentRoundJob) {
-    currentRoundJob.stop();
-    currentRoundJob = null;
-  }
-  isCollectingCurrent = false;
-  isSyncingRounds = false;
-  isFetchingPendingParticipants = false;
-  console.log('🔄 Rounds collector stopped');
-}
+export const roundsJobs: JobDefinition[] = [
+  {
+    name: 'rounds',
+    schedule: CONFIG.ROUNDS_SYNC_INTERVAL,
+    timeoutMs: 5 * 60_000,
+    staleAfterSeconds: 60 * 60,
+    runOnStart: true,
+    run: async () => {
+      const newRounds = await syncRounds();
+      triggerJob('round_participants');
+      // Refresh the current round so work-since-last-block resets promptly
+      if (newRounds > 0) triggerJob('current_round');
+    },
+  },
+  {
+    name: 'round_participants',
+    schedule: null,
+    timeoutMs: 30 * 60_000,
+    staleAfterSeconds: null,
+    run: fetchPendingRoundParticipants,
+  },
+  {
+    name: 'current_round',
+    schedule: CONFIG.CURRENT_ROUND_POLL_INTERVAL,
+    timeoutMs: 5 * 60_000,
+    staleAfterSeconds: 60 * 60,
+    runOnStart: true,
+    run: collectCurrentRound,
+  },
+];
@@ -0,0 +1,115 @@
+import { AsyncLocalStorage } from 'async_hooks';
+import { Pool, types, type PoolClient } from 'pg';
+import { getPostgresMigrationStatus, migratePostgres } from '../migrations/postgres';
+import type { MigrateOptions } from '../migrations';
+import { createLogger } from '../logger';
+import type { Db, RunResult, SqlValue } from './types';
+
+// BIGINT counts and ids, and NUMERIC results of SUM/AVG, arrive as strings by
+// default. Every value we store fits in a double, as it did in SQLite.
+types.setTypeParser(types.builtins.INT8, value => Number(value));
+types.setTypeParser(types.builtins.NUMERIC, value => Number(value));
+
+const log = createLogger({ module: 'postgres' });
+
+/**
+ * Rewrite `?` placeholders to Postgres' numbered `$1, $2, ...`, leaving
+ * question marks inside string literals and quoted identifiers alone
+ */
+export function toPostgresPlaceholders(sql: string): string {
+  let index = 0;
+  let quote: string | null = null;
+  let result = '';
+
+  for (const char of sql) {
+    if (quote) {
+      if (char === quote) quote = null;
+    } else if (char === "'" || char === '"') {
+      quote = char;
+    } else if (char === '?') {
+      result += `$${++index}`;
+      continue;
+    }
+    result += char;
+  }
+
+  return result;
+}
+
+/**
+ * Postgres storage on a connection pool, so several web instances and the
+ * jobs process can share one database. Transactions check out a client and
+ * every query made inside them is routed to it.
+ */
+export class PostgresDb implements Db {
+  readonly dialect = 'postgres';
+
+  private readonly transactionClient = new AsyncLocalStorage<PoolClient>();
+  private readonly texts = new Map<string, string>();
+
+  constructor(readonly pool: Pool) {}
+
+  static open(connectionString: string, maxConnections: number): PostgresDb {
+    const pool = new Pool({ connectionString, max: maxConnections });
+    pool.on('error', error => {
+      log.error('Postgres pool error', { error });
+    });
+    return new PostgresDb(pool);
+  }
+
+  private async query(sql: string, params: SqlValue[]) {
+    let text = this.texts.get(sql);
+    if (text === undefined) {
+      text = toPostgresPlaceholders(sql);
+      this.texts.set(sql, text);
+    }
+    const client = this.transactionClient.getStore() ?? this.pool;
+    return client.query(text, params);
+  }
+
+  async get<T>(sql: string, params: SqlValue[] = []): Promise<T | undefined> {
+    const result = await this.query(sql, params);
+    return result.rows[0] as T | undefined;
+  }
+
+  async all<T>(sql: string, params: SqlValue[] = []): Promise<T[]> {
+    const result = await this.query(sql, params);
+    return result.rows as T[];
+  }
+
+  async run(sql: string, params: SqlValue[] = []): Promise<RunResult> {
+    const result = await this.query(sql, params);
+    return { changes: result.rowCount ?? 0 };
+  }
+
+  async transaction<T>(fn: () => Promise<T>): Promise<T> {
+    if (this.transactionClient.getStore()) return fn();
+
+    const client = await this.pool.connect();
+    try {
+      await client.query('BEGIN');
+      const result = await this.transactionClient.run(client, fn);
+      await client.query('COMMIT');
+      return result;
+    } catch (error) {
+      await client.query('ROLLBACK').catch(rollbackError => {
+        log.error('Postgres rollback failed', { error: rollbackError });
+      });
+      throw error;
+    } finally {
+      client.release();
+    }
+  }
+
+  migrate(options?: MigrateOptions) {
+    return migratePostgres(this.pool, options);
+  }
+
+  getMigrationStatus() {
+    return getPostgresMigrationStatus(this.pool);
+  }
+
+  async close(): Promise<void> {
+    await this.pool.end();
+  }
+}
@@ -0,0 +1,132 @@
+import Database from 'better-sqlite3';
+import { AsyncLocalStorage } from 'async_hooks';
+import { getMigrationStatus, migrate, type MigrateOptions } from '../migrations';
+import type { Db, RunResult, SqlValue } from './types';
+
+/**
+ * SQLite storage on a single better-sqlite3 connection.
+ *
+ * Statements run synchronously, so the only thing to guard is transactions:
+ * while one is open, queries from outside it wait for it to finish instead of
+ * landing inside it.
+ */
+export class SqliteDb implements Db {
+  readonly dialect = 'sqlite';
+
+  private readonly statements = new Map<string, Database.Statement>();
+  private readonly transactionContext = new AsyncLocalStorage<true>();
+  private activeTransaction: Promise<void> | null = null;
+  private activeBackup: Promise<void> | null = null;
+
+  constructor(readonly connection: Database.Database) {}
+
+  static open(file: string): SqliteDb {
+    const connection = new Database(file, { timeout: 10000 });
+
+    connection.pragma('journal_mode = WAL');
+    connection.pragma('synchronous = NORMAL');
+    connection.pragma('foreign_keys = ON');
+
+    return new SqliteDb(connection);
+  }
+
+  private statement(sql: string): Database.Statement {
+    let statement = this.statements.get(sql);
+    if (!statement) {
+      statement = this.connection.prepare(sql);
+      this.statements.set(sql, statement);
+    }
+    return statement;
+  }
+
+  // The open transaction the caller has to wait for, if any. Callers check
+  // again after every wait and then run synchronously, so nothing can open a
+  // transaction in between.
+  private blocker(): Promise<void> | null {
+    return this.transactionContext.getStore() ? null : this.activeTransaction;
+  }
+
+  async get<T>(sql: string, params: SqlValue[] = []): Promise<T | undefined> {
+    let blocker;
+    while ((blocker = this.blocker())) await blocker;
+    return this.statement(sql).get(...params) as T | undefined;
+  }
+
+  async all<T>(sql: string, params: SqlValue[] = []): Promise<T[]> {
+    let blocker;
+    while ((blocker = this.blocker())) await blocker;
+    return this.statement(sql).all(...params) as T[];
+  }
+
+  async run(sql: string, params: SqlValue[] = []): Promise<RunResult> {
+    let blocker;
+    while ((blocker = this.blocker())) await blocker;
+    return { changes: this.statement(sql).run(...params).changes };
+  }
+
+  async transaction<T>(fn: () => Promise<T>): Promise<T> {
+    if (this.transactionContext.getStore()) return fn();
+    let blocker;
+    while ((blocker = this.blocker() ?? this.activeBackup)) await blocker;
+
+    let finish!: () => void;
+    this.activeTransaction = new Promise(resolve => { finish = resolve; });
+
+    try {
+      this.connection.exec('BEGIN IMMEDIATE');
+      const result = await this.transactionContext.run(true, fn);
+      this.connection.exec('COMMIT');
+      return result;
+    } catch (error) {
+      if (this.connection.inTransaction) this.connection.exec('ROLLBACK');
+      throw error;
+    } finally {
+      this.activeTransaction = null;
+      finish();
+    }
+  }
+
+  /**
+   * Copy the database to `destination` with SQLite's online backup API.
+   * Transactions wait for it so it never copies uncommitted changes; single
+   * statements keep running and SQLite carries their changes into the copy.
+   */
+  async backup(destination: string): Promise<void> {
+    let blocker;
+    while ((blocker = this.blocker() ?? this.activeBackup)) await blocker;
+
+    let finish!: () => void;
+    this.activeBackup = new Promise(resolve => { finish = resolve; });
+
+    try {
+      await this.connection.backup(destination);
+    } finally {
+      this.activeBackup = null;
+      finish();
+    }
+  }
+
+  async migrate(options?: MigrateOptions) {
+    let blocker;
+    while ((blocker = this.blocker())) await blocker;
+    return migrate(this.connection, options);
+  }
+
+  async getMigrationStatus() {
+    return getMigrationStatus(this.connection);
+  }
+
+  checkpointWal() {
+    const [result] = this.connection.pragma('wal_checkpoint(TRUNCATE)') as {
+      busy: number;
+      log: number;
+      checkpointed: number;
+    }[];
+    return result;
+  }
+
+  async close(): Promise<void> {
+    this.statements.clear();
+    this.connection.close();
+  }
+}
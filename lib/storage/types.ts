@@ -0,0 +1,36 @@
+import type { MigrateOptions, MigrationResult, MigrationStatus } from '../migrations';
+
+export type StorageDialect = 'sqlite' | 'postgres';
+
+export type SqlValue = string | number | bigint | Buffer | null;
+
+export interface RunResult {
+  changes: number;
+}
+
+/**
+ * Parastats' storage, backed by SQLite or Postgres.
+ *
+ * Queries are written once in the SQL both understand, with `?` placeholders.
+ * Booleans are stored as 0/1 integers on both backends. Where the dialects
+ * really differ, branch on `dialect`.
+ */
+export interface Db {
+  readonly dialect: StorageDialect;
+
+  get<T>(sql: string, params?: SqlValue[]): Promise<T | undefined>;
+  all<T>(sql: string, params?: SqlValue[]): Promise<T[]>;
+  run(sql: string, params?: SqlValue[]): Promise<RunResult>;
+
+  /**
+   * Run fn in a transaction. Every query made through this Db while fn runs
+   * joins it, including nested transaction calls; other callers wait until it
+   * commits. Keep network calls out of fn.
+   */
+  transaction<T>(fn: () => Promise<T>): Promise<T>;
+
+  migrate(options?: MigrateOptions): Promise<MigrationResult[]>;
+  getMigrationStatus(): Promise<MigrationStatus>;
+
+  close(): Promise<void>;
+}
@@ -0,0 +1,55 @@
+import { getDb } from './db';
+import { createLogger } from './logger';
+import { getBlockSubsidy, type RecordedJob } from './stratum-job-diff';
+import type { StratumNotification } from '@/app/api/stratum/types';
+
+const log = createLogger({ module: 'stratum-block-summary' });
+
+/**
+ * Per-height template summaries
+ *
+ * Folds every recorded job into one row per pool and block height so fee and
+ * reward trends survive long after the raw notifications are trimmed.
+ */
+export async function recordBlockSummary(notification: StratumNotification, job: RecordedJob): Promise<void> {
+  // Without a BIP 34 height there's nothing to key the summary on
+  if (job.blockHeight === undefined) return;
+
+  try {
+    const subsidy = getBlockSubsidy(job.blockHeight);
+    const fees = job.fees ?? Math.max(job.coinbaseValue - subsidy, 0);
+
+    const db = await getDb();
+    // Two-argument MIN/MAX is SQLite-only, so the running extremes use CASE
+    await db.run(`
+      INSERT INTO stratum_block_summaries AS s (
+        pool, block_height, prev_block_hash, first_job_at, last_job_at, first_clean_job_at,
+        job_count, max_coinbase_value, max_fees, subsidy
+      ) VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?, ?)
+      ON CONFLICT(pool, block_height) DO UPDATE SET
+        prev_block_hash = excluded.prev_block_hash,
+        first_job_at = CASE WHEN excluded.first_job_at < s.first_job_at
+          THEN excluded.first_job_at ELSE s.first_job_at END,
+        last_job_at = CASE WHEN excluded.last_job_at > s.last_job_at
+          THEN excluded.last_job_at ELSE s.last_job_at END,
+        first_clean_job_at = COALESCE(s.first_clean_job_at, excluded.first_clean_job_at),
+        job_count = s.job_count + 1,
+        max_coinbase_value = CASE WHEN excluded.max_coinbase_value > s.max_coinbase_value
+          THEN excluded.max_coinbase_value ELSE s.max_coinbase_value END,
+        max_fees = CASE WHEN excluded.max_fees > s.max_fees
+          THEN excluded.max_fees ELSE s.max_fees END
+    `, [
+      notification.pool,
+      job.blockHeight,
+      notification.prevBlockHash,
+      notification.timestamp,
+      notification.timestamp,
+      notification.cleanJobs ? notification.timestamp : null,
+      job.coinbaseValue,
+      fees,
+      subsidy,
+    ]);
+  } catch (error) {
+    log.error('Error recording block summary', { pool: notification.pool, blockHeight: job.blockHeight, error });
+  }
+}
@@ -1,7 +1,41 @@
 import net from 'net';
 import { getDb } from './db';
+import { parsePositiveInt } from './env';
+import { createLogger, type Logger } from './logger';
 import { triggerDelayedCollection, getCurrentBlockHeight } from './highest-diff-collector';
 import { triggerRoundsSync } from './rounds-collector';
+import { publishStratumNotification, registerLocalStratumPublisher } from './stratum-events';
+import { recordJobDiff } from './stratum-job-diff';
+import { recordBlockSummary } from './stratum-block-summary';
+import { toStratumNotification } from '@/app/api/stratum/types';
+import {
+  Sv2NoiseInitiator,
+  HANDSHAKE_RESPONSE_LENGTH,
+  encryptedPayloadLength,
+} from './sv2-noise';
+import {
+  MessageType,
+  FRAME_HEADER_LENGTH,
+  encodeFrameHeader,
+  decodeFrameHeader,
+  encodeSetupConnection,
+  encodeOpenExtendedMiningChannel,
+  decodeSetupConnectionSuccess,
+  decodeSetupConnectionError,
+  decodeOpenMiningChannelError,
+  decodeOpenExtendedMiningChannelSuccess,
+  decodeNewExtendedMiningJob,
+  decodeSetNewPrevHash,
+  decodeSetExtranoncePrefix,
+  decodeSetTarget,
+  isChannelMessage,
+  targetToDifficulty,
+  toStratumV1PrevHash,
+  toStratumV1Hex,
+  type FrameHeader,
+  type NewExtendedMiningJob,
+  type SetNewPrevHash,
+} from './sv2-codec';
 
 interface StratumMessage {
   id?: number;
@@ -11,7 +45,63 @@ interface StratumMessage {
   error?: unknown;
 }
 
-interface StratumNotificationData {
+/**
+ * A stratum endpoint to observe. Each endpoint runs as an independent collector
+ * and its notifications are stored under `name` in the `pool` column.
+ */
+export interface StratumEndpoint {
+  name: string;
+  host: string;
+  port: number;
+  username: string;
+  password?: string;
+  /** Only the primary pool's clean jobs trigger highest diff and rounds collection */
+  primary?: boolean;
+  /** Stratum V1 (JSON over TCP, default) or Stratum V2 (Noise-encrypted binary) */
+  protocol?: 'v1' | 'v2';
+}
+
+export const PRIMARY_POOL_NAME = 'Parasite';
+
+const CONFIG = {
+  CONNECT_TIMEOUT_MS: parsePositiveInt(process.env.STRATUM_CONNECT_TIMEOUT_MS, 10000),
+  RECONNECT_BASE_DELAY_MS: parsePositiveInt(process.env.STRATUM_RECONNECT_BASE_DELAY_MS, 1000),
+  RECONNECT_MAX_DELAY_MS: parsePositiveInt(process.env.STRATUM_RECONNECT_MAX_DELAY_MS, 5 * 60 * 1000),
+  HEALTH_WRITE_INTERVAL_MS: 5000, // Throttle last-message updates to the health table
+};
+
+/**
+ * The default endpoint, configurable via STRATUM_HOST, STRATUM_PORT,
+ * STRATUM_USERNAME and STRATUM_PASSWORD
+ */
+function getDefaultEndpoints(): StratumEndpoint[] {
+  return [
+    {
+      name: PRIMARY_POOL_NAME,
+      host: process.env.STRATUM_HOST || 'parasite.wtf',
+      port: parsePositiveInt(process.env.STRATUM_PORT, 42069),
+      // Any valid address works for observing jobs; Satoshi's is the placeholder
+      username: process.env.STRATUM_USERNAME || '1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa',
+      password: process.env.STRATUM_PASSWORD || 'x',
+      primary: true,
+    },
+  ];
+}
+
+/**
+ * Overrides for the collector's side effects, used by the replay harness so
+ * sessions can be driven against a local server without touching the network
+ */
+export interface StratumCollectorHooks {
+  /** Replaces the highest diff and rounds triggers fired on the primary pool's clean jobs */
+  onNewBlock?: (notification: StratumNotificationData) => void;
+  /** Called with the delay chosen each time a reconnect is scheduled */
+  onReconnectScheduled?: (delayMs: number, attempt: number) => void;
+}
+
+type StratumHealthStatus = 'connecting' | 'connected' | 'disconnected' | 'stopped';
+
+export interface StratumNotificationData {
   notification_id: string;
   timestamp: number;
   pool: string;
@@ -26,64 +116,111 @@ interface StratumNotificationData {
   clean_jobs: number;
   extranonce1: string | null;
   extranonce2_size: number | null;
+  version_rolling_mask: string | null;
+  difficulty: number | null;
   raw_message: string;
   created_at: number;
 }
 
-class StratumCollector {
+// BIP 310 version-rolling request: the bits ASICBoost miners roll (13-28)
+const VERSION_ROLLING_MASK = '1fffe000';
+const VERSION_ROLLING_MIN_BIT_COUNT = 2;
+// We never submit shares, so ask for the lowest difficulty the pool allows
+const MINIMUM_DIFFICULTY = 1;
+
+const log = createLogger({ collector: 'stratum' });
+
+export class StratumCollector {
   private socket: net.Socket | null = null;
   private messageId: number = 1;
   private reconnectTimer: NodeJS.Timeout | null = null;
+  // Consecutive failed sessions; drives the backoff and resets once the pool talks to us
   private reconnectAttempts: number = 0;
-  private maxReconnectAttempts: number = 10;
+  private reconnectCount: number = 0;
+  private lastMessageAt: number | null = null;
+  private lastHealthWriteAt: number = 0;
   private isConnecting: boolean = false;
   private isDestroyed: boolean = false;
   private messageBuffer: string = '';
   private extranonce1: string | null = null;
   private extranonce2Size: number | null = null;
+  private versionRollingMask: string | null = null;
+  private difficulty: number | null = null;
+  // Outstanding request ids and their methods, so responses can be matched
+  private pendingRequests: Map<number, string> = new Map();
   private notificationCount: number = 0;
+  // Database writes run one at a time, in the order messages arrived
+  private writes: Promise<void> = Promise.resolve();
   private readonly CLEANUP_INTERVAL = 50; // Run cleanup every 50 notifications
   private readonly MAX_NOTIFICATIONS = 100; // Keep only latest 100 notifications
+  private readonly MAX_DIFFICULTY_CHANGES = 1000; // Keep only latest 1000 difficulty changes
   private readonly MAX_BUFFER_SIZE = 1024 * 1024; // 1MB max buffer size
 
-  constructor(
-    private host: string = 'parasite.wtf',
-    private port: number = 42069
-  ) {}
+  // Stratum V2 session state, reset on every connection
+  private sv2: Sv2NoiseInitiator | null = null;
+  private sv2Buffer: Buffer = Buffer.alloc(0);
+  private sv2PendingHeader: FrameHeader | null = null;
+  private sv2ChannelId: number | null = null;
+  private sv2Jobs: Map<number, NewExtendedMiningJob> = new Map();
+  private sv2PrevHash: SetNewPrevHash | null = null;
+  private readonly SV2_MAX_PENDING_JOBS = 64;
+  private readonly SV2_ENCRYPTED_HEADER_LENGTH = FRAME_HEADER_LENGTH + 16;
+
+  private readonly log: Logger;
+
+  constructor(private endpoint: StratumEndpoint, private hooks: StratumCollectorHooks = {}) {
+    this.log = log.child({ pool: endpoint.name });
+  }
+
+  get poolName(): string {
+    return this.endpoint.name;
+  }
 
   async connect(): Promise<void> {
     if (this.isConnecting || this.isDestroyed) return;
 
     this.isConnecting = true;
-    console.log(`Attempting to connect to ${this.host}:${this.port}...`);
+    this.log.info('Connecting to stratum pool', { host: this.endpoint.host, port: this.endpoint.port });
+    this.writeHealth('connecting');
 
     try {
       this.socket = new net.Socket();
       
       // Set up socket event handlers
       this.socket.on('connect', () => {
-        console.log('Connected to Parasite stratum pool');
+        this.log.info('Connected to stratum pool');
         this.isConnecting = false;
-        this.reconnectAttempts = 0;
-        this.subscribe();
+        this.writeHealth('connected', { connectedAt: Math.floor(Date.now() / 1000) });
+        if (this.endpoint.protocol === 'v2') {
+          this.startSv2Handshake();
+        } else {
+          // BIP 310 asks for mining.configure before anything else
+          this.configure();
+          this.subscribe();
+        }
       });
 
       this.socket.on('data', (data) => {
-        this.handleData(data);
+        this.markMessageReceived();
+        if (this.endpoint.protocol === 'v2') {
+          this.handleSv2Data(data);
+        } else {
+          this.handleData(data);
+        }
       });
 
       this.socket.on('error', (error) => {
-        console.error('Stratum socket error:', error);
-        this.handleDisconnect();
+        this.log.error('Stratum socket error', { error });
+        this.handleDisconnect(error.message);
       });
 
       this.socket.on('close', () => {
-        console.log('Stratum connection closed');
+        this.log.info('Stratum connection closed');
         this.handleDisconnect();
       });
 
       this.socket.on('end', () => {
-        console.log('Stratum connection ended');
+        this.log.info('Stratum connection ended');
         this.handleDisconnect();
       });
 
@@ -91,9 +228,9 @@ class StratumCollector {
       await new Promise<void>((resolve, reject) => {
         const timeout = setTimeout(() => {
           reject(new Error('Connection timeout'));
-        }, 10000);
+        }, CONFIG.CONNECT_TIMEOUT_MS);
 
-        this.socket!.connect(this.port, this.host, () => {
+        this.socket!.connect(this.endpoint.port, this.endpoint.host, () => {
           clearTimeout(timeout);
           resolve();
         });
@@ -105,9 +242,12 @@ class StratumCollector {
       });
 
     } catch (error) {
-      console.error('Failed to connect to stratum pool:', error);
+      this.log.error('Failed to connect to stratum pool', { error });
       this.isConnecting = false;
-      this.handleDisconnect();
+      // A socket error has already been handled (and cleared the socket) by the error listener
+      if (this.socket) {
+        this.handleDisconnect(error instanceof Error ? error.message : String(error));
+      }
       throw error;
     }
   }
@@ -119,7 +259,7 @@ class StratumCollector {
 
       // Guard against unbounded buffer growth from malformed data
       if (this.messageBuffer.length > this.MAX_BUFFER_SIZE) {
-        console.warn(`Stratum message buffer exceeded ${this.MAX_BUFFER_SIZE} bytes, clearing`);
+        this.log.warn('Stratum message buffer full, clearing', { maxBytes: this.MAX_BUFFER_SIZE });
         this.messageBuffer = '';
         return;
       }
@@ -137,12 +277,15 @@ class StratumCollector {
             const message: StratumMessage = JSON.parse(line.trim());
             this.handleMessage(message);
           } catch (parseError) {
-            console.error('Failed to parse stratum message:', parseError, 'Raw:', line.substring(0, 200) + (line.length > 200 ? '...' : ''));
+            this.log.error('Failed to parse stratum message', {
+              error: parseError,
+              raw: line.substring(0, 200) + (line.length > 200 ? '...' : ''),
+            });
           }
         }
       }
     } catch (error) {
-      console.error('Error handling stratum data:', error);
+      this.log.error('Error handling stratum data', { error });
     }
   }
 
@@ -150,35 +293,109 @@ class StratumCollector {
     if (message.method === 'mining.notify') {
       this.processNotification(message);
     } else if (message.method === 'mining.set_difficulty') {
-      // Keep difficulty changes quiet unless there's an issue
-    } else if (message.id && message.result !== undefined) {
+      this.recordDifficulty(Number(message.params?.[0]));
+    } else if (message.method === 'mining.set_version_mask') {
+      this.updateVersionRollingMask(typeof message.params?.[0] === 'string' ? message.params[0] : null);
+    } else if (message.id !== undefined && message.id !== null) {
       // Handle method responses
-      
-      // If this is the subscription response (ID 1), capture extranonce values
-      if (message.id === 1 && message.result) {
-        console.log('📋 Connected and subscribed to Parasite stratum pool');
+      const method = this.pendingRequests.get(message.id);
+      this.pendingRequests.delete(message.id);
+
+      if (method === 'mining.configure') {
+        this.handleConfigureResponse(message);
+      } else if (message.error) {
+        this.log.error('Stratum error response', { method, stratumError: message.error });
+      } else if (method === 'mining.subscribe' && message.result) {
+        this.log.info('Subscribed to stratum pool');
         
         // Extract extranonce1 and extranonce2_size from subscription result
         // Result format: [[["mining.set_difficulty", "subscription_id"], ["mining.notify", "subscription_id"]], "extranonce1", extranonce2_size]
         if (Array.isArray(message.result) && message.result.length >= 3) {
           this.extranonce1 = message.result[1];
           this.extranonce2Size = message.result[2];
-          console.log(`🔧 Extranonce1: ${this.extranonce1}, Extranonce2 size: ${this.extranonce2Size}`);
+          this.log.info('Received extranonce', { extranonce1: this.extranonce1, extranonce2Size: this.extranonce2Size });
         }
         
+        this.markSessionEstablished();
         this.handleSubscriptionResponse();
-      } else if (message.id === 2 && message.result === true) {
-        console.log('🔐 Authorization successful - receiving mining notifications');
+      } else if (method === 'mining.authorize' && message.result === true) {
+        this.log.info('Authorized, receiving mining notifications');
       }
     } else if (message.error) {
-      console.error('❌ Stratum error:', message.error);
+      this.log.error('Stratum error', { stratumError: message.error });
     }
   }
 
+  /**
+   * mining.configure result, e.g.
+   * {"version-rolling": true, "version-rolling.mask": "1fffe000", "minimum-difficulty": true}
+   */
+  private handleConfigureResponse(message: StratumMessage): void {
+    // Plenty of pools don't implement mining.configure; that's not an error for us
+    if (message.error || !message.result || typeof message.result !== 'object') {
+      this.log.info('mining.configure not supported, continuing without extensions');
+      this.updateVersionRollingMask(null);
+      return;
+    }
+
+    const result = message.result as Record<string, unknown>;
+    const mask = result['version-rolling'] === true && typeof result['version-rolling.mask'] === 'string'
+      ? result['version-rolling.mask']
+      : null;
+    this.updateVersionRollingMask(mask);
+
+    this.log.info('Negotiated mining.configure extensions', {
+      versionRollingMask: mask,
+      minimumDifficulty: result['minimum-difficulty'] === true,
+    });
+  }
+
+  private updateVersionRollingMask(mask: string | null): void {
+    if (mask === this.versionRollingMask) return;
+
+    if (mask !== null && !/^[0-9a-f]{8}$/i.test(mask)) {
+      this.log.warn('Ignoring invalid version rolling mask', { mask });
+      return;
+    }
+
+    this.versionRollingMask = mask === null ? null : mask.toLowerCase();
+  }
+
+  private recordDifficulty(difficulty: number): void {
+    if (!Number.isFinite(difficulty) || difficulty <= 0) {
+      this.log.warn('Ignoring invalid difficulty', { difficulty });
+      return;
+    }
+    if (difficulty === this.difficulty) return;
+
+    const previousDifficulty = this.difficulty;
+    this.difficulty = difficulty;
+
+    this.enqueueWrite(async () => {
+      try {
+        const db = await getDb();
+        await db.run(`
+          INSERT INTO stratum_difficulty_changes (pool, difficulty, previous_difficulty, created_at)
+          VALUES (?, ?, ?, ?)
+        `, [this.endpoint.name, difficulty, previousDifficulty, Math.floor(Date.now() / 1000)]);
+
+        this.log.info('Share difficulty set', { difficulty });
+      } catch (error) {
+        this.log.error('Error recording difficulty change', { error });
+      }
+    });
+  }
+
+  private enqueueWrite(write: () => Promise<void>): void {
+    this.writes = this.writes.then(write).catch(error => {
+      this.log.error('Error writing to the database', { error });
+    });
+  }
+
   private processNotification(message: StratumMessage): void {
     try {
       if (!message.params || message.params.length < 9) {
-        console.error('Invalid mining.notify message:', message);
+        this.log.error('Invalid mining.notify message', { message });
         return;
       }
 
@@ -195,12 +412,13 @@ class StratumCollector {
       ] = message.params;
 
       const now = Math.floor(Date.now() / 1000);
-      const notificationId = `${now}_${String(jobId)}`;
+      // Job IDs are only unique per pool, so the pool name is part of the key
+      const notificationId = `${now}_${this.endpoint.name}_${String(jobId)}`;
 
       const notification: StratumNotificationData = {
         notification_id: notificationId,
         timestamp: now,
-        pool: 'Parasite',
+        pool: this.endpoint.name,
         job_id: String(jobId),
         prev_block_hash: String(prevBlockHash),
         coinbase1: String(coinbase1),
@@ -212,41 +430,91 @@ class StratumCollector {
         clean_jobs: cleanJobs ? 1 : 0,
         extranonce1: this.extranonce1,
         extranonce2_size: this.extranonce2Size,
+        version_rolling_mask: this.versionRollingMask,
+        difficulty: this.difficulty,
         raw_message: JSON.stringify(message),
         created_at: now
       };
 
-      this.storeNotification(notification);
+      this.recordNotification(notification);
+    } catch (error) {
+      this.log.error('Error processing stratum notification', { error });
+    }
+  }
+
+  /**
+   * Store a normalized notification and fire the new-block triggers, after
+   * any writes already queued. Shared by the V1 and V2 transports.
+   */
+  private recordNotification(notification: StratumNotificationData): void {
+    this.enqueueWrite(() => this.handleNotification(notification));
+  }
+
+  private async handleNotification(notification: StratumNotificationData): Promise<void> {
+    try {
+      if (await this.storeNotification(notification)) {
+        this.writeHealth('connected', { lastNotificationAt: notification.timestamp });
+        const stored = toStratumNotification(notification);
+        const job = await recordJobDiff(stored);
+        if (job) {
+          await recordBlockSummary(stored, job);
+        }
+        publishStratumNotification(stored);
+      }
       // Log only on clean job notifications (new blocks) to reduce noise
-      if (cleanJobs) {
-        console.log(`🎯 New block template: Job ${jobId} (${new Date(now * 1000).toISOString()})`);
-        
-        // Trigger highest diff collection for the previous block
-        // The new block means the previous block is now complete
-        this.triggerHighestDiffCollection();
+      if (notification.clean_jobs) {
+        this.log.info('New block template', { jobId: notification.job_id, timestamp: notification.timestamp });
 
-        // Trigger rounds re-sync in case a new round was found
-        triggerRoundsSync();
+        // Every observed pool sees the same new block, so only the primary
+        // pool drives the Parasite-specific collections
+        if (this.endpoint.primary) {
+          if (this.hooks.onNewBlock) {
+            this.hooks.onNewBlock(notification);
+          } else {
+            // Trigger highest diff collection for the previous block
+            // The new block means the previous block is now complete
+            this.triggerHighestDiffCollection();
+
+            // Trigger rounds re-sync in case a new round was found
+            triggerRoundsSync();
+          }
+        }
       }
 
     } catch (error) {
-      console.error('Error processing stratum notification:', error);
+      this.log.error('Error recording stratum notification', { error });
     }
   }
 
-  private storeNotification(notification: StratumNotificationData): void {
+  private async storeNotification(notification: StratumNotificationData): Promise<boolean> {
     try {
-      const db = getDb();
+      const db = await getDb();
       
-      const stmt = db.prepare(`
-        INSERT OR REPLACE INTO stratum_notifications (
+      await db.run(`
+        INSERT INTO stratum_notifications (
           notification_id, timestamp, pool, job_id, prev_block_hash,
           coinbase1, coinbase2, merkle_branches, version,
-          n_bits, n_time, clean_jobs, extranonce1, extranonce2_size, raw_message, created_at
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-      `);
-
-      stmt.run(
+          n_bits, n_time, clean_jobs, extranonce1, extranonce2_size,
+          version_rolling_mask, difficulty, raw_message, created_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(notification_id) DO UPDATE SET
+          timestamp = excluded.timestamp,
+          job_id = excluded.job_id,
+          prev_block_hash = excluded.prev_block_hash,
+          coinbase1 = excluded.coinbase1,
+          coinbase2 = excluded.coinbase2,
+          merkle_branches = excluded.merkle_branches,
+          version = excluded.version,
+          n_bits = excluded.n_bits,
+          n_time = excluded.n_time,
+          clean_jobs = excluded.clean_jobs,
+          extranonce1 = excluded.extranonce1,
+          extranonce2_size = excluded.extranonce2_size,
+          version_rolling_mask = excluded.version_rolling_mask,
+          difficulty = excluded.difficulty,
+          raw_message = excluded.raw_message,
+          created_at = excluded.created_at
+      `, [
         notification.notification_id,
         notification.timestamp,
         notification.pool,
@@ -261,65 +529,93 @@ class StratumCollector {
         notification.clean_jobs,
         notification.extranonce1,
         notification.extranonce2_size,
+        notification.version_rolling_mask,
+        notification.difficulty,
         notification.raw_message,
-        notification.created_at
-      );
+        notification.created_at,
+      ]);
 
       // Increment counter and cleanup periodically instead of every insert
       this.notificationCount++;
       if (this.notificationCount % this.CLEANUP_INTERVAL === 0) {
-        this.cleanupOldNotifications();
+        await this.cleanupOldNotifications();
       }
 
+      return true;
     } catch (error) {
-      console.error('Error storing stratum notification:', error);
+      this.log.error('Error storing stratum notification', { error });
+      return false;
     }
   }
 
-  private cleanupOldNotifications(): void {
+  private async cleanupOldNotifications(): Promise<void> {
     try {
-      const db = getDb();
+      const db = await getDb();
       
-      // Delete all notifications except the latest number defined in MAX_NOTIFICATIONS
-      const result = db.prepare(`
-        DELETE FROM stratum_notifications 
-        WHERE id <= (
-          SELECT COALESCE(MAX(id) - ?, 0) 
-          FROM stratum_notifications
+      // Delete all of this pool's notifications except the latest number defined
+      // in MAX_NOTIFICATIONS. Ids are shared across pools, so the cutoff is the
+      // id of this pool's oldest row worth keeping rather than MAX(id) - N.
+      const result = await db.run(`
+        DELETE FROM stratum_notifications
+        WHERE pool = ? AND id < (
+          SELECT COALESCE(MIN(id), 0) FROM (
+            SELECT id FROM stratum_notifications
+            WHERE pool = ?
+            ORDER BY id DESC
+            LIMIT ?
+          ) AS recent
         )
-      `).run(this.MAX_NOTIFICATIONS);
+      `, [this.endpoint.name, this.endpoint.name, this.MAX_NOTIFICATIONS]);
 
       if (result.changes > 0) {
-        console.log(`🧹 Cleaned up ${result.changes} old stratum notifications`);
+        this.log.info('Cleaned up old stratum notifications', { rows: result.changes });
       }
+
+      await db.run(`
+        DELETE FROM stratum_difficulty_changes
+        WHERE pool = ? AND id < (
+          SELECT COALESCE(MIN(id), 0) FROM (
+            SELECT id FROM stratum_difficulty_changes
+            WHERE pool = ?
+            ORDER BY id DESC
+            LIMIT ?
+          ) AS recent
+        )
+      `, [this.endpoint.name, this.endpoint.name, this.MAX_DIFFICULTY_CHANGES]);
     } catch (error) {
-      console.error('Error cleaning up old notifications:', error);
+      this.log.error('Error cleaning up old notifications', { error });
     }
   }
 
+  private configure(): void {
+    if (!this.socket) return;
+
+    this.sendRequest("mining.configure", [
+      ["version-rolling", "minimum-difficulty"],
+      {
+        "version-rolling.mask": VERSION_ROLLING_MASK,
+        "version-rolling.min-bit-count": VERSION_ROLLING_MIN_BIT_COUNT,
+        "minimum-difficulty.value": MINIMUM_DIFFICULTY
+      }
+    ]);
+  }
+
   private subscribe(): void {
     if (!this.socket) return;
 
     // Send mining.subscribe with more standard parameters
-    const subscribeMsg = {
-      id: this.messageId++,
-      method: "mining.subscribe",
-      params: ["parastats-collector/1.0"]
-    };
-
-    this.sendMessage(subscribeMsg);
+    this.sendRequest("mining.subscribe", ["parastats-collector/1.0"]);
     // Wait for subscription response before trying to authorize
   }
 
   private handleSubscriptionResponse(): void {
-    // Use proper Parasite pool credentials: Bitcoin address + "x" password
-    const authorizeMsg = {
-      id: this.messageId++,
-      method: "mining.authorize",
-      params: ["1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", "x"]  // Using Satoshi's address as example
-    };
-    
-    this.sendMessage(authorizeMsg);
+    this.sendRequest("mining.authorize", [this.endpoint.username, this.endpoint.password ?? "x"]);
+  }
+
+  private sendRequest(method: string, params: unknown[]): void {
+    const id = this.messageId++;
+    this.pendingRequests.set(id, method);
+    this.sendMessage({ id, method, params });
   }
 
   private sendMessage(message: Record<string, unknown>): void {
@@ -329,15 +625,313 @@ class StratumCollector {
       const messageStr = JSON.stringify(message) + '\n';
       this.socket.write(messageStr);
     } catch (error) {
-      console.error('Error sending stratum message:', error);
+      this.log.error('Error sending stratum message', { error });
     }
   }
 
-  private handleDisconnect(): void {
+  private startSv2Handshake(): void {
+    if (!this.socket) return;
+
+    this.sv2 = new Sv2NoiseInitiator();
+    this.socket.write(this.sv2.createHello());
+  }
+
+  private handleSv2Data(data: Buffer): void {
+    if (!this.sv2) return;
+
+    this.sv2Buffer = Buffer.concat([this.sv2Buffer, data]);
+    if (this.sv2Buffer.length > this.MAX_BUFFER_SIZE) {
+      this.log.warn('SV2 buffer full, reconnecting', { maxBytes: this.MAX_BUFFER_SIZE });
+      this.handleDisconnect('SV2 buffer overflow');
+      return;
+    }
+
+    try {
+      if (!this.sv2.isHandshakeComplete) {
+        if (this.sv2Buffer.length < HANDSHAKE_RESPONSE_LENGTH) return;
+
+        const certificate = this.sv2.readResponse(this.takeSv2Bytes(HANDSHAKE_RESPONSE_LENGTH));
+        this.log.info('Noise handshake complete', { certificateValidUntil: certificate.notValidAfter });
+
+        this.sendSv2Message(MessageType.SETUP_CONNECTION, encodeSetupConnection({
+          endpointHost: this.endpoint.host,
+          endpointPort: this.endpoint.port,
+          vendor: 'parastats',
+          firmware: 'parastats-collector/1.0',
+        }));
+      }
+
+      // Frames arrive as an encrypted header followed by the encrypted payload.
+      // The header is decrypted once and kept until its payload is complete,
+      // since every decryption advances the receive nonce.
+      // A handler may disconnect mid-loop, which clears the session
+      while (this.sv2) {
+        if (!this.sv2PendingHeader) {
+          if (this.sv2Buffer.length < this.SV2_ENCRYPTED_HEADER_LENGTH) return;
+          this.sv2PendingHeader = decodeFrameHeader(
+            this.sv2.decrypt(this.takeSv2Bytes(this.SV2_ENCRYPTED_HEADER_LENGTH))
+          );
+        }
+
+        const payloadLength = encryptedPayloadLength(this.sv2PendingHeader.msgLength);
+        if (this.sv2Buffer.length < payloadLength) return;
+
+        const header = this.sv2PendingHeader;
+        const payload = this.sv2.decrypt(this.takeSv2Bytes(payloadLength));
+        this.sv2PendingHeader = null;
+        this.handleSv2Message(header, payload);
+      }
+    } catch (error) {
+      // Decryption errors leave the nonces out of sync, so the session is unusable
+      this.log.error('Error handling SV2 data', { error });
+      this.handleDisconnect(error instanceof Error ? error.message : String(error));
+    }
+  }
+
+  private takeSv2Bytes(length: number): Buffer {
+    const bytes = this.sv2Buffer.subarray(0, length);
+    this.sv2Buffer = this.sv2Buffer.subarray(length);
+    return bytes;
+  }
+
+  private handleSv2Message(header: FrameHeader, payload: Buffer): void {
+    switch (header.msgType) {
+      case MessageType.SETUP_CONNECTION_SUCCESS: {
+        const success = decodeSetupConnectionSuccess(payload);
+        this.log.info('SV2 connection set up', { version: success.usedVersion, flags: success.flags });
+        this.sendSv2Message(MessageType.OPEN_EXTENDED_MINING_CHANNEL, encodeOpenExtendedMiningChannel({
+          requestId: this.messageId++,
+          userIdentity: this.endpoint.username,
+          nominalHashRate: 1e12,
+          minExtranonceSize: 0,
+        }));
+        break;
+      }
+      case MessageType.SETUP_CONNECTION_ERROR: {
+        const { errorCode } = decodeSetupConnectionError(payload);
+        this.log.error('SV2 SetupConnection rejected', { errorCode });
+        this.handleDisconnect(`SetupConnection rejected: ${errorCode}`);
+        break;
+      }
+      case MessageType.OPEN_MINING_CHANNEL_ERROR: {
+        const { errorCode } = decodeOpenMiningChannelError(payload);
+        this.log.error('SV2 channel rejected', { errorCode });
+        this.handleDisconnect(`OpenExtendedMiningChannel rejected: ${errorCode}`);
+        break;
+      }
+      case MessageType.OPEN_EXTENDED_MINING_CHANNEL_SUCCESS: {
+        const channel = decodeOpenExtendedMiningChannelSuccess(payload);
+        this.sv2ChannelId = channel.channelId;
+        this.extranonce1 = channel.extranoncePrefix;
+        this.extranonce2Size = channel.extranonceSize;
+        this.markSessionEstablished();
+        this.recordDifficulty(targetToDifficulty(channel.target));
+        this.log.info('Extended channel open', {
+          channelId: channel.channelId,
+          extranoncePrefix: this.extranonce1,
+          extranonceSize: this.extranonce2Size,
+        });
+        break;
+      }
+      case MessageType.SET_EXTRANONCE_PREFIX: {
+        const update = decodeSetExtranoncePrefix(payload);
+        if (update.channelId === this.sv2ChannelId) {
+          this.extranonce1 = update.extranoncePrefix;
+        }
+        break;
+      }
+      case MessageType.NEW_EXTENDED_MINING_JOB:
+        this.handleSv2Job(decodeNewExtendedMiningJob(payload));
+        break;
+      case MessageType.SET_NEW_PREV_HASH:
+        this.handleSv2PrevHash(decodeSetNewPrevHash(payload));
+        break;
+      case MessageType.SET_TARGET: {
+        const update = decodeSetTarget(payload);
+        if (update.channelId === this.sv2ChannelId) {
+          this.recordDifficulty(targetToDifficulty(update.maximumTarget));
+        }
+        break;
+      }
+      case MessageType.NEW_MINING_JOB:
+        // Standard-channel jobs carry only a merkle root, not the coinbase, so
+        // they can't be normalized; we only ever open an extended channel
+        break;
+      default:
+        // Reconnect and extension messages aren't needed to observe templates
+        if (!isChannelMessage(header) && header.extensionType !== 0) {
+          this.log.debug('Ignoring SV2 extension message', { extensionType: header.extensionType, msgType: header.msgType });
+        }
+    }
+  }
+
+  private handleSv2Job(job: NewExtendedMiningJob): void {
+    if (this.sv2ChannelId !== null && job.channelId !== this.sv2ChannelId) return;
+
+    // Future jobs (no min_ntime) only become active with a matching SetNewPrevHash
+    if (job.minNtime === null) {
+      this.sv2Jobs.set(job.jobId, job);
+      if (this.sv2Jobs.size > this.SV2_MAX_PENDING_JOBS) {
+        const oldestJobId = this.sv2Jobs.keys().next().value;
+        if (oldestJobId !== undefined) this.sv2Jobs.delete(oldestJobId);
+      }
+      return;
+    }
+
+    if (!this.sv2PrevHash) return;
+    this.recordNotification(this.buildSv2Notification(job, this.sv2PrevHash, job.minNtime, false));
+  }
+
+  private handleSv2PrevHash(prevHash: SetNewPrevHash): void {
+    if (this.sv2ChannelId !== null && prevHash.channelId !== this.sv2ChannelId) return;
+
+    this.sv2PrevHash = prevHash;
+    const job = this.sv2Jobs.get(prevHash.jobId);
+
+    // Every other pending future job was built on the previous tip
+    this.sv2Jobs.clear();
+
+    if (job) {
+      this.recordNotification(this.buildSv2Notification(job, prevHash, prevHash.minNtime, true));
+    }
+  }
+
+  /**
+   * Map an SV2 extended job onto the same row shape as a V1 mining.notify, so
+   * the API and template page don't need to know which transport produced it
+   */
+  private buildSv2Notification(
+    job: NewExtendedMiningJob,
+    prevHash: SetNewPrevHash,
+    nTime: number,
+    cleanJobs: boolean
+  ): StratumNotificationData {
+    const now = Math.floor(Date.now() / 1000);
+
+    return {
+      notification_id: `${now}_${this.endpoint.name}_${job.jobId}`,
+      timestamp: now,
+      pool: this.endpoint.name,
+      job_id: String(job.jobId),
+      prev_block_hash: toStratumV1PrevHash(prevHash.prevHash),
+      coinbase1: job.coinbaseTxPrefix,
+      coinbase2: job.coinbaseTxSuffix,
+      merkle_branches: JSON.stringify(job.merklePath),
+      version: toStratumV1Hex(job.version),
+      n_bits: toStratumV1Hex(prevHash.nbits),
+      n_time: toStratumV1Hex(nTime),
+      clean_jobs: cleanJobs ? 1 : 0,
+      extranonce1: this.extranonce1,
+      extranonce2_size: this.extranonce2Size,
+      // SV2 allows rolling bits 13-28 whenever the job permits version rolling
+      version_rolling_mask: job.versionRollingAllowed ? VERSION_ROLLING_MASK : null,
+      difficulty: this.difficulty,
+      raw_message: JSON.stringify({ protocol: 'sv2', job, prevHash }),
+      created_at: now
+    };
+  }
+
+  private sendSv2Message(msgType: number, payload: Buffer): void {
+    if (!this.socket || this.socket.destroyed || !this.sv2) return;
+
+    try {
+      const header = encodeFrameHeader({ extensionType: 0, msgType, msgLength: payload.length });
+      this.socket.write(Buffer.concat([this.sv2.encrypt(header), this.sv2.encrypt(payload)]));
+    } catch (error) {
+      this.log.error('Error sending SV2 message', { error });
+    }
+  }
+
+  private resetSv2State(): void {
+    this.sv2 = null;
+    this.sv2Buffer = Buffer.alloc(0);
+    this.sv2PendingHeader = null;
+    this.sv2ChannelId = null;
+    this.sv2Jobs.clear();
+    this.sv2PrevHash = null;
+  }
+
+  private markMessageReceived(): void {
+    const now = Date.now();
+    this.lastMessageAt = Math.floor(now / 1000);
+
+    if (now - this.lastHealthWriteAt >= CONFIG.HEALTH_WRITE_INTERVAL_MS) {
+      this.writeHealth('connected');
+    }
+  }
+
+  /**
+   * The backoff resets once the pool accepts our subscription or channel rather
+   * than on TCP connect, so a pool that accepts and then rejects or drops
+   * connections still backs off
+   */
+  private markSessionEstablished(): void {
+    this.reconnectAttempts = 0;
+    this.writeHealth('connected');
+  }
+
+  private writeHealth(
+    status: StratumHealthStatus,
+    options: { connectedAt?: number | null; lastError?: string | null; lastNotificationAt?: number } = {}
+  ): void {
+    this.lastHealthWriteAt = Date.now();
+    const lastMessageAt = this.lastMessageAt;
+    const reconnectCount = this.reconnectCount;
+    const reconnectAttempts = this.reconnectAttempts;
+
+    this.enqueueWrite(async () => {
+      try {
+        const db = await getDb();
+        // Columns not passed keep their current values (COALESCE with the existing row)
+        await db.run(`
+          INSERT INTO stratum_health AS h (
+            pool, host, port, is_primary, status, connected_at, last_message_at,
+            last_notification_at, reconnect_count, consecutive_failures, last_error, updated_at
+          ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+          ON CONFLICT(pool) DO UPDATE SET
+            host = excluded.host,
+            port = excluded.port,
+            is_primary = excluded.is_primary,
+            status = excluded.status,
+            connected_at = CASE WHEN ? = 1 THEN excluded.connected_at ELSE h.connected_at END,
+            last_message_at = COALESCE(excluded.last_message_at, h.last_message_at),
+            last_notification_at = COALESCE(excluded.last_notification_at, h.last_notification_at),
+            reconnect_count = excluded.reconnect_count,
+            consecutive_failures = excluded.consecutive_failures,
+            last_error = CASE WHEN ? = 1 THEN excluded.last_error ELSE h.last_error END,
+            updated_at = excluded.updated_at
+        `, [
+          this.endpoint.name,
+          this.endpoint.host,
+          this.endpoint.port,
+          this.endpoint.primary ? 1 : 0,
+          status,
+          options.connectedAt ?? null,
+          lastMessageAt,
+          options.lastNotificationAt ?? null,
+          reconnectCount,
+          reconnectAttempts,
+          options.lastError ?? null,
+          Math.floor(Date.now() / 1000),
+          options.connectedAt !== undefined ? 1 : 0,
+          options.lastError !== undefined ? 1 : 0,
+        ]);
+      } catch (error) {
+        this.log.error('Error writing stratum health', { error });
+      }
+    });
+  }
+
+  private handleDisconnect(reason?: string): void {
     if (this.isDestroyed) return;
 
     this.isConnecting = false;
     this.messageBuffer = ''; // Clear message buffer on disconnect
+    this.pendingRequests.clear();
+    // Difficulty and version mask are per session; the next session sets its own
+    this.difficulty = null;
+    this.versionRollingMask = null;
+    this.resetSv2State();
     this.notificationCount = 0; // Reset notification counter on disconnect
     
     if (this.socket) {
@@ -352,25 +946,37 @@ class StratumCollector {
       clearTimeout(this.reconnectTimer);
     }
 
-    // Implement exponential backoff for reconnection
-    if (this.reconnectAttempts < this.maxReconnectAttempts) {
-      const delay = Math.min(1000 * Math.pow(2, this.reconnectAttempts), 30000);
-      console.log(`Reconnecting in ${delay}ms (attempt ${this.reconnectAttempts + 1}/${this.maxReconnectAttempts})`);
-      
-      this.reconnectTimer = setTimeout(() => {
-        this.reconnectAttempts++;
-        this.connect().catch(error => {
-          console.error('Reconnection failed:', error);
-        });
-      }, delay);
-    } else {
-      console.error('Max reconnection attempts reached. Giving up.');
-    }
+    // Exponential backoff capped at RECONNECT_MAX_DELAY_MS, never giving up.
+    // Half the delay is randomized so collectors don't reconnect in lockstep.
+    const ceiling = Math.min(
+      CONFIG.RECONNECT_BASE_DELAY_MS * Math.pow(2, Math.min(this.reconnectAttempts, 20)),
+      CONFIG.RECONNECT_MAX_DELAY_MS
+    );
+    const delay = Math.round(ceiling / 2 + Math.random() * (ceiling / 2));
+    this.reconnectAttempts++;
+    this.reconnectCount++;
+
+    // A clean close keeps the previous error visible
+    this.writeHealth('disconnected', reason !== undefined ? { connectedAt: null, lastError: reason } : { connectedAt: null });
+    this.log.info('Reconnecting', { delayMs: delay, attempt: this.reconnectAttempts });
+    this.hooks.onReconnectScheduled?.(delay, this.reconnectAttempts);
+
+    this.reconnectTimer = setTimeout(() => {
+      this.reconnectTimer = null;
+      this.connect().catch(error => {
+        this.log.error('Reconnection failed', { error });
+      });
+    }, delay);
   }
 
-  public destroy(): void {
+  /**
+   * Stop the collector. Resolves once its queued database writes have finished.
+   */
+  public destroy(): Promise<void> {
     this.isDestroyed = true;
     this.messageBuffer = ''; // Clear message buffer
+    this.resetSv2State();
+    this.writeHealth('stopped', { connectedAt: null });
     
     if (this.reconnectTimer) {
       clearTimeout(this.reconnectTimer);
@@ -384,6 +990,8 @@ class StratumCollector {
       }
       this.socket = null;
     }
+
+    return this.writes;
   }
 
   /**
@@ -399,32 +1007,105 @@ class StratumCollector {
         triggerDelayedCollection(previousBlock);
       })
       .catch(error => {
-        console.error('Error getting block height for highest diff collection:', error);
+        this.log.error('Error getting block height for highest diff collection', { error });
       });
   }
 }
 
-// Singleton instance
-let stratumCollector: StratumCollector | null = null;
+/**
+ * Parse STRATUM_ENDPOINTS, a JSON array of endpoints, e.g.
+ * [{"name":"Parasite","host":"parasite.wtf","port":42069,"username":"bc1q..."}]
+ *
+ * Falls back to the Parasite endpoint when unset or invalid. Without an explicit
+ * `primary` flag, the endpoint named Parasite is treated as primary.
+ */
+export function getStratumEndpoints(): StratumEndpoint[] {
+  const raw = process.env.STRATUM_ENDPOINTS;
+  if (!raw) return getDefaultEndpoints();
 
-export function startStratumCollector(): StratumCollector {
-  if (stratumCollector) {
-    return stratumCollector;
-  }
+  try {
+    const parsed: unknown = JSON.parse(raw);
+    if (!Array.isArray(parsed) || parsed.length === 0) {
+      throw new Error('expected a non-empty array');
+    }
 
-  stratumCollector = new StratumCollector();
-  
-  stratumCollector.connect().catch(error => {
-    console.error('Failed to start stratum collector:', error);
-  });
+    const endpoints = parsed.map((entry, index): StratumEndpoint => {
+      const e = entry as Partial<StratumEndpoint>;
+      if (typeof e.name !== 'string' || !e.name ||
+          typeof e.host !== 'string' || !e.host ||
+          !Number.isInteger(e.port) || (e.port as number) <= 0 ||
+          typeof e.username !== 'string' || !e.username) {
+        throw new Error(`entry ${index} needs name, host, port and username`);
+      }
+      return {
+        name: e.name,
+        host: e.host,
+        port: e.port as number,
+        username: e.username,
+        password: typeof e.password === 'string' ? e.password : undefined,
+        primary: e.primary ?? e.name === PRIMARY_POOL_NAME,
+        protocol: e.protocol === 'v2' ? 'v2' : 'v1',
+      };
+    });
+
+    const names = new Set(endpoints.map(e => e.name));
+    if (names.size !== endpoints.length) {
+      throw new Error('endpoint names must be unique');
+    }
 
-  return stratumCollector;
+    return endpoints;
+  } catch (error) {
+    log.warn('Ignoring invalid STRATUM_ENDPOINTS, using default endpoint', {
+      error: error instanceof Error ? error.message : String(error),
+    });
+    return getDefaultEndpoints();
+  }
 }
 
-export function stopStratumCollector(): void {
-  if (stratumCollector) {
-    stratumCollector.destroy();
-    stratumCollector = null;
+/**
+ * Drop health rows for endpoints that are no longer configured, so they don't
+ * show up as dead feeds forever
+ */
+async function removeStaleHealthRows(endpoints: StratumEndpoint[]): Promise<void> {
+  try {
+    const placeholders = endpoints.map(() => '?').join(', ');
+    const db = await getDb();
+    await db.run(
+      `DELETE FROM stratum_health WHERE pool NOT IN (${placeholders})`,
+      endpoints.map(endpoint => endpoint.name)
+    );
+  } catch (error) {
+    log.error('Error removing stale stratum health rows', { error });
   }
 }
 
+// One collector per configured endpoint
+const stratumCollectors: Map<string, StratumCollector> = new Map();
+
+export function startStratumCollectors(): StratumCollector[] {
+  if (stratumCollectors.size > 0) {
+    return [...stratumCollectors.values()];
+  }
+
+  registerLocalStratumPublisher();
+
+  const endpoints = getStratumEndpoints();
+  void removeStaleHealthRows(endpoints);
+
+  for (const endpoint of endpoints) {
+    const collector = new StratumCollector(endpoint);
+    stratumCollectors.set(endpoint.name, collector);
+
+    collector.connect().catch(error => {
+      log.error('Failed to start stratum collector', { pool: endpoint.name, error });
+    });
+  }
+
+  return [...stratumCollectors.values()];
+}
+
+export async function stopStratumCollectors(): Promise<void> {
+  const collectors = [...stratumCollectors.values()];
+  stratumCollectors.clear();
+  await Promise.all(collectors.map(collector => collector.destroy()));
+}
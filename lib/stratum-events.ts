@@ -0,0 +1,120 @@
+import { EventEmitter } from 'events';
+import { getDb } from './db';
+import { createLogger } from './logger';
+import {
+  toStratumNotification,
+  type StratumNotification,
+  type StratumNotificationRow,
+} from '@/app/api/stratum/types';
+
+/**
+ * In-process event bus for stratum notifications
+ *
+ * The stratum collector publishes every stored notification here. The jobs
+ * process and the Next.js server are normally separate processes, so when
+ * nothing publishes locally the bus tails `stratum_notifications` by id
+ * while anyone is subscribed and republishes new rows itself.
+ */
+
+const CONFIG = {
+  TAIL_INTERVAL_MS: 250,
+  TAIL_BATCH_SIZE: 100,
+};
+
+const log = createLogger({ module: 'stratum-events' });
+
+const NOTIFICATION_EVENT = 'notification';
+
+const emitter = new EventEmitter();
+// One listener per connected SSE client
+emitter.setMaxListeners(0);
+
+let hasLocalPublisher = false;
+let tailTimer: NodeJS.Timeout | null = null;
+let isStartingTail = false;
+let isPolling = false;
+let lastSeenId = 0;
+
+export type StratumNotificationListener = (notification: StratumNotification) => void;
+
+/**
+ * Called by the collector when it runs in this process, so the bus doesn't
+ * also tail the database and deliver each notification twice
+ */
+export function registerLocalStratumPublisher(): void {
+  hasLocalPublisher = true;
+  stopTailing();
+}
+
+export function publishStratumNotification(notification: StratumNotification): void {
+  emitter.emit(NOTIFICATION_EVENT, notification);
+}
+
+/**
+ * Subscribe to new notifications. Returns a function that unsubscribes.
+ */
+export function subscribeStratumNotifications(listener: StratumNotificationListener): () => void {
+  emitter.on(NOTIFICATION_EVENT, listener);
+  if (!hasLocalPublisher) void startTailing();
+
+  return () => {
+    emitter.off(NOTIFICATION_EVENT, listener);
+    if (emitter.listenerCount(NOTIFICATION_EVENT) === 0) stopTailing();
+  };
+}
+
+async function startTailing(): Promise<void> {
+  if (tailTimer || isStartingTail) return;
+  isStartingTail = true;
+
+  try {
+    // Only rows inserted after the first subscriber arrives are streamed;
+    // clients load the current state from /api/stratum themselves
+    const db = await getDb();
+    const row = await db.get<{ maxId: number }>(
+      'SELECT COALESCE(MAX(id), 0) AS "maxId" FROM stratum_notifications'
+    );
+    lastSeenId = row?.maxId ?? 0;
+  } catch (error) {
+    log.error('Error initializing stratum notification tail', { error });
+    return;
+  } finally {
+    isStartingTail = false;
+  }
+
+  // Everyone may have unsubscribed, or a local publisher registered, meanwhile
+  if (hasLocalPublisher || emitter.listenerCount(NOTIFICATION_EVENT) === 0) return;
+  tailTimer = setInterval(pollNewNotifications, CONFIG.TAIL_INTERVAL_MS);
+}
+
+function stopTailing(): void {
+  if (tailTimer) {
+    clearInterval(tailTimer);
+    tailTimer = null;
+  }
+}
+
+async function pollNewNotifications(): Promise<void> {
+  // A slow query shouldn't overlap the next tick and publish rows twice
+  if (isPolling) return;
+  isPolling = true;
+
+  try {
+    const db = await getDb();
+    const rows = await db.all<StratumNotificationRow>(`
+      SELECT * FROM stratum_notifications
+      WHERE id > ?
+      ORDER BY id ASC
+      LIMIT ?
+    `, [lastSeenId, CONFIG.TAIL_BATCH_SIZE]);
+
+    for (const row of rows) {
+      lastSeenId = row.id;
+      publishStratumNotification(toStratumNotification(row));
+    }
+  } catch (error) {
+    log.error('Error polling stratum notifications', { error, lastSeenId });
+  } finally {
+    isPolling = false;
+  }
+}
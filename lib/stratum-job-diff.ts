@@ -0,0 +1,219 @@
+import { getDb } from './db';
+import { createLogger } from './logger';
+import type { StratumNotification } from '@/app/api/stratum/types';
+import {
+  computeCoinbaseOutputs,
+  computeCoinbaseOutputValue,
+  decodeCoinbaseScriptSigInfo,
+  getTransaction,
+} from '@/app/utils/bitcoinUtils';
+
+/**
+ * Stratum job diffing
+ *
+ * Summarizes every job as it's recorded and compares it with the previous
+ * job from the same pool on the same prev_block_hash, so the template's
+ * evolution over a block can be replayed after the raw notifications have
+ * been trimmed.
+ */
+
+const CONFIG = {
+  RETENTION_DAYS: 7,
+  CLEANUP_INTERVAL: 500, // Run retention cleanup every 500 recorded jobs
+};
+
+const log = createLogger({ module: 'stratum-job-diff' });
+
+const HALVING_INTERVAL = 210000;
+const INITIAL_SUBSIDY = 50 * 100_000_000;
+
+interface JobOutput {
+  script: string;
+  value: number;
+  address?: string;
+}
+
+interface JobSummary {
+  pool: string;
+  prevBlockHash: string;
+  jobId: string;
+  merkleBranchCount: number;
+  outputs: JobOutput[];
+  coinbaseValue: number;
+  nTime: string;
+  version: string;
+}
+
+// Values derived from a job's coinbase, returned for callers that aggregate per block
+export interface RecordedJob {
+  blockHeight?: number;
+  coinbaseValue: number;
+  fees: number | null;
+}
+
+// Last summarized job per pool; reloaded from the table after a restart
+const lastJobs = new Map<string, JobSummary>();
+let recordedCount = 0;
+
+export function getBlockSubsidy(height: number): number {
+  const halvings = Math.floor(height / HALVING_INTERVAL);
+  if (halvings >= 64) return 0;
+  return Math.floor(INITIAL_SUBSIDY / 2 ** halvings);
+}
+
+function buildCoinbaseRaw(notification: StratumNotification): string {
+  // Extranonce values don't affect outputs, so placeholders are enough
+  const extranonce1 = notification.extranonce1 || '00000000';
+  const extranonce2Size = notification.extranonce2Size || 4;
+  return notification.coinbase1 + extranonce1 + '00'.repeat(extranonce2Size) + notification.coinbase2;
+}
+
+async function loadPreviousJob(pool: string, prevBlockHash: string): Promise<JobSummary | undefined> {
+  const cached = lastJobs.get(pool);
+  if (cached) {
+    return cached.prevBlockHash === prevBlockHash ? cached : undefined;
+  }
+
+  const db = await getDb();
+  const row = await db.get<{
+    job_id: string;
+    merkle_branch_count: number;
+    outputs: string;
+    coinbase_value: number;
+    n_time: string;
+    version: string;
+  }>(`
+    SELECT job_id, merkle_branch_count, outputs, coinbase_value, n_time, version
+    FROM stratum_job_diffs
+    WHERE pool = ? AND prev_block_hash = ?
+    ORDER BY id DESC
+    LIMIT 1
+  `, [pool, prevBlockHash]);
+
+  if (!row) return undefined;
+
+  return {
+    pool,
+    prevBlockHash,
+    jobId: row.job_id,
+    merkleBranchCount: row.merkle_branch_count,
+    outputs: JSON.parse(row.outputs) as JobOutput[],
+    coinbaseValue: row.coinbase_value,
+    nTime: row.n_time,
+    version: row.version,
+  };
+}
+
+/**
+ * Outputs are matched by script; a value change on the same script (e.g. the
+ * pool's reward output growing with fees) is reported via coinbase_value_delta
+ */
+function diffOutputs(previous: JobOutput[], current: JobOutput[]): { added: JobOutput[]; removed: JobOutput[] } {
+  const previousScripts = new Set(previous.map(output => output.script));
+  const currentScripts = new Set(current.map(output => output.script));
+
+  return {
+    added: current.filter(output => !previousScripts.has(output.script)),
+    removed: previous.filter(output => !currentScripts.has(output.script)),
+  };
+}
+
+/**
+ * Summarize a recorded notification and store it with its deltas against the
+ * previous job on the same prev_block_hash
+ */
+export async function recordJobDiff(notification: StratumNotification): Promise<RecordedJob | null> {
+  try {
+    const coinbaseRaw = buildCoinbaseRaw(notification);
+
+    let blockHeight: number | undefined;
+    try {
+      const tx = getTransaction(coinbaseRaw);
+      blockHeight = decodeCoinbaseScriptSigInfo(Buffer.from(tx.ins[0].script)).height;
+    } catch (error) {
+      log.error('Error decoding coinbase height', { pool: notification.pool, jobId: notification.job_id, error });
+    }
+
+    const outputs: JobOutput[] = computeCoinbaseOutputs(coinbaseRaw).map(output => ({
+      script: output.hex ?? '',
+      value: output.value,
+      address: output.address,
+    }));
+    const coinbaseValue = computeCoinbaseOutputValue(coinbaseRaw);
+    const fees = blockHeight !== undefined ? Math.max(coinbaseValue - getBlockSubsidy(blockHeight), 0) : null;
+
+    const summary: JobSummary = {
+      pool: notification.pool,
+      prevBlockHash: notification.prevBlockHash,
+      jobId: notification.jobId,
+      merkleBranchCount: notification.merkleBranches.length,
+      outputs,
+      coinbaseValue,
+      nTime: notification.nTime,
+      version: notification.version,
+    };
+
+    const previous = await loadPreviousJob(notification.pool, notification.prevBlockHash);
+    const outputChanges = previous ? diffOutputs(previous.outputs, outputs) : null;
+
+    const db = await getDb();
+    await db.run(`
+      INSERT INTO stratum_job_diffs (
+        notification_id, pool, prev_block_hash, block_height, job_id, timestamp, clean_jobs,
+        merkle_branch_count, output_count, coinbase_value, fees, n_time, version, outputs,
+        previous_job_id, merkle_branch_delta, output_count_delta, coinbase_value_delta,
+        n_time_changed, version_changed, outputs_added, outputs_removed
+      ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+      ON CONFLICT DO NOTHING
+    `, [
+      notification.id,
+      notification.pool,
+      notification.prevBlockHash,
+      blockHeight ?? null,
+      notification.jobId,
+      notification.timestamp,
+      notification.cleanJobs ? 1 : 0,
+      summary.merkleBranchCount,
+      outputs.length,
+      coinbaseValue,
+      fees,
+      notification.nTime,
+      notification.version,
+      JSON.stringify(outputs),
+      previous?.jobId ?? null,
+      previous ? summary.merkleBranchCount - previous.merkleBranchCount : null,
+      previous ? outputs.length - previous.outputs.length : null,
+      previous ? coinbaseValue - previous.coinbaseValue : null,
+      previous ? (summary.nTime !== previous.nTime ? 1 : 0) : null,
+      previous ? (summary.version !== previous.version ? 1 : 0) : null,
+      outputChanges ? JSON.stringify(outputChanges.added) : null,
+      outputChanges ? JSON.stringify(outputChanges.removed) : null,
+    ]);
+
+    lastJobs.set(notification.pool, summary);
+
+    recordedCount++;
+    if (recordedCount % CONFIG.CLEANUP_INTERVAL === 0) {
+      await cleanupOldJobDiffs();
+    }
+
+    return { blockHeight, coinbaseValue, fees };
+  } catch (error) {
+    log.error('Error recording job diff', { pool: notification.pool, jobId: notification.job_id, error });
+    return null;
+  }
+}
+
+async function cleanupOldJobDiffs(): Promise<void> {
+  try {
+    const cutoff = Math.floor(Date.now() / 1000) - CONFIG.RETENTION_DAYS * 24 * 60 * 60;
+    const db = await getDb();
+    const result = await db.run('DELETE FROM stratum_job_diffs WHERE timestamp < ?', [cutoff]);
+
+    if (result.changes > 0) {
+      log.info('Cleaned up old stratum job diffs', { rows: result.changes });
+    }
+  } catch (error) {
+    log.error('Error cleaning up stratum job diffs', { error });
+  }
+}
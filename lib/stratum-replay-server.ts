@@ -0,0 +1,238 @@
+import net from 'net';
+import fs from 'fs';
+import { createLogger } from './logger';
+
+/**
+ * Stratum replay server
+ *
+ * A local stand-in for a stratum V1 pool. It answers the collector's
+ * mining.configure / subscribe / authorize requests, then replays a captured
+ * session (one JSON message per line, the format of `raw_message`) with
+ * controllable pacing, fragmentation and disconnects.
+ */
+
+export interface ReplayOptions {
+  /** Delay between replayed messages */
+  messageDelayMs?: number;
+  /**
+   * Split the outgoing byte stream into writes of at most this many bytes.
+   * 0 writes each message on its own; a negative value coalesces the whole
+   * session into as few writes as possible.
+   */
+  chunkSize?: number;
+  /** Delay between the chunks of a fragmented write */
+  chunkDelayMs?: number;
+  /** Drop each connection after replaying this many messages */
+  disconnectAfterMessages?: number;
+  /** Destroy this many incoming connections before answering anything */
+  rejectConnections?: number;
+  extranonce1?: string;
+  extranonce2Size?: number;
+  /** mining.configure result; null answers with an "unknown method" error */
+  configureResult?: Record<string, unknown> | null;
+}
+
+export interface ReplayConnection {
+  index: number;
+  openedAt: number;
+  closedAt: number | null;
+  rejected: boolean;
+  requests: Array<{ id: number | null; method: string; params: unknown[] }>;
+  messagesSent: number;
+}
+
+const DEFAULT_OPTIONS = {
+  messageDelayMs: 0,
+  chunkSize: 0,
+  chunkDelayMs: 0,
+  disconnectAfterMessages: Infinity,
+  rejectConnections: 0,
+  extranonce1: 'f000000d',
+  extranonce2Size: 8,
+  configureResult: {
+    'version-rolling': true,
+    'version-rolling.mask': '1fffe000',
+    'minimum-difficulty': true,
+  } as Record<string, unknown> | null,
+};
+
+/**
+ * Read a session file, skipping blank lines. Each line must be a JSON stratum
+ * message; anything else is a corrupt capture and throws.
+ */
+export function loadReplaySession(filePath: string): string[] {
+  return fs.readFileSync(filePath, 'utf8')
+    .split('\n')
+    .map(line => line.trim())
+    .filter(line => line.length > 0)
+    .map((line, index) => {
+      try {
+        return JSON.stringify(JSON.parse(line));
+      } catch {
+        throw new Error(`${filePath}:${index + 1} is not a JSON stratum message`);
+      }
+    });
+}
+
+const sleep = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
+
+const log = createLogger({ module: 'stratum-replay' });
+
+export class StratumReplayServer {
+  private server: net.Server;
+  private sockets: Set<net.Socket> = new Set();
+  private options: typeof DEFAULT_OPTIONS;
+  readonly connections: ReplayConnection[] = [];
+
+  constructor(private session: string[], options: ReplayOptions = {}) {
+    this.options = { ...DEFAULT_OPTIONS, ...options };
+    this.server = net.createServer(socket => this.handleConnection(socket));
+  }
+
+  /** Listen on 127.0.0.1; port 0 picks a free port. Resolves with the bound port. */
+  listen(port: number = 0): Promise<number> {
+    return new Promise((resolve, reject) => {
+      this.server.once('error', reject);
+      this.server.listen(port, '127.0.0.1', () => {
+        this.server.off('error', reject);
+        resolve((this.server.address() as net.AddressInfo).port);
+      });
+    });
+  }
+
+  close(): Promise<void> {
+    for (const socket of this.sockets) {
+      socket.destroy();
+    }
+    return new Promise(resolve => this.server.close(() => resolve()));
+  }
+
+  /** Drop every open connection without stopping the server */
+  disconnectAll(): void {
+    for (const socket of this.sockets) {
+      socket.destroy();
+    }
+  }
+
+  private handleConnection(socket: net.Socket): void {
+    const connection: ReplayConnection = {
+      index: this.connections.length,
+      openedAt: Date.now(),
+      closedAt: null,
+      rejected: false,
+      requests: [],
+      messagesSent: 0,
+    };
+    this.connections.push(connection);
+    this.sockets.add(socket);
+
+    socket.on('close', () => {
+      connection.closedAt = Date.now();
+      this.sockets.delete(socket);
+    });
+    socket.on('error', () => {
+      // The collector hanging up mid-write is expected
+    });
+
+    if (connection.index < this.options.rejectConnections) {
+      connection.rejected = true;
+      socket.destroy();
+      return;
+    }
+
+    let buffer = '';
+    socket.on('data', data => {
+      buffer += data.toString();
+      const lines = buffer.split('\n');
+      buffer = lines.pop() || '';
+
+      for (const line of lines) {
+        if (!line.trim()) continue;
+        try {
+          const request = JSON.parse(line) as { id?: number; method?: string; params?: unknown[] };
+          connection.requests.push({
+            id: request.id ?? null,
+            method: String(request.method),
+            params: request.params ?? [],
+          });
+          this.handleRequest(socket, connection, request);
+        } catch (error) {
+          log.error('Replay server received an unparseable request', { error });
+        }
+      }
+    });
+  }
+
+  private handleRequest(
+    socket: net.Socket,
+    connection: ReplayConnection,
+    request: { id?: number; method?: string; params?: unknown[] }
+  ): void {
+    const id = request.id ?? null;
+
+    switch (request.method) {
+      case 'mining.configure':
+        if (this.options.configureResult === null) {
+          this.write(socket, [JSON.stringify({ id, result: null, error: [20, 'Unknown method', null] })]);
+        } else {
+          this.write(socket, [JSON.stringify({ id, result: this.options.configureResult, error: null })]);
+        }
+        break;
+      case 'mining.subscribe':
+        this.write(socket, [JSON.stringify({
+          id,
+          result: [
+            [['mining.set_difficulty', 'replay'], ['mining.notify', 'replay']],
+            this.options.extranonce1,
+            this.options.extranonce2Size,
+          ],
+          error: null,
+        })]);
+        break;
+      case 'mining.authorize':
+        this.write(socket, [JSON.stringify({ id, result: true, error: null })]).then(() => {
+          this.replay(socket, connection).catch(error => {
+            log.error('Replay server failed to replay session', { error });
+          });
+        });
+        break;
+      default:
+        this.write(socket, [JSON.stringify({ id, result: null, error: [20, 'Unknown method', null] })]);
+    }
+  }
+
+  private async replay(socket: net.Socket, connection: ReplayConnection): Promise<void> {
+    const limit = Math.min(this.session.length, this.options.disconnectAfterMessages);
+    const messages = this.session.slice(0, limit);
+
+    if (this.options.chunkSize < 0) {
+      await this.write(socket, messages);
+      connection.messagesSent = messages.length;
+    } else {
+      for (const message of messages) {
+        if (socket.destroyed) return;
+        if (this.options.messageDelayMs > 0) await sleep(this.options.messageDelayMs);
+        await this.write(socket, [message]);
+        connection.messagesSent++;
+      }
+    }
+
+    // end() rather than destroy() so the replayed messages are flushed first
+    if (this.options.disconnectAfterMessages !== Infinity) {
+      socket.end();
+    }
+  }
+
+  private async write(socket: net.Socket, messages: string[]): Promise<void> {
+    const payload = Buffer.from(messages.map(message => message + '\n').join(''));
+    const chunkSize = this.options.chunkSize > 0 ? this.options.chunkSize : payload.length;
+
+    for (let offset = 0; offset < payload.length; offset += chunkSize) {
+      if (socket.destroyed) return;
+      socket.write(payload.subarray(offset, offset + chunkSize));
+      if (this.options.chunkDelayMs > 0 && offset + chunkSize < payload.length) {
+        await sleep(this.options.chunkDelayMs);
+      }
+    }
+  }
+}
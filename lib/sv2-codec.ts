@@ -0,0 +1,436 @@
+/**
+ * Stratum V2 binary message encoding for the Mining protocol
+ *
+ * Only the messages the collector needs to open an extended channel and
+ * observe jobs are implemented, plus the other direction of each where the
+ * tests need to play the pool. All integers are little-endian; U256 values
+ * are kept as raw 32-byte buffers in wire order.
+ */
+
+export const FRAME_HEADER_LENGTH = 6;
+
+// Set on extension_type for messages addressed to a specific channel
+const CHANNEL_MSG_BIT = 0x8000;
+
+export const MessageType = {
+  SETUP_CONNECTION: 0x00,
+  SETUP_CONNECTION_SUCCESS: 0x01,
+  SETUP_CONNECTION_ERROR: 0x02,
+  OPEN_EXTENDED_MINING_CHANNEL: 0x13,
+  OPEN_EXTENDED_MINING_CHANNEL_SUCCESS: 0x14,
+  OPEN_MINING_CHANNEL_ERROR: 0x12,
+  NEW_MINING_JOB: 0x15,
+  SET_EXTRANONCE_PREFIX: 0x19,
+  NEW_EXTENDED_MINING_JOB: 0x1f,
+  SET_NEW_PREV_HASH: 0x20,
+  SET_TARGET: 0x21,
+  RECONNECT: 0x25,
+} as const;
+
+const MINING_PROTOCOL = 0;
+const PROTOCOL_VERSION = 2;
+
+export interface FrameHeader {
+  extensionType: number;
+  msgType: number;
+  msgLength: number;
+}
+
+export interface SetupConnection {
+  protocol: number;
+  minVersion: number;
+  maxVersion: number;
+  flags: number;
+  endpointHost: string;
+  endpointPort: number;
+  vendor: string;
+  hardwareVersion: string;
+  firmware: string;
+  deviceId: string;
+}
+
+export interface OpenExtendedMiningChannel {
+  requestId: number;
+  userIdentity: string;
+  nominalHashRate: number;
+  maxTarget: string;
+  minExtranonceSize: number;
+}
+
+export interface SetupConnectionSuccess {
+  usedVersion: number;
+  flags: number;
+}
+
+export interface ErrorMessage {
+  requestId?: number;
+  errorCode: string;
+}
+
+export interface OpenExtendedMiningChannelSuccess {
+  requestId: number;
+  channelId: number;
+  target: string;
+  extranonceSize: number;
+  extranoncePrefix: string;
+}
+
+export interface NewMiningJob {
+  channelId: number;
+  jobId: number;
+  minNtime: number | null;
+  version: number;
+  merkleRoot: string;
+}
+
+export interface NewExtendedMiningJob {
+  channelId: number;
+  jobId: number;
+  minNtime: number | null;
+  version: number;
+  versionRollingAllowed: boolean;
+  merklePath: string[];
+  coinbaseTxPrefix: string;
+  coinbaseTxSuffix: string;
+}
+
+export interface SetNewPrevHash {
+  channelId: number;
+  jobId: number;
+  prevHash: string;
+  minNtime: number;
+  nbits: number;
+}
+
+export interface SetTarget {
+  channelId: number;
+  maximumTarget: string;
+}
+
+export interface SetExtranoncePrefix {
+  channelId: number;
+  extranoncePrefix: string;
+}
+
+class Writer {
+  private parts: Buffer[] = [];
+
+  u8(value: number): this {
+    this.parts.push(Buffer.from([value]));
+    return this;
+  }
+
+  u16(value: number): this {
+    const buf = Buffer.alloc(2);
+    buf.writeUInt16LE(value);
+    this.parts.push(buf);
+    return this;
+  }
+
+  u32(value: number): this {
+    const buf = Buffer.alloc(4);
+    buf.writeUInt32LE(value);
+    this.parts.push(buf);
+    return this;
+  }
+
+  f32(value: number): this {
+    const buf = Buffer.alloc(4);
+    buf.writeFloatLE(value);
+    this.parts.push(buf);
+    return this;
+  }
+
+  u256(value: Buffer): this {
+    if (value.length !== 32) throw new Error('U256 must be 32 bytes');
+    this.parts.push(value);
+    return this;
+  }
+
+  optionU32(value: number | null): this {
+    return value === null ? this.u8(0) : this.u8(1).u32(value);
+  }
+
+  str0_255(value: string): this {
+    const bytes = Buffer.from(value, 'utf8');
+    if (bytes.length > 255) throw new Error(`STR0_255 too long: ${bytes.length} bytes`);
+    this.parts.push(Buffer.from([bytes.length]), bytes);
+    return this;
+  }
+
+  toBuffer(): Buffer {
+    return Buffer.concat(this.parts);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private buf: Buffer) {}
+
+  private take(length: number): Buffer {
+    if (this.offset + length > this.buf.length) {
+      throw new Error(`SV2 message truncated: need ${length} bytes at offset ${this.offset}`);
+    }
+    const slice = this.buf.subarray(this.offset, this.offset + length);
+    this.offset += length;
+    return slice;
+  }
+
+  u8(): number {
+    return this.take(1).readUInt8(0);
+  }
+
+  u16(): number {
+    return this.take(2).readUInt16LE(0);
+  }
+
+  u32(): number {
+    return this.take(4).readUInt32LE(0);
+  }
+
+  f32(): number {
+    return this.take(4).readFloatLE(0);
+  }
+
+  bool(): boolean {
+    return this.u8() !== 0;
+  }
+
+  u256(): Buffer {
+    return this.take(32);
+  }
+
+  str0_255(): string {
+    return this.take(this.u8()).toString('utf8');
+  }
+
+  b0_32(): Buffer {
+    const length = this.u8();
+    if (length > 32) throw new Error(`B0_32 length ${length} exceeds 32`);
+    return this.take(length);
+  }
+
+  b0_64k(): Buffer {
+    return this.take(this.u16());
+  }
+
+  optionU32(): number | null {
+    // OPTION is encoded as a SEQ0_1
+    return this.u8() === 0 ? null : this.u32();
+  }
+
+  seq0_255U256(): Buffer[] {
+    const count = this.u8();
+    const items: Buffer[] = [];
+    for (let i = 0; i < count; i++) items.push(this.u256());
+    return items;
+  }
+}
+
+export function encodeFrameHeader(header: FrameHeader): Buffer {
+  const buf = Buffer.alloc(FRAME_HEADER_LENGTH);
+  buf.writeUInt16LE(header.extensionType, 0);
+  buf.writeUInt8(header.msgType, 2);
+  buf.writeUIntLE(header.msgLength, 3, 3);
+  return buf;
+}
+
+export function decodeFrameHeader(buf: Buffer): FrameHeader {
+  return {
+    extensionType: buf.readUInt16LE(0),
+    msgType: buf.readUInt8(2),
+    msgLength: buf.readUIntLE(3, 3),
+  };
+}
+
+export function isChannelMessage(header: FrameHeader): boolean {
+  return (header.extensionType & CHANNEL_MSG_BIT) !== 0;
+}
+
+export function encodeSetupConnection(options: {
+  endpointHost: string;
+  endpointPort: number;
+  vendor: string;
+  firmware: string;
+}): Buffer {
+  return new Writer()
+    .u8(MINING_PROTOCOL)
+    .u16(PROTOCOL_VERSION)
+    .u16(PROTOCOL_VERSION)
+    .u32(0) // flags: no standard-job, work-selection or version-rolling requirements
+    .str0_255(options.endpointHost)
+    .u16(options.endpointPort)
+    .str0_255(options.vendor)
+    .str0_255('') // hardware_version
+    .str0_255(options.firmware)
+    .str0_255('') // device_id
+    .toBuffer();
+}
+
+export function encodeOpenExtendedMiningChannel(options: {
+  requestId: number;
+  userIdentity: string;
+  nominalHashRate: number;
+  minExtranonceSize: number;
+}): Buffer {
+  return new Writer()
+    .u32(options.requestId)
+    .str0_255(options.userIdentity)
+    .f32(options.nominalHashRate)
+    .u256(Buffer.alloc(32, 0xff)) // max_target: accept any share target
+    .u16(options.minExtranonceSize)
+    .toBuffer();
+}
+
+export function decodeSetupConnection(payload: Buffer): SetupConnection {
+  const r = new Reader(payload);
+  return {
+    protocol: r.u8(),
+    minVersion: r.u16(),
+    maxVersion: r.u16(),
+    flags: r.u32(),
+    endpointHost: r.str0_255(),
+    endpointPort: r.u16(),
+    vendor: r.str0_255(),
+    hardwareVersion: r.str0_255(),
+    firmware: r.str0_255(),
+    deviceId: r.str0_255(),
+  };
+}
+
+export function decodeOpenExtendedMiningChannel(payload: Buffer): OpenExtendedMiningChannel {
+  const r = new Reader(payload);
+  return {
+    requestId: r.u32(),
+    userIdentity: r.str0_255(),
+    nominalHashRate: r.f32(),
+    maxTarget: r.u256().toString('hex'),
+    minExtranonceSize: r.u16(),
+  };
+}
+
+export function decodeSetupConnectionSuccess(payload: Buffer): SetupConnectionSuccess {
+  const r = new Reader(payload);
+  return { usedVersion: r.u16(), flags: r.u32() };
+}
+
+export function decodeSetupConnectionError(payload: Buffer): ErrorMessage {
+  const r = new Reader(payload);
+  r.u32(); // flags
+  return { errorCode: r.str0_255() };
+}
+
+export function decodeOpenMiningChannelError(payload: Buffer): ErrorMessage {
+  const r = new Reader(payload);
+  return { requestId: r.u32(), errorCode: r.str0_255() };
+}
+
+export function decodeOpenExtendedMiningChannelSuccess(payload: Buffer): OpenExtendedMiningChannelSuccess {
+  const r = new Reader(payload);
+  return {
+    requestId: r.u32(),
+    channelId: r.u32(),
+    target: r.u256().toString('hex'),
+    extranonceSize: r.u16(),
+    extranoncePrefix: r.b0_32().toString('hex'),
+  };
+}
+
+export function decodeNewMiningJob(payload: Buffer): NewMiningJob {
+  const r = new Reader(payload);
+  return {
+    channelId: r.u32(),
+    jobId: r.u32(),
+    minNtime: r.optionU32(),
+    version: r.u32(),
+    merkleRoot: r.u256().toString('hex'),
+  };
+}
+
+export function encodeNewMiningJob(job: NewMiningJob): Buffer {
+  return new Writer()
+    .u32(job.channelId)
+    .u32(job.jobId)
+    .optionU32(job.minNtime)
+    .u32(job.version)
+    .u256(Buffer.from(job.merkleRoot, 'hex'))
+    .toBuffer();
+}
+
+export function decodeNewExtendedMiningJob(payload: Buffer): NewExtendedMiningJob {
+  const r = new Reader(payload);
+  return {
+    channelId: r.u32(),
+    jobId: r.u32(),
+    minNtime: r.optionU32(),
+    version: r.u32(),
+    versionRollingAllowed: r.bool(),
+    merklePath: r.seq0_255U256().map(hash => hash.toString('hex')),
+    coinbaseTxPrefix: r.b0_64k().toString('hex'),
+    coinbaseTxSuffix: r.b0_64k().toString('hex'),
+  };
+}
+
+export function decodeSetNewPrevHash(payload: Buffer): SetNewPrevHash {
+  const r = new Reader(payload);
+  return {
+    channelId: r.u32(),
+    jobId: r.u32(),
+    prevHash: r.u256().toString('hex'),
+    minNtime: r.u32(),
+    nbits: r.u32(),
+  };
+}
+
+export function encodeSetNewPrevHash(message: SetNewPrevHash): Buffer {
+  return new Writer()
+    .u32(message.channelId)
+    .u32(message.jobId)
+    .u256(Buffer.from(message.prevHash, 'hex'))
+    .u32(message.minNtime)
+    .u32(message.nbits)
+    .toBuffer();
+}
+
+export function decodeSetExtranoncePrefix(payload: Buffer): SetExtranoncePrefix {
+  const r = new Reader(payload);
+  return { channelId: r.u32(), extranoncePrefix: r.b0_32().toString('hex') };
+}
+
+export function decodeSetTarget(payload: Buffer): SetTarget {
+  const r = new Reader(payload);
+  return { channelId: r.u32(), maximumTarget: r.u256().toString('hex') };
+}
+
+// Difficulty 1 target (0x00000000ffff0000...0000)
+const DIFF1_TARGET = BigInt('0x00000000ffff0000000000000000000000000000000000000000000000000000');
+
+/**
+ * Convert an SV2 U256 target (little-endian hex) to the equivalent SV1 share difficulty
+ */
+export function targetToDifficulty(target: string): number {
+  const value = BigInt('0x' + Buffer.from(target, 'hex').reverse().toString('hex'));
+  if (value === BigInt(0)) return 0;
+  return Number(DIFF1_TARGET) / Number(value);
+}
+
+/**
+ * Convert an SV2 prev_hash (internal byte order) to the SV1 mining.notify
+ * representation, which reverses the bytes of each 32-bit word
+ */
+export function toStratumV1PrevHash(prevHash: string): string {
+  const bytes = Buffer.from(prevHash, 'hex');
+  const words: Buffer[] = [];
+  for (let i = 0; i < bytes.length; i += 4) {
+    words.push(Buffer.from(bytes.subarray(i, i + 4)).reverse());
+  }
+  return Buffer.concat(words).toString('hex');
+}
+
+/**
+ * Format a U32 as SV1 does for version, nbits and ntime (big-endian hex)
+ */
+export function toStratumV1Hex(value: number): string {
+  return value.toString(16).padStart(8, '0');
+}
@@ -0,0 +1,352 @@
+import crypto from 'crypto';
+
+/**
+ * Noise NX handshake and transport encryption for Stratum V2
+ *
+ * Implements the initiator side of Noise_NX_Secp256k1+EllSwift_ChaChaPoly_SHA256
+ * as specified by the SV2 protocol. Public keys are exchanged as 64-byte
+ * ElligatorSwift encodings (BIP 324) and ECDH uses BIP 324's x-only hash, so
+ * the only primitives needed beyond Node's crypto are the ElligatorSwift
+ * field maps implemented below.
+ *
+ * The collector only observes jobs and never submits shares, so the server's
+ * certificate is checked for its validity window but its signature is not
+ * verified against a pool authority key.
+ */
+
+const PROTOCOL_NAME = 'Noise_NX_Secp256k1+EllSwift_ChaChaPoly_SHA256';
+const ELLSWIFT_LENGTH = 64;
+const MAC_LENGTH = 16;
+const SIGNATURE_NOISE_MESSAGE_LENGTH = 74;
+
+// Responder's reply: ephemeral key, encrypted static key, encrypted certificate
+export const HANDSHAKE_RESPONSE_LENGTH =
+  ELLSWIFT_LENGTH + (ELLSWIFT_LENGTH + MAC_LENGTH) + (SIGNATURE_NOISE_MESSAGE_LENGTH + MAC_LENGTH);
+
+// Encrypted frames are split into chunks of at most 65535 bytes including the MAC
+export const MAX_CHUNK_PLAINTEXT = 65535 - MAC_LENGTH;
+
+/**
+ * Number of bytes an encrypted payload of `length` plaintext bytes occupies on the wire
+ */
+export function encryptedPayloadLength(length: number): number {
+  if (length === 0) return 0;
+  return length + Math.ceil(length / MAX_CHUNK_PLAINTEXT) * MAC_LENGTH;
+}
+
+/**
+ * Certificate sent by the pool at the end of the handshake
+ */
+export interface SignatureNoiseMessage {
+  version: number;
+  validFrom: number;
+  notValidAfter: number;
+  signature: string;
+}
+
+// secp256k1 field arithmetic (p = 2^256 - 2^32 - 977)
+const P = 2n ** 256n - 2n ** 32n - 977n;
+
+function mod(a: bigint): bigint {
+  const r = a % P;
+  return r >= 0n ? r : r + P;
+}
+
+function powMod(base: bigint, exponent: bigint): bigint {
+  let result = 1n;
+  let b = mod(base);
+  let e = exponent;
+  while (e > 0n) {
+    if (e & 1n) result = (result * b) % P;
+    b = (b * b) % P;
+    e >>= 1n;
+  }
+  return result;
+}
+
+function invert(a: bigint): bigint {
+  return powMod(a, P - 2n);
+}
+
+function sqrt(a: bigint): bigint | null {
+  const value = mod(a);
+  const root = powMod(value, (P + 1n) / 4n);
+  return (root * root) % P === value ? root : null;
+}
+
+function isValidX(x: bigint): boolean {
+  return sqrt(x ** 3n + 7n) !== null;
+}
+
+const MINUS_3_SQRT = sqrt(-3n)!;
+
+function toBytes32(value: bigint): Buffer {
+  return Buffer.from(value.toString(16).padStart(64, '0'), 'hex');
+}
+
+function fromBytes(bytes: Buffer): bigint {
+  return BigInt('0x' + bytes.toString('hex'));
+}
+
+/**
+ * Decode an ElligatorSwift (u, t) pair to an x coordinate on the curve (BIP 324 XSwiftEC)
+ */
+export function xswiftec(uIn: bigint, tIn: bigint): bigint {
+  let u = mod(uIn);
+  let t = mod(tIn);
+  if (u === 0n) u = 1n;
+  if (t === 0n) t = 1n;
+  if (mod(u ** 3n + t * t + 7n) === 0n) t = mod(2n * t);
+
+  const X = mod((u ** 3n + 7n - t * t) * invert(2n * t));
+  const Y = mod((X + t) * invert(MINUS_3_SQRT * u));
+  const candidates = [
+    mod(u + 4n * Y * Y),
+    mod((-X * invert(Y) - u) * invert(2n)),
+    mod((X * invert(Y) - u) * invert(2n)),
+  ];
+
+  for (const x of candidates) {
+    if (isValidX(x)) return x;
+  }
+  throw new Error('XSwiftEC produced no valid x coordinate');
+}
+
+/**
+ * Find t such that xswiftec(u, t) = x for one of the 8 inverse cases (BIP 324 XSwiftECInv)
+ */
+export function xswiftecInv(x: bigint, u: bigint, branch: number): bigint | null {
+  let v = x;
+  let s: bigint;
+
+  if ((branch & 2) === 0) {
+    if (isValidX(mod(-x - u))) return null;
+    s = mod(-(u ** 3n + 7n) * invert(u * u + u * v + v * v));
+  } else {
+    s = mod(x - u);
+    if (s === 0n) return null;
+    let r = sqrt(-s * (4n * (u ** 3n + 7n) + 3n * s * u * u));
+    if (r === null) return null;
+    if ((branch & 1) && r === 0n) return null;
+    if (branch & 1) r = mod(-r);
+    v = mod((-u + r * invert(s)) * invert(2n));
+  }
+
+  let w = sqrt(s);
+  if (w === null) return null;
+  if (branch & 4) w = mod(-w);
+  return mod(w * (u * (MINUS_3_SQRT - 1n) * invert(2n) - v));
+}
+
+function decodeEllSwift(encoding: Buffer): bigint {
+  return xswiftec(fromBytes(encoding.subarray(0, 32)), fromBytes(encoding.subarray(32, 64)));
+}
+
+export interface EllSwiftKeypair {
+  ecdh: crypto.ECDH;
+  encoding: Buffer;
+}
+
+export function createEllSwiftKeypair(): EllSwiftKeypair {
+  const ecdh = crypto.createECDH('secp256k1');
+  ecdh.generateKeys();
+  const x = fromBytes(ecdh.getPublicKey().subarray(1, 33));
+
+  // Randomized search as in BIP 324's ellswift_create; roughly 1 in 4 tries succeeds
+  for (;;) {
+    const u = fromBytes(crypto.randomBytes(32)) % P;
+    if (u === 0n) continue;
+    const t = xswiftecInv(x, u, crypto.randomInt(8));
+    if (t === null || xswiftec(u, t) !== x) continue;
+    return { ecdh, encoding: Buffer.concat([toBytes32(u), toBytes32(t)]) };
+  }
+}
+
+function sha256(...parts: Buffer[]): Buffer {
+  return crypto.createHash('sha256').update(Buffer.concat(parts)).digest();
+}
+
+function hmacSha256(key: Buffer, ...parts: Buffer[]): Buffer {
+  return crypto.createHmac('sha256', key).update(Buffer.concat(parts)).digest();
+}
+
+const ECDH_TAG = sha256(Buffer.from('bip324_ellswift_xonly_ecdh'));
+
+/**
+ * BIP 324 x-only ECDH where the initiator is always party A
+ */
+export function ellSwiftEcdh(
+  local: EllSwiftKeypair,
+  remote: Buffer,
+  role: 'initiator' | 'responder' = 'initiator'
+): Buffer {
+  const remoteX = decodeEllSwift(remote);
+  // Either y works: x(d * P) == x(d * -P)
+  const shared = local.ecdh.computeSecret(Buffer.concat([Buffer.from([0x02]), toBytes32(remoteX)]));
+  return role === 'initiator'
+    ? sha256(ECDH_TAG, ECDH_TAG, local.encoding, remote, shared)
+    : sha256(ECDH_TAG, ECDH_TAG, remote, local.encoding, shared);
+}
+
+function hkdf(chainingKey: Buffer, inputKeyMaterial: Buffer): [Buffer, Buffer] {
+  const tempKey = hmacSha256(chainingKey, inputKeyMaterial);
+  const output1 = hmacSha256(tempKey, Buffer.from([0x01]));
+  const output2 = hmacSha256(tempKey, output1, Buffer.from([0x02]));
+  return [output1, output2];
+}
+
+class CipherState {
+  private nonce = 0n;
+
+  constructor(private key: Buffer) {}
+
+  private nextNonce(): Buffer {
+    // 32 zero bits followed by the 64-bit little-endian counter
+    const nonce = Buffer.alloc(12);
+    nonce.writeBigUInt64LE(this.nonce++, 4);
+    return nonce;
+  }
+
+  encrypt(ad: Buffer, plaintext: Buffer): Buffer {
+    const cipher = crypto.createCipheriv('chacha20-poly1305', this.key, this.nextNonce(), {
+      authTagLength: MAC_LENGTH,
+    });
+    cipher.setAAD(ad, { plaintextLength: plaintext.length });
+    return Buffer.concat([cipher.update(plaintext), cipher.final(), cipher.getAuthTag()]);
+  }
+
+  decrypt(ad: Buffer, ciphertext: Buffer): Buffer {
+    const body = ciphertext.subarray(0, ciphertext.length - MAC_LENGTH);
+    const decipher = crypto.createDecipheriv('chacha20-poly1305', this.key, this.nextNonce(), {
+      authTagLength: MAC_LENGTH,
+    });
+    decipher.setAAD(ad, { plaintextLength: body.length });
+    decipher.setAuthTag(ciphertext.subarray(ciphertext.length - MAC_LENGTH));
+    return Buffer.concat([decipher.update(body), decipher.final()]);
+  }
+}
+
+const EMPTY = Buffer.alloc(0);
+
+/**
+ * Initiator side of the SV2 Noise NX handshake plus the resulting transport ciphers
+ */
+export class Sv2NoiseInitiator {
+  private h: Buffer;
+  private ck: Buffer;
+  private handshakeCipher: CipherState | null = null;
+  private ephemeral: EllSwiftKeypair | null = null;
+  private sendCipher: CipherState | null = null;
+  private receiveCipher: CipherState | null = null;
+
+  constructor() {
+    this.h = sha256(Buffer.from(PROTOCOL_NAME));
+    this.ck = this.h;
+    // Empty prologue
+    this.mixHash(EMPTY);
+  }
+
+  get isHandshakeComplete(): boolean {
+    return this.sendCipher !== null && this.receiveCipher !== null;
+  }
+
+  private mixHash(data: Buffer): void {
+    this.h = sha256(this.h, data);
+  }
+
+  private mixKey(inputKeyMaterial: Buffer): void {
+    const [chainingKey, key] = hkdf(this.ck, inputKeyMaterial);
+    this.ck = chainingKey;
+    this.handshakeCipher = new CipherState(key);
+  }
+
+  private decryptAndHash(ciphertext: Buffer): Buffer {
+    if (!this.handshakeCipher) {
+      this.mixHash(ciphertext);
+      return ciphertext;
+    }
+    const plaintext = this.handshakeCipher.decrypt(this.h, ciphertext);
+    this.mixHash(ciphertext);
+    return plaintext;
+  }
+
+  /**
+   * Act 1: `-> e`. Returns the 64 bytes to send to the pool.
+   */
+  createHello(): Buffer {
+    this.ephemeral = createEllSwiftKeypair();
+    this.mixHash(this.ephemeral.encoding);
+    // EncryptAndHash of the empty payload without a key is just MixHash
+    this.mixHash(EMPTY);
+    return this.ephemeral.encoding;
+  }
+
+  /**
+   * Act 2: `<- e, ee, s, es, SIGNATURE_NOISE_MESSAGE`. Derives the transport keys.
+   */
+  readResponse(message: Buffer): SignatureNoiseMessage {
+    if (!this.ephemeral) {
+      throw new Error('Noise handshake response received before hello was sent');
+    }
+    if (message.length !== HANDSHAKE_RESPONSE_LENGTH) {
+      throw new Error(`Noise handshake response must be ${HANDSHAKE_RESPONSE_LENGTH} bytes, got ${message.length}`);
+    }
+
+    const remoteEphemeral = message.subarray(0, ELLSWIFT_LENGTH);
+    this.mixHash(remoteEphemeral);
+    this.mixKey(ellSwiftEcdh(this.ephemeral, remoteEphemeral));
+
+    const staticEnd = ELLSWIFT_LENGTH * 2 + MAC_LENGTH;
+    const remoteStatic = this.decryptAndHash(message.subarray(ELLSWIFT_LENGTH, staticEnd));
+    this.mixKey(ellSwiftEcdh(this.ephemeral, remoteStatic));
+
+    const certificate = this.decryptAndHash(message.subarray(staticEnd));
+
+    const [initiatorKey, responderKey] = hkdf(this.ck, EMPTY);
+    this.sendCipher = new CipherState(initiatorKey);
+    this.receiveCipher = new CipherState(responderKey);
+    this.handshakeCipher = null;
+    this.ephemeral = null;
+
+    const signatureNoiseMessage: SignatureNoiseMessage = {
+      version: certificate.readUInt16LE(0),
+      validFrom: certificate.readUInt32LE(2),
+      notValidAfter: certificate.readUInt32LE(6),
+      signature: certificate.subarray(10, SIGNATURE_NOISE_MESSAGE_LENGTH).toString('hex'),
+    };
+
+    const now = Math.floor(Date.now() / 1000);
+    if (now < signatureNoiseMessage.validFrom || now > signatureNoiseMessage.notValidAfter) {
+      throw new Error(
+        `Pool certificate not valid now (valid ${signatureNoiseMessage.validFrom}-${signatureNoiseMessage.notValidAfter})`
+      );
+    }
+
+    return signatureNoiseMessage;
+  }
+
+  /**
+   * Encrypt one transport message, chunking the plaintext as SV2 requires
+   */
+  encrypt(plaintext: Buffer): Buffer {
+    if (!this.sendCipher) throw new Error('Noise handshake not complete');
+    const chunks: Buffer[] = [];
+    for (let offset = 0; offset < plaintext.length; offset += MAX_CHUNK_PLAINTEXT) {
+      chunks.push(this.sendCipher.encrypt(EMPTY, plaintext.subarray(offset, offset + MAX_CHUNK_PLAINTEXT)));
+    }
+    return Buffer.concat(chunks);
+  }
+
+  /**
+   * Decrypt a transport message produced by `encryptedPayloadLength`-sized reads
+   */
+  decrypt(ciphertext: Buffer): Buffer {
+    if (!this.receiveCipher) throw new Error('Noise handshake not complete');
+    const chunks: Buffer[] = [];
+    const chunkSize = MAX_CHUNK_PLAINTEXT + MAC_LENGTH;
+    for (let offset = 0; offset < ciphertext.length; offset += chunkSize) {
+      chunks.push(this.receiveCipher.decrypt(EMPTY, ciphertext.subarray(offset, offset + chunkSize)));
+    }
+    return Buffer.concat(chunks);
+  }
+}
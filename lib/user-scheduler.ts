@@ -0,0 +1,277 @@
+import type { Db } from './db';
+import { parsePositiveInt } from './env';
+
+/**
+ * Adaptive user collection schedule
+ *
+ * Rather than fetching every active user every minute, each user is due again
+ * after an interval that follows their activity: users whose page was viewed
+ * recently, who have notification subscriptions or who hash above
+ * USER_POLL_LOW_HASHRATE_THS are polled every USER_POLL_ACTIVE_SECONDS,
+ * low-hashrate users every USER_POLL_LOW_HASHRATE_SECONDS and users without a
+ * share in the last hour every USER_POLL_IDLE_SECONDS. Each cycle collects
+ * at most USER_POLL_MAX_PER_CYCLE due users, highest priority and most
+ * overdue first; the rest stay due for the next cycle.
+ */
+
+const CONFIG = {
+  ACTIVE_INTERVAL_SECONDS: parsePositiveInt(process.env.USER_POLL_ACTIVE_SECONDS, 60),
+  LOW_HASHRATE_INTERVAL_SECONDS: parsePositiveInt(process.env.USER_POLL_LOW_HASHRATE_SECONDS, 300),
+  IDLE_INTERVAL_SECONDS: parsePositiveInt(process.env.USER_POLL_IDLE_SECONDS, 900),
+  LOW_HASHRATE_HS: parsePositiveInt(process.env.USER_POLL_LOW_HASHRATE_THS, 1) * 1e12,
+  MAX_USERS_PER_CYCLE: parsePositiveInt(process.env.USER_POLL_MAX_PER_CYCLE, 5000),
+  // No share for this long counts as idle
+  IDLE_AFTER_SECONDS: 60 * 60,
+  // A page view keeps the user on the active interval for this long
+  VIEW_BOOST_SECONDS: 15 * 60,
+  // Page views are written at most this often per user
+  VIEW_RECORD_INTERVAL_SECONDS: 60,
+};
+
+export interface SchedulePolicy {
+  activeIntervalSeconds: number;
+  lowHashrateIntervalSeconds: number;
+  idleIntervalSeconds: number;
+  lowHashrateHs: number;
+  idleAfterSeconds: number;
+  viewBoostSeconds: number;
+  maxUsersPerCycle: number;
+}
+
+export const DEFAULT_SCHEDULE_POLICY: SchedulePolicy = {
+  activeIntervalSeconds: CONFIG.ACTIVE_INTERVAL_SECONDS,
+  lowHashrateIntervalSeconds: CONFIG.LOW_HASHRATE_INTERVAL_SECONDS,
+  idleIntervalSeconds: CONFIG.IDLE_INTERVAL_SECONDS,
+  lowHashrateHs: CONFIG.LOW_HASHRATE_HS,
+  idleAfterSeconds: CONFIG.IDLE_AFTER_SECONDS,
+  viewBoostSeconds: CONFIG.VIEW_BOOST_SECONDS,
+  maxUsersPerCycle: CONFIG.MAX_USERS_PER_CYCLE,
+};
+
+export interface ScheduledUser {
+  id: number;
+  address: string;
+  /** 0 for users never collected */
+  next_collect_at: number;
+  last_collected_at: number | null;
+  hashrate_hs: number;
+  last_share_at: number | null;
+  last_viewed_at: number | null;
+  subscribed: number;
+}
+
+export type UserActivity = 'watched' | 'active' | 'low_hashrate' | 'idle' | 'new';
+
+export function classifyUserActivity(
+  user: ScheduledUser,
+  now: number,
+  policy: SchedulePolicy = DEFAULT_SCHEDULE_POLICY
+): UserActivity {
+  if (user.subscribed || (user.last_viewed_at !== null && now - user.last_viewed_at < policy.viewBoostSeconds)) {
+    return 'watched';
+  }
+  if (user.last_collected_at === null) return 'new';
+  if (user.last_share_at === null || now - user.last_share_at >= policy.idleAfterSeconds || user.hashrate_hs <= 0) {
+    return 'idle';
+  }
+  return user.hashrate_hs < policy.lowHashrateHs ? 'low_hashrate' : 'active';
+}
+
+export function collectionIntervalSeconds(activity: UserActivity, policy: SchedulePolicy = DEFAULT_SCHEDULE_POLICY): number {
+  switch (activity) {
+    case 'idle':
+      return policy.idleIntervalSeconds;
+    case 'low_hashrate':
+      return policy.lowHashrateIntervalSeconds;
+    default:
+      return policy.activeIntervalSeconds;
+  }
+}
+
+const PRIORITY: Record<UserActivity, number> = {
+  watched: 4,
+  new: 3,
+  active: 2,
+  low_hashrate: 1,
+  idle: 0,
+};
+
+/**
+ * Split users into the ones to collect this cycle and the due ones left for
+ * later. Users whose next_collect_at is still ahead are neither.
+ */
+export function selectDueUsers(
+  users: ScheduledUser[],
+  now: number,
+  policy: SchedulePolicy = DEFAULT_SCHEDULE_POLICY
+): { selected: ScheduledUser[]; deferred: ScheduledUser[]; due: number } {
+  const due = users
+    .filter(user => user.next_collect_at <= now)
+    .map(user => ({ user, priority: PRIORITY[classifyUserActivity(user, now, policy)] }))
+    .sort((a, b) => b.priority - a.priority || a.user.next_collect_at - b.user.next_collect_at)
+    .map(entry => entry.user);
+
+  return {
+    selected: due.slice(0, policy.maxUsersPerCycle),
+    deferred: due.slice(policy.maxUsersPerCycle),
+    due: due.length,
+  };
+}
+
+/**
+ * When a user collected at `now` is due again. Up to 10% is taken off the
+ * interval so users discovered together drift apart instead of all coming
+ * due in the same cycle.
+ */
+export function nextCollectAt(
+  activity: UserActivity,
+  now: number,
+  policy: SchedulePolicy = DEFAULT_SCHEDULE_POLICY,
+  random: () => number = Math.random
+): number {
+  const interval = collectionIntervalSeconds(activity, policy);
+  return now + interval - Math.floor(interval * 0.1 * random());
+}
+
+/** Schedule entries, with subscription flags, for the given active users */
+export async function loadSchedule(db: Db, users: { id: number; address: string }[]): Promise<ScheduledUser[]> {
+  const rows = await db.all<ScheduledUser>(`
+    SELECT
+      u.id,
+      u.address,
+      COALESCE(s.next_collect_at, 0) AS next_collect_at,
+      s.last_collected_at,
+      COALESCE(s.hashrate_hs, 0) AS hashrate_hs,
+      s.last_share_at,
+      s.last_viewed_at,
+      CASE WHEN EXISTS (
+        SELECT 1 FROM notification_subscriptions n WHERE n.address = u.address AND n.is_active = 1
+      ) THEN 1 ELSE 0 END AS subscribed
+    FROM monitored_users u
+    LEFT JOIN user_collection_schedule s ON s.user_id = u.id
+    WHERE u.is_active = 1
+  `);
+
+  const wanted = new Set(users.map(user => user.id));
+  return rows.filter(row => wanted.has(row.id));
+}
+
+/**
+ * Store a successful collection and when the user is due next. Runs inside
+ * the collector's batch transaction.
+ */
+export async function recordCollection(
+  db: Db,
+  user: ScheduledUser,
+  sample: { hashrateHs: number; lastShareAt: number | null },
+  now: number
+): Promise<void> {
+  const updated: ScheduledUser = {
+    ...user,
+    last_collected_at: now,
+    hashrate_hs: sample.hashrateHs,
+    last_share_at: sample.lastShareAt,
+  };
+  const next = nextCollectAt(classifyUserActivity(updated, now), now);
+
+  await db.run(`
+    INSERT INTO user_collection_schedule (user_id, next_collect_at, last_collected_at, hashrate_hs, last_share_at)
+    VALUES (?, ?, ?, ?, ?)
+    ON CONFLICT(user_id) DO UPDATE SET
+      next_collect_at = excluded.next_collect_at,
+      last_collected_at = excluded.last_collected_at,
+      hashrate_hs = excluded.hashrate_hs,
+      last_share_at = excluded.last_share_at
+  `, [user.id, next, now, sample.hashrateHs, sample.lastShareAt]);
+}
+
+/**
+ * Note that someone is looking at a user's page, which moves the user onto
+ * the active interval and makes them due now. Writes at most once a minute
+ * per user.
+ */
+export async function recordUserView(db: Db, address: string): Promise<void> {
+  const now = Math.floor(Date.now() / 1000);
+  await db.run(`
+    INSERT INTO user_collection_schedule AS s (user_id, next_collect_at, last_viewed_at)
+    SELECT id, ?, ? FROM monitored_users WHERE address = ?
+    ON CONFLICT(user_id) DO UPDATE SET
+      last_viewed_at = excluded.last_viewed_at,
+      next_collect_at = CASE
+        WHEN s.next_collect_at > excluded.next_collect_at THEN excluded.next_collect_at
+        ELSE s.next_collect_at
+      END
+    WHERE s.last_viewed_at IS NULL OR s.last_viewed_at < ?
+  `, [now, now, address, now - CONFIG.VIEW_RECORD_INTERVAL_SECONDS]);
+}
+
+export interface CollectionCycleReport {
+  startedAt: number;
+  durationMs: number;
+  /** Active users in the pool that could be collected */
+  eligible: number;
+  due: number;
+  collected: number;
+  failed: number;
+  /** Due users left for a later cycle (over the per-cycle limit or cut off by a rate limit) */
+  deferred: number;
+  rateLimited: boolean;
+  /** Share of eligible users that are on schedule after the cycle */
+  coverage: number;
+  /** How long collected users waited past their due time */
+  avgLagSeconds: number;
+  maxLagSeconds: number;
+}
+
+export function buildCycleReport(
+  input: {
+    startedAt: number;
+    finishedAtMs: number;
+    eligible: number;
+    due: number;
+    collected: ScheduledUser[];
+    failed: number;
+    deferred: number;
+    rateLimited: boolean;
+  }
+): CollectionCycleReport {
+  const lags = input.collected
+    .filter(user => user.next_collect_at > 0)
+    .map(user => Math.max(input.startedAt - user.next_collect_at, 0));
+  const onSchedule = input.eligible - input.due + input.collected.length;
+
+  return {
+    startedAt: input.startedAt,
+    durationMs: Math.max(input.finishedAtMs - input.startedAt * 1000, 0),
+    eligible: input.eligible,
+    due: input.due,
+    collected: input.collected.length,
+    failed: input.failed,
+    deferred: input.deferred,
+    rateLimited: input.rateLimited,
+    coverage: input.eligible > 0 ? onSchedule / input.eligible : 1,
+    avgLagSeconds: lags.length > 0 ? lags.reduce((sum, lag) => sum + lag, 0) / lags.length : 0,
+    maxLagSeconds: lags.length > 0 ? Math.max(...lags) : 0,
+  };
+}
+
+export async function storeCycleReport(db: Db, report: CollectionCycleReport): Promise<void> {
+  await db.run(`
+    INSERT INTO user_collection_cycles (
+      started_at, duration_ms, eligible_users, due_users, collected, failed,
+      deferred, rate_limited, coverage, avg_lag_seconds, max_lag_seconds
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+  `, [
+    report.startedAt,
+    report.durationMs,
+    report.eligible,
+    report.due,
+    report.collected,
+    report.failed,
+    report.deferred,
+    report.rateLimited ? 1 : 0,
+    report.coverage,
+    report.avgLagSeconds,
+    report.maxLagSeconds,
+  ]);
+}
@@ -0,0 +1,71 @@
+/**
+ * User hashrate history queries
+ *
+ * SQL over user_stats_history for the historical user chart, one row per
+ * interval.
+ */
+
+export const AGGREGATES = ['last', 'avg', 'max', 'p50', 'p95'] as const;
+export type Aggregate = typeof AGGREGATES[number];
+
+/**
+ * One row per interval with the chosen aggregate of `column` from
+ * user_stats_history. Percentiles use the nearest-rank method over each
+ * interval's non-NULL samples. Parameters, in order: start, interval, user id,
+ * start, end.
+ */
+export function aggregateQuery(aggregate: Aggregate, column: string): string {
+  const samples = `
+    WITH samples AS (
+      SELECT (created_at - ?) / ? AS bucket, created_at, ${column} AS hashrate
+      FROM user_stats_history
+      WHERE user_id = ? AND created_at >= ? AND created_at < ?
+    )
+  `;
+
+  if (aggregate === 'p50' || aggregate === 'p95') {
+    const percent = aggregate === 'p50' ? 50 : 95;
+    // Nearest rank is CEIL(samples * percent / 100), in integer arithmetic
+    return `
+      ${samples},
+      ranked AS (
+        SELECT
+          bucket,
+          hashrate,
+          ROW_NUMBER() OVER (PARTITION BY bucket ORDER BY hashrate) AS position,
+          COUNT(*) OVER (PARTITION BY bucket) AS sample_count
+        FROM samples
+        WHERE hashrate IS NOT NULL
+      )
+      SELECT bucket, hashrate
+      FROM ranked
+      WHERE position = (sample_count * ${percent} + 99) / 100
+      ORDER BY bucket ASC
+    `;
+  }
+
+  if (aggregate === 'last') {
+    return `
+      ${samples},
+      ranked AS (
+        SELECT
+          bucket,
+          hashrate,
+          ROW_NUMBER() OVER (PARTITION BY bucket ORDER BY created_at DESC) AS position
+        FROM samples
+      )
+      SELECT bucket, hashrate
+      FROM ranked
+      WHERE position = 1
+      ORDER BY bucket ASC
+    `;
+  }
+
+  return `
+    ${samples}
+    SELECT bucket, ${aggregate === 'avg' ? 'AVG' : 'MAX'}(hashrate) AS hashrate
+    FROM samples
+    GROUP BY bucket
+    ORDER BY bucket ASC
+  `;
+}
@@ -0,0 +1,212 @@
+import type { Db } from './db';
+import { parsePositiveInt } from './env';
+
+/**
+ * Worker state transitions
+ *
+ * Each user sample classifies every worker by the age of its last share:
+ * online, idle after WORKER_IDLE_AFTER_SECONDS, offline after
+ * WORKER_OFFLINE_AFTER_SECONDS (or once the pool stops listing it). A change
+ * from the state stored in worker_state becomes a worker_events row. While
+ * online, an hourly hashrate more than WORKER_HASHRATE_DROP_PERCENT below
+ * the daily one raises `hashrate_drop`; `hashrate_recovered` follows once it
+ * is back within half that margin, so a hashrate hovering at the threshold
+ * doesn't flap.
+ */
+
+const CONFIG = {
+  IDLE_AFTER_SECONDS: parsePositiveInt(process.env.WORKER_IDLE_AFTER_SECONDS, 600),
+  OFFLINE_AFTER_SECONDS: parsePositiveInt(process.env.WORKER_OFFLINE_AFTER_SECONDS, 3600),
+  HASHRATE_DROP_PERCENT: Math.min(parsePositiveInt(process.env.WORKER_HASHRATE_DROP_PERCENT, 50), 100),
+};
+
+export type WorkerState = 'online' | 'idle' | 'offline';
+export type WorkerEventType = WorkerState | 'hashrate_drop' | 'hashrate_recovered';
+
+export interface WorkerThresholds {
+  idleAfterSeconds: number;
+  offlineAfterSeconds: number;
+  hashrateDropPercent: number;
+}
+
+export const DEFAULT_WORKER_THRESHOLDS: WorkerThresholds = {
+  idleAfterSeconds: CONFIG.IDLE_AFTER_SECONDS,
+  offlineAfterSeconds: CONFIG.OFFLINE_AFTER_SECONDS,
+  hashrateDropPercent: CONFIG.HASHRATE_DROP_PERCENT,
+};
+
+export interface WorkerSample {
+  workerName: string;
+  lastshare: number;
+  /** Hourly hashrate in H/s, compared against the daily baseline */
+  hashrate: number;
+  baselineHashrate: number;
+}
+
+export interface WorkerStateRow {
+  worker_name: string;
+  state: WorkerState;
+  hashrate_dropped: number;
+  lastshare: number;
+}
+
+export interface WorkerEvent {
+  workerName: string;
+  type: WorkerEventType;
+  previousState: WorkerState | null;
+  state: WorkerState;
+  hashrate: number | null;
+  baselineHashrate: number | null;
+  lastshare: number;
+}
+
+export function classifyWorker(
+  lastshare: number,
+  now: number,
+  thresholds: WorkerThresholds = DEFAULT_WORKER_THRESHOLDS
+): WorkerState {
+  if (lastshare <= 0) return 'offline';
+  const age = now - lastshare;
+  if (age >= thresholds.offlineAfterSeconds) return 'offline';
+  if (age >= thresholds.idleAfterSeconds) return 'idle';
+  return 'online';
+}
+
+/**
+ * Compare a user's current workers with their stored states. Returns the
+ * states to store and the events to record. Stored workers missing from
+ * `samples` go offline; a worker seen for the first time only raises an
+ * event when it is online.
+ */
+export function deriveWorkerEvents(
+  previous: WorkerStateRow[],
+  samples: WorkerSample[],
+  now: number,
+  thresholds: WorkerThresholds = DEFAULT_WORKER_THRESHOLDS
+): { states: WorkerStateRow[]; events: WorkerEvent[] } {
+  const previousByName = new Map(previous.map(row => [row.worker_name, row]));
+  const states: WorkerStateRow[] = [];
+  const events: WorkerEvent[] = [];
+
+  const dropBelow = 1 - thresholds.hashrateDropPercent / 100;
+  const recoverAbove = 1 - thresholds.hashrateDropPercent / 200;
+
+  for (const sample of samples) {
+    const before = previousByName.get(sample.workerName);
+    previousByName.delete(sample.workerName);
+
+    const state = classifyWorker(sample.lastshare, now, thresholds);
+    let dropped = before?.hashrate_dropped === 1;
+
+    if (state !== before?.state && (before || state === 'online')) {
+      events.push({
+        workerName: sample.workerName,
+        type: state,
+        previousState: before?.state ?? null,
+        state,
+        hashrate: sample.hashrate,
+        baselineHashrate: sample.baselineHashrate,
+        lastshare: sample.lastshare,
+      });
+    }
+
+    if (state !== 'online') {
+      // An idle or offline worker's drop is already covered by its state
+      dropped = false;
+    } else if (sample.baselineHashrate > 0) {
+      const ratio = sample.hashrate / sample.baselineHashrate;
+      const type = !dropped && ratio < dropBelow
+        ? 'hashrate_drop'
+        : dropped && ratio >= recoverAbove ? 'hashrate_recovered' : null;
+
+      if (type) {
+        dropped = type === 'hashrate_drop';
+        events.push({
+          workerName: sample.workerName,
+          type,
+          previousState: before?.state ?? null,
+          state,
+          hashrate: sample.hashrate,
+          baselineHashrate: sample.baselineHashrate,
+          lastshare: sample.lastshare,
+        });
+      }
+    }
+
+    states.push({
+      worker_name: sample.workerName,
+      state,
+      hashrate_dropped: dropped ? 1 : 0,
+      lastshare: sample.lastshare,
+    });
+  }
+
+  // Workers the pool no longer lists
+  for (const before of previousByName.values()) {
+    if (before.state === 'offline') continue;
+
+    events.push({
+      workerName: before.worker_name,
+      type: 'offline',
+      previousState: before.state,
+      state: 'offline',
+      hashrate: null,
+      baselineHashrate: null,
+      lastshare: before.lastshare,
+    });
+    states.push({ ...before, state: 'offline', hashrate_dropped: 0 });
+  }
+
+  return { states, events };
+}
+
+/**
+ * Derive and store worker state transitions for one user sample. Runs
+ * inside the collector's batch transaction.
+ */
+export async function recordWorkerEvents(
+  db: Db,
+  userId: number,
+  samples: WorkerSample[],
+  now: number
+): Promise<WorkerEvent[]> {
+  const previous = await db.all<WorkerStateRow>(
+    'SELECT worker_name, state, hashrate_dropped, lastshare FROM worker_state WHERE user_id = ?',
+    [userId]
+  );
+
+  const { states, events } = deriveWorkerEvents(previous, samples, now);
+
+  for (const state of states) {
+    await db.run(`
+      INSERT INTO worker_state (user_id, worker_name, state, hashrate_dropped, lastshare, updated_at)
+      VALUES (?, ?, ?, ?, ?, ?)
+      ON CONFLICT(user_id, worker_name) DO UPDATE SET
+        state = excluded.state,
+        hashrate_dropped = excluded.hashrate_dropped,
+        lastshare = excluded.lastshare,
+        updated_at = excluded.updated_at
+    `, [userId, state.worker_name, state.state, state.hashrate_dropped, state.lastshare, now]);
+  }
+
+  for (const event of events) {
+    await db.run(`
+      INSERT INTO worker_events (
+        user_id, worker_name, event_type, previous_state, state,
+        hashrate_hs, baseline_hashrate_hs, lastshare, created_at
+      ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    `, [
+      userId,
+      event.workerName,
+      event.type,
+      event.previousState,
+      event.state,
+      event.hashrate,
+      event.baselineHashrate,
+      event.lastshare,
+      now,
+    ]);
+  }
+
+  return events;
+}
@@ -0,0 +1,96 @@
+#!/usr/bin/env node
+import 'dotenv/config';
+import path from 'path';
+import { parseArgs } from 'util';
+import { closeDb } from '../lib/db';
+import { createBackup, getBackupDir, listBackups, restoreBackup, verifyBackup } from '../lib/backup';
+
+/**
+ * Back up, verify and restore the stats database
+ *
+ *   pnpm backup create
+ *   pnpm backup list
+ *   pnpm backup verify <file>
+ *   pnpm backup restore <file> --data-dir <dir>
+ *
+ * `create` is safe while the collector and web server are running. `restore`
+ * only writes into a data dir without a database; point PARASTATS_DATA_DIR at
+ * it (or move the file into place) once the processes are stopped.
+ */
+
+const { positionals, values } = parseArgs({
+  allowPositionals: true,
+  options: {
+    'data-dir': { type: 'string' },
+  },
+});
+
+const [command, file] = positionals;
+
+function formatSize(bytes: number): string {
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+function list(): void {
+  const backups = listBackups();
+  console.log(`${backups.length} backup(s) in ${getBackupDir()}`);
+  for (const backup of backups) {
+    console.log(`  ${path.basename(backup.file)} (${formatSize(backup.sizeBytes)}, ${backup.createdAt.toISOString()})`);
+  }
+}
+
+function verify(): void {
+  if (!file) {
+    console.error('Usage: backup verify <file>');
+    process.exitCode = 1;
+    return;
+  }
+
+  const problems = verifyBackup(file);
+  if (problems.length === 0) {
+    console.log(`✅ ${file} passed integrity check`);
+    return;
+  }
+
+  console.error(`❌ ${file} failed integrity check:`);
+  for (const problem of problems) {
+    console.error(`  ${problem}`);
+  }
+  process.exitCode = 1;
+}
+
+function restore(): void {
+  const dataDir = values['data-dir'];
+  if (!file || !dataDir) {
+    console.error('Usage: backup restore <file> --data-dir <dir>');
+    process.exitCode = 1;
+    return;
+  }
+
+  const target = restoreBackup(file, path.resolve(dataDir));
+  console.log(`✅ Restored ${file} to ${target}`);
+}
+
+async function main(): Promise<void> {
+  try {
+    if (command === 'create') {
+      await createBackup();
+    } else if (command === 'list') {
+      list();
+    } else if (command === 'verify') {
+      verify();
+    } else if (command === 'restore') {
+      restore();
+    } else {
+      console.error('Usage: backup <create|list|verify|restore> [file] [--data-dir <dir>]');
+      process.exitCode = 1;
+    }
+  } catch (error) {
+    console.error('Backup command failed:', error);
+    process.exitCode = 1;
+  } finally {
+    await closeDb();
+  }
+}
+
+main();
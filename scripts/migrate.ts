@@ -0,0 +1,77 @@
+#!/usr/bin/env node
+import { parseArgs } from 'util';
+import { closeDb, getDbWithoutMigrations } from '../lib/db';
+
+/**
+ * Inspect and apply schema migrations
+ *
+ *   pnpm migrate status
+ *   pnpm migrate apply [--to 8] [--dry-run]
+ *
+ * A dry run applies the pending migrations in a transaction and rolls it
+ * back, so it catches failures against the real data without changing it.
+ */
+
+const { positionals, values } = parseArgs({
+  allowPositionals: true,
+  options: {
+    to: { type: 'string' },
+    'dry-run': { type: 'boolean', default: false },
+  },
+});
+
+const [command] = positionals;
+
+function formatTimestamp(seconds: number): string {
+  return new Date(seconds * 1000).toISOString();
+}
+
+async function status(): Promise<void> {
+  const { currentVersion, latestVersion, applied, pending } = await getDbWithoutMigrations().getMigrationStatus();
+
+  console.log(`Schema version ${currentVersion} of ${latestVersion}`);
+  for (const migration of applied) {
+    console.log(
+      `  ✅ ${migration.version} ${migration.name} (applied ${formatTimestamp(migration.applied_at)}, ${migration.duration_ms}ms)`
+    );
+  }
+  for (const migration of pending) {
+    console.log(`  ⏳ ${migration.version} ${migration.name}`);
+  }
+}
+
+async function apply(): Promise<void> {
+  const dryRun = values['dry-run'];
+  const targetVersion = values.to !== undefined ? Number(values.to) : undefined;
+
+  const results = await getDbWithoutMigrations().migrate({ dryRun, targetVersion });
+
+  if (results.length === 0) {
+    console.log('No pending migrations');
+  } else if (dryRun) {
+    for (const result of results) {
+      console.log(`  🧪 ${result.version} ${result.name} (${result.durationMs}ms)`);
+    }
+    console.log(`Dry run: ${results.length} migration(s) succeeded and were rolled back`);
+  }
+}
+
+async function main(): Promise<void> {
+  try {
+    if (command === 'status') {
+      await status();
+    } else if (command === 'apply') {
+      await apply();
+    } else {
+      console.error('Usage: migrate <status|apply> [--to <version>] [--dry-run]');
+      process.exitCode = 1;
+    }
+  } catch (error) {
+    console.error('Migration failed:', error);
+    process.exitCode = 1;
+  } finally {
+    await closeDb();
+  }
+}
+
+main();
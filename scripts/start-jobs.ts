@@ -1,105 +1,154 @@
 #!/usr/bin/env node
 import 'dotenv/config';
 import { validateEnv } from "../app/lib/env-validation";
-import {
-  startPoolStatsCollector,
-  purgeOldData,
-} from "../lib/pool-stats-collector";
-import { startStratumCollector, stopStratumCollector } from "../lib/stratum-collector";
-import { startHighestDiffCollector, stopHighestDiffCollector } from "../lib/highest-diff-collector";
-import { startRoundsCollector, stopRoundsCollector } from "../lib/rounds-collector";
-import { checkpointWal, closeDb } from "../lib/db";
+import { poolStatsJobs } from "../lib/pool-stats-collector";
+import { startStratumCollectors, stopStratumCollectors } from "../lib/stratum-collector";
+import { highestDiffJobs, stopHighestDiffCollector } from "../lib/highest-diff-collector";
+import { roundsJobs } from "../lib/rounds-collector";
+import { checkpointWal, closeDb, getDb, getDbWithoutMigrations } from "../lib/db";
+import { createBackup } from "../lib/backup";
 import { syncRefineryBadges } from "../lib/refinery-badge-sync";
-import cron from "node-cron";
+import { deliverPendingNotifications } from "../lib/notifications";
+import { startMetricsServer } from "../lib/metrics-exporter";
+import { abortJobs, getJobsHealth, registerJobs, startJobs, stopJobs } from "../lib/job-supervisor";
+import { getLease, startLeaderElection } from "../lib/leader-lease";
+import { getCircuitStates } from "../lib/circuit-breaker";
+import { parsePositiveInt } from "../lib/env";
+import { createLogger } from "../lib/logger";
+
+const log = createLogger({ module: "jobs" });
 
 // Validate environment variables before starting
 validateEnv();
 
-// Global references to jobs
-let statsCollectorJob = startPoolStatsCollector();
-console.log("📊 Pool stats collector started");
-
 // Log auto-discovery status
 const autoDiscoverEnabled = process.env.AUTO_DISCOVER_USERS !== 'false';
 if (autoDiscoverEnabled) {
-  console.log("👥 User auto-discovery enabled (runs with stats collection; set AUTO_DISCOVER_USERS=false to disable)");
+  log.info("User auto-discovery enabled (runs with stats collection; set AUTO_DISCOVER_USERS=false to disable)");
 } else {
-  console.log("👥 User auto-discovery disabled (set AUTO_DISCOVER_USERS=true to enable)");
+  log.info("User auto-discovery disabled (set AUTO_DISCOVER_USERS=true to enable)");
 }
 
-// Start stratum collector
-let stratumCollector = startStratumCollector();
-console.log("⚡ Stratum collector started");
-
-// Start highest diff collector (backfills on startup, periodic collection every 10 min)
-let highestDiffCollectorJob = startHighestDiffCollector();
-console.log("🏆 Highest diff collector started");
-
-// Start rounds collector (syncs rounds, polls current round every 10 min)
-startRoundsCollector();
-console.log("🔄 Rounds collector started");
-
-// Set up a job to purge old data daily at midnight
-let purgeJob = cron.schedule("0 0 * * *", async () => {
-  await purgeOldData();
-  console.log("🧹 Purged old pool stats data");
+let stratumCollectors: ReturnType<typeof startStratumCollectors> = [];
+
+// Collectors and maintenance jobs, scheduled and tracked by the job supervisor
+registerJobs([
+  ...poolStatsJobs,
+  ...highestDiffJobs,
+  ...roundsJobs,
+  {
+    // Deliver queued notifications (worker offline, top diff, block found)
+    name: "notifications",
+    schedule: "* * * * *",
+    timeoutMs: 5 * 60_000,
+    staleAfterSeconds: 15 * 60,
+    run: deliverPendingNotifications,
+  },
+  {
+    name: "wal_checkpoint",
+    schedule: "*/5 * * * *",
+    timeoutMs: 60_000,
+    staleAfterSeconds: 30 * 60,
+    runOnStart: true,
+    run: checkpointWal,
+  },
+  {
+    name: "refinery_badges",
+    schedule: null,
+    timeoutMs: 60 * 60_000,
+    runOnStart: true,
+    run: syncRefineryBadges,
+  },
+]);
+
+// Back up the SQLite database daily, after the midnight purge
+const backupEnabled = getDbWithoutMigrations().dialect === "sqlite";
+if (backupEnabled) {
+  registerJobs([{
+    name: "backup",
+    schedule: "30 0 * * *",
+    timeoutMs: 60 * 60_000,
+    staleAfterSeconds: 26 * 60 * 60,
+    run: createBackup,
+  }]);
+}
+log.info(backupEnabled ? "Daily backup job enabled" : "Daily backup job disabled (use pg_dump for Postgres)");
+
+// Only the holder of the leader lease collects; other processes wait as hot
+// standbys. A demoted leader exits so its process manager restarts it as one,
+// cancelling its running jobs first since the new leader is already collecting.
+const election = startLeaderElection({
+  onElected: async () => {
+    // Start one stratum collector per configured endpoint (STRATUM_ENDPOINTS)
+    stratumCollectors = startStratumCollectors();
+    log.info("Stratum collectors started", { pools: stratumCollectors.map(c => c.poolName) });
+
+    try {
+      await startJobs();
+    } catch (error) {
+      log.error("Failed to start jobs", { error });
+      void shutdown(1);
+    }
+  },
+  onDemoted: () => {
+    abortJobs("Lost the leader lease");
+    stopHighestDiffCollector();
+    void shutdown(1);
+  },
 });
 
-checkpointWal();
-let checkpointJob = cron.schedule("*/5 * * * *", () => {
-  checkpointWal();
-});
-console.log("🗜️ WAL checkpoint job started");
+// Serve Prometheus metrics and the job health check for this process when METRICS_PORT is set
+const metricsPort = parsePositiveInt(process.env.METRICS_PORT, 0);
+let metricsServer: Awaited<ReturnType<typeof startMetricsServer>> | null = null;
+if (metricsPort > 0) {
+  startMetricsServer(metricsPort, async () => {
+    const db = await getDb();
+    const leader = await getLease(db);
+    // A standby is alive as long as it answers; the leader is judged by its jobs
+    const upstreams = getCircuitStates();
+    if (!election.isLeader()) return { healthy: true, role: "standby", leader, upstreams };
+    return { ...(await getJobsHealth(db)), role: "leader", leader, upstreams };
+  })
+    .then(server => {
+      metricsServer = server;
+      log.info("Metrics server listening", { port: metricsPort, paths: ["/metrics", "/health"] });
+    })
+    .catch(error => log.error("Failed to start metrics server", { error }));
+}
 
 // Handle graceful shutdown
-process.on("SIGTERM", shutdown);
-process.on("SIGINT", shutdown);
+process.on("SIGTERM", () => void shutdown());
+process.on("SIGINT", () => void shutdown());
 
 let isShuttingDown = false;
 
-function shutdown() {
+async function shutdown(exitCode = 0) {
   if (isShuttingDown) return;
   isShuttingDown = true;
-  console.log("🛑 Shutting down jobs...");
-
-  // Stop cron jobs
-  if (statsCollectorJob) {
-    statsCollectorJob.poolJob.stop();
-    statsCollectorJob.userJob.stop();
-    statsCollectorJob.accountJob.stop();
-  }
+  log.info("Shutting down jobs");
 
-  if (purgeJob) {
-    purgeJob.stop();
+  if (metricsServer) {
+    metricsServer.close();
   }
 
-  if (checkpointJob) {
-    checkpointJob.stop();
+  // Stop stratum collectors
+  if (stratumCollectors.length > 0) {
+    await stopStratumCollectors();
+    log.info("Stratum collectors stopped");
   }
 
-  // Stop stratum collector
-  if (stratumCollector) {
-    stopStratumCollector();
-    console.log("⚡ Stratum collector stopped");
-  }
+  // Stop the highest diff collector's delayed collections and historical backfill
+  stopHighestDiffCollector();
 
-  // Stop highest diff collector
-  if (highestDiffCollectorJob) {
-    highestDiffCollectorJob.job.stop();
-    stopHighestDiffCollector();
-    console.log("🏆 Highest diff collector stopped");
-  }
+  // Stop scheduling and let running jobs finish before the database closes
+  await stopJobs();
 
-  // Stop rounds collector
-  stopRoundsCollector();
+  // Hand over to a standby right away instead of after the lease expires
+  await election.stop();
 
   // Close database connection
-  closeDb();
+  await closeDb();
 
-  console.log("✅ Shutdown complete");
-  process.exit(0);
+  log.info("Shutdown complete");
+  process.exit(exitCode);
 }
-
-syncRefineryBadges().catch(err => console.error('🏭 Refinery badge sync error:', err));
-
-console.log("🚀 All jobs initialized and running");
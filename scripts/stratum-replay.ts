@@ -0,0 +1,86 @@
+#!/usr/bin/env node
+import { parseArgs } from 'util';
+import { StratumReplayServer, loadReplaySession } from '../lib/stratum-replay-server';
+
+/**
+ * Capture and replay stratum sessions
+ *
+ *   pnpm stratum-replay export [--pool Parasite] > session.jsonl
+ *   pnpm stratum-replay serve session.jsonl [--port 42069] [--delay 1000]
+ *     [--chunk 16] [--chunk-delay 5] [--disconnect-after 20] [--reject 2]
+ *
+ * Point the collector at a running replay with STRATUM_HOST=127.0.0.1 and
+ * STRATUM_PORT set to the replay port.
+ */
+
+const { positionals, values } = parseArgs({
+  allowPositionals: true,
+  options: {
+    pool: { type: 'string', default: 'Parasite' },
+    port: { type: 'string', default: '42069' },
+    delay: { type: 'string', default: '1000' },
+    chunk: { type: 'string', default: '0' },
+    'chunk-delay': { type: 'string', default: '0' },
+    'disconnect-after': { type: 'string' },
+    reject: { type: 'string', default: '0' },
+  },
+});
+
+const [command, sessionFile] = positionals;
+
+async function exportSession(): Promise<void> {
+  // Imported lazily so `serve` never opens (or creates) the database
+  const { getDb, closeDb } = await import('../lib/db');
+  const db = await getDb();
+  const rows = await db.all<{ raw_message: string }>(`
+    SELECT raw_message FROM stratum_notifications
+    WHERE pool = ?
+    ORDER BY id ASC
+  `, [values.pool]);
+  await closeDb();
+
+  for (const row of rows) {
+    console.log(row.raw_message);
+  }
+  console.error(`-- Exported ${rows.length} messages from ${values.pool}`);
+}
+
+async function serve(): Promise<void> {
+  if (!sessionFile) {
+    console.error('Usage: stratum-replay serve <session.jsonl> [options]');
+    process.exit(1);
+  }
+
+  const session = loadReplaySession(sessionFile);
+  const server = new StratumReplayServer(session, {
+    messageDelayMs: Number(values.delay),
+    chunkSize: Number(values.chunk),
+    chunkDelayMs: Number(values['chunk-delay']),
+    disconnectAfterMessages: values['disconnect-after'] ? Number(values['disconnect-after']) : Infinity,
+    rejectConnections: Number(values.reject),
+  });
+
+  const port = await server.listen(Number(values.port));
+  console.log(`🔁 Replaying ${session.length} messages from ${sessionFile} on 127.0.0.1:${port}`);
+
+  const shutdown = () => {
+    server.close().then(() => process.exit(0));
+  };
+  process.on('SIGTERM', shutdown);
+  process.on('SIGINT', shutdown);
+}
+
+if (command === 'export') {
+  exportSession().catch(error => {
+    console.error('Failed to export stratum session:', error);
+    process.exit(1);
+  });
+} else if (command === 'serve') {
+  serve().catch(error => {
+    console.error('Failed to start replay server:', error);
+    process.exit(1);
+  });
+} else {
+  console.error('Usage: stratum-replay <export|serve> [options]');
+  process.exit(1);
+}
@@ -0,0 +1,53 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type * as BackupModule from '../lib/backup';
+
+// The database and backup module read their configuration at import time
+const dataDir = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-backup-'));
+process.env.PARASTATS_DATA_DIR = dataDir;
+process.env.BACKUP_DIR = path.join(dataDir, 'backups');
+process.env.BACKUP_RETENTION_COUNT = '2';
+
+let backup: typeof BackupModule;
+let closeDb: () => Promise<void>;
+
+before(async () => {
+  backup = await import('../lib/backup');
+  ({ closeDb } = await import('../lib/db'));
+});
+
+after(async () => {
+  await closeDb();
+  fs.rmSync(dataDir, { recursive: true, force: true });
+});
+
+describe('database backups', () => {
+  it('writes a verified copy and keeps only the newest backups', async () => {
+    const first = await backup.createBackup();
+    assert.deepEqual(backup.verifyBackup(first.file), []);
+
+    // Backup names have one-second resolution
+    for (let i = 0; i < 2; i++) {
+      await new Promise(resolve => setTimeout(resolve, 1100));
+      await backup.createBackup();
+    }
+
+    const backups = backup.listBackups();
+    assert.equal(backups.length, 2);
+    assert.ok(!backups.some(info => info.file === first.file));
+  });
+
+  it('restores into an empty data dir only', () => {
+    const [latest] = backup.listBackups();
+    const restoreDir = path.join(dataDir, 'restore');
+
+    const target = backup.restoreBackup(latest.file, restoreDir);
+    assert.deepEqual(backup.verifyBackup(target), []);
+
+    assert.throws(() => backup.restoreBackup(latest.file, restoreDir), /already exists/);
+    assert.throws(() => backup.restoreBackup(latest.file, dataDir), /already exists/);
+  });
+});
@@ -0,0 +1,98 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import {
+  computeMerkleRoot,
+  fromStratumPrevHash,
+  nBitsToTarget,
+  reconstructHeader,
+  rollVersion,
+  targetToDifficulty,
+  toStratumPrevHash,
+} from '../lib/block-header';
+
+/**
+ * The genesis block, split into a stratum job the way a pool would send it:
+ * the scriptSig's first push (04 ffff001d) stands in for extranonce1 and the
+ * second (01 04) for extranonce2.
+ */
+const GENESIS_COINBASE =
+  '01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000';
+const GENESIS_HASH = '000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f';
+const GENESIS_MERKLE_ROOT = '4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b';
+
+const coinbase1 = GENESIS_COINBASE.slice(0, 84);
+const extranonce1 = GENESIS_COINBASE.slice(84, 94);
+const extranonce2 = GENESIS_COINBASE.slice(94, 98);
+const coinbase2 = GENESIS_COINBASE.slice(98);
+
+const genesisJob = {
+  prevBlockHash: '00'.repeat(32),
+  coinbase1,
+  coinbase2,
+  merkleBranches: [],
+  version: '00000001',
+  nBits: '1d00ffff',
+  nTime: '495fab29',
+};
+
+describe('reconstructHeader', () => {
+  it('rebuilds the genesis block header', () => {
+    const result = reconstructHeader(genesisJob, { extranonce1, extranonce2, nonce: '7c2bac1d' });
+
+    assert.equal(result.coinbase, GENESIS_COINBASE);
+    assert.equal(result.coinbaseTxid, GENESIS_MERKLE_ROOT);
+    assert.equal(result.merkleRoot, GENESIS_MERKLE_ROOT);
+    assert.equal(result.header.length, 160);
+    assert.equal(result.hash, GENESIS_HASH);
+    assert.equal(result.networkDifficulty, 1);
+    assert.equal(result.meetsNetworkTarget, true);
+    assert.ok(result.shareDifficulty > 2536 && result.shareDifficulty < 2537);
+  });
+
+  it('misses the target with the wrong nonce', () => {
+    const result = reconstructHeader(genesisJob, { extranonce1, extranonce2, nonce: '00000000' });
+
+    assert.notEqual(result.hash, GENESIS_HASH);
+    assert.equal(result.meetsNetworkTarget, false);
+  });
+
+  it('applies ntime and rolled version bits', () => {
+    const result = reconstructHeader(
+      { ...genesisJob, version: '20000000' },
+      {
+        extranonce1,
+        extranonce2,
+        nonce: '00000000',
+        nTime: '495fab2a',
+        versionBits: '00ffe000',
+        versionRollingMask: '1fffe000',
+      }
+    );
+
+    assert.equal(result.version, '20ffe000');
+    assert.equal(result.nTime, '495fab2a');
+    assert.equal(result.header.slice(0, 8), '00e0ff20');
+  });
+});
+
+describe('helpers', () => {
+  it('folds the coinbase txid up the merkle branches', () => {
+    const branch = 'aa'.repeat(32);
+    assert.notEqual(computeMerkleRoot(GENESIS_COINBASE, [branch]), computeMerkleRoot(GENESIS_COINBASE, []));
+  });
+
+  it('round-trips stratum prev hashes', () => {
+    const prevHash = toStratumPrevHash(GENESIS_HASH);
+    assert.equal(prevHash, '0a8ce26f72b3f1b646a2a6c14ff763ae65831e939c085ae10019d66800000000');
+    assert.equal(fromStratumPrevHash(prevHash), GENESIS_HASH);
+  });
+
+  it('keeps bits outside the rolling mask', () => {
+    assert.equal(rollVersion('20000004', 'ffffffff', '1fffe000'), '3fffe004');
+  });
+
+  it('converts nBits to difficulty', () => {
+    assert.equal(targetToDifficulty(nBitsToTarget('1d00ffff')), 1);
+    assert.ok(Math.abs(targetToDifficulty(nBitsToTarget('1b0404cb')) - 16307.42) < 0.01);
+  });
+});
@@ -0,0 +1,62 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+
+process.env.API_URL = 'https://api.example.com';
+process.env.ROUTER_API_URL = 'https://router.example.com/v1';
+
+import { CircuitBreaker, CircuitOpenError, acquireCircuit, getCircuitBreaker, upstreamForUrl } from '../lib/circuit-breaker';
+import { backoffDelay } from '../lib/retry';
+
+const NOW = 1_700_000_000_000;
+
+describe('circuit breaker', () => {
+  it('opens after consecutive failures and probes once when half-open', () => {
+    const breaker = new CircuitBreaker('dispenser', 3, 30_000);
+
+    breaker.recordFailure(new Error('boom'), NOW);
+    breaker.recordSuccess();
+    breaker.recordFailure(new Error('boom'), NOW);
+    breaker.recordFailure(new Error('boom'), NOW);
+    assert.equal(breaker.status().state, 'closed');
+
+    breaker.recordFailure(new Error('boom'), NOW);
+    assert.equal(breaker.status().state, 'open');
+    assert.equal(breaker.tryAcquire(NOW + 29_999), false);
+    assert.equal(breaker.isOpen(NOW + 29_999), true);
+
+    // One probe after the open period; a failed probe reopens at once
+    assert.equal(breaker.tryAcquire(NOW + 30_000), true);
+    assert.equal(breaker.status().state, 'half_open');
+    assert.equal(breaker.tryAcquire(NOW + 30_000), false);
+    breaker.recordFailure(new Error('still down'), NOW + 30_000);
+    assert.equal(breaker.status().state, 'open');
+    assert.equal(breaker.status().opens, 2);
+
+    assert.equal(breaker.tryAcquire(NOW + 60_000), true);
+    breaker.recordSuccess();
+    assert.equal(breaker.status().state, 'closed');
+    assert.equal(breaker.tryAcquire(NOW + 60_000), true);
+  });
+
+  it('maps request URLs to upstreams by origin', () => {
+    assert.equal(upstreamForUrl('https://api.example.com/users/bc1q?x=1'), 'api');
+    assert.equal(upstreamForUrl('https://router.example.com/orders'), 'router');
+    assert.equal(upstreamForUrl('https://mempool.space/api/blocks/tip/height'), 'mempool');
+    assert.equal(upstreamForUrl('https://api.example.com:8443/users'), null);
+    assert.equal(upstreamForUrl('not a url'), null);
+  });
+
+  it('refuses requests to an open upstream with CircuitOpenError', () => {
+    const breaker = getCircuitBreaker('api');
+    for (let i = 0; i < 5; i++) breaker.recordFailure(new Error('down'));
+
+    assert.throws(() => acquireCircuit('https://api.example.com/pool/stats'), CircuitOpenError);
+    assert.equal(acquireCircuit('https://example.org/'), null);
+  });
+
+  it('backs off linearly with jitter', () => {
+    assert.equal(backoffDelay(1, 500, 0.5), 500);
+    assert.equal(backoffDelay(3, 500, 0), 750);
+    assert.equal(backoffDelay(3, 500, 0.999), 2248);
+  });
+});
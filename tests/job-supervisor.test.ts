@@ -0,0 +1,153 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type * as SupervisorModule from '../lib/job-supervisor';
+import type * as DbModule from '../lib/db';
+import { HttpError } from '../lib/http-errors';
+import { withRetry } from '../lib/retry';
+
+// The database reads its location at import time
+process.env.PARASTATS_DATA_DIR = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-jobs-'));
+
+let supervisor: typeof SupervisorModule;
+let dbModule: typeof DbModule;
+
+const NOW = 1_700_000_000;
+
+function row(overrides: Partial<SupervisorModule.JobStatusRow> = {}): SupervisorModule.JobStatusRow {
+  return {
+    name: 'pool_stats',
+    schedule: '* * * * *',
+    timeout_ms: 120_000,
+    stale_after_seconds: 600,
+    depends_on: '',
+    registered_at: NOW - 3600,
+    running: 0,
+    last_started_at: NOW - 60,
+    last_finished_at: NOW - 59,
+    last_duration_ms: 1000,
+    last_success_at: NOW - 59,
+    last_error: null,
+    last_error_at: null,
+    runs: 60,
+    failures: 0,
+    timeouts: 0,
+    overlap_skips: 0,
+    dependency_skips: 0,
+    updated_at: NOW - 59,
+    ...overrides,
+  };
+}
+
+describe('job supervisor', () => {
+  before(async () => {
+    supervisor = await import('../lib/job-supervisor');
+    dbModule = await import('../lib/db');
+  });
+
+  after(async () => {
+    await supervisor.stopJobs();
+    await dbModule.closeDb();
+  });
+
+  it('flags hung and stale jobs as unhealthy', () => {
+    assert.equal(supervisor.evaluateJobHealth(row(), NOW).status, 'ok');
+
+    const failing = supervisor.evaluateJobHealth(row({ last_error: 'boom', last_error_at: NOW - 10 }), NOW);
+    assert.equal(failing.status, 'failing');
+    assert.equal(failing.healthy, true);
+
+    const hung = supervisor.evaluateJobHealth(row({ running: 1, last_started_at: NOW - 121 }), NOW);
+    assert.equal(hung.status, 'hung');
+    assert.equal(hung.healthy, false);
+
+    const stale = supervisor.evaluateJobHealth(row({ last_success_at: NOW - 601 }), NOW);
+    assert.equal(stale.status, 'stale');
+    assert.equal(stale.healthy, false);
+
+    // Never succeeded: staleness counts from registration
+    const fresh = supervisor.evaluateJobHealth(row({ last_success_at: null, registered_at: NOW - 30 }), NOW);
+    assert.equal(fresh.status, 'never_run');
+    assert.equal(fresh.healthy, true);
+  });
+
+  it('skips overlapping runs and runs waiting on dependencies', async () => {
+    let release!: () => void;
+    let markStarted!: () => void;
+    const started = new Promise<void>(resolve => { markStarted = resolve; });
+    let slowRuns = 0;
+
+    supervisor.registerJobs([
+      {
+        name: 'test_slow',
+        schedule: null,
+        timeoutMs: 60_000,
+        run: () => {
+          slowRuns++;
+          markStarted();
+          return new Promise<void>(resolve => { release = resolve; });
+        },
+      },
+      {
+        name: 'test_dependent',
+        schedule: null,
+        timeoutMs: 60_000,
+        dependsOn: ['test_slow'],
+        run: async () => {},
+      },
+    ]);
+    await supervisor.startJobs();
+
+    const first = supervisor.runJob('test_slow');
+    await supervisor.runJob('test_slow');
+    await supervisor.runJob('test_dependent');
+    await started;
+    release();
+    await first;
+    await supervisor.runJob('test_dependent');
+
+    assert.equal(slowRuns, 1);
+    const { jobs } = await supervisor.getJobsHealth(await dbModule.getDb());
+    const slow = jobs.find(job => job.name === 'test_slow');
+    const dependent = jobs.find(job => job.name === 'test_dependent');
+    assert.equal(slow?.runs, 1);
+    assert.equal(slow?.overlapSkips, 1);
+    assert.equal(slow?.status, 'ok');
+    assert.equal(dependent?.dependencySkips, 1);
+    assert.equal(dependent?.runs, 1);
+  });
+
+  it('aborts running jobs mid-backoff', async () => {
+    let markStarted!: () => void;
+    const started = new Promise<void>(resolve => { markStarted = resolve; });
+    let attempts = 0;
+    let failure: unknown;
+
+    supervisor.registerJobs([{
+      name: 'test_abortable',
+      schedule: null,
+      timeoutMs: 60_000,
+      run: signal => withRetry(async () => {
+        attempts++;
+        markStarted();
+        throw new HttpError(503, 'Service Unavailable', 'https://api.example.com/users');
+      }, { baseDelayMs: 60_000, signal }).catch(error => {
+        failure = error;
+        throw error;
+      }),
+    }]);
+
+    const startedAtMs = Date.now();
+    const run = supervisor.runJob('test_abortable');
+    await started;
+    supervisor.abortJobs('Lost the leader lease');
+    await run;
+
+    // Settled without sitting out the backoff or retrying
+    assert.ok(Date.now() - startedAtMs < 5_000);
+    assert.equal(attempts, 1);
+    assert.equal((failure as Error).message, 'Lost the leader lease');
+  });
+});
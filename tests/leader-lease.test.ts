@@ -0,0 +1,73 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type * as LeaseModule from '../lib/leader-lease';
+import type * as DbModule from '../lib/db';
+
+// The database reads its location at import time
+process.env.PARASTATS_DATA_DIR = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-lease-'));
+
+let lease: typeof LeaseModule;
+let dbModule: typeof DbModule;
+
+const NOW = 1_700_000_000;
+const TTL = 30;
+
+describe('leader lease', () => {
+  before(async () => {
+    lease = await import('../lib/leader-lease');
+    dbModule = await import('../lib/db');
+  });
+
+  after(async () => {
+    await dbModule.closeDb();
+  });
+
+  it('hands the lease to a standby only once it expires', async () => {
+    const db = await dbModule.getDb();
+
+    assert.equal(await lease.tryAcquireLease(db, 'test', 'primary', NOW, TTL), true);
+    assert.equal(await lease.tryAcquireLease(db, 'test', 'standby', NOW + 10, TTL), false);
+
+    assert.equal(await lease.renewLease(db, 'test', 'primary', NOW + 20, TTL), true);
+    assert.equal(await lease.tryAcquireLease(db, 'test', 'standby', NOW + 40, TTL), false);
+
+    // Primary stopped renewing at NOW + 20
+    assert.equal(await lease.tryAcquireLease(db, 'test', 'standby', NOW + 50, TTL), true);
+    assert.equal(await lease.renewLease(db, 'test', 'primary', NOW + 51, TTL), false);
+
+    const current = await lease.getLease(db, 'test');
+    assert.equal(current?.holder, 'standby');
+    assert.equal(current?.acquiredAt, NOW + 50);
+    assert.equal(current?.expiresAt, NOW + 50 + TTL);
+  });
+
+  it('keeps the acquisition time when the holder re-acquires and frees the lease on release', async () => {
+    const db = await dbModule.getDb();
+
+    assert.equal(await lease.tryAcquireLease(db, 'release', 'primary', NOW, TTL), true);
+    assert.equal(await lease.tryAcquireLease(db, 'release', 'primary', NOW + 5, TTL), true);
+    assert.equal((await lease.getLease(db, 'release'))?.acquiredAt, NOW);
+
+    await lease.releaseLease(db, 'release', 'standby');
+    assert.notEqual(await lease.getLease(db, 'release'), null);
+
+    await lease.releaseLease(db, 'release', 'primary');
+    assert.equal(await lease.tryAcquireLease(db, 'release', 'standby', NOW + 6, TTL), true);
+  });
+
+  it('fences writes to the unexpired holder', async () => {
+    const db = await dbModule.getDb();
+
+    assert.equal(await lease.tryAcquireLease(db, 'fence', 'primary', NOW, TTL), true);
+    assert.equal(await lease.holdsLease(db, 'fence', 'primary', NOW + TTL - 1), true);
+    assert.equal(await lease.holdsLease(db, 'fence', 'standby', NOW + 1), false);
+    // Expired but not yet taken over: the old holder must already stop writing
+    assert.equal(await lease.holdsLease(db, 'fence', 'primary', NOW + TTL), false);
+
+    // Without an election in this process there is nothing to fence
+    await lease.assertLeaseHeld(db);
+  });
+});
@@ -0,0 +1,63 @@
+import { describe, it, before } from 'node:test';
+import assert from 'node:assert/strict';
+import type * as LoggerModule from '../lib/logger';
+
+// The logger reads its configuration at import time
+process.env.LOG_FORMAT = 'json';
+process.env.LOG_LEVEL = 'info';
+
+let createLogger: typeof LoggerModule.createLogger;
+
+function capture(stream: NodeJS.WriteStream, fn: () => void): string[] {
+  const lines: string[] = [];
+  const original = stream.write;
+  stream.write = ((chunk: string | Uint8Array) => {
+    lines.push(String(chunk).trimEnd());
+    return true;
+  }) as typeof stream.write;
+  try {
+    fn();
+  } finally {
+    stream.write = original;
+  }
+  return lines;
+}
+
+describe('logger', () => {
+  before(async () => {
+    ({ createLogger } = await import('../lib/logger'));
+  });
+
+  it('writes one JSON object per entry with context and fields', () => {
+    const log = createLogger({ collector: 'rounds' }).child({ cycle: 'abcd1234' });
+    const [line] = capture(process.stdout, () => log.info('Synced rounds', { rounds: 3, durationMs: 12 }));
+
+    const entry = JSON.parse(line);
+    assert.equal(entry.level, 'info');
+    assert.equal(entry.msg, 'Synced rounds');
+    assert.equal(entry.collector, 'rounds');
+    assert.equal(entry.cycle, 'abcd1234');
+    assert.equal(entry.rounds, 3);
+    assert.equal(entry.durationMs, 12);
+    assert.ok(!Number.isNaN(Date.parse(entry.time)));
+  });
+
+  it('truncates addresses and expands errors', () => {
+    const log = createLogger();
+    const [line] = capture(process.stderr, () => log.error('Failed', {
+      address: 'bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq',
+      error: new TypeError('boom'),
+    }));
+
+    const entry = JSON.parse(line);
+    assert.equal(entry.address, 'bc1q...5mdq');
+    assert.equal(entry.error.name, 'TypeError');
+    assert.equal(entry.error.message, 'boom');
+    assert.equal(typeof entry.error.stack, 'string');
+  });
+
+  it('drops entries below LOG_LEVEL', () => {
+    const lines = capture(process.stdout, () => createLogger().debug('Noise'));
+    assert.deepEqual(lines, []);
+  });
+});
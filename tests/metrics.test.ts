@@ -0,0 +1,50 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import { counter, gauge, histogram, renderMetrics, routeLabel } from '../lib/metrics';
+
+describe('metrics', () => {
+  it('renders counters and gauges with escaped labels', () => {
+    const requests = counter('test_requests_total', 'Test requests');
+    requests.inc({ route: '/a' });
+    requests.inc({ route: '/a' }, 2);
+    requests.inc({ route: 'say "hi"' });
+    gauge('test_temperature', 'Test gauge').set({}, 21.5);
+
+    const text = renderMetrics();
+    assert.match(text, /# HELP test_requests_total Test requests\n# TYPE test_requests_total counter\n/);
+    assert.match(text, /test_requests_total\{route="\/a"\} 3\n/);
+    assert.match(text, /test_requests_total\{route="say \\"hi\\""\} 1\n/);
+    assert.match(text, /test_temperature 21\.5\n/);
+  });
+
+  it('returns the registered metric for a repeated name', () => {
+    const first = counter('test_shared_total', 'Shared');
+    first.inc();
+    counter('test_shared_total', 'Shared').inc();
+    assert.equal(first.get(), 2);
+    assert.throws(() => gauge('test_shared_total', 'Shared'));
+  });
+
+  it('renders cumulative histogram buckets', () => {
+    const duration = histogram('test_duration_seconds', 'Test durations', [0.1, 1]);
+    duration.observe({ job: 'x' }, 0.05);
+    duration.observe({ job: 'x' }, 0.5);
+    duration.observe({ job: 'x' }, 5);
+
+    const text = renderMetrics();
+    assert.match(text, /test_duration_seconds_bucket\{job="x",le="0\.1"\} 1\n/);
+    assert.match(text, /test_duration_seconds_bucket\{job="x",le="1"\} 2\n/);
+    assert.match(text, /test_duration_seconds_bucket\{job="x",le="\+Inf"\} 3\n/);
+    assert.match(text, /test_duration_seconds_sum\{job="x"\} 5\.55\n/);
+    assert.match(text, /test_duration_seconds_count\{job="x"\} 3\n/);
+  });
+
+  it('collapses ids in routes', () => {
+    assert.equal(routeLabel('https://api.example.com/aggregator/pool/pool.status'), '/aggregator/pool/pool.status');
+    assert.equal(
+      routeLabel('https://api.example.com/aggregator/users/bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?x=1'),
+      '/aggregator/users/:id'
+    );
+    assert.equal(routeLabel('/api/highest-diff/850000'), '/api/highest-diff/:n');
+  });
+});
@@ -0,0 +1,133 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import Database from 'better-sqlite3';
+import { LATEST_SCHEMA_VERSION, MIGRATIONS, getMigrationStatus, migrate } from '../lib/migrations';
+
+function tableNames(db: Database.Database): string[] {
+  return (db.prepare(`SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name`).all() as { name: string }[])
+    .map(row => row.name);
+}
+
+/**
+ * A database as the collector left it before total_work, the numeric
+ * hashrates or any of the stratum tables existed
+ */
+function createLegacyDatabase(): Database.Database {
+  const db = new Database(':memory:');
+  db.exec(`
+    CREATE TABLE pool_stats (
+      id INTEGER PRIMARY KEY AUTOINCREMENT,
+      timestamp INTEGER NOT NULL,
+      runtime INTEGER NOT NULL,
+      users INTEGER NOT NULL,
+      workers INTEGER NOT NULL,
+      idle INTEGER NOT NULL,
+      disconnected INTEGER NOT NULL,
+      hashrate1m TEXT NOT NULL,
+      hashrate5m TEXT NOT NULL,
+      hashrate15m TEXT NOT NULL,
+      hashrate1hr TEXT NOT NULL,
+      hashrate6hr TEXT NOT NULL,
+      hashrate1d TEXT NOT NULL,
+      hashrate7d TEXT NOT NULL
+    );
+    INSERT INTO pool_stats VALUES (1, 1700000000, 60, 10, 20, 0, 0, '1.5P', '2T', '3G', '4M', '5K', '600', '7E');
+
+    CREATE TABLE rounds (
+      block_height INTEGER PRIMARY KEY,
+      block_hash TEXT NOT NULL,
+      coinbase_value INTEGER,
+      winner_diff REAL,
+      winner_username TEXT,
+      participant_status TEXT NOT NULL DEFAULT 'pending',
+      participant_fetched_at INTEGER,
+      error_message TEXT,
+      created_at INTEGER NOT NULL
+    );
+    INSERT INTO rounds (block_height, block_hash, participant_status, created_at)
+      VALUES (900000, 'abc', 'complete', 1700000000);
+
+    CREATE TABLE round_participants (
+      block_height INTEGER NOT NULL,
+      username TEXT NOT NULL,
+      top_diff REAL NOT NULL DEFAULT 0,
+      blocks_participated INTEGER NOT NULL DEFAULT 0,
+      PRIMARY KEY (block_height, username)
+    );
+  `);
+  return db;
+}
+
+describe('migrate', () => {
+  it('builds an empty database up to the latest version', () => {
+    const db = new Database(':memory:');
+
+    const results = migrate(db);
+
+    assert.deepEqual(results.map(result => result.version), MIGRATIONS.map(migration => migration.version));
+    const status = getMigrationStatus(db);
+    assert.equal(status.currentVersion, LATEST_SCHEMA_VERSION);
+    assert.equal(status.applied.length, MIGRATIONS.length);
+    assert.deepEqual(status.pending, []);
+    assert.ok(tableNames(db).includes('pool_stats_1d'));
+  });
+
+  it('does nothing once the database is current', () => {
+    const db = new Database(':memory:');
+    migrate(db);
+
+    assert.deepEqual(migrate(db), []);
+    assert.equal(getMigrationStatus(db).applied.length, MIGRATIONS.length);
+  });
+
+  it('upgrades a pre-versioning database in place', () => {
+    const db = createLegacyDatabase();
+
+    migrate(db);
+
+    const stats = db.prepare(`SELECT hashrate1m_hs, hashrate1d_hs, hashrate7d_hs FROM pool_stats`).get();
+    assert.deepEqual(stats, { hashrate1m_hs: 1.5e15, hashrate1d_hs: 600, hashrate7d_hs: 7e18 });
+
+    // Rounds cached without total_work are refetched
+    const round = db.prepare(`SELECT participant_status FROM rounds`).get() as { participant_status: string };
+    assert.equal(round.participant_status, 'pending');
+  });
+
+  it('stops at the target version', () => {
+    const db = new Database(':memory:');
+
+    migrate(db, { targetVersion: 3 });
+
+    const status = getMigrationStatus(db);
+    assert.equal(status.currentVersion, 3);
+    assert.deepEqual(status.pending.map(migration => migration.version), MIGRATIONS.slice(3).map(m => m.version));
+  });
+
+  it('rolls back a dry run', () => {
+    const db = createLegacyDatabase();
+    const tablesBefore = tableNames(db);
+
+    const results = migrate(db, { dryRun: true });
+
+    assert.equal(results.length, MIGRATIONS.length);
+    assert.equal(getMigrationStatus(db).currentVersion, 0);
+    assert.deepEqual(tableNames(db), tablesBefore);
+  });
+
+  it('leaves the previous version in place when a migration fails', () => {
+    const db = new Database(':memory:');
+    migrate(db, { targetVersion: 1 });
+    // Collides with the table migration 2 creates, with an incompatible shape
+    db.exec(`CREATE VIEW stratum_job_diffs AS SELECT 1 AS pool`);
+
+    assert.throws(() => migrate(db));
+    assert.equal(getMigrationStatus(db).currentVersion, 1);
+  });
+
+  it('refuses a database from a newer build', () => {
+    const db = new Database(':memory:');
+    db.pragma(`user_version = ${LATEST_SCHEMA_VERSION + 1}`);
+
+    assert.throws(() => migrate(db), /newer than this build/);
+  });
+});
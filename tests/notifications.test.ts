@@ -0,0 +1,69 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import { createHmac } from 'crypto';
+import { isPrivateAddress, resolvePublicAddresses, signWebhook, webhookDeliverer } from '../lib/notifications/webhook';
+import { decodeNpub, parseNostrPubkey } from '../lib/notifications/nostr';
+
+describe('webhook notifications', () => {
+  it('signs the timestamp and body with the subscription secret', () => {
+    const body = JSON.stringify({ event: 'block_found' });
+    const expected = createHmac('sha256', 'secret').update(`1700000000.${body}`).digest('hex');
+    assert.equal(signWebhook('secret', 1_700_000_000, body), expected);
+    assert.notEqual(signWebhook('secret', 1_700_000_001, body), expected);
+  });
+
+  it('only accepts public https targets', () => {
+    assert.equal(webhookDeliverer.validateTarget('https://hooks.example.com/parastats'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('http://hooks.example.com/parastats'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('https://localhost/hook'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('https://192.168.1.10/hook'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('https://[::1]/hook'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('not a url'), null);
+    // WHATWG URL normalises this to [::ffff:7f00:1]
+    assert.notEqual(webhookDeliverer.validateTarget('https://[::ffff:127.0.0.1]/hook'), null);
+    assert.notEqual(webhookDeliverer.validateTarget('https://100.64.0.1/hook'), null);
+  });
+
+  it('refuses private, CGNAT and mapped addresses', () => {
+    for (const address of ['127.0.0.1', '10.1.2.3', '100.100.0.1', '169.254.169.254', '0.0.0.0', '::', '::1', '::ffff:7f00:1', '::ffff:192.168.1.1', 'fd00::1', 'fe80::1']) {
+      assert.equal(isPrivateAddress(address), true, address);
+    }
+    for (const address of ['93.184.216.34', '2606:4700::1111', '::ffff:5db8:d822']) {
+      assert.equal(isPrivateAddress(address), false, address);
+    }
+  });
+
+  it('refuses a public name that resolves to a private address', async () => {
+    const resolve = async () => [
+      { address: '93.184.216.34', family: 4 },
+      { address: '10.0.0.5', family: 4 },
+    ];
+    await assert.rejects(resolvePublicAddresses('hooks.example.com', resolve), /non-public address 10\.0\.0\.5/);
+
+    const addresses = await resolvePublicAddresses('hooks.example.com', async () => [{ address: '93.184.216.34', family: 4 }]);
+    assert.deepEqual(addresses, [{ address: '93.184.216.34', family: 4 }]);
+  });
+
+  it('does not deliver to a mapped loopback address', async () => {
+    const subscription = { id: 1, address: 'bc1q', channel: 'webhook' as const, target: 'https://[::ffff:127.0.0.1]/hook', events: [], secret: 's' };
+    const notification = { id: 1, address: 'bc1q', event: 'block_found' as const, title: '', message: '', data: {}, timestamp: 0 };
+    await assert.rejects(webhookDeliverer.deliver(subscription, notification), /non-public address/);
+  });
+});
+
+describe('nostr notifications', () => {
+  const NPUB = 'npub10elfcs4fr0l0r8af98jlmgdh9c8tcxjvz9qkw038js35mp4dma8qzvjptg';
+  const HEX = '7e7e9c42a91bfef19fa929e5fda1b72e0ebc1a4c1141673e2794234d86addf4e';
+
+  it('decodes npub keys', () => {
+    assert.equal(decodeNpub(NPUB), HEX);
+    assert.equal(decodeNpub(NPUB.slice(0, -1) + 'q'), null);
+    assert.equal(decodeNpub('nsec1' + NPUB.slice(5)), null);
+  });
+
+  it('accepts npub or hex recipients', () => {
+    assert.equal(parseNostrPubkey(NPUB), HEX);
+    assert.equal(parseNostrPubkey(HEX.toUpperCase()), HEX);
+    assert.equal(parseNostrPubkey('alice@example.com'), null);
+  });
+});
@@ -0,0 +1,61 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type * as RollupsModule from '../lib/pool-stats-rollups';
+import type * as DbModule from '../lib/db';
+
+// The database reads its location at import time
+process.env.PARASTATS_DATA_DIR = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-pool-stats-rollups-'));
+
+let rollups: typeof RollupsModule;
+let dbModule: typeof DbModule;
+
+describe('pool stats rollups', () => {
+  before(async () => {
+    rollups = await import('../lib/pool-stats-rollups');
+    dbModule = await import('../lib/db');
+
+    const db = await dbModule.getDb();
+    // The first 5m bucket mixes NULL hashrates with 100 and 200; the second has only a NULL
+    const samples: [number, number | null][] = [[0, 100], [60, null], [120, 200], [300, null]];
+    for (const [timestamp, hashrate] of samples) {
+      await db.run(`
+        INSERT INTO pool_stats
+          (timestamp, runtime, users, workers, idle, disconnected,
+           hashrate1m, hashrate5m, hashrate15m, hashrate1hr, hashrate6hr, hashrate1d, hashrate7d, hashrate1d_hs)
+        VALUES (?, 60, 5, 10, 0, 0, '0', '0', '0', '0', '0', '0', '0', ?)
+      `, [timestamp, hashrate]);
+    }
+  });
+
+  after(async () => {
+    await dbModule.closeDb();
+  });
+
+  it('skips NULL samples per metric', async () => {
+    await rollups.updatePoolStatsRollups();
+
+    const db = await dbModule.getDb();
+    const buckets = await db.all(`
+      SELECT bucket_start, sample_count, users_count, hashrate1d_count,
+        hashrate1d_min, hashrate1d_avg, hashrate1d_last
+      FROM pool_stats_5m ORDER BY bucket_start
+    `);
+    assert.deepEqual(buckets, [
+      {
+        bucket_start: 0, sample_count: 3, users_count: 3, hashrate1d_count: 2,
+        hashrate1d_min: 100, hashrate1d_avg: 150, hashrate1d_last: 200,
+      },
+      {
+        bucket_start: 300, sample_count: 1, users_count: 1, hashrate1d_count: 0,
+        hashrate1d_min: null, hashrate1d_avg: null, hashrate1d_last: null,
+      },
+    ]);
+
+    // The empty 5m bucket leaves the hourly stats alone
+    const hour = await db.get('SELECT hashrate1d_count, hashrate1d_avg, hashrate1d_last FROM pool_stats_1h');
+    assert.deepEqual(hour, { hashrate1d_count: 2, hashrate1d_avg: 150, hashrate1d_last: 200 });
+  });
+});
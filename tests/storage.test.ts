@@ -0,0 +1,63 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import Database from 'better-sqlite3';
+import { SqliteDb } from '../lib/storage/sqlite';
+import { toPostgresPlaceholders } from '../lib/storage/postgres';
+
+function createDb(): SqliteDb {
+  const db = new SqliteDb(new Database(':memory:'));
+  db.connection.exec('CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL)');
+  return db;
+}
+
+describe('toPostgresPlaceholders', () => {
+  it('numbers placeholders in order', () => {
+    assert.equal(
+      toPostgresPlaceholders('SELECT * FROM t WHERE a = ? AND b IN (?, ?) LIMIT ?'),
+      'SELECT * FROM t WHERE a = $1 AND b IN ($2, $3) LIMIT $4'
+    );
+  });
+
+  it('leaves question marks inside quotes alone', () => {
+    assert.equal(
+      toPostgresPlaceholders(`SELECT '?' AS "why?", x FROM t WHERE y = ?`),
+      `SELECT '?' AS "why?", x FROM t WHERE y = $1`
+    );
+  });
+});
+
+describe('SqliteDb transactions', () => {
+  it('rolls back when the callback throws', async () => {
+    const db = createDb();
+
+    await assert.rejects(db.transaction(async () => {
+      await db.run('INSERT INTO items (name) VALUES (?)', ['lost']);
+      throw new Error('boom');
+    }), /boom/);
+
+    assert.deepEqual(await db.all('SELECT * FROM items'), []);
+    await db.close();
+  });
+
+  it('keeps queries from outside an open transaction out of it', async () => {
+    const db = createDb();
+    let release!: () => void;
+    const held = new Promise<void>(resolve => { release = resolve; });
+
+    const transaction = db.transaction(async () => {
+      await db.run('INSERT INTO items (name) VALUES (?)', ['inside']);
+      await held;
+      throw new Error('rolled back');
+    });
+    // Waits for the transaction instead of running inside it
+    const outside = db.run('INSERT INTO items (name) VALUES (?)', ['outside']);
+
+    release();
+    await assert.rejects(transaction, /rolled back/);
+    await outside;
+
+    const rows = await db.all<{ name: string }>('SELECT name FROM items');
+    assert.deepEqual(rows.map(row => row.name), ['outside']);
+    await db.close();
+  });
+});
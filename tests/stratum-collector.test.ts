@@ -0,0 +1,261 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type { StratumNotification } from '../app/api/stratum/types';
+import type { StratumCollector as StratumCollectorType, StratumCollectorHooks } from '../lib/stratum-collector';
+import type { StratumReplayServer as StratumReplayServerType, ReplayOptions } from '../lib/stratum-replay-server';
+
+/**
+ * Drives StratumCollector against the replay server with the captured session
+ * in fixtures/stratum-session.jsonl: 6 jobs over 3 blocks (clean jobs 1a0,
+ * 1a3 and 1a5) with a difficulty change from 1 to 2 before the last block.
+ */
+
+const SESSION_FILE = path.join(__dirname, 'fixtures', 'stratum-session.jsonl');
+const SESSION_JOB_IDS = ['1a0', '1a1', '1a2', '1a3', '1a4', '1a5'];
+const SESSION_CLEAN_JOB_IDS = ['1a0', '1a3', '1a5'];
+
+const RECONNECT_BASE_DELAY_MS = 20;
+const RECONNECT_MAX_DELAY_MS = 80;
+
+// The collector and database read their configuration at import time
+const dataDir = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-replay-'));
+process.env.PARASTATS_DATA_DIR = dataDir;
+process.env.STRATUM_RECONNECT_BASE_DELAY_MS = String(RECONNECT_BASE_DELAY_MS);
+process.env.STRATUM_RECONNECT_MAX_DELAY_MS = String(RECONNECT_MAX_DELAY_MS);
+process.env.STRATUM_CONNECT_TIMEOUT_MS = '1000';
+
+let StratumCollector: typeof StratumCollectorType;
+let StratumReplayServer: typeof StratumReplayServerType;
+let session: string[];
+let subscribe: (listener: (notification: StratumNotification) => void) => () => void;
+let closeDb: () => Promise<void>;
+
+let poolCounter = 0;
+
+interface Harness {
+  server: StratumReplayServerType;
+  collector: StratumCollectorType;
+  notifications: StratumNotification[];
+  stop: () => Promise<void>;
+}
+
+/**
+ * Start a replay server and a collector pointed at it. Each harness uses its
+ * own pool name so notifications from earlier tests never leak in.
+ */
+async function startHarness(
+  options: ReplayOptions = {},
+  hooks: StratumCollectorHooks = {},
+  replaySession: string[] = session,
+  primary: boolean = true
+): Promise<Harness> {
+  const server = new StratumReplayServer(replaySession, options);
+  const port = await server.listen();
+  const pool = `Replay${++poolCounter}`;
+
+  const notifications: StratumNotification[] = [];
+  const unsubscribe = subscribe(notification => {
+    if (notification.pool === pool) notifications.push(notification);
+  });
+
+  const collector = new StratumCollector(
+    { name: pool, host: '127.0.0.1', port, username: 'bc1qreplay', password: 'replay', primary },
+    { onNewBlock: () => {}, ...hooks }
+  );
+  collector.connect().catch(() => {
+    // Rejected connections are retried by the collector itself
+  });
+
+  return {
+    server,
+    collector,
+    notifications,
+    stop: async () => {
+      unsubscribe();
+      await collector.destroy();
+      await server.close();
+    },
+  };
+}
+
+async function waitFor(condition: () => boolean, timeoutMs: number = 5000): Promise<void> {
+  const deadline = Date.now() + timeoutMs;
+  while (!condition()) {
+    if (Date.now() > deadline) throw new Error('Timed out waiting for condition');
+    await new Promise(resolve => setTimeout(resolve, 10));
+  }
+}
+
+before(async () => {
+  const events = await import('../lib/stratum-events');
+  // Deliver notifications in-process instead of tailing the database
+  events.registerLocalStratumPublisher();
+  subscribe = events.subscribeStratumNotifications;
+
+  ({ StratumCollector } = await import('../lib/stratum-collector'));
+  const replay = await import('../lib/stratum-replay-server');
+  StratumReplayServer = replay.StratumReplayServer;
+  session = replay.loadReplaySession(SESSION_FILE);
+  ({ closeDb } = await import('../lib/db'));
+});
+
+after(async () => {
+  await closeDb();
+  fs.rmSync(dataDir, { recursive: true, force: true });
+});
+
+describe('session handshake', () => {
+  it('configures, subscribes and authorizes in order', async () => {
+    const harness = await startHarness();
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+
+      const requests = harness.server.connections[0].requests;
+      assert.deepEqual(requests.map(request => request.method), [
+        'mining.configure',
+        'mining.subscribe',
+        'mining.authorize',
+      ]);
+      assert.deepEqual(requests[2].params, ['bc1qreplay', 'replay']);
+    } finally {
+      await harness.stop();
+    }
+  });
+
+  it('attaches the subscription extranonce to every job', async () => {
+    const harness = await startHarness({ extranonce1: 'deadbeef', extranonce2Size: 4 });
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+
+      for (const notification of harness.notifications) {
+        assert.equal(notification.extranonce1, 'deadbeef');
+        assert.equal(notification.extranonce2Size, 4);
+      }
+    } finally {
+      await harness.stop();
+    }
+  });
+
+  it('records the negotiated version rolling mask and difficulty', async () => {
+    const harness = await startHarness();
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+
+      assert.ok(harness.notifications.every(notification => notification.versionRollingMask === '1fffe000'));
+      assert.deepEqual(harness.notifications.map(notification => notification.difficulty), [1, 1, 1, 1, 1, 2]);
+    } finally {
+      await harness.stop();
+    }
+  });
+
+  it('continues without version rolling when mining.configure is unsupported', async () => {
+    const harness = await startHarness({ configureResult: null });
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+
+      assert.ok(harness.notifications.every(notification => notification.versionRollingMask === undefined));
+    } finally {
+      await harness.stop();
+    }
+  });
+});
+
+describe('message buffering', () => {
+  for (const [label, options] of [
+    ['one write per message', { chunkSize: 0 }],
+    ['the whole session in one write', { chunkSize: -1 }],
+    ['7-byte fragments', { chunkSize: 7, chunkDelayMs: 1 }],
+    ['single bytes', { chunkSize: 1 }],
+  ] as Array<[string, ReplayOptions]>) {
+    it(`parses every job from ${label}`, async () => {
+      const harness = await startHarness(options);
+      try {
+        await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+        assert.deepEqual(harness.notifications.map(notification => notification.jobId), SESSION_JOB_IDS);
+      } finally {
+        await harness.stop();
+      }
+    });
+  }
+
+  it('recovers after an oversized line overflows the buffer', async () => {
+    const oversized = JSON.stringify({ id: null, method: 'mining.notify', params: ['x'.repeat(1200 * 1024)] });
+    const harness = await startHarness({ chunkSize: 64 * 1024 }, {}, [oversized, ...session]);
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+      assert.deepEqual(harness.notifications.map(notification => notification.jobId), SESSION_JOB_IDS);
+    } finally {
+      await harness.stop();
+    }
+  });
+});
+
+describe('clean job triggers', () => {
+  it('fires once per clean job on the primary pool', async () => {
+    const cleanJobs: string[] = [];
+    const harness = await startHarness({}, { onNewBlock: notification => cleanJobs.push(notification.job_id) });
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+      assert.deepEqual(cleanJobs, SESSION_CLEAN_JOB_IDS);
+    } finally {
+      await harness.stop();
+    }
+  });
+
+  it('never fires for secondary pools', async () => {
+    const cleanJobs: string[] = [];
+    const harness = await startHarness(
+      {},
+      { onNewBlock: notification => cleanJobs.push(notification.job_id) },
+      session,
+      false
+    );
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+      assert.deepEqual(cleanJobs, []);
+    } finally {
+      await harness.stop();
+    }
+  });
+});
+
+describe('reconnect backoff', () => {
+  it('backs off exponentially with jitter while connections are refused', async () => {
+    const delays: Array<{ delayMs: number; attempt: number }> = [];
+    const harness = await startHarness(
+      { rejectConnections: 4 },
+      { onReconnectScheduled: (delayMs, attempt) => delays.push({ delayMs, attempt }) }
+    );
+    try {
+      await waitFor(() => harness.notifications.length === SESSION_JOB_IDS.length);
+
+      assert.deepEqual(delays.slice(0, 4).map(delay => delay.attempt), [1, 2, 3, 4]);
+      delays.slice(0, 4).forEach(({ delayMs, attempt }) => {
+        const ceiling = Math.min(RECONNECT_BASE_DELAY_MS * 2 ** (attempt - 1), RECONNECT_MAX_DELAY_MS);
+        assert.ok(delayMs >= ceiling / 2 && delayMs <= ceiling, `attempt ${attempt} waited ${delayMs}ms`);
+      });
+      assert.equal(harness.server.connections.length, 5);
+    } finally {
+      await harness.stop();
+    }
+  });
+
+  it('resets the backoff once the pool accepts the subscription', async () => {
+    const delays: number[] = [];
+    const harness = await startHarness(
+      { disconnectAfterMessages: 3 },
+      { onReconnectScheduled: (_delayMs, attempt) => delays.push(attempt) }
+    );
+    try {
+      await waitFor(() => delays.length >= 3);
+      // Every session subscribes before it's dropped, so each reconnect is a first attempt
+      assert.deepEqual(delays.slice(0, 3), [1, 1, 1]);
+      assert.ok(harness.server.connections.length >= 3);
+    } finally {
+      await harness.stop();
+    }
+  });
+});
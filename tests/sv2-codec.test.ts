@@ -0,0 +1,96 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import {
+  FRAME_HEADER_LENGTH,
+  MessageType,
+  decodeFrameHeader,
+  decodeNewMiningJob,
+  decodeOpenExtendedMiningChannel,
+  decodeSetNewPrevHash,
+  decodeSetupConnection,
+  encodeFrameHeader,
+  encodeNewMiningJob,
+  encodeOpenExtendedMiningChannel,
+  encodeSetNewPrevHash,
+  encodeSetupConnection,
+  isChannelMessage,
+  toStratumV1PrevHash,
+} from '../lib/sv2-codec';
+
+describe('SV2 codec', () => {
+  it('round-trips frame headers', () => {
+    const header = { extensionType: 0x8000, msgType: MessageType.NEW_MINING_JOB, msgLength: 0x012345 };
+    const encoded = encodeFrameHeader(header);
+    assert.equal(encoded.length, FRAME_HEADER_LENGTH);
+    assert.deepEqual(decodeFrameHeader(encoded), header);
+    assert.equal(isChannelMessage(header), true);
+  });
+
+  it('round-trips SetupConnection', () => {
+    const payload = encodeSetupConnection({
+      endpointHost: 'pool.example.com',
+      endpointPort: 3336,
+      vendor: 'parastats',
+      firmware: '1.0.0',
+    });
+    assert.deepEqual(decodeSetupConnection(payload), {
+      protocol: 0,
+      minVersion: 2,
+      maxVersion: 2,
+      flags: 0,
+      endpointHost: 'pool.example.com',
+      endpointPort: 3336,
+      vendor: 'parastats',
+      hardwareVersion: '',
+      firmware: '1.0.0',
+      deviceId: '',
+    });
+  });
+
+  it('round-trips OpenExtendedMiningChannel', () => {
+    const payload = encodeOpenExtendedMiningChannel({
+      requestId: 7,
+      userIdentity: 'bc1qexample.parastats',
+      nominalHashRate: 2 ** 40,
+      minExtranonceSize: 8,
+    });
+    assert.deepEqual(decodeOpenExtendedMiningChannel(payload), {
+      requestId: 7,
+      userIdentity: 'bc1qexample.parastats',
+      nominalHashRate: 2 ** 40,
+      maxTarget: 'ff'.repeat(32),
+      minExtranonceSize: 8,
+    });
+  });
+
+  it('round-trips NewMiningJob and SetNewPrevHash', () => {
+    const futureJob = { channelId: 1, jobId: 42, minNtime: null, version: 0x20000000, merkleRoot: '11'.repeat(32) };
+    assert.deepEqual(decodeNewMiningJob(encodeNewMiningJob(futureJob)), futureJob);
+
+    const activeJob = { ...futureJob, jobId: 43, minNtime: 1_700_000_000 };
+    assert.deepEqual(decodeNewMiningJob(encodeNewMiningJob(activeJob)), activeJob);
+
+    const prevHash = {
+      channelId: 1,
+      jobId: 42,
+      prevHash: '00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff',
+      minNtime: 1_700_000_000,
+      nbits: 0x17034219,
+    };
+    const decoded = decodeSetNewPrevHash(encodeSetNewPrevHash(prevHash));
+    assert.deepEqual(decoded, prevHash);
+    assert.equal(toStratumV1PrevHash(decoded.prevHash).slice(0, 16), '3322110077665544');
+  });
+
+  it('rejects truncated messages', () => {
+    const payload = encodeNewMiningJob({ channelId: 1, jobId: 1, minNtime: 1, version: 1, merkleRoot: '00'.repeat(32) });
+    assert.throws(() => decodeNewMiningJob(payload.subarray(0, payload.length - 1)), /truncated/);
+
+    const prevHash = encodeSetNewPrevHash({ channelId: 1, jobId: 1, prevHash: '00'.repeat(32), minNtime: 1, nbits: 1 });
+    assert.throws(() => decodeSetNewPrevHash(prevHash.subarray(0, 20)), /truncated/);
+
+    // A string length prefix running past the end of the frame
+    const setup = encodeSetupConnection({ endpointHost: 'pool.example.com', endpointPort: 3336, vendor: 'v', firmware: 'f' });
+    assert.throws(() => decodeSetupConnection(setup.subarray(0, 15)), /truncated/);
+  });
+});
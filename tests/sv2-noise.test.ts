@@ -0,0 +1,214 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import crypto from 'crypto';
+import {
+  HANDSHAKE_RESPONSE_LENGTH,
+  MAX_CHUNK_PLAINTEXT,
+  Sv2NoiseInitiator,
+  createEllSwiftKeypair,
+  ellSwiftEcdh,
+  encryptedPayloadLength,
+  xswiftec,
+  xswiftecInv,
+} from '../lib/sv2-noise';
+
+const P = 2n ** 256n - 2n ** 32n - 977n;
+const ZERO = '0'.repeat(64);
+
+const fromHex = (hex: string) => BigInt('0x' + hex);
+const toHex = (value: bigint) => value.toString(16).padStart(64, '0');
+
+// BIP 324 ellswift_decode_test_vectors.csv: (u || t, x)
+const DECODE_VECTORS = [
+  [ZERO + ZERO, 'edd1fd3e327ce90cc7a3542614289aee9682003e9cf7dcc9cf2ca9743be5aa0c'],
+  [ZERO + '01d3475bf7655b0fb2d852921035b2ef607f49069b97454e6795251062741771', 'b5da00b73cd6560520e7c364086e7cd23a34bf60d0e707be9fc34d4cd5fdfa2c'],
+  [ZERO + '82277c4a71f9d22e66ece523f8fa08741a7c0912c66a69ce68514bfd3515b49f', 'f482f2e241753ad0fb89150d8491dc1e34ff0b8acfbb442cfe999e2e5e6fd1d2'],
+  [ZERO + '8421cc930e77c9f514b6915c3dbe2a94c6d8f690b5b739864ba6789fb8a55dd0', '9f59c40275f5085a006f05dae77eb98c6fd0db1ab4a72ac47eae90a4fc9e57e0'],
+  [ZERO + 'bde70df51939b94c9c24979fa7dd04ebd9b3572da7802290438af2a681895441', 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa9fffffd6b'],
+  [ZERO + 'd19c182d2759cd99824228d94799f8c6557c38a1c0d6779b9d4b729c6f1ccc42', '70720db7e238d04121f5b1afd8cc5ad9d18944c6bdc94881f502b7a3af3aecff'],
+  // t >= p decodes as t mod p
+  [ZERO + 'fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f', 'edd1fd3e327ce90cc7a3542614289aee9682003e9cf7dcc9cf2ca9743be5aa0c'],
+];
+
+// BIP 324 xswiftec_inv_test_vectors.csv: u, x and t for cases 0-7 ('' = no preimage)
+const INVERSE_VECTOR = {
+  u: '05ff6bdad900fc3261bc7fe34e2fb0f569f06e091ae437d3a52e9da0cbfb9590',
+  x: '80cdf63774ec7022c89a5a8558e373a279170285e0ab27412dbce510bdfe23fc',
+  t: [
+    '',
+    '',
+    '45654798ece071ba79286d04f7f3eb1c3f1d17dd883610f2ad2efd82a287466b',
+    '0aeaa886f6b76c7158452418cbf5033adc5747e9e9b5d3b2303db96936528557',
+    '',
+    '',
+    'ba9ab867131f8e4586d792fb080c14e3c0e2e82277c9ef0d52d1027c5d78b5c4',
+    'f51557790948938ea7badbe7340afcc523a8b816164a2c4dcfc24695c9ad76d8',
+  ],
+};
+
+const PROTOCOL_NAME = 'Noise_NX_Secp256k1+EllSwift_ChaChaPoly_SHA256';
+const MAC_LENGTH = 16;
+const EMPTY = Buffer.alloc(0);
+
+const sha256 = (...parts: Buffer[]) => crypto.createHash('sha256').update(Buffer.concat(parts)).digest();
+const hmac = (key: Buffer, ...parts: Buffer[]) => crypto.createHmac('sha256', key).update(Buffer.concat(parts)).digest();
+
+function hkdf(chainingKey: Buffer, inputKeyMaterial: Buffer): [Buffer, Buffer] {
+  const tempKey = hmac(chainingKey, inputKeyMaterial);
+  const output1 = hmac(tempKey, Buffer.from([0x01]));
+  return [output1, hmac(tempKey, output1, Buffer.from([0x02]))];
+}
+
+function nonceBytes(nonce: number): Buffer {
+  const bytes = Buffer.alloc(12);
+  bytes.writeBigUInt64LE(BigInt(nonce), 4);
+  return bytes;
+}
+
+function seal(key: Buffer, nonce: number, ad: Buffer, plaintext: Buffer): Buffer {
+  const cipher = crypto.createCipheriv('chacha20-poly1305', key, nonceBytes(nonce), { authTagLength: MAC_LENGTH });
+  cipher.setAAD(ad, { plaintextLength: plaintext.length });
+  return Buffer.concat([cipher.update(plaintext), cipher.final(), cipher.getAuthTag()]);
+}
+
+function open(key: Buffer, nonce: number, ad: Buffer, ciphertext: Buffer): Buffer {
+  const body = ciphertext.subarray(0, ciphertext.length - MAC_LENGTH);
+  const decipher = crypto.createDecipheriv('chacha20-poly1305', key, nonceBytes(nonce), { authTagLength: MAC_LENGTH });
+  decipher.setAAD(ad, { plaintextLength: body.length });
+  decipher.setAuthTag(ciphertext.subarray(ciphertext.length - MAC_LENGTH));
+  return Buffer.concat([decipher.update(body), decipher.final()]);
+}
+
+/**
+ * The pool's side of the NX handshake, written independently of the
+ * initiator in lib/sv2-noise.ts apart from the ElligatorSwift primitives
+ */
+function respond(hello: Buffer, certificate: Buffer) {
+  let h = sha256(Buffer.from(PROTOCOL_NAME));
+  let ck = h;
+  let key: Buffer;
+  const mixHash = (data: Buffer) => { h = sha256(h, data); };
+  const mixKey = (ikm: Buffer) => { [ck, key] = hkdf(ck, ikm); };
+  const encryptAndHash = (plaintext: Buffer) => {
+    const ciphertext = seal(key, 0, h, plaintext);
+    mixHash(ciphertext);
+    return ciphertext;
+  };
+
+  mixHash(EMPTY);
+  mixHash(hello);
+  mixHash(EMPTY);
+
+  const ephemeral = createEllSwiftKeypair();
+  mixHash(ephemeral.encoding);
+  mixKey(ellSwiftEcdh(ephemeral, hello, 'responder'));
+
+  const staticKey = createEllSwiftKeypair();
+  const encryptedStatic = encryptAndHash(staticKey.encoding);
+  mixKey(ellSwiftEcdh(staticKey, hello, 'responder'));
+  const encryptedCertificate = encryptAndHash(certificate);
+
+  const [receiveKey, sendKey] = hkdf(ck, EMPTY);
+  return {
+    response: Buffer.concat([ephemeral.encoding, encryptedStatic, encryptedCertificate]),
+    receiveKey,
+    sendKey,
+  };
+}
+
+function certificate(validFrom: number, notValidAfter: number): Buffer {
+  const bytes = Buffer.alloc(74);
+  bytes.writeUInt16LE(0, 0);
+  bytes.writeUInt32LE(validFrom, 2);
+  bytes.writeUInt32LE(notValidAfter, 6);
+  bytes.fill(0xab, 10);
+  return bytes;
+}
+
+describe('ElligatorSwift', () => {
+  it('decodes the BIP 324 test vectors', () => {
+    for (const [encoding, x] of DECODE_VECTORS) {
+      const u = fromHex(encoding.slice(0, 64));
+      const t = fromHex(encoding.slice(64));
+      assert.equal(toHex(xswiftec(u, t)), x, encoding);
+    }
+  });
+
+  it('inverts the BIP 324 test vectors for all eight cases', () => {
+    const u = fromHex(INVERSE_VECTOR.u);
+    const x = fromHex(INVERSE_VECTOR.x);
+    INVERSE_VECTOR.t.forEach((expected, branch) => {
+      const t = xswiftecInv(x, u, branch);
+      assert.equal(t === null ? '' : toHex(t), expected, `case ${branch}`);
+      if (t !== null) assert.equal(xswiftec(u, t), x);
+    });
+    assert.equal(fromHex(INVERSE_VECTOR.t[6]), P - fromHex(INVERSE_VECTOR.t[2]));
+  });
+
+  it('agrees on the shared secret from both sides', () => {
+    const initiator = createEllSwiftKeypair();
+    const responder = createEllSwiftKeypair();
+    assert.deepEqual(
+      ellSwiftEcdh(initiator, responder.encoding, 'initiator'),
+      ellSwiftEcdh(responder, initiator.encoding, 'responder')
+    );
+  });
+});
+
+describe('Noise NX handshake', () => {
+  const now = Math.floor(Date.now() / 1000);
+
+  it('completes against a responder and exchanges transport messages', () => {
+    const initiator = new Sv2NoiseInitiator();
+    const hello = initiator.createHello();
+    assert.equal(hello.length, 64);
+
+    const pool = respond(hello, certificate(now - 60, now + 3600));
+    assert.equal(pool.response.length, HANDSHAKE_RESPONSE_LENGTH);
+
+    const signatureNoiseMessage = initiator.readResponse(pool.response);
+    assert.equal(initiator.isHandshakeComplete, true);
+    assert.equal(signatureNoiseMessage.validFrom, now - 60);
+    assert.equal(signatureNoiseMessage.signature, 'ab'.repeat(64));
+
+    const fromPool = Buffer.from('SetupConnection.Success');
+    assert.deepEqual(initiator.decrypt(seal(pool.sendKey, 0, EMPTY, fromPool)), fromPool);
+
+    // Messages over one chunk are split into MAC-terminated chunks
+    const large = crypto.randomBytes(MAX_CHUNK_PLAINTEXT + 100);
+    const ciphertext = initiator.encrypt(large);
+    assert.equal(ciphertext.length, encryptedPayloadLength(large.length));
+    const chunkLength = MAX_CHUNK_PLAINTEXT + MAC_LENGTH;
+    assert.deepEqual(
+      Buffer.concat([
+        open(pool.receiveKey, 0, EMPTY, ciphertext.subarray(0, chunkLength)),
+        open(pool.receiveKey, 1, EMPTY, ciphertext.subarray(chunkLength)),
+      ]),
+      large
+    );
+  });
+
+  it('rejects a response with a tampered MAC', () => {
+    const initiator = new Sv2NoiseInitiator();
+    const { response } = respond(initiator.createHello(), certificate(now - 60, now + 3600));
+    response[response.length - 1] ^= 0x01;
+    assert.throws(() => initiator.readResponse(response));
+    assert.equal(initiator.isHandshakeComplete, false);
+  });
+
+  it('rejects a tampered transport message', () => {
+    const initiator = new Sv2NoiseInitiator();
+    const pool = respond(initiator.createHello(), certificate(now - 60, now + 3600));
+    initiator.readResponse(pool.response);
+
+    const ciphertext = seal(pool.sendKey, 0, EMPTY, Buffer.from('NewMiningJob'));
+    ciphertext[0] ^= 0x01;
+    assert.throws(() => initiator.decrypt(ciphertext));
+  });
+
+  it('rejects an expired pool certificate', () => {
+    const initiator = new Sv2NoiseInitiator();
+    const { response } = respond(initiator.createHello(), certificate(now - 7200, now - 3600));
+    assert.throws(() => initiator.readResponse(response), /certificate not valid/);
+  });
+});
@@ -0,0 +1,58 @@
+import { describe, it, before, after } from 'node:test';
+import assert from 'node:assert/strict';
+import fs from 'fs';
+import os from 'os';
+import path from 'path';
+import type * as UserStatsHistoryModule from '../lib/user-stats-history';
+import type * as DbModule from '../lib/db';
+
+// The database reads its location at import time
+process.env.PARASTATS_DATA_DIR = fs.mkdtempSync(path.join(os.tmpdir(), 'parastats-user-historical-'));
+
+let userStatsHistory: typeof UserStatsHistoryModule;
+let dbModule: typeof DbModule;
+
+describe('user historical aggregates', () => {
+  before(async () => {
+    userStatsHistory = await import('../lib/user-stats-history');
+    dbModule = await import('../lib/db');
+
+    const db = await dbModule.getDb();
+    await db.run(
+      'INSERT INTO monitored_users (id, address, created_at, updated_at) VALUES (1, ?, 0, 0)',
+      ['bc1qexample']
+    );
+    // Bucket 0 mixes NULL readings with 10..50; bucket 1 has only NULLs
+    const samples: [number, number | null][] = [
+      [0, null], [10, 10], [20, null], [30, 20], [40, 30], [50, null], [60, 40], [70, 50],
+      [100, null], [110, null],
+    ];
+    for (const [createdAt, hashrate] of samples) {
+      await db.run(`
+        INSERT INTO user_stats_history
+          (user_id, hashrate1m, hashrate5m, hashrate1hr, hashrate1d, hashrate7d, hashrate5m_hs,
+           workers, bestshare, bestever, created_at)
+        VALUES (1, '0', '0', '0', '0', '0', ?, 1, 0, 0, ?)
+      `, [hashrate, createdAt]);
+    }
+  });
+
+  after(async () => {
+    await dbModule.closeDb();
+  });
+
+  async function aggregate(name: UserStatsHistoryModule.Aggregate) {
+    const db = await dbModule.getDb();
+    return db.all<{ bucket: number; hashrate: number | null }>(
+      userStatsHistory.aggregateQuery(name, 'hashrate5m_hs'),
+      [0, 100, 1, 0, 200]
+    );
+  }
+
+  it('takes percentiles over the non-NULL samples only', async () => {
+    // Nearest rank over [10, 20, 30, 40, 50]: p50 is the 3rd, p95 the 5th
+    assert.deepEqual(await aggregate('p50'), [{ bucket: 0, hashrate: 30 }]);
+    assert.deepEqual(await aggregate('p95'), [{ bucket: 0, hashrate: 50 }]);
+    assert.deepEqual(await aggregate('max'), [{ bucket: 0, hashrate: 50 }, { bucket: 1, hashrate: null }]);
+  });
+});
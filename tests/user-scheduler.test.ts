@@ -0,0 +1,79 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import {
+  buildCycleReport,
+  classifyUserActivity,
+  nextCollectAt,
+  selectDueUsers,
+  type ScheduledUser,
+  type SchedulePolicy,
+} from '../lib/user-scheduler';
+
+const NOW = 1_700_000_000;
+const POLICY: SchedulePolicy = {
+  activeIntervalSeconds: 60,
+  lowHashrateIntervalSeconds: 300,
+  idleIntervalSeconds: 900,
+  lowHashrateHs: 1e12,
+  idleAfterSeconds: 3600,
+  viewBoostSeconds: 900,
+  maxUsersPerCycle: 2,
+};
+
+function user(overrides: Partial<ScheduledUser> = {}): ScheduledUser {
+  return {
+    id: 1,
+    address: 'bc1qexample',
+    next_collect_at: NOW - 10,
+    last_collected_at: NOW - 70,
+    hashrate_hs: 5e12,
+    last_share_at: NOW - 30,
+    last_viewed_at: null,
+    subscribed: 0,
+    ...overrides,
+  };
+}
+
+describe('user collection schedule', () => {
+  it('polls by activity', () => {
+    assert.equal(classifyUserActivity(user(), NOW, POLICY), 'active');
+    assert.equal(classifyUserActivity(user({ hashrate_hs: 1e11 }), NOW, POLICY), 'low_hashrate');
+    assert.equal(classifyUserActivity(user({ last_share_at: NOW - 7200 }), NOW, POLICY), 'idle');
+    assert.equal(classifyUserActivity(user({ last_collected_at: null }), NOW, POLICY), 'new');
+    assert.equal(classifyUserActivity(user({ hashrate_hs: 0, last_viewed_at: NOW - 60 }), NOW, POLICY), 'watched');
+    assert.equal(classifyUserActivity(user({ hashrate_hs: 0, subscribed: 1 }), NOW, POLICY), 'watched');
+
+    assert.equal(nextCollectAt('idle', NOW, POLICY, () => 0), NOW + 900);
+    assert.equal(nextCollectAt('idle', NOW, POLICY, () => 0.999), NOW + 811);
+  });
+
+  it('collects watched and most overdue users first, up to the cycle limit', () => {
+    const users = [
+      user({ id: 1, next_collect_at: NOW - 500, last_share_at: NOW - 7200 }),
+      user({ id: 2, next_collect_at: NOW - 20 }),
+      user({ id: 3, next_collect_at: NOW - 10, last_viewed_at: NOW - 30 }),
+      user({ id: 4, next_collect_at: NOW + 30 }),
+    ];
+    const { selected, deferred, due } = selectDueUsers(users, NOW, POLICY);
+    assert.deepEqual(selected.map(u => u.id), [3, 2]);
+    assert.deepEqual(deferred.map(u => u.id), [1]);
+    assert.equal(due, 3);
+  });
+
+  it('reports coverage and lag', () => {
+    const report = buildCycleReport({
+      startedAt: NOW,
+      finishedAtMs: NOW * 1000 + 1500,
+      eligible: 4,
+      due: 3,
+      collected: [user({ next_collect_at: NOW - 20 }), user({ next_collect_at: 0 })],
+      failed: 0,
+      deferred: 1,
+      rateLimited: false,
+    });
+    assert.equal(report.coverage, 0.75);
+    assert.equal(report.maxLagSeconds, 20);
+    assert.equal(report.avgLagSeconds, 20);
+    assert.equal(report.durationMs, 1500);
+  });
+});
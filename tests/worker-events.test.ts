@@ -0,0 +1,47 @@
+import { describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import { deriveWorkerEvents, type WorkerSample, type WorkerStateRow } from '../lib/worker-events';
+
+const NOW = 1_700_000_000;
+const THRESHOLDS = { idleAfterSeconds: 600, offlineAfterSeconds: 3600, hashrateDropPercent: 50 };
+
+function sample(overrides: Partial<WorkerSample> = {}): WorkerSample {
+  return { workerName: 'bc1qexample.rig1', lastshare: NOW - 30, hashrate: 100e12, baselineHashrate: 100e12, ...overrides };
+}
+
+function stored(overrides: Partial<WorkerStateRow> = {}): WorkerStateRow {
+  return { worker_name: 'bc1qexample.rig1', state: 'online', hashrate_dropped: 0, lastshare: NOW - 300, ...overrides };
+}
+
+function eventTypes(previous: WorkerStateRow[], samples: WorkerSample[]) {
+  return deriveWorkerEvents(previous, samples, NOW, THRESHOLDS).events.map(event => event.type);
+}
+
+describe('deriveWorkerEvents', () => {
+  it('walks a worker from online through idle to offline', () => {
+    assert.deepEqual(eventTypes([], [sample()]), ['online']);
+    assert.deepEqual(eventTypes([stored()], [sample({ lastshare: NOW - 900 })]), ['idle']);
+    assert.deepEqual(eventTypes([stored({ state: 'idle' })], [sample({ lastshare: NOW - 7200 })]), ['offline']);
+    assert.deepEqual(eventTypes([stored()], [sample()]), []);
+  });
+
+  it('takes workers the pool stops listing offline once', () => {
+    const { states, events } = deriveWorkerEvents([stored()], [], NOW, THRESHOLDS);
+    assert.deepEqual(events.map(event => event.type), ['offline']);
+    assert.equal(states[0].state, 'offline');
+
+    assert.deepEqual(eventTypes([stored({ state: 'offline' })], []), []);
+  });
+
+  it('raises a hashrate drop once and recovers with hysteresis', () => {
+    const drop = deriveWorkerEvents([stored()], [sample({ hashrate: 40e12 })], NOW, THRESHOLDS);
+    assert.deepEqual(drop.events.map(event => event.type), ['hashrate_drop']);
+    assert.equal(drop.states[0].hashrate_dropped, 1);
+
+    const dropped = stored({ hashrate_dropped: 1 });
+    assert.deepEqual(eventTypes([dropped], [sample({ hashrate: 30e12 })]), []);
+    // Above the drop threshold but still short of half the margin
+    assert.deepEqual(eventTypes([dropped], [sample({ hashrate: 60e12 })]), []);
+    assert.deepEqual(eventTypes([dropped], [sample({ hashrate: 80e12 })]), ['hashrate_recovered']);
+  });
+});